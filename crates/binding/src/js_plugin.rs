@@ -16,6 +16,11 @@ impl Plugin for JsPlugin {
         "js_plugin"
     }
 
+    // the loaded content is part of the cache keys already
+    fn module_cache_key(&self) -> Option<String> {
+        Some(format!("js_plugin:{}", self.hooks.name))
+    }
+
     fn build_start(&self, _context: &Arc<Context>) -> Result<()> {
         if let Some(hook) = &self.hooks.build_start {
             let (tx, rx) = mpsc::channel::<napi::Result<()>>();
@@ -41,18 +41,33 @@ pub struct BuildParams {
     #[napi(ts_type = r#"
 {
     entry?: Record<string, string>;
+    env?: {
+        development?: Record<string, any>;
+        production?: Record<string, any>;
+    };
     output?: {
         path: string;
         mode: "bundle" | "bundless" ;
         esVersion?: string;
         meta?: boolean;
+        chunkLoadTimeout?: number;
+        chunkLoadRetries?: number;
         preserveModules?: boolean;
         preserveModulesRoot?: string;
         skipWrite?: boolean;
+        filename?: string;
+        assetFileName?: string;
+        format?: "esm";
+        clean?: boolean | { keep?: string[] };
+        banner?: string;
+        footer?: string;
     };
     resolve?: {
        alias?: Array<[string, string]>;
        extensions?: string[];
+       mainFields?: string[];
+       optionalDeps?: string[];
+       nodeBuiltins?: Record<string, string>;
     };
     manifest?: false | {
         fileName: string;
@@ -81,6 +96,12 @@ pub struct BuildParams {
       | false
       | {
           strategy: 'auto';
+          options?: {
+            vendor?: boolean;
+            allowChunks?: 'all' | 'entry' | 'async';
+            minSize?: number;
+            minChunks?: number;
+          };
         }
       | {
           strategy: 'granular';
@@ -93,6 +114,7 @@ pub struct BuildParams {
           strategy: "advanced",
           options: {
             minSize?: number;
+            maxSize?: number;
             groups: {
               name: string;
               allowChunks?: 'all' | 'entry' | 'async';
@@ -107,10 +129,22 @@ pub struct BuildParams {
     providers?: Record<string, string[]>;
     publicPath?: string;
     inlineLimit?: number;
-    targets?: Record<string, number>;
+    targets?: Record<string, number> | string | string[];
+    polyfill?: "usage" | "entry" | false;
     platform?: "node" | "browser";
     hmr?: false | {};
-    devServer?: false | { host?: string; port?: number };
+    devServer?: false | {
+        host?: string;
+        port?: number;
+        proxy?: Record<string, {
+            target: string;
+            changeOrigin?: boolean;
+            pathRewrite?: Record<string, string>;
+        }>;
+        historyApiFallback?: boolean | {
+            rewrites?: { from: string; to: string }[];
+        };
+    };
     px2rem?: false | {
         root?: number;
         propBlackList?: string[];
@@ -120,20 +154,42 @@ pub struct BuildParams {
         selectorDoubleList?: string[];
         mediaQuery?: boolean;
     };
+    autoprefixer?: false | {
+        targets?: Record<string, number>;
+    };
     stats?: boolean;
+    analyze?: boolean | {};
     hash?: boolean;
     autoCSSModules?: boolean;
     ignoreCSSParserErrors?: boolean;
     dynamicImportToRequire?: boolean;
     umd?: false | string;
+    threads?: number;
+    profile?: boolean;
     cjs?: boolean;
+    buildTargets?: {
+        platform: "browser" | "node";
+        format?: "esm" | "cjs";
+        path: string;
+        externals?: Record<string, string>;
+    }[];
     writeToDisk?: boolean;
     transformImport?: { libraryName: string; libraryDirectory?: string; style?: boolean | string }[];
     clean?: boolean;
+    cache?: boolean | {
+        dir?: string;
+    };
     nodePolyfill?: boolean;
     ignores?: string[];
-    moduleIdStrategy?: "hashed" | "named";
-    minify?: boolean;
+    moduleIdStrategy?: "hashed" | "named" | "deterministic" | "numeric";
+    minify?: boolean | {
+        dropConsole?: boolean | string[];
+        dropDebugger?: boolean;
+    };
+    mangle?: false | {
+        reserved?: string[];
+        toplevel?: boolean;
+    };
     _minifish?: false | {
         mapping: Record<string, string>;
         metaPath?: string;
@@ -147,6 +203,8 @@ pub struct BuildParams {
     };
     optimization?: false | {
         skipModules?: boolean;
+        runtimeChunk?: "single";
+        moduleIds?: "hashed" | "named" | "deterministic" | "numeric";
     };
     react?: {
         runtime?: "automatic" | "classic";
@@ -155,7 +213,12 @@ pub struct BuildParams {
         pragmaFrag?: string;
     };
     emitAssets?: boolean;
+    svgr?: boolean;
+    cssInJs?: false | {
+        labelFormat?: string;
+    };
     cssModulesExportOnlyLocales?: boolean;
+    decorators?: "legacy" | "2022-03";
     inlineCSS?: false | {};
     rscServer?: false | {
         "emitCSS": boolean;
@@ -167,9 +230,15 @@ pub struct BuildParams {
     experimental?: {
         webpackSyntaxValidate?: string[];
     };
+    warnings?: {
+        cjsInterop?: boolean;
+        circularDependencies?: boolean;
+    };
     watch?: {
         ignoredPaths?: string[];
+        ignored?: string[];
         _nodeModulesRegexes?: string[];
+        debounce?: number;
     };
 }"#)]
     pub config: serde_json::Value,
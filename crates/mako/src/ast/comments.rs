@@ -35,7 +35,6 @@ impl Comments {
     /**
      * Check for `/*#__PURE__*/`
      */
-    #[allow(dead_code)]
     pub fn has_pure(&self, span: Span) -> bool {
         self.has_flag(span, "PURE")
     }
@@ -48,7 +47,6 @@ impl Comments {
         self.has_flag(span, "NO_SIDE_EFFECTS")
     }
 
-    #[allow(dead_code)]
     fn has_flag(&self, span: Span, text: &'static str) -> bool {
         self.find_comment(span, |c| {
             if c.kind == common::comments::CommentKind::Block {
@@ -66,7 +64,6 @@ impl Comments {
         })
     }
 
-    #[allow(dead_code)]
     fn find_comment<F>(&self, span: Span, mut op: F) -> bool
     where
         F: FnMut(&common::comments::Comment) -> bool,
@@ -118,7 +118,8 @@ impl CssAst {
         let mut gen = CodeGenerator::new(
             writer,
             CodegenConfig {
-                minify: context.config.minify && matches!(context.config.mode, Mode::Production),
+                minify: context.config.minify.is_some()
+                    && matches!(context.config.mode, Mode::Production),
             },
         );
         gen.emit(&self.ast).map_err(|err| {
@@ -161,6 +162,8 @@ impl CssAst {
     ) -> String {
         let result = Self::compile_css_modules(path, ast);
         let mut export_names = Vec::new();
+        // e.g. composes foo from "./foo.module.css"
+        let mut composes_sources: Vec<String> = Vec::new();
         for (name, classes) in result.renamed.iter() {
             let mut after_transform_classes = Vec::new();
             for v in classes {
@@ -172,9 +175,20 @@ impl CssAst {
                         // e.g. composes foo from global
                         after_transform_classes.push(name.value.to_string());
                     }
-                    CssClassName::Import { name, from: _ } => {
-                        // TODO: support composes from external files
-                        after_transform_classes.push(name.value.to_string());
+                    CssClassName::Import { name, from } => {
+                        let index = composes_sources
+                            .iter()
+                            .position(|source| source == from.as_ref())
+                            .unwrap_or_else(|| {
+                                composes_sources.push(from.to_string());
+                                composes_sources.len() - 1
+                            });
+                        after_transform_classes.push(format!(
+                            "${{{}{}[{}]}}",
+                            COMPOSES_IMPORT_PREFIX,
+                            index,
+                            serde_json::to_string(name.value.as_ref()).unwrap()
+                        ));
                     }
                 }
             }
@@ -185,26 +199,41 @@ impl CssAst {
             .map(|(name, classes)| format!("\"{}\": `{}`", name, classes.join(" ").trim()))
             .collect::<Vec<String>>()
             .join(",");
+        let composes_imports = composes_sources
+            .iter()
+            .enumerate()
+            .map(|(index, source)| {
+                format!(
+                    "import {}{} from {};\n",
+                    COMPOSES_IMPORT_PREFIX,
+                    index,
+                    serde_json::to_string(&format!("{}?asmodule", source)).unwrap()
+                )
+            })
+            .collect::<Vec<String>>()
+            .join("");
 
         if export_only {
             format!(
                 r#"
-export default {{{}}}
+{}export default {{{}}}
 "#,
-                export_names
+                composes_imports, export_names
             )
         } else {
             format!(
                 r#"
 import "{}?modules";
-export default {{{}}}
+{}export default {{{}}}
 "#,
-                path, export_names
+                path, composes_imports, export_names
             )
         }
     }
 }
 
+const COMPOSES_IMPORT_PREFIX: &str = "__mako_css_modules_composes_";
+
 pub struct CSSAstGenerated {
     pub code: String,
     pub sourcemap: String,
@@ -229,3 +258,72 @@ fn ident_name(path: &str, name: &str) -> String {
     let hash_slice = hash[..8].to_string();
     format!("{}-{}", name, hash_slice)
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::CssAst;
+    use crate::ast::file::{Content, File};
+    use crate::compiler::Context;
+
+    #[test]
+    fn test_css_modules_local_scoping() {
+        let code = generate(".foo { color: red; }", false);
+        assert!(code.contains(r#"import "/test.module.css?modules";"#));
+        assert!(code.contains(r#""foo": `foo-"#));
+    }
+
+    #[test]
+    fn test_css_modules_global() {
+        let code = generate(":global(.foo) { color: red; } .bar { color: blue; }", true);
+        assert!(!code.contains(r#""foo""#));
+        assert!(code.contains(r#""bar": `bar-"#));
+    }
+
+    #[test]
+    fn test_css_modules_composes() {
+        let code = generate(
+            r#"
+.a { color: red; }
+.b { composes: a; color: blue; }
+.c { composes: b; composes: g from global; composes: x y from "./other.module.css"; }
+"#,
+            true,
+        );
+        assert!(code.contains(
+            r#"import __mako_css_modules_composes_0 from "./other.module.css?asmodule";"#
+        ));
+        let c = code
+            .split(',')
+            .find(|s| s.contains(r#""c": "#))
+            .unwrap()
+            .to_string();
+        assert!(c.contains("c-"));
+        assert!(c.contains("b-"));
+        assert!(c.contains("a-"));
+        assert!(c.contains(" g"));
+        assert!(c.contains(r#"${__mako_css_modules_composes_0["x"]}"#));
+        assert!(c.contains(r#"${__mako_css_modules_composes_0["y"]}"#));
+    }
+
+    #[test]
+    fn test_css_modules_composes_source_escaped() {
+        let code = generate(r#".a { composes: x from './it"s.module.css'; }"#, true);
+        assert!(
+            code.contains(
+                r#"import __mako_css_modules_composes_0 from "./it\"s.module.css?asmodule";"#
+            ),
+            "{}",
+            code
+        );
+    }
+
+    fn generate(content: &str, export_only: bool) -> String {
+        let context = Arc::new(Context::default());
+        let mut file = File::new("/test.module.css".to_string(), context.clone());
+        file.set_content(Content::Css(content.to_string()));
+        let mut ast = CssAst::new(&file, context, true).unwrap();
+        CssAst::generate_css_modules_exports("/test.module.css", &mut ast.ast, export_only)
+    }
+}
@@ -42,6 +42,7 @@ impl Default for JsContent {
 pub enum Content {
     Js(JsContent),
     Css(String),
+    Json(String),
     // TODO: unify the assets handler
     // it's used in minifish plugin(bundless mode) only
     // and bundle mode will emit assets to context.assets_info
@@ -111,6 +112,13 @@ fn css_source_map_regex() -> &'static Regex {
     })
 }
 
+fn js_source_map_regex() -> &'static Regex {
+    static JS_SOURCE_MAP_REGEXP: OnceLock<Regex> = OnceLock::new();
+
+    JS_SOURCE_MAP_REGEXP
+        .get_or_init(|| Regex::new(r"(?m)^//[#@] sourceMappingURL=(\S+?)\s*$").unwrap())
+}
+
 impl File {
     pub fn new(path: String, context: Arc<Context>) -> Self {
         let path = PathBuf::from(path);
@@ -189,9 +197,9 @@ impl File {
 
     pub fn get_content_raw(&self) -> String {
         match &self.content {
-            Some(Content::Js(JsContent { content, .. })) | Some(Content::Css(content)) => {
-                content.clone()
-            }
+            Some(Content::Js(JsContent { content, .. }))
+            | Some(Content::Css(content))
+            | Some(Content::Json(content)) => content.clone(),
             Some(Content::Assets(asset)) => asset.content.clone(),
             None => "".to_string(),
         }
@@ -203,6 +211,7 @@ impl File {
             match content {
                 Content::Js(JsContent { content, .. })
                 | Content::Css(content)
+                | Content::Json(content)
                 | Content::Assets(Asset { content, .. }) => {
                     // hasher.write_u64(init);
                     hasher.write(content.as_bytes());
@@ -264,8 +273,7 @@ impl File {
             buf.consume(part_len);
         }
         let digest = context.compute();
-        let hash = format!("{:x}", digest);
-        Ok(hash[0..8].to_string())
+        Ok(format!("{:x}", digest))
     }
 
     pub fn is_content_jsx(&self) -> bool {
@@ -298,13 +306,38 @@ impl File {
                     chain.push(base64_decode(source_map_base64.as_bytes()));
                 }
             }
-            // TODO: support js source map chain
-            Some(Content::Js(_)) => {}
+            Some(Content::Js(content)) => {
+                if let Some(source_map) = self.get_js_input_source_map(&content.content) {
+                    chain.push(source_map);
+                }
+            }
             _ => {}
         }
         chain
     }
 
+    // input source map of pre-compiled code, e.g. //# sourceMappingURL=index.js.map
+    // supports both inline data uri and the .map file next to the source
+    fn get_js_input_source_map(&self, content: &str) -> Option<Vec<u8>> {
+        let url = js_source_map_regex()
+            .captures_iter(content)
+            .last()?
+            .get(1)?
+            .as_str();
+        let source_map = if url.starts_with("data:") {
+            let (_, data) = url.split_once(";base64,")?;
+            engine::GeneralPurpose::new(&STANDARD, engine::general_purpose::PAD)
+                .decode(data)
+                .ok()?
+        } else {
+            let url = percent_decode_str(url.split(['?', '#']).next()?).decode_utf8_lossy();
+            std::fs::read(self.pathname.parent()?.join(url.as_ref())).ok()?
+        };
+        // ignore invalid source maps
+        serde_json::from_slice::<serde_json::Value>(&source_map).ok()?;
+        Some(source_map)
+    }
+
     pub fn path(&self) -> Option<String> {
         let path_string = self.path.to_string_lossy().to_string();
         if path_string.starts_with(VIRTUAL) {
@@ -374,4 +407,29 @@ mod tests {
         );
         assert_eq!(f.path(), Some("/root/d.js".to_string()));
     }
+
+    #[test]
+    fn test_js_input_source_map() {
+        let f = File::new("/a/b/c.js".to_string(), Arc::new(Context::default()));
+        let source_map = r#"{"version":3,"sources":["c.ts"],"names":[],"mappings":"AAAA"}"#;
+        let inline = format!(
+            "a;\n//# sourceMappingURL=data:application/json;charset=utf-8;base64,{}\n",
+            crate::utils::base64_encode(source_map)
+        );
+        assert_eq!(
+            f.get_js_input_source_map(&inline),
+            Some(source_map.as_bytes().to_vec())
+        );
+        // invalid source map
+        assert_eq!(
+            f.get_js_input_source_map("a;\n//# sourceMappingURL=data:application/json;base64,YQ=="),
+            None
+        );
+        // file not exists
+        assert_eq!(
+            f.get_js_input_source_map("a;\n//# sourceMappingURL=c.js.map"),
+            None
+        );
+        assert_eq!(f.get_js_input_source_map("a;"), None);
+    }
 }
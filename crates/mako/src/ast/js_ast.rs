@@ -16,6 +16,7 @@ use swc_core::ecma::transforms::base::helpers::{inject_helpers, Helpers, HELPERS
 use swc_core::ecma::utils::contains_top_level_await;
 use swc_core::ecma::visit;
 use swc_core::ecma::visit::{VisitMutWith, VisitWith};
+use tracing::warn;
 
 use crate::ast::file::{Content, File, JsContent};
 use crate::ast::sourcemap::build_source_map_to_buf;
@@ -64,6 +65,7 @@ impl JsAst {
                 jsx,
                 decorators: true,
                 decorators_before_export: true,
+                import_attributes: true,
                 ..Default::default()
             })
         };
@@ -205,11 +207,31 @@ impl JsAst {
     }
 
     pub fn analyze_deps(&self, context: Arc<Context>) -> Vec<Dependency> {
-        let mut visitor = DepAnalyzer::new(self.unresolved_mark);
+        let comments = context
+            .meta
+            .script
+            .origin_comments
+            .read()
+            .unwrap()
+            .get_swc_comments()
+            .clone();
+        let mut visitor = DepAnalyzer::new(self.unresolved_mark).with_comments(comments);
         GLOBALS.set(&context.meta.script.globals, || {
             self.ast.visit_with(&mut visitor);
-            visitor.dependencies
-        })
+        });
+        // e.g. import(lang), it's kept as is and may fail at runtime
+        if !self.path.contains("node_modules") {
+            for span in visitor.fully_dynamic_imports {
+                let loc = context.meta.script.cm.lookup_char_pos(span.lo);
+                warn!(
+                    "Fully dynamic import() found in {}:{}:{}, it can't be bundled, add a static prefix to import the matching files, e.g. import(`./locales/${{lang}}.js`)",
+                    self.path,
+                    loc.line,
+                    loc.col_display + 1
+                );
+            }
+        }
+        visitor.dependencies
     }
 
     pub fn generate(&self, context: Arc<Context>) -> Result<JSAstGenerated> {
@@ -220,7 +242,7 @@ impl JsAst {
             let comments = context.meta.script.origin_comments.read().unwrap();
             let swc_comments = comments.get_swc_comments();
             let is_prod = matches!(context.config.mode, Mode::Production);
-            let minify = context.config.minify && is_prod;
+            let minify = context.config.minify.is_some() && is_prod;
             let ascii_only = if context.config.output.mode == OutputMode::Bundless {
                 false
             } else {
@@ -0,0 +1,103 @@
+use anyhow::{anyhow, Result};
+use serde_json::Value;
+use swc_core::common::DUMMY_SP;
+use swc_core::ecma::ast::{
+    ArrayLit, AssignOp, Expr, ExprOrSpread, KeyValueProp, Lit, Null, ObjectLit, Prop, PropName,
+    PropOrSpread, Stmt,
+};
+use swc_core::ecma::utils::{member_expr, quote_str, ExprFactory};
+
+use crate::ast::file::{Content, File};
+
+// json is parsed with serde_json instead of the js parser,
+// which is faster and more strict for large json files
+pub fn build_json_ast(file: &File) -> Result<Value> {
+    let content = match &file.content {
+        Some(Content::Json(content)) => content,
+        _ => return Err(anyhow!("json content is required")),
+    };
+    Ok(serde_json::from_str(content)?)
+}
+
+// module.exports = <json>;
+pub fn json_to_module_stmt(value: &Value) -> Stmt {
+    json_to_expr(value)
+        .make_assign_to(
+            AssignOp::Assign,
+            member_expr!(DUMMY_SP, module.exports).as_pat_or_expr(),
+        )
+        .into_stmt()
+}
+
+pub(crate) fn json_to_expr(value: &Value) -> Expr {
+    match value {
+        Value::Null => Lit::Null(Null { span: DUMMY_SP }).into(),
+        Value::Bool(b) => (*b).into(),
+        // precision of large numbers is lost in js as well
+        Value::Number(n) => n.as_f64().unwrap_or(f64::NAN).into(),
+        Value::String(s) => quote_str!(s.as_str()).into(),
+        Value::Array(items) => Expr::Array(ArrayLit {
+            span: DUMMY_SP,
+            elems: items
+                .iter()
+                .map(|item| Some(json_to_expr(item).as_arg()))
+                .collect::<Vec<Option<ExprOrSpread>>>(),
+        }),
+        Value::Object(map) => Expr::Object(ObjectLit {
+            span: DUMMY_SP,
+            props: map
+                .iter()
+                .map(|(key, value)| {
+                    PropOrSpread::Prop(Box::new(Prop::KeyValue(KeyValueProp {
+                        key: PropName::Str(quote_str!(key.as_str())),
+                        value: Box::new(json_to_expr(value)),
+                    })))
+                })
+                .collect(),
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use swc_core::ecma::ast::ModuleItem;
+
+    use crate::ast::file::{Content, File};
+    use crate::ast::tests::TestUtils;
+
+    #[test]
+    fn test_json_to_module_stmt() {
+        assert_eq!(
+            run(r#"{"a":1,"b":[true,null,"c"],"d":{"e-f":1.5}}"#).unwrap(),
+            r#"
+module.exports = {
+    "a": 1,
+    "b": [
+        true,
+        null,
+        "c"
+    ],
+    "d": {
+        "e-f": 1.5
+    }
+};
+            "#
+            .trim()
+        );
+    }
+
+    #[test]
+    fn test_invalid_json() {
+        assert!(run(r#"{"a":1,}"#).is_err());
+    }
+
+    fn run(json: &str) -> anyhow::Result<String> {
+        let mut test_utils = TestUtils::gen_js_ast("");
+        let mut file = File::new("test.json".to_string(), test_utils.context.clone());
+        file.set_content(Content::Json(json.to_string()));
+        let value = super::build_json_ast(&file)?;
+        test_utils.ast.js_mut().ast.body =
+            vec![ModuleItem::Stmt(super::json_to_module_stmt(&value))];
+        Ok(test_utils.js_ast_to_code())
+    }
+}
@@ -3,6 +3,7 @@ pub(crate) mod css_ast;
 pub(crate) mod error;
 pub mod file;
 pub(crate) mod js_ast;
+pub(crate) mod json_ast;
 pub(crate) mod sourcemap;
 #[cfg(test)]
 pub mod tests;
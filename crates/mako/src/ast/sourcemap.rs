@@ -1,7 +1,9 @@
-use std::path::PathBuf;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 
-use merge_source_map::sourcemap::SourceMap as MergeSourceMap;
+use merge_source_map::sourcemap::{SourceMap as MergeSourceMap, SourceMapBuilder};
 use merge_source_map::{merge, MergeOptions};
+use path_clean::PathClean;
 use pathdiff::diff_paths;
 use swc_core::base::sourcemap;
 use swc_core::common::source_map::SourceMapGenConfig;
@@ -100,3 +102,78 @@ pub fn merge_source_map(source_map_chain: Vec<Vec<u8>>, root: PathBuf) -> Vec<u8
     merged.to_writer(&mut buf).unwrap();
     buf
 }
+
+#[derive(Debug, Clone)]
+pub struct InputSourceMap {
+    // sources of input source maps are relative to the module
+    pub dir: PathBuf,
+    pub source_map_chain: Vec<Vec<u8>>,
+}
+
+// remap tokens of modules with input source maps, e.g. pre-compiled code shipped with its own .map,
+// to the original sources, tokens of other modules are kept
+pub fn merge_input_source_maps(
+    source_map: &[u8],
+    input_source_maps: &HashMap<String, InputSourceMap>,
+    root: &Path,
+) -> Vec<u8> {
+    let source_map = MergeSourceMap::from_slice(source_map).unwrap();
+    let input_source_maps = input_source_maps
+        .iter()
+        .map(|(source, input)| {
+            let chain = input
+                .source_map_chain
+                .iter()
+                .filter_map(|s| MergeSourceMap::from_slice(s).ok())
+                .collect::<Vec<_>>();
+            (
+                source.as_str(),
+                (&input.dir, merge(chain, Default::default())),
+            )
+        })
+        .filter(|(_, (_, input_source_map))| input_source_map.get_token_count() > 0)
+        .collect::<HashMap<_, _>>();
+
+    let mut builder = SourceMapBuilder::new(source_map.get_file());
+    for token in source_map.tokens() {
+        let input = token
+            .get_source()
+            .and_then(|source| input_source_maps.get(source));
+        let (src_token, source) = match input {
+            Some((dir, input_source_map)) => {
+                let Some(input_token) =
+                    input_source_map.lookup_token(token.get_src_line(), token.get_src_col())
+                else {
+                    continue;
+                };
+                let source = input_token.get_source().map(|source| {
+                    let path = dir.join(source).clean();
+                    diff_paths(&path, root)
+                        .unwrap_or(path)
+                        .to_string_lossy()
+                        .to_string()
+                });
+                (input_token, source)
+            }
+            None => (token, token.get_source().map(|s| s.to_string())),
+        };
+        let added_token = builder.add(
+            token.get_dst_line(),
+            token.get_dst_col(),
+            src_token.get_src_line(),
+            src_token.get_src_col(),
+            source.as_deref(),
+            src_token.get_name().or(token.get_name()),
+        );
+        if !builder.has_source_contents(added_token.src_id) {
+            builder.set_source_contents(
+                added_token.src_id,
+                src_token.get_source_view().map(|view| view.source()),
+            );
+        }
+    }
+
+    let mut buf = vec![];
+    builder.into_sourcemap().to_writer(&mut buf).unwrap();
+    buf
+}
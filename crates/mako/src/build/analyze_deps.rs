@@ -6,8 +6,10 @@ use thiserror::Error;
 
 use crate::ast::error;
 use crate::ast::file::File;
+use crate::build::load::Load;
 use crate::compiler::Context;
-use crate::module::{Dependency, ModuleAst};
+use crate::features::node::Node;
+use crate::module::{Dependency, ModuleAst, ResolveType};
 use crate::resolve::{resolve, ResolverResource};
 
 #[derive(Debug, Error)]
@@ -22,6 +24,10 @@ pub struct AnalyzeDepsResult {
     // why use hash map?
     // since we need source as key to replace in generate step
     pub missing_deps: HashMap<String, Dependency>,
+    // assets referenced by `new URL('./a.png', import.meta.url)`
+    // they are emitted directly instead of being added to the module graph
+    // source => emitted file name
+    pub url_assets: HashMap<String, String>,
 }
 
 #[derive(Debug, Clone)]
@@ -44,11 +50,13 @@ impl AnalyzeDeps {
             ModuleAst::Css(ast) => ast.analyze_deps(),
             _ => vec![],
         };
+        Self::sort_by_source_order(&mut deps);
         context.plugin_driver.before_resolve(&mut deps, &context)?;
         Self::check_deps(&deps, file)?;
 
         let mut resolved_deps = vec![];
         let mut missing_deps = HashMap::new();
+        let mut url_assets = HashMap::new();
 
         for dep in deps {
             let result = resolve(
@@ -59,6 +67,12 @@ impl AnalyzeDeps {
                 &context,
             );
             match result {
+                Ok(resolver_resource) if dep.resolve_type == ResolveType::UrlAsset => {
+                    let asset_file =
+                        File::new(resolver_resource.get_resolved_path(), context.clone());
+                    let file_name = Load::emit_asset(&asset_file, context.clone());
+                    url_assets.insert(dep.source.clone(), file_name);
+                }
                 Ok(resolver_resource) => {
                     resolved_deps.push(ResolvedDep {
                         resolver_resource,
@@ -71,9 +85,15 @@ impl AnalyzeDeps {
             }
         }
 
-        if !missing_deps.is_empty() {
+        // optional deps are replaced with modules which throw at runtime, see DepReplacer
+        let optional_deps = &context.config.resolve.optional_deps;
+        if missing_deps
+            .keys()
+            .any(|source| !optional_deps.contains(source))
+        {
             let messages = missing_deps
                 .values()
+                .filter(|dep| !optional_deps.contains(&dep.source))
                 .map(|dep| Self::get_resolved_error(dep, context.clone()))
                 .collect::<Vec<String>>()
                 .join("\n");
@@ -89,9 +109,21 @@ impl AnalyzeDeps {
         Ok(AnalyzeDepsResult {
             resolved_deps,
             missing_deps,
+            url_assets,
         })
     }
 
+    // dependencies are ordered by the source position, so that the execution order
+    // follows the code even if the visitors collect them in a different order,
+    // then renumbered to start with 1 (0 for swc helpers), which keeps the order
+    // independent of where the file is placed in the source map
+    fn sort_by_source_order(deps: &mut [Dependency]) {
+        deps.sort_by_key(|dep| dep.order);
+        deps.iter_mut().enumerate().for_each(|(i, dep)| {
+            dep.order = i + 1;
+        });
+    }
+
     fn check_deps(deps: &Vec<Dependency>, file: &File) -> Result<()> {
         for dep in deps {
             // webpack loader syntax is not supported
@@ -109,12 +141,69 @@ impl AnalyzeDeps {
     }
 
     pub fn get_resolved_error(dep: &Dependency, context: Arc<Context>) -> String {
-        let message = format!("Module not found: Can't resolve '{}'", dep.source);
-        if dep.span.is_some() {
-            // TODO: support css resolved error
-            error::code_frame(error::ErrorSpan::Js(dep.span.unwrap()), &message, context)
-        } else {
-            message
+        let mut message = format!("Module not found: Can't resolve '{}'", dep.source);
+        if let Some(hint) = Node::unshimmed_builtin_hint(&dep.source, &context.config) {
+            message = format!("{}, {}", message, hint);
         }
+        match dep.span {
+            Some(span) if dep.resolve_type == ResolveType::Css => {
+                error::code_frame(error::ErrorSpan::Css(span), &message, context)
+            }
+            Some(span) => error::code_frame(error::ErrorSpan::Js(span), &message, context),
+            None => message,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::AnalyzeDeps;
+    use crate::ast::tests::TestUtils;
+
+    #[test]
+    fn test_css_resolved_error_with_code_frame() {
+        let mut test_utils =
+            TestUtils::gen_css_ast(r#".a {} @import "./not-exists.css";"#.to_string(), false);
+        let deps = test_utils.ast.css_mut().analyze_deps();
+        let message = AnalyzeDeps::get_resolved_error(&deps[0], test_utils.context.clone());
+        assert!(message.contains("Module not found: Can't resolve './not-exists.css'"));
+        assert!(message.contains(r#"@import "./not-exists.css";"#));
+    }
+
+    #[test]
+    fn test_node_builtin_resolved_error() {
+        let mut test_utils = TestUtils::gen_js_ast(r#"import fs from "node:fs";"#);
+        let deps = test_utils
+            .ast
+            .js_mut()
+            .analyze_deps(test_utils.context.clone());
+        let message = AnalyzeDeps::get_resolved_error(&deps[0], test_utils.context.clone());
+        assert!(message.contains("Module not found: Can't resolve 'node:fs'"));
+        assert!(message.contains("Node.js builtin module 'fs' is not available in browsers"));
+        assert!(message.contains("resolve.nodeBuiltins"));
+    }
+
+    #[test]
+    fn test_sort_by_source_order() {
+        let mut test_utils = TestUtils::gen_js_ast(
+            r#"
+const c = require('c');
+import a from 'a';
+function f() { require('b'); }
+export * from 'd';
+            "#,
+        );
+        let mut deps = test_utils
+            .ast
+            .js_mut()
+            .analyze_deps(test_utils.context.clone());
+        deps.reverse();
+        AnalyzeDeps::sort_by_source_order(&mut deps);
+        assert_eq!(
+            deps.iter()
+                .map(|dep| (dep.source.as_str(), dep.order))
+                .collect::<Vec<_>>(),
+            vec![("c", 1), ("a", 2), ("b", 3), ("d", 4)]
+        );
     }
 }
@@ -0,0 +1,422 @@
+use std::collections::HashSet;
+use std::fs;
+use std::hash::Hasher;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Condvar, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use anyhow::Result;
+use swc_core::common::GLOBALS;
+use swc_core::ecma::codegen::text_writer::JsWriter;
+use swc_core::ecma::codegen::{Config as JsCodegenConfig, Emitter};
+use swc_core::ecma::transforms::base::fixer::fixer;
+use swc_core::ecma::transforms::base::hygiene::hygiene;
+use swc_core::ecma::transforms::base::resolver;
+use swc_core::ecma::visit::VisitMutWith;
+use tracing::debug;
+use twox_hash::XxHash64;
+
+use crate::ast::file::{parse_path, Content, File, JsContent};
+use crate::ast::js_ast::JsAst;
+use crate::ast::sourcemap::build_source_map_to_buf;
+use crate::build::analyze_deps::AnalyzeDepsResult;
+use crate::compiler::{Args, Context};
+use crate::config::Config;
+use crate::module::ResolveType;
+use crate::plugin::PluginDriver;
+use crate::plugins::require_context::VIRTUAL_REQUIRE_CONTEXT_MODULE;
+use crate::utils::{base64_encode, thread_pool};
+
+// entries of other configs which are not used for the time are removed
+const UNUSED_CONFIG_TTL: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+const LAST_USED_FILE: &str = "last-used";
+
+// persistent cache of the transformed js modules, since the transform result depends on
+// the file path, content, config and plugins, they are all part of the key, e.g.
+// node_modules/.cache_mako/modules/<hash of config and plugins>/<hash of path and content>.js
+pub struct ModuleCache {
+    dir: PathBuf,
+    config_hash: u64,
+    // keys of the entries read or written in this build, the others are removed after builds
+    used: Mutex<HashSet<String>>,
+    // number of the entries being written in the thread pool
+    pending: Arc<(Mutex<usize>, Condvar)>,
+}
+
+impl ModuleCache {
+    pub fn new(
+        root: &Path,
+        config: &Config,
+        args: &Args,
+        plugin_driver: &PluginDriver,
+    ) -> Option<Self> {
+        let cache_config = config.cache.as_ref()?;
+        // rsc transforms are done in parse, which is not cached
+        if config.rsc_server.is_some() || config.rsc_client.is_some() {
+            return None;
+        }
+        // plugins which can't tell whether their transforms are changed
+        let plugin_keys = plugin_driver.module_cache_keys()?;
+        let dir = root.join(
+            cache_config
+                .dir
+                .as_deref()
+                .unwrap_or("node_modules/.cache_mako/modules"),
+        );
+        let mut hasher: XxHash64 = Default::default();
+        hasher.write(env!("CARGO_PKG_VERSION").as_bytes());
+        hasher.write_u8(args.watch as u8);
+        // serialized maps are sorted, so the hash is stable between builds
+        let config = sort_keys(serde_json::to_value(config).unwrap());
+        hasher.write(config.to_string().as_bytes());
+        for key in plugin_keys {
+            hasher.write(key.as_bytes());
+            hasher.write_u8(0);
+        }
+        Some(Self {
+            dir,
+            config_hash: hasher.finish(),
+            used: Default::default(),
+            pending: Default::default(),
+        })
+    }
+
+    // returns the ast and its source map chain
+    pub fn get(&self, file: &File, context: &Arc<Context>) -> Option<(JsAst, Vec<Vec<u8>>)> {
+        let key = self.key(file, context)?;
+        let code = fs::read_to_string(self.entries_dir().join(&key)).ok()?;
+        let cached = parse(file, code, context).ok()?;
+        self.used.lock().unwrap().insert(key);
+        debug!("module cache hit: {}", file.path.display());
+        Some(cached)
+    }
+
+    // the entry is written in the thread pool, modules whose results depend on other files
+    // are not cached, e.g. the ones using import.meta.glob or require.context
+    pub fn set(&self, file: &File, ast: &JsAst, deps: &AnalyzeDepsResult, context: &Arc<Context>) {
+        if depends_on_other_files(deps) {
+            return;
+        }
+        let Some(key) = self.key(file, context) else {
+            return;
+        };
+        if !self.used.lock().unwrap().insert(key.clone()) {
+            return;
+        }
+        let path = self.entries_dir().join(key);
+        let ast = ast.clone();
+        let context = context.clone();
+        let pending = self.pending.clone();
+        *pending.0.lock().unwrap() += 1;
+        thread_pool::spawn(move || {
+            if let Err(e) = codegen(&ast, &context).and_then(|code| write_atomic(&path, &code)) {
+                debug!("write module cache failed: {}", e);
+            }
+            let (count, done) = &*pending;
+            *count.lock().unwrap() -= 1;
+            done.notify_all();
+        });
+    }
+
+    // waits for the pending writes, and removes the unused entries, which should be called
+    // after full builds only, since other builds don't use all the modules
+    pub fn flush(&self) -> Result<()> {
+        let (count, done) = &*self.pending;
+        let _count = done
+            .wait_while(count.lock().unwrap(), |count| *count > 0)
+            .unwrap();
+
+        let entries_dir = self.entries_dir();
+        if !entries_dir.exists() {
+            return Ok(());
+        }
+        let used = self.used.lock().unwrap();
+        for entry in fs::read_dir(&entries_dir)? {
+            let path = entry?.path();
+            let name = path.file_name().unwrap().to_string_lossy();
+            if name != LAST_USED_FILE && !used.contains(name.as_ref()) {
+                fs::remove_file(&path)?;
+            }
+        }
+        let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+        fs::write(entries_dir.join(LAST_USED_FILE), now.to_string())?;
+        for entry in fs::read_dir(&self.dir)? {
+            let path = entry?.path();
+            if path == entries_dir || !path.is_dir() {
+                continue;
+            }
+            let last_used = fs::read_to_string(path.join(LAST_USED_FILE))
+                .ok()
+                .and_then(|last_used| last_used.trim().parse::<u64>().ok())
+                .unwrap_or(0);
+            if now.saturating_sub(last_used) > UNUSED_CONFIG_TTL.as_secs() {
+                fs::remove_dir_all(&path)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn entries_dir(&self) -> PathBuf {
+        self.dir.join(format!("{:x}", self.config_hash))
+    }
+
+    fn key(&self, file: &File, context: &Arc<Context>) -> Option<String> {
+        let Some(Content::Js(JsContent { content, .. })) = &file.content else {
+            return None;
+        };
+        // input source maps can't be chained with the cached one
+        if !file.get_source_map_chain(context.clone()).is_empty() {
+            return None;
+        }
+        let mut hasher: XxHash64 = Default::default();
+        hasher.write(file.path.to_string_lossy().as_bytes());
+        hasher.write(content.as_bytes());
+        Some(format!("{:x}.js", hasher.finish()))
+    }
+}
+
+fn depends_on_other_files(deps: &AnalyzeDepsResult) -> bool {
+    deps.resolved_deps
+        .iter()
+        .map(|dep| &dep.dependency)
+        .chain(deps.missing_deps.values())
+        .any(|dep| {
+            dep.resolve_type == ResolveType::Glob
+                || dep.source.starts_with(VIRTUAL_REQUIRE_CONTEXT_MODULE)
+                || parse_path(&dep.source)
+                    .is_ok_and(|(_, _, params, _)| params.iter().any(|(k, _)| k == "context"))
+        })
+}
+
+// written to a temp file in the same dir first, so a partial entry is never read
+fn write_atomic(path: &Path, content: &str) -> Result<()> {
+    static COUNTER: AtomicUsize = AtomicUsize::new(0);
+    let dir = path.parent().unwrap();
+    fs::create_dir_all(dir)?;
+    let tmp = dir.join(format!(
+        ".{}.{}.{}.tmp",
+        path.file_name().unwrap().to_string_lossy(),
+        std::process::id(),
+        COUNTER.fetch_add(1, Ordering::Relaxed)
+    ));
+    fs::write(&tmp, content)?;
+    fs::rename(&tmp, path).map_err(|e| {
+        let _ = fs::remove_file(&tmp);
+        e.into()
+    })
+}
+
+fn parse(file: &File, code: String, context: &Arc<Context>) -> Result<(JsAst, Vec<Vec<u8>>)> {
+    let mut cached_file = file.clone();
+    // cached code is transformed to plain js
+    cached_file.extname = "js".to_string();
+    cached_file.set_content(Content::Js(JsContent {
+        content: code,
+        is_jsx: false,
+    }));
+    let mut ast = JsAst::new(&cached_file, context.clone())?;
+    GLOBALS.set(&context.meta.script.globals, || {
+        ast.ast.visit_mut_with(&mut resolver(
+            ast.unresolved_mark,
+            ast.top_level_mark,
+            false,
+        ));
+    });
+    Ok((ast, cached_file.get_source_map_chain(context.clone())))
+}
+
+// the cached code is parsed again without transforms, so idents should be renamed and
+// parens should be added as the generate stage does, source map is inlined when needed
+fn codegen(ast: &JsAst, context: &Arc<Context>) -> Result<String> {
+    let cm = context.meta.script.cm.clone();
+    let comments = context.meta.script.origin_comments.read().unwrap();
+    let swc_comments = comments.get_swc_comments();
+    let mut module = ast.ast.clone();
+    GLOBALS.set(&context.meta.script.globals, || {
+        module.visit_mut_with(&mut hygiene());
+        module.visit_mut_with(&mut fixer(None));
+    });
+
+    let mut buf = vec![];
+    let mut source_map_buf = vec![];
+    {
+        let mut emitter = Emitter {
+            cfg: JsCodegenConfig::default().with_target(context.config.output.es_version),
+            cm: cm.clone(),
+            comments: Some(swc_comments),
+            wr: Box::new(JsWriter::new(
+                cm.clone(),
+                "\n",
+                &mut buf,
+                Some(&mut source_map_buf),
+            )),
+        };
+        emitter.emit_module(&module)?;
+    }
+    let mut code = String::from_utf8(buf)?;
+    if context.config.devtool.is_some() {
+        let source_map = build_source_map_to_buf(&source_map_buf, &cm);
+        code.push_str(&format!(
+            "\n//# sourceMappingURL=data:application/json;charset=utf-8;base64,{}",
+            base64_encode(source_map)
+        ));
+    }
+    Ok(code)
+}
+
+fn sort_keys(value: serde_json::Value) -> serde_json::Value {
+    match value {
+        serde_json::Value::Object(map) => {
+            let mut entries = map.into_iter().collect::<Vec<_>>();
+            entries.sort_by(|(a, _), (b, _)| a.cmp(b));
+            serde_json::Value::Object(
+                entries
+                    .into_iter()
+                    .map(|(k, v)| (k, sort_keys(v)))
+                    .collect(),
+            )
+        }
+        serde_json::Value::Array(values) => {
+            serde_json::Value::Array(values.into_iter().map(sort_keys).collect())
+        }
+        value => value,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::path::{Path, PathBuf};
+    use std::sync::Arc;
+
+    use super::ModuleCache;
+    use crate::config::Config;
+    use crate::plugin::{Plugin, PluginDriver};
+    use crate::utils::test_helper::setup_compiler;
+
+    #[test]
+    fn test_cache_reuse() {
+        let base = "test/build/tmp/module-cache";
+        let root = std::env::current_dir().unwrap().join(base);
+        if root.exists() {
+            fs::remove_dir_all(&root).unwrap();
+        }
+        fs::create_dir_all(root.join("src/dir")).unwrap();
+        fs::write(
+            root.join("mako.config.json"),
+            r#"{"entry":{"index":"./src/index.js"},"cache":{"dir":"cache"}}"#,
+        )
+        .unwrap();
+        fs::write(root.join("src/index.js"), "import './a'; import './glob';").unwrap();
+        fs::write(root.join("src/a.js"), "console.log('a');").unwrap();
+        fs::write(
+            root.join("src/glob.js"),
+            "console.log(import.meta.glob('./dir/*.js'));",
+        )
+        .unwrap();
+        fs::write(root.join("src/dir/b.js"), "console.log('b');").unwrap();
+        let cache_dir = root.join("cache");
+
+        build(base);
+        // modules using import.meta.glob are not cached, while the expanded glob module,
+        // whose content lists the matched files, is cached
+        let entries = cache_entries(&cache_dir);
+        assert_eq!(entries.len(), 4);
+        assert!(!entries.iter().any(|entry| fs::read_to_string(entry)
+            .unwrap()
+            .contains("import-meta-glob")));
+
+        // the cached code is used in the second build
+        let a = entries
+            .iter()
+            .find(|entry| {
+                fs::read_to_string(entry)
+                    .unwrap()
+                    .contains("console.log('a')")
+            })
+            .unwrap();
+        fs::write(a, "console.log('a from cache');").unwrap();
+        assert!(build(base).contains("console.log('a from cache')"));
+        assert_eq!(cache_entries(&cache_dir).len(), 4);
+
+        // content changes bust the entry, and the unused one is removed
+        fs::write(root.join("src/a.js"), "console.log('a changed');").unwrap();
+        assert!(build(base).contains("console.log('a changed')"));
+        assert_eq!(cache_entries(&cache_dir).len(), 4);
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn test_config_changes() {
+        let root = std::env::current_dir().unwrap().join("test/build/fan-out");
+        let cache = |define: &str, plugins: Vec<Arc<dyn Plugin>>| {
+            let config = Config::new(
+                &root,
+                None,
+                Some(&format!(r#"{{"cache":true,"define":{}}}"#, define)),
+            )
+            .unwrap();
+            ModuleCache::new(
+                &root,
+                &config,
+                &Default::default(),
+                &PluginDriver::new(plugins),
+            )
+            .map(|cache| cache.config_hash)
+        };
+        assert_eq!(
+            cache(r#"{"A":"1","B":"2"}"#, vec![]),
+            cache(r#"{"B":"2","A":"1"}"#, vec![])
+        );
+        assert_ne!(cache(r#"{"A":"1"}"#, vec![]), cache(r#"{"A":"2"}"#, vec![]));
+        // plugins are part of the key, and can disable the cache
+        assert_ne!(
+            cache(r#"{}"#, vec![]),
+            cache(r#"{}"#, vec![Arc::new(TestPlugin(Some("test@1")))])
+        );
+        assert_ne!(
+            cache(r#"{}"#, vec![Arc::new(TestPlugin(Some("test@1")))]),
+            cache(r#"{}"#, vec![Arc::new(TestPlugin(Some("test@2")))])
+        );
+        assert!(cache(r#"{}"#, vec![Arc::new(TestPlugin(None))]).is_none());
+
+        let config = Config::new(&root, None, None).unwrap();
+        assert!(ModuleCache::new(
+            &root,
+            &config,
+            &Default::default(),
+            &PluginDriver::new(vec![])
+        )
+        .is_none());
+    }
+
+    struct TestPlugin(Option<&'static str>);
+
+    impl Plugin for TestPlugin {
+        fn name(&self) -> &str {
+            "test"
+        }
+
+        fn module_cache_key(&self) -> Option<String> {
+            self.0.map(String::from)
+        }
+    }
+
+    fn build(base: &str) -> String {
+        let compiler = setup_compiler(base, false);
+        compiler.compile().unwrap();
+        fs::read_to_string(compiler.context.config.output.path.join("index.js")).unwrap()
+    }
+
+    // the entries of the only config
+    fn cache_entries(dir: &Path) -> Vec<PathBuf> {
+        let entries_dir = fs::read_dir(dir).unwrap().next().unwrap().unwrap().path();
+        fs::read_dir(entries_dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .filter(|path| path.extension().is_some_and(|ext| ext == "js"))
+            .collect()
+    }
+}
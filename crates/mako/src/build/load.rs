@@ -1,9 +1,10 @@
 use std::io::Read;
 use std::path::Path;
-use std::sync::Arc;
+use std::sync::{Arc, OnceLock};
 
 use anyhow::{anyhow, Result};
 use mdxjs::{compile, Options as MdxOptions};
+use regex::{Captures, Regex};
 use serde_xml_rs::from_str as from_xml_str;
 use serde_yaml::{from_str as from_yaml_str, Value as YamlValue};
 use thiserror::Error;
@@ -99,6 +100,21 @@ export function moduleToDom(css) {
             }));
         }
 
+        // ?url, emit the file and return its url regardless of the inline limit
+        if file.has_param("url") {
+            let asset_path = Self::handle_asset(file, true, false, context.clone())?;
+            return Ok(Content::Js(JsContent {
+                content: format!("module.exports = {};", asset_path),
+                ..Default::default()
+            }));
+        }
+
+        // ?json, e.g. import a from './a.txt' assert { type: 'json' }
+        if file.has_param("json") {
+            let content = FileSystem::read_file(&file.pathname)?;
+            return Ok(Content::Json(content));
+        }
+
         // js
         if JS_EXTENSIONS.contains(&file.extname.as_str()) {
             // entry with ?hmr
@@ -115,7 +131,8 @@ export function moduleToDom(css) {
             return Ok(Content::Js(JsContent { content, is_jsx }));
         }
 
-        // css
+        // css, keyed on the extension of the resolved file rather than how it's imported,
+        // so `import './a.css'` from js is still parsed as css
         if CSS_EXTENSIONS.contains(&file.extname.as_str()) {
             let content = FileSystem::read_file(&file.pathname)?;
             return Ok(Content::Css(content));
@@ -143,7 +160,7 @@ export function moduleToDom(css) {
 
         // svg
         // TODO: Not all svg files need to be converted to React Component, unnecessary performance consumption here
-        if SVG_EXTENSIONS.contains(&file.extname.as_str()) {
+        if context.config.svgr && SVG_EXTENSIONS.contains(&file.extname.as_str()) {
             let content = FileSystem::read_file(&file.pathname)?;
             let svgr_transformed = svgr_rs::transform(
                 content,
@@ -178,22 +195,25 @@ export function moduleToDom(css) {
             }));
         }
 
-        // wasm
-        if WASM_EXTENSIONS.contains(&file.extname.as_str()) {
-            let final_file_name = format!(
-                "{}.{}.{}",
-                file.get_file_stem(),
-                file.get_content_hash()?,
-                file.extname
-            );
-            context.emit_assets(
-                file.pathname.to_string_lossy().to_string(),
-                final_file_name.clone(),
-            );
+        // wasm, e.g. import a from './a.bin' with { type: 'webassembly' }
+        if is_wasm(file) {
+            // files smaller than inline limit are fetched as data url
+            let url = if file.get_file_size()? > context.config.inline_limit as u64 {
+                format!(
+                    "`${{require.publicPath}}{}`",
+                    Self::emit_asset(file, context.clone())
+                )
+            } else {
+                let content = std::fs::read(&file.pathname)?;
+                format!(
+                    "\"data:application/wasm;base64,{}\"",
+                    crate::utils::base64_encode(content)
+                )
+            };
             return Ok(Content::Js(JsContent {
                 content: format!(
-                    "module.exports = require._interopreRequireWasm(exports, \"{}\")",
-                    final_file_name
+                    "module.exports = require._interopreRequireWasm(exports, {})",
+                    url
                 ),
                 ..Default::default()
             }));
@@ -222,6 +242,12 @@ export function moduleToDom(css) {
         }
 
         // json
+        if file.extname == "json" {
+            let content = FileSystem::read_file(&file.pathname)?;
+            return Ok(Content::Json(content));
+        }
+
+        // json5, which is not supported by serde_json, parse it as js
         if JSON_EXTENSIONS.contains(&file.extname.as_str()) {
             let content = FileSystem::read_file(&file.pathname)?;
             return Ok(Content::Js(JsContent {
@@ -276,17 +302,49 @@ export function moduleToDom(css) {
 
     pub fn emit_asset(file: &File, context: Arc<Context>) -> String {
         let path = file.pathname.to_string_lossy().to_string();
-        let final_file_name = format!(
-            "{}.{}.{}",
-            file.get_file_stem(),
-            file.get_content_hash().unwrap(),
-            file.extname
-        );
+        let hash = file.get_content_hash().unwrap();
+        let final_file_name = match &context.config.output.asset_file_name {
+            Some(template) => {
+                asset_file_name(template, &file.get_file_stem(), &file.extname, &hash)
+            }
+            None => format!(
+                "{}.{}.{}",
+                file.get_file_stem(),
+                &hash[..ASSET_HASH_LENGTH],
+                file.extname
+            ),
+        };
         context.emit_assets(path, final_file_name.clone());
         final_file_name
     }
 }
 
+// length of the hash in the default asset file names and [hash]
+const ASSET_HASH_LENGTH: usize = 8;
+
+// e.g. static/[name].[hash:6].[ext] => static/logo.1a2b3c.png,
+// the hash is the md5 hex digest of the content, so [hash:n] is at most 32 chars
+fn asset_file_name(template: &str, name: &str, ext: &str, hash: &str) -> String {
+    let file_name = hash_regex().replace_all(template, |caps: &Captures| {
+        let len = caps
+            .get(1)
+            .and_then(|len| len.as_str().parse::<usize>().ok())
+            .map_or(ASSET_HASH_LENGTH, |len| len)
+            .min(hash.len());
+        hash[..len].to_string()
+    });
+    file_name.replace("[name]", name).replace("[ext]", ext)
+}
+
+fn hash_regex() -> &'static Regex {
+    static HASH_REGEX: OnceLock<Regex> = OnceLock::new();
+    HASH_REGEX.get_or_init(|| Regex::new(r"\[hash(?::(\d+))?\]").unwrap())
+}
+
+pub fn is_wasm(file: &File) -> bool {
+    WASM_EXTENSIONS.contains(&file.extname.as_str()) || file.has_param("webassembly")
+}
+
 pub struct FileSystem {}
 
 impl FileSystem {
@@ -297,3 +355,206 @@ impl FileSystem {
         Ok(String::from_utf8_lossy(&buf).to_string())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::sync::Arc;
+
+    use regex::Regex;
+
+    use super::{asset_file_name, Load};
+    use crate::ast::file::{Content, File};
+    use crate::compiler::Context;
+    use crate::config::Config;
+    use crate::utils::test_helper::setup_compiler;
+
+    #[test]
+    fn test_svgr() {
+        let content = load_svg(true);
+        assert!(content.contains(r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 16 16" {...props}><circle cx={8} cy={8} r={8}/></svg>"#));
+        assert!(content.contains("export { SvgComponent as ReactComponent };"));
+        assert!(content.contains("export default \"data:image/svg+xml;base64,"));
+    }
+
+    #[test]
+    fn test_svgr_false() {
+        let content = load_svg(false);
+        assert!(content.starts_with("module.exports = \"data:image/svg+xml;base64,"));
+        assert!(!content.contains("ReactComponent"));
+    }
+
+    #[test]
+    fn test_raw() {
+        let content = load("test/build/load-query/foo.txt?raw", Config::default());
+        assert_eq!(content, r#"module.exports = "foo\nbar\n""#);
+    }
+
+    #[test]
+    fn test_url() {
+        let content = load("test/build/load-query/foo.txt?url", Config::default());
+        assert!(content.starts_with("module.exports = `${require.publicPath}foo."));
+        // below the inline limit, but not inlined
+        let content = load("test/build/load-query/small.png?url", Config::default());
+        assert!(content.starts_with("module.exports = `${require.publicPath}small."));
+        assert!(content.ends_with(".png`;"));
+        let content = load("test/build/load-query/small.png", Config::default());
+        assert!(content.starts_with("module.exports = \"data:image/png;base64,"));
+    }
+
+    #[test]
+    fn test_wasm_inline() {
+        let content = load("test/build/wasm/add.wasm", Config::default());
+        assert!(content.starts_with(
+            "module.exports = require._interopreRequireWasm(exports, \"data:application/wasm;base64,"
+        ));
+    }
+
+    #[test]
+    fn test_wasm() {
+        let compiler = setup_compiler("test/build/wasm", false);
+        compiler.compile().unwrap();
+        let output = compiler.context.config.output.path.clone();
+        let js = fs::read_to_string(output.join("index.js")).unwrap();
+        let mut emitted = fs::read_dir(&output)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().to_string_lossy().to_string())
+            .filter(|name| name.starts_with("add."))
+            .collect::<Vec<_>>();
+        emitted.sort();
+        fs::remove_dir_all(&output).unwrap();
+        // loaded as wasm by extension or import attributes
+        assert_eq!(emitted.len(), 2, "{:?}", emitted);
+        assert!(emitted[0].ends_with(".bin") && emitted[1].ends_with(".wasm"));
+        for name in emitted {
+            assert!(js.contains(&format!(
+                "module.exports = __mako_require__._interopreRequireWasm(exports, `${{__mako_require__.publicPath}}{}`)",
+                name
+            )), "{}", js);
+        }
+        assert!(js.contains("requireModule._interopreRequireWasm = "));
+    }
+
+    fn load_svg(svgr: bool) -> String {
+        load(
+            "test/build/svgr/icon.svg",
+            Config {
+                svgr,
+                ..Default::default()
+            },
+        )
+    }
+
+    fn load(path: &str, config: Config) -> String {
+        let context = Arc::new(Context {
+            config,
+            ..Default::default()
+        });
+        let path = std::env::current_dir().unwrap().join(path);
+        let file = File::new(path.to_string_lossy().to_string(), context.clone());
+        match Load::load(&file, context).unwrap() {
+            Content::Js(js) => js.content,
+            _ => panic!("{} should be loaded as js", file.path.display()),
+        }
+    }
+
+    #[test]
+    fn test_asset_file_name() {
+        let hash = "0123456789abcdef0123456789abcdef";
+        assert_eq!(
+            asset_file_name("[name].[hash].[ext]", "logo", "png", hash),
+            "logo.01234567.png"
+        );
+        assert_eq!(
+            asset_file_name("static/[name].[hash:4].[ext]", "logo", "png", hash),
+            "static/logo.0123.png"
+        );
+        assert_eq!(
+            asset_file_name("static/[name].[hash:12].[ext]", "logo", "png", hash),
+            "static/logo.0123456789ab.png"
+        );
+        assert_eq!(
+            asset_file_name("[hash:64].[ext]", "logo", "png", hash),
+            "0123456789abcdef0123456789abcdef.png"
+        );
+    }
+
+    #[test]
+    fn test_asset_file_name_template() {
+        let compiler = setup_compiler("test/build/asset-file-name", false);
+        compiler.compile().unwrap();
+        let output = compiler.context.config.output.path.clone();
+        let js = fs::read_to_string(output.join("index.js")).unwrap();
+        let css = fs::read_to_string(output.join("index.css")).unwrap();
+        let mut emitted = fs::read_dir(output.join("static"))
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().to_string_lossy().to_string())
+            .collect::<Vec<_>>();
+        emitted.sort();
+        fs::remove_dir_all(&output).unwrap();
+
+        let file_name_regex = Regex::new(r"^(logo|logo-copy)\.([0-9a-f]{6})\.png$").unwrap();
+        assert_eq!(emitted.len(), 2, "{:?}", emitted);
+        let hashes = emitted
+            .iter()
+            .map(|name| {
+                let caps = file_name_regex.captures(name).expect(name);
+                caps[2].to_string()
+            })
+            .collect::<Vec<_>>();
+        // the same content has the same hash
+        assert_eq!(hashes[0], hashes[1]);
+        // referenced by the templated names
+        assert!(js.contains(&format!(
+            "`${{__mako_require__.publicPath}}static/{}`",
+            emitted[1]
+        )));
+        assert!(css.contains(&format!("url(static/{})", emitted[0])));
+    }
+
+    #[test]
+    fn test_css_imported_from_js() {
+        let compiler = setup_compiler("test/build/css-import", false);
+        compiler.compile().unwrap();
+        let output = compiler.context.config.output.path.clone();
+        let js = fs::read_to_string(output.join("index.js")).unwrap();
+        let css = fs::read_to_string(output.join("index.css")).unwrap();
+        fs::remove_dir_all(&output).unwrap();
+
+        // side effect, css modules and namespace imports are all extracted
+        assert!(css.contains(".global {"));
+        assert!(css.contains(".plain {"));
+        assert!(css.contains("color: blue;"));
+        assert!(!js.contains(".global {"));
+        assert!(!js.contains("color: green;"));
+        // only the class names of css modules are left in js
+        assert!(js.contains("\"foo\": `foo-"), "{}", js);
+    }
+
+    #[test]
+    fn test_inline_limit() {
+        let compiler = setup_compiler("test/build/inline-limit", false);
+        compiler.compile().unwrap();
+        let output = compiler.context.config.output.path.clone();
+        let js = fs::read_to_string(output.join("index.js")).unwrap();
+        let css = fs::read_to_string(output.join("index.css")).unwrap();
+        let big = fs::read_dir(&output)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().to_string_lossy().to_string())
+            .find(|name| name.starts_with("big.") && name.ends_with(".png"))
+            .expect("big.png should be emitted");
+        let small_emitted = output.join("small.png").exists();
+        fs::remove_dir_all(&output).unwrap();
+
+        // below the limit, inlined as base64
+        assert!(js.contains("module.exports = \"data:image/png;base64,"));
+        assert!(css.contains("url(data:image/png;base64,"));
+        assert!(!small_emitted);
+        // above the limit, emitted with hash
+        assert!(js.contains(&format!(
+            "module.exports = `${{__mako_require__.publicPath}}{}`;",
+            big
+        )));
+        assert!(css.contains(&format!("url({})", big)));
+    }
+}
@@ -1,4 +1,5 @@
 pub(crate) mod analyze_deps;
+pub(crate) mod cache;
 pub(crate) mod load;
 pub(crate) mod parse;
 pub(crate) mod targets;
@@ -17,7 +18,8 @@ use crate::compiler::{Compiler, Context};
 use crate::generate::chunk_pot::util::hash_hashmap;
 use crate::module::{Module, ModuleAst, ModuleId, ModuleInfo};
 use crate::plugin::NextBuildParam;
-use crate::resolve::ResolverResource;
+use crate::resolve::{is_global_external, ResolverResource};
+use crate::utils::profiler::Phase;
 use crate::utils::thread_pool;
 
 #[derive(Debug, Error)]
@@ -39,7 +41,7 @@ impl Compiler {
         let build_with_pool = |file: File, parent_resource: Option<ResolverResource>| {
             let rs = rs.clone();
             let context = self.context.clone();
-            thread_pool::spawn(move || {
+            thread_pool::spawn_catch(rs, move |rs| {
                 let result = Self::build_module(&file, parent_resource, context.clone());
                 let result = Self::handle_build_result(result, &file, context);
                 rs.send(result).unwrap();
@@ -170,6 +172,21 @@ __mako_require__.loadScript('{}', (e) => e.type === 'load' ? resolve() : reject(
 "#,
                 url, external_name
             )
+        } else if context.config.umd.is_some()
+            && is_global_external(&context.config.externals, &origin_path)
+        {
+            // provided by the umd wrapper, see UmdExternals
+            format!(
+                "module.exports = __mako_require__.umdExternals[{}];",
+                serde_json::to_string(&origin_path).unwrap()
+            )
+        } else if context.config.cjs && is_global_external(&context.config.externals, &origin_path)
+        {
+            // cjs output is loaded by node, so externals are required instead of read from global
+            format!(
+                "module.exports = require({});",
+                serde_json::to_string(&origin_path).unwrap()
+            )
         } else {
             format!("module.exports = {};", external_name)
         };
@@ -274,26 +291,47 @@ __mako_require__.loadScript('{}', (e) => e.type === 'load' ? resolve() : reject(
         let content = load::Load::load(&file, context.clone())?;
         file.set_content(content);
 
-        // 2. parse
-        let mut ast = parse::Parse::parse(&file, context.clone())?;
-
-        // 3. transform
-        transform::Transform::transform(&mut ast, &file, context.clone())?;
+        // 2. parse + 3. transform, skipped when the module is cached
+        let cached_ast = context.profiler.record(Phase::Parse, || {
+            context
+                .module_cache
+                .as_ref()
+                .and_then(|cache| cache.get(&file, &context))
+        });
+        let is_cached = cached_ast.is_some();
+        let (ast, source_map_chain) = if let Some((ast, source_map_chain)) = cached_ast {
+            (ModuleAst::Script(ast), source_map_chain)
+        } else {
+            let mut ast = context
+                .profiler
+                .record(Phase::Parse, || parse::Parse::parse(&file, context.clone()))?;
+            context.profiler.record(Phase::Transform, || {
+                transform::Transform::transform(&mut ast, &file, context.clone())
+            })?;
+            let source_map_chain = file.get_source_map_chain(context.clone());
+            (ast, source_map_chain)
+        };
 
         // 4. analyze deps + resolve
-        let deps = analyze_deps::AnalyzeDeps::analyze_deps(&ast, &file, context.clone())?;
+        let deps = context.profiler.record(Phase::Resolve, || {
+            analyze_deps::AnalyzeDeps::analyze_deps(&ast, &file, context.clone())
+        })?;
+        if !is_cached
+            && let (Some(cache), ModuleAst::Script(js_ast)) = (&context.module_cache, &ast)
+        {
+            cache.set(&file, js_ast, &deps, &context);
+        }
 
         // 5. create module
         let path = file.path.to_string_lossy().to_string();
         let module_id = ModuleId::new(path.clone());
         let raw = file.get_content_raw();
         let is_entry = file.is_entry;
-        let source_map_chain = file.get_source_map_chain(context.clone());
         let top_level_await = match &ast {
             ModuleAst::Script(ast) => ast.contains_top_level_await,
             _ => false,
         };
-        let is_async_module = file.extname == "wasm";
+        let is_async_module = load::is_wasm(&file);
         let is_async = is_async_module || top_level_await;
 
         // raw_hash is only used in watch mode
@@ -320,3 +358,34 @@ __mako_require__.loadScript('{}', (e) => e.type === 'load' ? resolve() : reject(
         Ok(module)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::module::ModuleId;
+    use crate::utils::test_helper::setup_compiler;
+
+    #[test]
+    fn test_fan_out() {
+        // modules are built concurrently, run it several times to catch lost modules
+        for _ in 0..5 {
+            let compiler = setup_compiler("test/build/fan-out", false);
+            compiler.compile().unwrap();
+            let root = &compiler.context.root;
+            let module_graph = compiler.context.module_graph.read().unwrap();
+            assert_eq!(module_graph.modules().len(), 18);
+
+            // dependencies keep the import order though built out of order
+            let index = ModuleId::new(root.join("src/index.js").to_string_lossy().to_string());
+            let deps = module_graph
+                .get_dependencies(&index)
+                .into_iter()
+                .map(|(_, dep)| dep.source.clone())
+                .collect::<Vec<_>>();
+            let expected = (0..16).map(|i| format!("./m{}", i)).collect::<Vec<_>>();
+            assert_eq!(deps, expected);
+
+            let shared = ModuleId::new(root.join("src/shared.js").to_string_lossy().to_string());
+            assert_eq!(module_graph.get_dependents(&shared).len(), 16);
+        }
+    }
+}
@@ -8,6 +8,7 @@ use tracing::debug;
 use crate::ast::css_ast::CssAst;
 use crate::ast::file::{Content, File, JsContent};
 use crate::ast::js_ast::JsAst;
+use crate::ast::json_ast::build_json_ast;
 use crate::build::analyze_deps::AnalyzeDeps;
 use crate::build::transform::Transform;
 use crate::compiler::Context;
@@ -31,6 +32,8 @@ pub enum ParseError {
     DirectiveNotOnTop { directive: String },
     #[error("{path}\n{message}")]
     InvalidExpression { message: String, path: String },
+    #[error("Invalid JSON: {path}\n{message}")]
+    InvalidJson { message: String, path: String },
 }
 
 pub struct Parse {}
@@ -48,8 +51,20 @@ impl Parse {
         }
 
         // js
-        if let Some(Content::Js(_)) = &file.content {
+        if let Some(Content::Js(js_content)) = &file.content {
             debug!("parse js: {:?}", file.path);
+            let transformed_file = context
+                .plugin_driver
+                .transform(&js_content.content, &file.path.to_string_lossy(), &context)?
+                .map(|content| {
+                    let mut file = file.clone();
+                    file.set_content(Content::Js(JsContent {
+                        content,
+                        is_jsx: js_content.is_jsx,
+                    }));
+                    file
+                });
+            let file = transformed_file.as_ref().unwrap_or(file);
             let ast = JsAst::new(file, context.clone())?;
             if let Some(ast) = Rsc::parse_js(file, &ast, context.clone())? {
                 return Ok(ast);
@@ -57,6 +72,18 @@ impl Parse {
             return Ok(ModuleAst::Script(ast));
         }
 
+        // json
+        if let Some(Content::Json(_)) = &file.content {
+            debug!("parse json: {:?}", file.path);
+            let ast = build_json_ast(file).map_err(|e| {
+                anyhow!(ParseError::InvalidJson {
+                    message: e.to_string(),
+                    path: file.path.to_string_lossy().to_string(),
+                })
+            })?;
+            return Ok(ModuleAst::Json(ast));
+        }
+
         // css
         if let Some(Content::Css(_)) = &file.content {
             debug!("parse css: {:?}", file.path);
@@ -1,9 +1,56 @@
 use std::collections::HashMap;
 
-use swc_core::ecma::preset_env::Targets as SwcPresetEnvTargets;
+use anyhow::{anyhow, Result};
+use swc_core::ecma::preset_env::{Targets as SwcPresetEnvTargets, Versions};
 
 pub fn swc_preset_env_targets_from_map(map: HashMap<String, f32>) -> SwcPresetEnvTargets {
     let serialized_str = serde_json::to_string(&map).unwrap();
     let targets: SwcPresetEnvTargets = serde_json::from_str(&serialized_str).unwrap();
     targets
 }
+
+// resolve a browserslist query, e.g. `["> 1%", "not dead"]`, to the lowest version of each browser
+pub fn targets_from_browserslist(queries: &[String]) -> Result<HashMap<String, f32>> {
+    let distribs = browserslist::resolve(
+        queries,
+        browserslist::Opts::new()
+            .mobile_to_desktop(true)
+            .ignore_unknown_versions(true),
+    )
+    .map_err(|e| anyhow!("invalid targets {:?}: {}", queries, e))?;
+    let versions = Versions::parse_versions(distribs)?;
+    Ok(versions
+        .iter()
+        .filter_map(|(browser, version)| {
+            version.map(|v| {
+                let version = format!("{}.{}", v.major, v.minor).parse().unwrap();
+                (browser.to_string(), version)
+            })
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::targets_from_browserslist;
+
+    #[test]
+    fn test_targets_from_browserslist() {
+        let targets = targets_from_browserslist(&["chrome 49".to_string()]).unwrap();
+        assert_eq!(targets.get("chrome"), Some(&49.0));
+        assert_eq!(targets.len(), 1);
+    }
+
+    #[test]
+    fn test_targets_from_browserslist_lowest_version() {
+        let targets =
+            targets_from_browserslist(&["safari 13".to_string(), "safari 12.1".to_string()])
+                .unwrap();
+        assert_eq!(targets.get("safari"), Some(&12.1));
+    }
+
+    #[test]
+    fn test_targets_from_invalid_browserslist() {
+        assert!(targets_from_browserslist(&["not-a-browser 1".to_string()]).is_err());
+    }
+}
@@ -1,8 +1,12 @@
+use std::collections::HashMap;
+use std::ffi::OsStr;
+use std::path::{Component, Path};
 use std::sync::Arc;
 
 use anyhow::Result;
+use swc_core::common::comments::Comments;
 use swc_core::common::sync::Lrc;
-use swc_core::common::GLOBALS;
+use swc_core::common::{Mark, GLOBALS};
 use swc_core::css::ast::{AtRule, AtRulePrelude, ImportHref, Rule, Str, Stylesheet, UrlValue};
 use swc_core::css::compat::compiler::{self, Compiler};
 use swc_core::css::{compat as swc_css_compat, prefixer, visit as swc_css_visit};
@@ -13,6 +17,7 @@ use swc_core::ecma::transforms::base::{resolver, Assumptions};
 use swc_core::ecma::transforms::compat::reserved_words;
 use swc_core::ecma::transforms::optimization::simplifier;
 use swc_core::ecma::transforms::optimization::simplify::{dce, Config as SimpilifyConfig};
+use swc_core::ecma::transforms::proposal::decorator_2022_03::decorator_2022_03;
 use swc_core::ecma::transforms::proposal::decorators;
 use swc_core::ecma::visit::{Fold, VisitMut};
 
@@ -21,7 +26,7 @@ use crate::ast::file::File;
 use crate::build::targets;
 use crate::build::targets::swc_preset_env_targets_from_map;
 use crate::compiler::Context;
-use crate::config::Mode;
+use crate::config::{AutoprefixerConfig, DecoratorsConfig, Mode, PolyfillConfig};
 use crate::features;
 use crate::module::ModuleAst;
 use crate::plugins::context_module::ContextModuleVisitor;
@@ -33,8 +38,9 @@ use crate::visitors::dynamic_import_to_require::DynamicImportToRequire;
 use crate::visitors::env_replacer::{build_env_map, EnvReplacer};
 use crate::visitors::fix_helper_inject_position::FixHelperInjectPosition;
 use crate::visitors::fix_symbol_conflict::FixSymbolConflict;
+use crate::visitors::import_meta_glob::ImportMetaGlob;
+use crate::visitors::import_meta_hot::ImportMetaHot;
 use crate::visitors::import_template_to_string_literal::ImportTemplateToStringLiteral;
-use crate::visitors::new_url_assets::NewUrlAssets;
 use crate::visitors::provide::Provide;
 use crate::visitors::public_path_assignment::PublicPathAssignment;
 use crate::visitors::react::react;
@@ -44,6 +50,13 @@ use crate::visitors::tsx_strip::tsx_strip;
 use crate::visitors::virtual_css_modules::VirtualCSSModules;
 use crate::visitors::worker_module::WorkerModule;
 
+// the core-js version which the injected polyfills are picked for
+const CORE_JS_VERSION: swc_preset_env::Version = swc_preset_env::Version {
+    major: 3,
+    minor: 36,
+    patch: 0,
+};
+
 pub struct Transform {}
 
 impl Transform {
@@ -72,11 +85,6 @@ impl Transform {
                         // ref: https://github.com/umijs/mako/issues/1193
                         Box::new(FixHelperInjectPosition::new()),
                         Box::new(FixSymbolConflict::new(top_level_mark)),
-                        Box::new(NewUrlAssets {
-                            context: context.clone(),
-                            path: file.path.clone(),
-                            unresolved_mark,
-                        }),
                         Box::new(WorkerModule::new(unresolved_mark)),
                     ];
                     if is_tsx {
@@ -143,6 +151,11 @@ impl Transform {
                         auto_css_modules: context.config.auto_css_modules,
                         unresolved_mark,
                     }));
+                    visitors.push(Box::new(ImportMetaGlob { unresolved_mark }));
+                    visitors.push(Box::new(ImportMetaHot {
+                        hmr: context.args.watch && context.config.hmr.is_some(),
+                        unresolved_mark,
+                    }));
                     // TODO: move ContextModuleVisitor out of plugin
                     visitors.push(Box::new(ContextModuleVisitor { unresolved_mark }));
                     visitors.push(Box::new(ImportTemplateToStringLiteral {}));
@@ -164,27 +177,27 @@ impl Transform {
                     // decorators should go before preset_env, when compile down to es5,
                     // classes become functions, then the decorators on the functions
                     // will be removed silently.
-                    folders.push(Box::new(decorators(decorators::Config {
-                        legacy: true,
-                        emit_metadata: context.config.emit_decorator_metadata,
-                        ..Default::default()
-                    })));
+                    folders.push(decorators_folder(
+                        &context.config.decorators,
+                        context.config.emit_decorator_metadata,
+                    ));
                     let comments = origin_comments.get_swc_comments().clone();
                     let assumptions = context.assumptions_for(file);
 
-                    folders.push(Box::new(swc_preset_env::preset_env(
+                    // core-js should not be polyfilled with itself
+                    let polyfill = if file.is_under_node_modules && is_core_js(&file.path) {
+                        None
+                    } else {
+                        context.config.polyfill
+                    };
+
+                    folders.push(preset_env_folder(
                         unresolved_mark,
                         Some(comments),
-                        swc_preset_env::Config {
-                            mode: Some(swc_preset_env::Mode::Entry),
-                            targets: Some(swc_preset_env_targets_from_map(
-                                context.config.targets.clone(),
-                            )),
-                            ..Default::default()
-                        },
+                        &context.config.targets,
+                        polyfill,
                         assumptions,
-                        &mut FeatureFlag::default(),
-                    )));
+                    ));
                     folders.push(Box::new(reserved_words::reserved_words()));
                     folders.push(Box::new(paren_remover(Default::default())));
                     // simplify, but keep top level dead code
@@ -237,11 +250,12 @@ impl Transform {
                     )));
                 }
                 // prefixer
-                visitors.push(Box::new(prefixer::prefixer(prefixer::options::Options {
-                    env: Some(targets::swc_preset_env_targets_from_map(
-                        context.config.targets.clone(),
-                    )),
-                })));
+                if let Some(autoprefixer) = &context.config.autoprefixer {
+                    visitors.push(Box::new(css_prefixer(
+                        autoprefixer,
+                        &context.config.targets,
+                    )));
+                }
                 ast.transform(&mut visitors)?;
 
                 // css modules
@@ -252,11 +266,73 @@ impl Transform {
 
                 Ok(())
             }
-            ModuleAst::None => Ok(()),
+            ModuleAst::Json(_) | ModuleAst::None => Ok(()),
         }
     }
 }
 
+// e.g. node_modules/core-js/modules/es.array.at.js, compared by components so that
+// windows separators match and packages like core-js-pure don't
+fn is_core_js(path: &Path) -> bool {
+    let components = path.components().collect::<Vec<_>>();
+    components.windows(2).any(|pair| {
+        pair[0] == Component::Normal(OsStr::new("node_modules"))
+            && pair[1] == Component::Normal(OsStr::new("core-js"))
+    })
+}
+
+// add vendor prefixes like autoprefixer, e.g. user-select => -webkit-user-select
+fn css_prefixer(
+    autoprefixer: &AutoprefixerConfig,
+    targets: &HashMap<String, f32>,
+) -> impl swc_css_visit::VisitMut {
+    let targets = autoprefixer.targets.as_ref().unwrap_or(targets);
+    prefixer::prefixer(prefixer::options::Options {
+        env: Some(targets::swc_preset_env_targets_from_map(targets.clone())),
+    })
+}
+
+// downlevel syntax like optional chaining only when the targets require it,
+// and inject core-js polyfills for the apis missing in the targets
+fn preset_env_folder<C>(
+    unresolved_mark: Mark,
+    comments: Option<C>,
+    targets: &HashMap<String, f32>,
+    polyfill: Option<PolyfillConfig>,
+    assumptions: Assumptions,
+) -> Box<dyn Fold>
+where
+    C: Comments + Clone + 'static,
+{
+    Box::new(swc_preset_env::preset_env(
+        unresolved_mark,
+        comments,
+        swc_preset_env::Config {
+            mode: polyfill.map(|polyfill| match polyfill {
+                PolyfillConfig::Usage => swc_preset_env::Mode::Usage,
+                PolyfillConfig::Entry => swc_preset_env::Mode::Entry,
+            }),
+            core_js: Some(CORE_JS_VERSION),
+            targets: Some(swc_preset_env_targets_from_map(targets.clone())),
+            ..Default::default()
+        },
+        assumptions,
+        &mut FeatureFlag::default(),
+    ))
+}
+
+// emitDecoratorMetadata is only supported by legacy decorators
+fn decorators_folder(decorators: &DecoratorsConfig, emit_metadata: bool) -> Box<dyn Fold> {
+    match decorators {
+        DecoratorsConfig::Legacy => Box::new(decorators::decorators(decorators::Config {
+            legacy: true,
+            emit_metadata,
+            ..Default::default()
+        })),
+        DecoratorsConfig::V202203 => Box::new(decorator_2022_03()),
+    }
+}
+
 impl Context {
     pub fn assumptions_for(&self, file: &File) -> Assumptions {
         let is_ts = file.extname == "ts" || file.extname == "tsx";
@@ -300,3 +376,221 @@ fn import_url_to_href(ast: &mut Stylesheet) {
         }
     });
 }
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::path::Path;
+
+    use swc_core::common::GLOBALS;
+    use swc_core::css::visit::VisitMutWith;
+    use swc_core::ecma::transforms::base::Assumptions;
+    use swc_node_comments::SwcComments;
+
+    use super::{css_prefixer, decorators_folder, is_core_js, preset_env_folder};
+    use crate::ast::file::File;
+    use crate::ast::tests::TestUtils;
+    use crate::config::{AutoprefixerConfig, DecoratorsConfig, PolyfillConfig};
+
+    #[test]
+    fn test_is_core_js() {
+        assert!(is_core_js(Path::new(
+            "/app/node_modules/core-js/modules/es.array.at.js"
+        )));
+        assert!(!is_core_js(Path::new(
+            "/app/node_modules/core-js-pure/modules/es.array.at.js"
+        )));
+        assert!(!is_core_js(Path::new("/app/src/node_modules/core-js.js")));
+    }
+
+    #[test]
+    fn test_user_select() {
+        let code = run(".a { user-select: none; }", &[("safari", 14.0)]);
+        assert!(code.contains("-webkit-user-select:none"), "{}", code);
+        assert!(code.contains("user-select:none"));
+    }
+
+    #[test]
+    fn test_appearance() {
+        let code = run(".a { appearance: none; }", &[("chrome", 80.0)]);
+        assert!(code.contains("-webkit-appearance:none"), "{}", code);
+        let code = run(".a { appearance: none; }", &[("chrome", 100.0)]);
+        assert!(!code.contains("-webkit-appearance"), "{}", code);
+    }
+
+    #[test]
+    fn test_flex() {
+        let code = run(".a { display: flex; flex: 1; }", &[("safari", 8.0)]);
+        assert!(code.contains("display:-webkit-flex"), "{}", code);
+        assert!(code.contains("-webkit-flex:1"), "{}", code);
+    }
+
+    #[test]
+    fn test_autoprefixer_targets() {
+        let mut test_utils = TestUtils::gen_css_ast(".a { display: flex; }".to_string(), true);
+        let ast = test_utils.ast.css_mut();
+        let autoprefixer = AutoprefixerConfig {
+            targets: Some(HashMap::from([("safari".to_string(), 8.0)])),
+        };
+        let targets = HashMap::from([("chrome".to_string(), 100.0)]);
+        ast.ast
+            .visit_mut_with(&mut css_prefixer(&autoprefixer, &targets));
+        let code = test_utils.css_ast_to_code();
+        assert!(code.contains("display:-webkit-flex"), "{}", code);
+    }
+
+    fn run(css_code: &str, targets: &[(&str, f32)]) -> String {
+        let mut test_utils = TestUtils::gen_css_ast(css_code.to_string(), true);
+        let ast = test_utils.ast.css_mut();
+        let targets = targets
+            .iter()
+            .map(|(k, v)| (k.to_string(), *v))
+            .collect::<HashMap<_, _>>();
+        ast.ast
+            .visit_mut_with(&mut css_prefixer(&AutoprefixerConfig::default(), &targets));
+        test_utils.css_ast_to_code()
+    }
+
+    #[test]
+    fn test_legacy_decorators() {
+        let code = run_decorators(DecoratorsConfig::Legacy, false);
+        assert!(code.contains("_ts_decorate(["), "{}", code);
+        assert!(
+            code.contains("log\n], Foo.prototype, \"bar\", null);"),
+            "{}",
+            code
+        );
+        assert!(!code.contains("_ts_metadata"), "{}", code);
+    }
+
+    #[test]
+    fn test_legacy_decorators_emit_metadata() {
+        let code = run_decorators(DecoratorsConfig::Legacy, true);
+        assert!(
+            code.contains("_ts_metadata(\"design:type\", Function)"),
+            "{}",
+            code
+        );
+    }
+
+    #[test]
+    fn test_2022_03_decorators() {
+        let code = run_decorators(DecoratorsConfig::V202203, true);
+        assert!(code.contains("_apply_decs_2203_r(this, ["), "{}", code);
+        assert!(!code.contains("_ts_decorate"), "{}", code);
+        assert!(!code.contains("_ts_metadata"), "{}", code);
+    }
+
+    #[test]
+    fn test_preset_env_old_targets() {
+        let code = run_preset_env(PRESET_ENV_CODE, &[("chrome", 49.0)], None);
+        assert!(!code.contains("?."), "{}", code);
+        assert!(!code.contains("??"), "{}", code);
+    }
+
+    #[test]
+    fn test_preset_env_modern_targets() {
+        let code = run_preset_env(PRESET_ENV_CODE, &[("chrome", 100.0)], None);
+        assert!(code.contains("a?.b"), "{}", code);
+        assert!(code.contains("c ?? d"), "{}", code);
+        assert!(code.contains("foo = 1;"), "{}", code);
+    }
+
+    #[test]
+    fn test_polyfill_usage() {
+        let code = run_preset_env(
+            "Promise.allSettled([]);",
+            &[("chrome", 49.0)],
+            Some(PolyfillConfig::Usage),
+        );
+        assert!(
+            code.contains(r#"import "core-js/modules/es.promise.all-settled.js";"#),
+            "{}",
+            code
+        );
+        let code = run_preset_env(
+            "Promise.all([]);",
+            &[("chrome", 49.0)],
+            Some(PolyfillConfig::Usage),
+        );
+        assert!(!code.contains("es.promise.all-settled"), "{}", code);
+    }
+
+    #[test]
+    fn test_polyfill_entry() {
+        let code = run_preset_env(
+            r#"import "core-js";"#,
+            &[("chrome", 49.0)],
+            Some(PolyfillConfig::Entry),
+        );
+        assert!(code.contains("es.promise.all-settled"), "{}", code);
+        let code = run_preset_env(
+            r#"import "core-js";"#,
+            &[("chrome", 100.0)],
+            Some(PolyfillConfig::Entry),
+        );
+        assert!(!code.contains("es.promise.all-settled"), "{}", code);
+    }
+
+    #[test]
+    fn test_polyfill_disabled() {
+        let code = run_preset_env(r#"import "core-js";"#, &[("chrome", 49.0)], None);
+        assert_eq!(code.trim(), r#"import "core-js";"#);
+    }
+
+    const PRESET_ENV_CODE: &str = r#"
+const x = a?.b;
+const y = c ?? d;
+class Foo {
+    foo = 1;
+}
+    "#;
+
+    fn run_preset_env(
+        js_code: &str,
+        targets: &[(&str, f32)],
+        polyfill: Option<PolyfillConfig>,
+    ) -> String {
+        let mut test_utils = TestUtils::gen_js_ast(js_code);
+        let ast = test_utils.ast.js_mut();
+        let unresolved_mark = ast.unresolved_mark;
+        let targets = targets
+            .iter()
+            .map(|(k, v)| (k.to_string(), *v))
+            .collect::<HashMap<_, _>>();
+        GLOBALS.set(&test_utils.context.meta.script.globals, || {
+            let mut folders = vec![preset_env_folder(
+                unresolved_mark,
+                None::<SwcComments>,
+                &targets,
+                polyfill,
+                Assumptions::default(),
+            )];
+            let context = test_utils.context.clone();
+            let file = File::new("test.js".to_string(), context.clone());
+            ast.transform(&mut vec![], &mut folders, &file, false, context)
+                .unwrap();
+        });
+        test_utils.js_ast_to_code()
+    }
+
+    fn run_decorators(decorators: DecoratorsConfig, emit_metadata: bool) -> String {
+        let mut test_utils = TestUtils::gen_js_ast(
+            r#"
+class Foo {
+    @log
+    bar() {}
+}
+            "#,
+        );
+        let ast = test_utils.ast.js_mut();
+        GLOBALS.set(&test_utils.context.meta.script.globals, || {
+            let mut folders = vec![decorators_folder(&decorators, emit_metadata)];
+            let context = test_utils.context.clone();
+            let file = File::new("test.ts".to_string(), context.clone());
+            ast.transform(&mut vec![], &mut folders, &file, false, context)
+                .unwrap();
+        });
+        test_utils.js_ast_to_code()
+    }
+}
@@ -22,4 +22,10 @@ pub struct Cli {
                 })
     )]
     pub mode: Mode,
+    // print the shortest import path from entries through each importer of the module after build
+    #[arg(long)]
+    pub why: Option<PathBuf>,
+    // write the chrome trace of build phases to the file after build, implies profile
+    #[arg(long)]
+    pub trace: Option<PathBuf>,
 }
@@ -1,6 +1,6 @@
 use std::collections::HashMap;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex, RwLock};
 use std::time::Instant;
 
@@ -13,14 +13,18 @@ use swc_core::ecma::ast::Ident;
 use tracing::debug;
 
 use crate::ast::comments::Comments;
+use crate::build::cache::ModuleCache;
 use crate::config::{Config, OutputMode};
+use crate::features::clean::{EmittedFiles, OutputSnapshot};
 use crate::generate::chunk_graph::ChunkGraph;
 use crate::generate::optimize_chunk::OptimizeChunksInfo;
+use crate::module::{assign_module_ids, relative_to_root, ModuleId};
 use crate::module_graph::ModuleGraph;
 use crate::plugin::{Plugin, PluginDriver, PluginGenerateEndParams};
 use crate::plugins;
 use crate::resolve::{get_resolvers, Resolvers};
 use crate::stats::StatsInfo;
+use crate::utils::profiler::Profiler;
 use crate::utils::{thread_pool, ParseRegex};
 
 pub struct Context {
@@ -28,6 +32,8 @@ pub struct Context {
     pub chunk_graph: RwLock<ChunkGraph>,
     pub assets_info: Mutex<HashMap<String, String>>,
     pub modules_with_missing_deps: RwLock<Vec<String>>,
+    // ids assigned by assign_module_ids, i.e. the deterministic and numeric ones
+    pub module_ids: RwLock<HashMap<String, String>>,
     pub config: Config,
     pub args: Args,
     pub root: PathBuf,
@@ -37,6 +43,9 @@ pub struct Context {
     pub resolvers: Resolvers,
     pub static_cache: RwLock<MemoryChunkFileCache>,
     pub optimize_infos: Mutex<Option<Vec<OptimizeChunksInfo>>>,
+    pub module_cache: Option<ModuleCache>,
+    pub profiler: Profiler,
+    pub emitted_files: EmittedFiles,
 }
 
 #[derive(Default)]
@@ -83,7 +92,7 @@ impl MemoryChunkFileCache {
     }
 }
 
-#[derive(Default)]
+#[derive(Default, Clone)]
 pub struct Args {
     pub watch: bool,
 }
@@ -95,6 +104,10 @@ impl Context {
         content: Vec<u8>,
         hash: u64,
     ) -> Result<()> {
+        if self.config.write_to_disk {
+            self.emitted_files
+                .add(self.config.output.path.join(path.as_ref()));
+        }
         let mut map = self.static_cache.write().unwrap();
         map.write(path, content, hash)
     }
@@ -117,12 +130,16 @@ impl Default for Context {
             chunk_graph: RwLock::new(ChunkGraph::new()),
             assets_info: Mutex::new(HashMap::new()),
             modules_with_missing_deps: RwLock::new(Vec::new()),
+            module_ids: RwLock::new(HashMap::new()),
             meta: Meta::new(),
             plugin_driver: Default::default(),
             stats_info: StatsInfo::new(),
             resolvers,
             optimize_infos: Mutex::new(None),
             static_cache: Default::default(),
+            module_cache: None,
+            profiler: Default::default(),
+            emitted_files: Default::default(),
         }
     }
 }
@@ -203,6 +220,9 @@ impl Context {
 
 pub struct Compiler {
     pub context: Arc<Context>,
+    // compilers of config.buildTargets, which are compiled instead of this one,
+    // each target has its own module graph
+    targets: Vec<Compiler>,
 }
 
 impl Compiler {
@@ -216,6 +236,30 @@ impl Compiler {
             return Err(anyhow!("root path must be absolute"));
         }
 
+        // init thread pool before any parallel work, it's a no-op if already initialized
+        thread_pool::init_thread_pool(config.threads);
+
+        if !config.build_targets.is_empty() && args.watch {
+            return Err(anyhow!("buildTargets is not supported in watch mode"));
+        }
+        let targets = config
+            .build_targets
+            .iter()
+            .enumerate()
+            .map(|(index, target)| {
+                let target_config = config
+                    .build_target_config(target, &root)
+                    .map_err(|e| anyhow!("buildTargets[{}]: {}", index, e))?;
+                Compiler::new(
+                    target_config,
+                    root.clone(),
+                    args.clone(),
+                    extra_plugins.clone(),
+                )
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let mut config = config;
+
         // why add plugins before builtin plugins?
         // because plugins like less-loader need to be added before assets plugin
         // TODO: support plugin orders
@@ -227,9 +271,11 @@ impl Compiler {
             // features
             Arc::new(plugins::manifest::ManifestPlugin {}),
             Arc::new(plugins::copy::CopyPlugin {}),
+            Arc::new(plugins::html::HtmlPlugin::default()),
             Arc::new(plugins::import::ImportPlugin {}),
             // file types
             Arc::new(plugins::context_module::ContextModulePlugin {}),
+            Arc::new(plugins::import_meta_glob::ImportMetaGlobPlugin {}),
             Arc::new(plugins::runtime::MakoRuntime {}),
             Arc::new(plugins::invalid_webpack_syntax::InvalidWebpackSyntaxPlugin {}),
             Arc::new(plugins::hmr_runtime::HMRRuntimePlugin {}),
@@ -238,11 +284,10 @@ impl Compiler {
             Arc::new(plugins::emotion::EmotionPlugin {}),
             Arc::new(plugins::tree_shaking::FarmTreeShake {}),
             Arc::new(plugins::detect_circular_dependence::LoopDetector {}),
+            Arc::new(plugins::cjs_interop_warning::CjsInteropWarning {}),
         ];
         plugins.extend(builtin_plugins);
 
-        let mut config = config;
-
         if let Some(progress) = &config.progress {
             plugins.push(Arc::new(plugins::progress::ProgressPlugin::new(
                 plugins::progress::ProgressPluginOptions {
@@ -321,6 +366,8 @@ impl Compiler {
         plugin_driver.modify_config(&mut config, &root, &args)?;
 
         let resolvers = get_resolvers(&config);
+        let module_cache = ModuleCache::new(&root, &config, &args, &plugin_driver);
+        let profiler = Profiler::new(config.profile);
         Ok(Self {
             context: Arc::new(Context {
                 static_cache: if config.write_to_disk {
@@ -335,21 +382,40 @@ impl Compiler {
                 chunk_graph: RwLock::new(ChunkGraph::new()),
                 assets_info: Mutex::new(HashMap::new()),
                 modules_with_missing_deps: RwLock::new(Vec::new()),
+                module_ids: RwLock::new(HashMap::new()),
                 meta: Meta::new(),
                 plugin_driver,
                 stats_info: StatsInfo::new(),
                 resolvers,
                 optimize_infos: Mutex::new(None),
+                module_cache,
+                profiler,
+                emitted_files: Default::default(),
             }),
+            targets,
         })
     }
 
     pub fn compile(&self) -> Result<()> {
-        // 先清空 dist 目录
-        if self.context.config.clean {
-            self.clean_dist()?;
+        if !self.targets.is_empty() {
+            return self.compile_targets();
         }
 
+        // 先清空 dist 目录
+        // with output.clean, only the stale files are removed after generating
+        let output_snapshot = match &self.context.config.output.clean {
+            Some(clean) => Some(OutputSnapshot::new(
+                &self.context.config.output.path,
+                clean,
+            )?),
+            None => {
+                if self.context.config.clean {
+                    self.clean_dist()?;
+                }
+                None
+            }
+        };
+
         let t_compiler = Instant::now();
         let start_time = chrono::Local::now().timestamp_millis();
         let building_with_message = format!(
@@ -383,6 +449,11 @@ impl Compiler {
             self.context.plugin_driver.build_start(&self.context)?;
 
             self.build(files)?;
+            assign_module_ids(&self.context);
+
+            if let Some(module_cache) = &self.context.module_cache {
+                module_cache.flush()?;
+            }
 
             debug!("start after build");
 
@@ -402,6 +473,10 @@ impl Compiler {
         let t_compiler_duration = t_compiler.elapsed();
         match result {
             Ok(mut stats) => {
+                if let Some(snapshot) = &output_snapshot {
+                    let removed = snapshot.clean_stale(&self.context.emitted_files)?;
+                    debug!("stale files removed: {:?}", removed);
+                }
                 stats.start_time = start_time;
                 stats.end_time = chrono::Local::now().timestamp_millis();
                 println!(
@@ -415,6 +490,9 @@ impl Compiler {
                 if !self.context.args.watch {
                     println!("{}", "Complete!".bold());
                 }
+                if self.context.config.profile {
+                    self.context.profiler.print_summary();
+                }
                 let params = PluginGenerateEndParams {
                     is_first_compile: true,
                     time: t_compiler.elapsed().as_millis() as i64,
@@ -429,6 +507,65 @@ impl Compiler {
         }
     }
 
+    // the targets are built concurrently, each on its own thread instead of a pool thread,
+    // since the build tasks and the module cache flushing wait for the thread pool
+    fn compile_targets(&self) -> Result<()> {
+        std::thread::scope(|s| {
+            let handles = self
+                .targets
+                .iter()
+                .map(|target| s.spawn(|| target.compile()))
+                .collect::<Vec<_>>();
+            handles
+                .into_iter()
+                .map(|handle| handle.join().unwrap())
+                .collect::<Result<Vec<_>>>()
+        })?;
+        Ok(())
+    }
+
+    // the trace of each target is written next to the path, e.g. trace.0.json
+    pub fn write_trace(&self, path: &Path) -> Result<()> {
+        if self.targets.is_empty() {
+            return self.context.profiler.write_trace(path);
+        }
+        let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+        let ext = path.extension().unwrap_or_default().to_string_lossy();
+        for (index, target) in self.targets.iter().enumerate() {
+            let target_path = path.with_file_name(format!("{}.{}.{}", stem, index, ext));
+            target.context.profiler.write_trace(&target_path)?;
+        }
+        Ok(())
+    }
+
+    // import paths from entries to the module of path, relative to root,
+    // of all the targets with buildTargets
+    pub fn why(&self, path: &Path) -> Vec<String> {
+        if !self.targets.is_empty() {
+            let mut chains = self
+                .targets
+                .iter()
+                .flat_map(|target| target.why(path))
+                .collect::<Vec<_>>();
+            chains.sort();
+            chains.dedup();
+            return chains;
+        }
+        let module_id = ModuleId::from_path(self.context.root.join(path));
+        let module_graph = self.context.module_graph.read().unwrap();
+        module_graph
+            .dependency_chains(&module_id)
+            .iter()
+            .map(|chain| {
+                chain
+                    .iter()
+                    .map(|id| relative_to_root(&id.id, &self.context.root))
+                    .collect::<Vec<_>>()
+                    .join(" -> ")
+            })
+            .collect()
+    }
+
     pub fn full_hash(&self) -> u64 {
         crate::mako_profile_function!();
         let cg = self.context.chunk_graph.read().unwrap();
@@ -445,3 +582,34 @@ impl Compiler {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use crate::utils::test_helper::setup_compiler;
+
+    #[test]
+    fn test_build_targets() {
+        let compiler = setup_compiler("test/build/build-targets", false);
+        let root = compiler.context.root.clone();
+        compiler.compile().unwrap();
+        let browser = fs::read_to_string(root.join("dist/browser/index.js")).unwrap();
+        let node = fs::read_to_string(root.join("dist/node/index.js")).unwrap();
+        fs::remove_dir_all(root.join("dist")).unwrap();
+
+        // the config passed to the compiler, i.e. minify: None by setup_compiler,
+        // applies to the targets
+        // esm with the browser field
+        assert!(browser.contains("env is browser"), "{}", browser);
+        assert!(!browser.contains("env is node"));
+        assert!(browser.contains("shared is bundled"));
+        assert!(browser.contains("export {"), "{}", browser);
+        // cjs with the main field and the externals of the target
+        assert!(node.contains("env is node"), "{}", node);
+        assert!(!node.contains("env is browser"));
+        assert!(!node.contains("shared is bundled"));
+        assert!(node.contains(r#"require("shared-pkg")"#), "{}", node);
+        assert!(node.contains("module.exports = "), "{}", node);
+    }
+}
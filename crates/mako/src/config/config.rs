@@ -13,7 +13,9 @@ use swc_core::ecma::ast::EsVersion;
 use thiserror::Error;
 use {clap, config, thiserror};
 
+use crate::build::targets::targets_from_browserslist;
 use crate::features::node::Node;
+use crate::resolve::{get_external_regex_key, parse_tsconfig_paths};
 use crate::{plugins, visitors};
 
 #[derive(Debug, Diagnostic)]
@@ -87,11 +89,37 @@ macro_rules! create_deserialize_fn {
         }
     };
 }
+/**
+ * a macro like create_deserialize_fn, which also allows true value for the struct with
+ * default fields, e.g. `analyze: true` equals to `analyze: {}`
+ */
+macro_rules! create_deserialize_fn_allow_true {
+    ($fn_name:ident, $struct_type:ty) => {
+        pub fn $fn_name<'de, D>(deserializer: D) -> Result<Option<$struct_type>, D::Error>
+        where
+            D: Deserializer<'de>,
+        {
+            let value: serde_json::Value = serde_json::Value::deserialize(deserializer)?;
+            let value = match value {
+                serde_json::Value::Bool(true) => serde_json::Value::Object(Default::default()),
+                value => value,
+            };
+            create_deserialize_fn!($fn_name, $struct_type);
+            $fn_name(value).map_err(serde::de::Error::custom)
+        }
+    };
+}
 create_deserialize_fn!(deserialize_hmr, HmrConfig);
 create_deserialize_fn!(deserialize_dev_server, DevServerConfig);
+create_deserialize_fn_allow_true!(deserialize_history_api_fallback, HistoryApiFallbackConfig);
 create_deserialize_fn!(deserialize_manifest, ManifestConfig);
 create_deserialize_fn!(deserialize_code_splitting, CodeSplitting);
 create_deserialize_fn!(deserialize_px2rem, Px2RemConfig);
+create_deserialize_fn!(deserialize_autoprefixer, AutoprefixerConfig);
+create_deserialize_fn_allow_true!(deserialize_minify, MinifyConfig);
+create_deserialize_fn!(deserialize_mangle, MangleConfig);
+create_deserialize_fn!(deserialize_css_in_js, CssInJsConfig);
+create_deserialize_fn_allow_true!(deserialize_cache, CacheConfig);
 create_deserialize_fn!(deserialize_progress, ProgressConfig);
 create_deserialize_fn!(deserialize_umd, String);
 create_deserialize_fn!(deserialize_devtool, DevtoolConfig);
@@ -103,8 +131,38 @@ create_deserialize_fn!(deserialize_rsc_client, RscClientConfig);
 create_deserialize_fn!(deserialize_rsc_server, RscServerConfig);
 create_deserialize_fn!(deserialize_stats, StatsConfig);
 create_deserialize_fn!(deserialize_detect_loop, DetectCircularDependence);
+create_deserialize_fn!(deserialize_polyfill, PolyfillConfig);
+create_deserialize_fn_allow_true!(deserialize_analyze, AnalyzeConfig);
+create_deserialize_fn_allow_true!(deserialize_output_clean, OutputCleanConfig);
 
-#[derive(Deserialize, Serialize, Debug)]
+/**
+ * targets can be a map of browser versions, or browserslist queries
+ * which are resolved to the lowest version of each browser
+ */
+pub fn deserialize_targets<'de, D>(deserializer: D) -> Result<HashMap<String, f32>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let value: serde_json::Value = serde_json::Value::deserialize(deserializer)?;
+    let queries = match value {
+        serde_json::Value::Object(obj) => {
+            return serde_json::from_value(serde_json::Value::Object(obj))
+                .map_err(serde::de::Error::custom);
+        }
+        serde_json::Value::String(s) => vec![s],
+        serde_json::Value::Array(arr) => serde_json::from_value(serde_json::Value::Array(arr))
+            .map_err(serde::de::Error::custom)?,
+        _ => {
+            return Err(serde::de::Error::custom(format!(
+                "invalid `targets` value: {}",
+                value
+            )))
+        }
+    };
+    targets_from_browserslist(&queries).map_err(serde::de::Error::custom)
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct OutputConfig {
     pub path: PathBuf,
@@ -112,12 +170,35 @@ pub struct OutputConfig {
     pub es_version: EsVersion,
     pub meta: bool,
     pub chunk_loading_global: String,
+    // async chunks fail to load after the timeout in ms, and are retried with backoff
+    pub chunk_load_timeout: u64,
+    pub chunk_load_retries: u32,
     pub preserve_modules: bool,
     pub preserve_modules_root: PathBuf,
     pub skip_write: bool,
+    // e.g. [name].[contenthash:8].js
+    pub filename: Option<String>,
+    // e.g. static/[name].[hash:8].[ext], for the emitted images, fonts, etc.
+    pub asset_file_name: Option<String>,
+    // chunks are emitted as es modules instead of being loaded by the runtime
+    pub format: Option<OutputFormat>,
+    // removes the stale files of previous builds after generating, instead of
+    // removing the whole output dir before building
+    #[serde(deserialize_with = "deserialize_output_clean", default)]
+    pub clean: Option<OutputCleanConfig>,
+    // prepended and appended to the js and css files, e.g. a license comment
+    pub banner: Option<String>,
+    pub footer: Option<String>,
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug, Default)]
+pub struct OutputCleanConfig {
+    // globs relative to the output dir, e.g. "static/**"
+    #[serde(default)]
+    pub keep: Vec<String>,
 }
 
-#[derive(Deserialize, Serialize, Debug)]
+#[derive(Deserialize, Serialize, Debug, Clone)]
 pub struct ManifestConfig {
     #[serde(
         rename(deserialize = "fileName"),
@@ -128,10 +209,21 @@ pub struct ManifestConfig {
     pub base_path: String,
 }
 
-#[derive(Deserialize, Serialize, Debug)]
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
 pub struct ResolveConfig {
     pub alias: Vec<(String, String)>,
+    // tried in order for imports without extension
     pub extensions: Vec<String>,
+    // package.json fields for the entry of packages, defaults depend on the platform
+    pub main_fields: Option<Vec<String>>,
+    // specifiers which are built as modules throwing at runtime when they can't be resolved
+    pub optional_deps: Vec<String>,
+    // shims of Node.js builtin modules for browser builds, e.g. { "fs": "" } for an empty module
+    pub node_builtins: HashMap<String, String>,
+    // aliases from compilerOptions.paths of tsconfig.json
+    #[serde(skip)]
+    pub tsconfig_paths: Vec<(String, Vec<String>)>,
 }
 
 // format: HashMap<identifier, (import_source, specifier)>
@@ -156,7 +248,13 @@ pub enum OutputMode {
     Bundless,
 }
 
-#[derive(Deserialize, Serialize, Debug, PartialEq, Eq)]
+#[derive(Deserialize, Serialize, Debug, PartialEq, Eq, Clone, Copy)]
+pub enum OutputFormat {
+    #[serde(rename = "esm")]
+    Esm,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
 pub enum Platform {
     #[serde(rename = "browser")]
     Browser,
@@ -170,7 +268,7 @@ impl std::fmt::Display for Mode {
     }
 }
 
-#[derive(Deserialize, Serialize, Debug)]
+#[derive(Deserialize, Serialize, Debug, Clone)]
 pub enum DevtoolConfig {
     /// Generate separate sourcemap file
     #[serde(rename = "source-map")]
@@ -180,12 +278,18 @@ pub enum DevtoolConfig {
     InlineSourceMap,
 }
 
-#[derive(Deserialize, Serialize, Clone, Copy, Debug)]
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, PartialEq, Eq)]
 pub enum ModuleIdStrategy {
     #[serde(rename = "hashed")]
     Hashed,
     #[serde(rename = "named")]
     Named,
+    /// Short hash of the path relative to root, stable across machines and builds
+    #[serde(rename = "deterministic")]
+    Deterministic,
+    /// Incremental numbers, assigned in the order of the module paths
+    #[serde(rename = "numeric")]
+    Numeric,
 }
 
 #[derive(Deserialize, Serialize, Clone, Debug)]
@@ -196,6 +300,39 @@ pub struct CodeSplittingGranularOptions {
     pub lib_min_size: usize,
 }
 
+#[derive(Deserialize, Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct CodeSplittingAutoOptions {
+    // split node_modules into the vendors chunk
+    #[serde(default = "CodeSplittingAutoOptions::default_vendor")]
+    pub vendor: bool,
+    #[serde(default)]
+    pub allow_chunks: OptimizeAllowChunks,
+    // the minimum size of the vendors chunk
+    #[serde(default = "GenericUsizeDefault::<20000>::value")]
+    pub min_size: usize,
+    // modules shared by at least min_chunks chunks are split into the common chunk
+    #[serde(default = "GenericUsizeDefault::<2>::value")]
+    pub min_chunks: usize,
+}
+
+impl CodeSplittingAutoOptions {
+    fn default_vendor() -> bool {
+        true
+    }
+}
+
+impl Default for CodeSplittingAutoOptions {
+    fn default() -> Self {
+        CodeSplittingAutoOptions {
+            vendor: CodeSplittingAutoOptions::default_vendor(),
+            allow_chunks: OptimizeAllowChunks::default(),
+            min_size: GenericUsizeDefault::<20000>::value(),
+            min_chunks: GenericUsizeDefault::<2>::value(),
+        }
+    }
+}
+
 #[derive(Deserialize, Serialize, Clone, Debug)]
 pub struct StatsConfig {
     pub modules: bool,
@@ -219,6 +356,8 @@ pub enum CodeSplittingStrategy {
 pub enum CodeSplittingStrategyOptions {
     Granular(CodeSplittingGranularOptions),
     Advanced(CodeSplittingAdvancedOptions),
+    // must be the last one, since all of its fields are optional
+    Auto(CodeSplittingAutoOptions),
 }
 
 #[derive(Deserialize, Serialize, Clone, Debug)]
@@ -227,6 +366,25 @@ pub struct CodeSplitting {
     pub options: Option<CodeSplittingStrategyOptions>,
 }
 
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PolyfillConfig {
+    // inject core-js modules for the apis used in each module
+    #[serde(rename = "usage")]
+    Usage,
+    // expand `import "core-js"` to the modules required by the targets
+    #[serde(rename = "entry")]
+    Entry,
+}
+
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DecoratorsConfig {
+    // typescript experimentalDecorators
+    #[serde(rename = "legacy")]
+    Legacy,
+    #[serde(rename = "2022-03")]
+    V202203,
+}
+
 #[derive(Deserialize, Serialize, Clone, Copy, Debug)]
 pub enum TreeShakingStrategy {
     #[serde(rename = "basic")]
@@ -235,6 +393,63 @@ pub enum TreeShakingStrategy {
     Advanced,
 }
 
+#[derive(Deserialize, Serialize, Clone, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct MinifyConfig {
+    // removes the console.* calls, or only the listed methods, e.g. ["log", "debug"]
+    #[serde(default)]
+    pub drop_console: DropConsoleConfig,
+    #[serde(default)]
+    pub drop_debugger: bool,
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+#[serde(untagged)]
+pub enum DropConsoleConfig {
+    Bool(bool),
+    Methods(Vec<String>),
+}
+
+impl Default for DropConsoleConfig {
+    fn default() -> Self {
+        DropConsoleConfig::Bool(false)
+    }
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug, Default)]
+pub struct MangleConfig {
+    // names never to be mangled
+    #[serde(default)]
+    pub reserved: Vec<String>,
+    // mangle top level names too, which are kept by default
+    #[serde(default)]
+    pub toplevel: bool,
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug, Default)]
+pub struct CacheConfig {
+    // relative to root, default is node_modules/.cache_mako/modules
+    pub dir: Option<String>,
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct CssInJsConfig {
+    // e.g. [dirname]-[filename]-[local]
+    #[serde(default = "default_label_format")]
+    pub label_format: String,
+}
+
+fn default_label_format() -> String {
+    "[local]".to_string()
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug, Default)]
+pub struct AutoprefixerConfig {
+    // use the top level targets if not set
+    pub targets: Option<HashMap<String, f32>>,
+}
+
 #[derive(Deserialize, Serialize, Clone, Debug)]
 pub struct Px2RemConfig {
     #[serde(default = "visitors::css_px2rem::default_root")]
@@ -291,19 +506,19 @@ pub struct TransformImportConfig {
     pub style: Option<TransformImportStyle>,
 }
 
-#[derive(Deserialize, Serialize, Debug, Hash)]
+#[derive(Deserialize, Serialize, Debug, Clone, Hash)]
 pub enum ExternalAdvancedSubpathConverter {
     PascalCase,
 }
 
-#[derive(Deserialize, Serialize, Debug, Hash)]
+#[derive(Deserialize, Serialize, Debug, Clone, Hash)]
 #[serde(untagged)]
 pub enum ExternalAdvancedSubpathTarget {
     Empty,
     Tpl(String),
 }
 
-#[derive(Deserialize, Serialize, Debug, Hash)]
+#[derive(Deserialize, Serialize, Debug, Clone, Hash)]
 pub struct ExternalAdvancedSubpathRule {
     pub regex: String,
     #[serde(with = "external_target_format")]
@@ -345,13 +560,13 @@ mod external_target_format {
     }
 }
 
-#[derive(Deserialize, Serialize, Debug, Hash)]
+#[derive(Deserialize, Serialize, Debug, Clone, Hash)]
 pub struct ExternalAdvancedSubpath {
     pub exclude: Option<Vec<String>>,
     pub rules: Vec<ExternalAdvancedSubpathRule>,
 }
 
-#[derive(Deserialize, Serialize, Debug, Hash)]
+#[derive(Deserialize, Serialize, Debug, Clone, Hash)]
 pub struct ExternalAdvanced {
     pub root: String,
     #[serde(rename = "type")]
@@ -360,14 +575,34 @@ pub struct ExternalAdvanced {
     pub subpath: Option<ExternalAdvancedSubpath>,
 }
 
-#[derive(Deserialize, Serialize, Debug, Hash)]
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct BuildTargetConfig {
+    pub platform: Platform,
+    pub format: Option<BuildTargetFormat>,
+    // relative to root, e.g. dist/node
+    pub path: PathBuf,
+    // merged into the top level externals
+    #[serde(default)]
+    pub externals: HashMap<String, ExternalConfig>,
+}
+
+#[derive(Deserialize, Serialize, Debug, PartialEq, Eq, Clone, Copy)]
+pub enum BuildTargetFormat {
+    #[serde(rename = "esm")]
+    Esm,
+    #[serde(rename = "cjs")]
+    Cjs,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Hash)]
 #[serde(untagged)]
 pub enum ExternalConfig {
     Basic(String),
     Advanced(ExternalAdvanced),
 }
 
-#[derive(Deserialize, Serialize, Debug)]
+#[derive(Deserialize, Serialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct InjectItem {
     pub from: String,
@@ -386,7 +621,7 @@ pub enum ReactRuntimeConfig {
     Classic,
 }
 
-#[derive(Deserialize, Serialize, Debug)]
+#[derive(Deserialize, Serialize, Debug, Clone)]
 pub struct ReactConfig {
     pub pragma: String,
     #[serde(rename = "importSource")]
@@ -396,7 +631,7 @@ pub struct ReactConfig {
     pub pragma_frag: String,
 }
 
-#[derive(Deserialize, Serialize, Debug)]
+#[derive(Deserialize, Serialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct MinifishConfig {
     pub mapping: HashMap<String, String>,
@@ -404,17 +639,26 @@ pub struct MinifishConfig {
     pub inject: Option<HashMap<String, InjectItem>>,
 }
 
-#[derive(Deserialize, Serialize, Debug)]
+#[derive(Deserialize, Serialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct OptimizationConfig {
     pub skip_modules: Option<bool>,
     pub concatenate_modules: Option<bool>,
+    pub runtime_chunk: Option<RuntimeChunk>,
+    pub module_ids: Option<ModuleIdStrategy>,
+}
+
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RuntimeChunk {
+    // one runtime chunk shared by all entries
+    #[serde(rename = "single")]
+    Single,
 }
 
-#[derive(Deserialize, Serialize, Debug)]
+#[derive(Deserialize, Serialize, Debug, Clone)]
 pub struct InlineCssConfig {}
 
-#[derive(Deserialize, Serialize, Debug)]
+#[derive(Deserialize, Serialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct RscServerConfig {
     pub client_component_tpl: String,
@@ -430,20 +674,20 @@ pub enum LogServerComponent {
     Ignore,
 }
 
-#[derive(Deserialize, Serialize, Debug)]
+#[derive(Deserialize, Serialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct RscClientConfig {
     pub log_server_component: LogServerComponent,
 }
 
-#[derive(Deserialize, Serialize, Debug)]
+#[derive(Deserialize, Serialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct DetectCircularDependence {
     pub ignores: Vec<String>,
     pub graphviz: bool,
 }
 
-#[derive(Deserialize, Serialize, Debug)]
+#[derive(Deserialize, Serialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct ExperimentalConfig {
     pub webpack_syntax_validate: Vec<String>,
@@ -452,26 +696,70 @@ pub struct ExperimentalConfig {
     pub detect_circular_dependence: Option<DetectCircularDependence>,
 }
 
-#[derive(Deserialize, Serialize, Debug)]
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct WarningsConfig {
+    // warn about default imports of CommonJS modules without __esModule
+    pub cjs_interop: bool,
+    // warn about circular dependencies, see experimental.detectCircularDependence for the options
+    pub circular_dependencies: bool,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct WatchConfig {
     pub ignore_paths: Option<Vec<String>>,
+    // globs relative to the root, paths in the matched dirs are ignored too
+    pub ignored: Vec<String>,
+    // events within the ms are coalesced into one rebuild
+    pub debounce: u64,
     #[serde(rename = "_nodeModulesRegexes")]
     pub node_modules_regexes: Option<Vec<String>>,
 }
 
-#[derive(Deserialize, Serialize, Debug)]
+#[derive(Deserialize, Serialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct HmrConfig {}
 
-#[derive(Deserialize, Serialize, Debug)]
+#[derive(Deserialize, Serialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct DevServerConfig {
     pub host: String,
     pub port: u16,
+    // path prefix => proxy options, e.g. { "/api": { "target": "http://localhost:8080" } }
+    #[serde(default)]
+    pub proxy: HashMap<String, ProxyConfig>,
+    #[serde(deserialize_with = "deserialize_history_api_fallback", default)]
+    pub history_api_fallback: Option<HistoryApiFallbackConfig>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct HistoryApiFallbackConfig {
+    #[serde(default)]
+    pub rewrites: Vec<HistoryApiFallbackRewrite>,
 }
 
-#[derive(Deserialize, Serialize, Debug)]
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct HistoryApiFallbackRewrite {
+    // regex
+    pub from: String,
+    pub to: String,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ProxyConfig {
+    pub target: String,
+    #[serde(default)]
+    pub change_origin: bool,
+    // regex => replacement, e.g. { "^/api": "" }
+    #[serde(default)]
+    pub path_rewrite: HashMap<String, String>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct Config {
     pub entry: HashMap<String, PathBuf>,
@@ -480,7 +768,10 @@ pub struct Config {
     #[serde(deserialize_with = "deserialize_manifest", default)]
     pub manifest: Option<ManifestConfig>,
     pub mode: Mode,
-    pub minify: bool,
+    #[serde(deserialize_with = "deserialize_minify", default)]
+    pub minify: Option<MinifyConfig>,
+    #[serde(deserialize_with = "deserialize_mangle", default)]
+    pub mangle: Option<MangleConfig>,
     #[serde(deserialize_with = "deserialize_devtool")]
     pub devtool: Option<DevtoolConfig>,
     pub externals: HashMap<String, ExternalConfig>,
@@ -488,10 +779,14 @@ pub struct Config {
     pub copy: Vec<String>,
     pub public_path: String,
     pub inline_limit: usize,
+    #[serde(deserialize_with = "deserialize_targets")]
     pub targets: HashMap<String, f32>,
+    #[serde(deserialize_with = "deserialize_polyfill", default)]
+    pub polyfill: Option<PolyfillConfig>,
     pub platform: Platform,
     pub module_id_strategy: ModuleIdStrategy,
     pub define: HashMap<String, Value>,
+    #[serde(deserialize_with = "deserialize_analyze", default)]
     pub analyze: Option<AnalyzeConfig>,
     pub stats: Option<StatsConfig>,
     pub mdx: bool,
@@ -503,6 +798,8 @@ pub struct Config {
     pub code_splitting: Option<CodeSplitting>,
     #[serde(deserialize_with = "deserialize_px2rem", default)]
     pub px2rem: Option<Px2RemConfig>,
+    #[serde(deserialize_with = "deserialize_autoprefixer", default)]
+    pub autoprefixer: Option<AutoprefixerConfig>,
     #[serde(deserialize_with = "deserialize_progress", default)]
     pub progress: Option<ProgressConfig>,
     pub hash: bool,
@@ -516,10 +813,15 @@ pub struct Config {
     #[serde(deserialize_with = "deserialize_umd", default)]
     pub umd: Option<String>,
     pub cjs: bool,
+    // builds for several platforms at once, each with its own compiler and output dir
+    #[serde(default)]
+    pub build_targets: Vec<BuildTargetConfig>,
     pub write_to_disk: bool,
     pub transform_import: Vec<TransformImportConfig>,
     pub chunk_parallel: bool,
     pub clean: bool,
+    #[serde(deserialize_with = "deserialize_cache", default)]
+    pub cache: Option<CacheConfig>,
     pub node_polyfill: bool,
     pub ignores: Vec<String>,
     #[serde(
@@ -531,11 +833,14 @@ pub struct Config {
     #[serde(rename = "optimizePackageImports")]
     pub optimize_package_imports: bool,
     pub emotion: bool,
+    #[serde(deserialize_with = "deserialize_css_in_js", default)]
+    pub css_in_js: Option<CssInJsConfig>,
     pub flex_bugs: bool,
     #[serde(deserialize_with = "deserialize_optimization")]
     pub optimization: Option<OptimizationConfig>,
     pub react: ReactConfig,
     pub emit_assets: bool,
+    pub svgr: bool,
     #[serde(rename = "cssModulesExportOnlyLocales")]
     pub css_modules_export_only_locales: bool,
     #[serde(
@@ -557,9 +862,13 @@ pub struct Config {
     )]
     pub rsc_client: Option<RscClientConfig>,
     pub experimental: ExperimentalConfig,
+    pub warnings: WarningsConfig,
     pub watch: WatchConfig,
     pub use_define_for_class_fields: bool,
+    pub decorators: DecoratorsConfig,
     pub emit_decorator_metadata: bool,
+    pub threads: Option<usize>,
+    pub profile: bool,
 }
 
 #[derive(Deserialize, Serialize, Clone, Debug, Default)]
@@ -573,23 +882,19 @@ pub enum OptimizeAllowChunks {
     Async,
 }
 
-#[derive(Deserialize, Serialize, Clone, Debug)]
+#[derive(Deserialize, Serialize, Clone, Debug, Default)]
 #[serde(rename_all = "camelCase")]
 pub struct CodeSplittingAdvancedOptions {
-    #[serde(default = "GenericUsizeDefault::<20000>::value")]
+    // async chunks smaller than min_size are merged into the chunk which imports them,
+    // 0 to disable
+    #[serde(default)]
     pub min_size: usize,
+    // async chunks larger than max_size are split at module boundaries
+    #[serde(default)]
+    pub max_size: Option<usize>,
     pub groups: Vec<OptimizeChunkGroup>,
 }
 
-impl Default for CodeSplittingAdvancedOptions {
-    fn default() -> Self {
-        CodeSplittingAdvancedOptions {
-            min_size: GenericUsizeDefault::<20000>::value(),
-            groups: vec![],
-        }
-    }
-}
-
 #[derive(Deserialize, Serialize, Clone, Debug)]
 pub enum OptimizeChunkNameSuffixStrategy {
     #[serde(rename = "packageName")]
@@ -679,13 +984,16 @@ const DEFAULT_CONFIG: &str = r#"
       "esVersion": "es2022",
       "meta": false,
       "chunkLoadingGlobal": "",
+      "chunkLoadTimeout": 120000,
+      "chunkLoadRetries": 0,
       "preserveModules": false,
       "preserveModulesRoot": "",
       "skipWrite": false
     },
-    "resolve": { "alias": [], "extensions": ["js", "jsx", "ts", "tsx"] },
+    "resolve": { "alias": [], "extensions": [".js", ".jsx", ".ts", ".tsx", ".mjs", ".cjs", ".json"], "optionalDeps": [], "nodeBuiltins": {} },
     "mode": "development",
     "minify": true,
+    "mangle": {},
     "devtool": "source-map",
     "externals": {},
     "copy": ["public"],
@@ -693,6 +1001,7 @@ const DEFAULT_CONFIG: &str = r#"
     "publicPath": "/",
     "inlineLimit": 10000,
     "targets": { "chrome": 80 },
+    "polyfill": "entry",
     "less": { "theme": {}, "lesscPath": "", javascriptEnabled: true },
     "define": {},
     "mdx": false,
@@ -708,12 +1017,16 @@ const DEFAULT_CONFIG: &str = r#"
     "transformImport": [],
     "chunkParallel": true,
     "clean": true,
+    "cache": false,
     "nodePolyfill": true,
     "ignores": [],
     "optimizePackageImports": false,
     "emotion": false,
+    "cssInJs": false,
     "flexBugs": false,
+    "autoprefixer": {},
     "cjs": false,
+    "buildTargets": [],
     "optimization": { "skipModules": true, "concatenateModules": true },
     "react": {
       "pragma": "React.createElement",
@@ -725,6 +1038,7 @@ const DEFAULT_CONFIG: &str = r#"
       "progressChars": "▨▨"
     },
     "emitAssets": true,
+    "svgr": true,
     "cssModulesExportOnlyLocales": false,
     "inlineCSS": false,
     "rscServer": false,
@@ -734,9 +1048,12 @@ const DEFAULT_CONFIG: &str = r#"
       "requireContext": true,
       "detectCircularDependence": { "ignores": ["node_modules"], "graphviz": false }
     },
+    "warnings": { "cjsInterop": false, "circularDependencies": false },
     "useDefineForClassFields": true,
+    "decorators": "legacy",
     "emitDecoratorMetadata": false,
-    "watch": { "ignorePaths": [], "_nodeModulesRegexes": [] },
+    "profile": false,
+    "watch": { "ignorePaths": [], "ignored": ["**/node_modules", "**/.git", "dist"], "_nodeModulesRegexes": [], "debounce": 10 },
     "devServer": { "host": "127.0.0.1", "port": 3000 }
 }
 "#;
@@ -747,39 +1064,121 @@ impl Config {
         default_config: Option<&str>,
         cli_config: Option<&str>,
     ) -> Result<Self> {
+        let config = Self::load(root, default_config, cli_config)?;
+        for (index, target) in config.build_targets.iter().enumerate() {
+            config
+                .build_target_config(target, root)
+                .map_err(|e| anyhow!("buildTargets[{}]: {}", index, e))?;
+        }
+        Ok(config)
+    }
+
+    // the config of a build target, i.e. this config overridden by the target,
+    // so the changes made to this config after Config::new apply to the targets too
+    // checks of the options which are overridden by build targets
+    fn validate_output(&self) -> Result<()> {
+        if self.cjs && self.umd.is_some() {
+            return Err(anyhow!("cjs and umd cannot be used at the same time",));
+        }
+
+        if self.runtime_chunk().is_some()
+            && (self.umd.is_some() || self.cjs || !matches!(self.platform, Platform::Browser))
+        {
+            return Err(anyhow!(
+                "optimization.runtimeChunk can only be used with browser platform, without umd and cjs",
+            ));
+        }
+
+        if self.output.format == Some(OutputFormat::Esm)
+            && (self.umd.is_some()
+                || self.cjs
+                || self.runtime_chunk().is_some()
+                || self.output.mode == OutputMode::Bundless
+                || self.public_path == "runtime")
+        {
+            return Err(anyhow!(
+                "output.format esm can not be used with umd, cjs, optimization.runtimeChunk, bundless mode or runtime publicPath",
+            ));
+        }
+        Ok(())
+    }
+
+    pub fn build_target_config(&self, target: &BuildTargetConfig, root: &Path) -> Result<Self> {
+        let mut config = self.clone();
+        config.platform = target.platform.clone();
+        config.cjs = target.format == Some(BuildTargetFormat::Cjs);
+        config.output.path = root.join(&target.path);
+        config.output.format =
+            (target.format == Some(BuildTargetFormat::Esm)).then_some(OutputFormat::Esm);
+        config.externals.extend(target.externals.clone());
+        config.build_targets = vec![];
+        config.validate_output()?;
+        Ok(config)
+    }
+
+    fn load(root: &Path, default_config: Option<&str>, cli_config: Option<&str>) -> Result<Self> {
         let abs_config_file = root.join(CONFIG_FILE);
         let abs_config_file = abs_config_file.to_str().unwrap();
-        let c = config::Config::builder();
-        // default config
-        let c = c.add_source(config::File::from_str(
-            DEFAULT_CONFIG,
-            config::FileFormat::Json5,
-        ));
-        // default config from args
-        let c = if let Some(default_config) = default_config {
-            c.add_source(config::File::from_str(
-                default_config,
-                config::FileFormat::Json5,
-            ))
-        } else {
-            c
-        };
         // validate user config
         validate_mako_config(abs_config_file.to_string())
             .map_err(|e| anyhow!("{}", format!("{:?}", e)))?;
-        // user config
-        let c = c.add_source(config::File::with_name(abs_config_file).required(false));
-        // cli config
-        let c = if let Some(cli_config) = cli_config {
-            c.add_source(config::File::from_str(
-                cli_config,
+        // the mode is decided by all the sources, and then the sources for the mode
+        // are merged, i.e. the mode defaults after the default config,
+        // and env.development or env.production after the user config
+        let build = |mode_sources: Option<(&str, String)>| {
+            let c = config::Config::builder();
+            // default config
+            let c = c.add_source(config::File::from_str(
+                DEFAULT_CONFIG,
                 config::FileFormat::Json5,
-            ))
-        } else {
-            c
+            ));
+            let c = if let Some((mode_default_config, _)) = &mode_sources {
+                c.add_source(config::File::from_str(
+                    mode_default_config,
+                    config::FileFormat::Json5,
+                ))
+            } else {
+                c
+            };
+            // default config from args
+            let c = if let Some(default_config) = default_config {
+                c.add_source(config::File::from_str(
+                    default_config,
+                    config::FileFormat::Json5,
+                ))
+            } else {
+                c
+            };
+            // user config
+            let c = c.add_source(config::File::with_name(abs_config_file).required(false));
+            let c = if let Some((_, env_config)) = &mode_sources {
+                c.add_source(config::File::from_str(env_config, config::FileFormat::Json))
+            } else {
+                c
+            };
+            // cli config
+            let c = if let Some(cli_config) = cli_config {
+                c.add_source(config::File::from_str(
+                    cli_config,
+                    config::FileFormat::Json5,
+                ))
+            } else {
+                c
+            };
+            c.build()
         };
 
-        let c = c.build()?;
+        let c = build(None)?;
+        let mode = c.get::<Mode>("mode")?;
+        let env_config = c
+            .get::<Option<Value>>(&format!("env.{}", mode))
+            .ok()
+            .flatten()
+            .unwrap_or(Value::Object(Default::default()));
+        let c = build(Some((
+            mode_default_config(&mode),
+            serde_json::to_string(&env_config)?,
+        )))?;
         let mut ret = c.try_deserialize::<Config>();
         // normalize & check
         if let Ok(config) = &mut ret {
@@ -807,9 +1206,7 @@ impl Config {
                 }
             }
 
-            if config.cjs && config.umd.is_some() {
-                return Err(anyhow!("cjs and umd cannot be used at the same time",));
-            }
+            config.validate_output()?;
 
             if config.hmr.is_some() && config.dev_server.is_none() {
                 return Err(anyhow!("hmr can only be used with devServer",));
@@ -819,13 +1216,71 @@ impl Config {
                 return Err(anyhow!("inlineCSS can only be used with umd",));
             }
 
+            config.entry = expand_glob_entries(&config.entry, root)?;
+
+            if config.runtime_chunk().is_some() && config.entry.contains_key("runtime") {
+                return Err(anyhow!(
+                    "entry name runtime is reserved for optimization.runtimeChunk",
+                ));
+            }
+
+            if let Some(dev_server) = &config.dev_server {
+                for (path, proxy) in dev_server.proxy.iter() {
+                    if proxy.target.parse::<hyper::Uri>().is_err() {
+                        return Err(anyhow!(
+                            "devServer.proxy[\"{}\"].target is not a valid url: {}",
+                            path,
+                            proxy.target
+                        ));
+                    }
+                    for pattern in proxy.path_rewrite.keys() {
+                        if Regex::new(pattern).is_err() {
+                            return Err(anyhow!(
+                                "devServer.proxy[\"{}\"].pathRewrite has invalid regex: {}",
+                                path,
+                                pattern
+                            ));
+                        }
+                    }
+                }
+                if let Some(history_api_fallback) = &dev_server.history_api_fallback {
+                    for rewrite in history_api_fallback.rewrites.iter() {
+                        if Regex::new(&rewrite.from).is_err() {
+                            return Err(anyhow!(
+                                "devServer.historyApiFallback.rewrites has invalid regex: {}",
+                                rewrite.from
+                            ));
+                        }
+                        if rewrite.to.parse::<hyper::Uri>().is_err() {
+                            return Err(anyhow!(
+                                "devServer.historyApiFallback.rewrites has invalid path: {}",
+                                rewrite.to
+                            ));
+                        }
+                    }
+                }
+            }
+
+            for key in config.externals.keys() {
+                if let Some(re) = get_external_regex_key(key)
+                    && Regex::new(re).is_err()
+                {
+                    return Err(anyhow!("externals has invalid regex: {}", key));
+                }
+            }
+
             let mode = format!("\"{}\"", config.mode);
             config
                 .define
                 .insert("NODE_ENV".to_string(), serde_json::Value::String(mode));
 
-            if config.public_path != "runtime" && !config.public_path.ends_with('/') {
-                return Err(anyhow!("public_path must end with '/' or be 'runtime'"));
+            if config.public_path != "runtime"
+                && config.public_path != "auto"
+                && !config.public_path.ends_with('/')
+            {
+                return Err(anyhow!(
+                    "public_path must end with '/' or be 'runtime' or 'auto'"
+                ));
             }
 
             // 暂不支持 remote external
@@ -892,6 +1347,30 @@ impl Config {
                     (k, v)
                 })
                 .collect();
+            // tsconfig.json may extend a file which is not generated yet, so don't fail the build
+            config.resolve.tsconfig_paths = parse_tsconfig_paths(root).unwrap_or_else(|e| {
+                println!(
+                    "{}: tsconfig paths are ignored, {}",
+                    "warning".to_string().yellow(),
+                    e
+                );
+                vec![]
+            });
+
+            // optimization.moduleIds takes precedence over moduleIdStrategy
+            if let Some(module_ids) = config.optimization.as_ref().and_then(|o| o.module_ids) {
+                config.module_id_strategy = module_ids;
+            }
+
+            // [contenthash] in output.filename enables hash
+            if config
+                .output
+                .filename
+                .as_ref()
+                .is_some_and(|f| f.contains("[contenthash"))
+            {
+                config.hash = true;
+            }
 
             // dev 环境下不产生 hash, prod 环境下根据用户配置
             if config.mode == Mode::Development {
@@ -903,6 +1382,10 @@ impl Config {
         }
         ret.map_err(|e| anyhow!("{}: {}", "config error".red(), e.to_string().red()))
     }
+
+    pub fn runtime_chunk(&self) -> Option<RuntimeChunk> {
+        self.optimization.as_ref().and_then(|o| o.runtime_chunk)
+    }
 }
 
 impl Default for Config {
@@ -917,6 +1400,72 @@ impl Default for Config {
     }
 }
 
+// entries like { "[name]": "./src/pages/*.ts" } are expanded to an entry per matched file,
+// [name] is replaced by the matched path relative to the dir before the first glob segment,
+// without the extension, e.g. src/pages/about/index.ts is named about/index
+fn expand_glob_entries(
+    entry: &HashMap<String, PathBuf>,
+    root: &Path,
+) -> Result<HashMap<String, PathBuf>> {
+    let is_glob = |s: &str| s.contains(['*', '?', '[']);
+    let mut expanded = HashMap::new();
+    let mut insert = |name: String, path: PathBuf| {
+        if expanded.insert(name.clone(), path).is_some() {
+            return Err(anyhow!("entry:{} is duplicated", name));
+        }
+        Ok(())
+    };
+    for (key, value) in entry {
+        let value_str = value.to_string_lossy();
+        // existing files are literal paths, e.g. ./src/pages/[id].tsx of file-based routing
+        if !is_glob(&value_str) || root.join(value).exists() {
+            insert(key.clone(), value.clone())?;
+            continue;
+        }
+        if !key.contains("[name]") {
+            return Err(anyhow!(
+                "entry:{} is a glob pattern, its name must contain [name]",
+                key
+            ));
+        }
+        let pattern = value_str.trim_start_matches("./");
+        let base = root.join(
+            pattern
+                .split('/')
+                .take_while(|segment| !is_glob(segment))
+                .collect::<Vec<_>>()
+                .join("/"),
+        );
+        let mut matched = false;
+        for path in glob::glob(&root.join(pattern).to_string_lossy())? {
+            let path = path?;
+            if !path.is_file() {
+                continue;
+            }
+            let relative = path
+                .strip_prefix(&base)?
+                .with_extension("")
+                .to_string_lossy()
+                .replace('\\', "/");
+            insert(key.replace("[name]", &relative), path)?;
+            matched = true;
+        }
+        if !matched {
+            return Err(anyhow!("entry:{} matches no files: {}", key, value_str));
+        }
+    }
+    Ok(expanded)
+}
+
+// defaults which differ between modes, and are overridden by the user config
+fn mode_default_config(mode: &Mode) -> &'static str {
+    match mode {
+        // inline source maps in development, no .map files are written on every rebuild
+        Mode::Development => r#"{ "minify": false, "devtool": "inline-source-map" }"#,
+        Mode::Production => r#"{ "minify": true, "devtool": "source-map" }"#,
+    }
+}
+
 pub(crate) fn get_pkg_name(root: &Path) -> Option<String> {
     let pkg_json_path = root.join("package.json");
 
@@ -954,8 +1503,14 @@ impl<const U: usize> GenericUsizeDefault<U> {
 
 #[cfg(test)]
 mod tests {
+    use std::collections::HashMap;
+    use std::fs;
+
     use crate::config::config::GenericUsizeDefault;
-    use crate::config::{Config, Mode, Platform};
+    use crate::config::{
+        Config, DevtoolConfig, DropConsoleConfig, Mode, ModuleIdStrategy, Platform, RuntimeChunk,
+    };
+    use crate::utils::test_helper::setup_compiler;
 
     #[test]
     fn test_config() {
@@ -1007,7 +1562,7 @@ mod tests {
     }
 
     #[test]
-    #[should_panic(expected = "public_path must end with '/' or be 'runtime'")]
+    #[should_panic(expected = "public_path must end with '/' or be 'runtime' or 'auto'")]
     fn test_config_invalid_public_path() {
         let current_dir = std::env::current_dir().unwrap();
         Config::new(
@@ -1018,6 +1573,260 @@ mod tests {
         .unwrap();
     }
 
+    #[test]
+    fn test_glob_entries() {
+        let compiler = setup_compiler("test/build/glob-entry", false);
+        let root = compiler.context.root.clone();
+        let mut names = compiler
+            .context
+            .config
+            .entry
+            .keys()
+            .cloned()
+            .collect::<Vec<_>>();
+        names.sort();
+        assert_eq!(names, vec!["about", "index", "login", "main"]);
+        assert_eq!(
+            compiler.context.config.entry.get("about").unwrap(),
+            &root.join("src/pages/about.ts").canonicalize().unwrap()
+        );
+
+        compiler.compile().unwrap();
+        let dist = compiler.context.config.output.path.clone();
+        for name in ["about", "index", "login"] {
+            let js = fs::read_to_string(dist.join(format!("{}.js", name))).unwrap();
+            assert!(js.contains(&format!("page {}", name)));
+        }
+        fs::remove_dir_all(&dist).unwrap();
+    }
+
+    #[test]
+    fn test_glob_entries_bracketed_file_name() {
+        let current_dir = std::env::current_dir().unwrap();
+        let root = current_dir.join("test/build/glob-entry");
+        let config = Config::new(
+            &root,
+            None,
+            Some(r#"{"entry":{"detail":"./src/routes/[id].ts"}}"#),
+        )
+        .unwrap();
+        assert_eq!(
+            config.entry.get("detail").unwrap(),
+            &root.join("src/routes/[id].ts")
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "entry:pages is a glob pattern, its name must contain [name]")]
+    fn test_glob_entries_without_name() {
+        let current_dir = std::env::current_dir().unwrap();
+        Config::new(
+            &current_dir.join("test/build/glob-entry"),
+            None,
+            Some(r#"{"entry":{"pages":"./src/pages/*.ts"}}"#),
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_minify_config() {
+        let current_dir = std::env::current_dir().unwrap();
+        let config = |minify: &str| {
+            Config::new(
+                &current_dir.join("test/config/normal"),
+                None,
+                Some(&format!(r#"{{"minify":{}}}"#, minify)),
+            )
+            .unwrap()
+            .minify
+        };
+        assert!(config("false").is_none());
+        let minify = config("true").unwrap();
+        assert!(matches!(
+            minify.drop_console,
+            DropConsoleConfig::Bool(false)
+        ));
+        assert!(!minify.drop_debugger);
+        let minify = config(r#"{"dropConsole":["log"],"dropDebugger":true}"#).unwrap();
+        assert!(
+            matches!(minify.drop_console, DropConsoleConfig::Methods(methods) if methods == vec!["log"])
+        );
+        assert!(minify.drop_debugger);
+    }
+
+    #[test]
+    fn test_mode_default_devtool() {
+        let root = std::env::current_dir().unwrap().join("test/config/normal");
+        let devtool =
+            |cli_config: &str| Config::new(&root, None, Some(cli_config)).unwrap().devtool;
+        assert!(matches!(
+            devtool(r#"{"mode":"development"}"#),
+            Some(DevtoolConfig::InlineSourceMap)
+        ));
+        assert!(matches!(
+            devtool(r#"{"mode":"production"}"#),
+            Some(DevtoolConfig::SourceMap)
+        ));
+        // overridden by the user config
+        assert!(matches!(
+            devtool(r#"{"mode":"development","devtool":"source-map"}"#),
+            Some(DevtoolConfig::SourceMap)
+        ));
+        assert!(devtool(r#"{"mode":"development","devtool":false}"#).is_none());
+    }
+
+    #[test]
+    fn test_env_config() {
+        let root = std::env::current_dir().unwrap().join("test/config/env");
+        let config = |cli_config: &str| Config::new(&root, None, Some(cli_config)).unwrap();
+
+        let config_dev = config(r#"{"mode":"development"}"#);
+        assert!(matches!(
+            config_dev.devtool,
+            Some(DevtoolConfig::InlineSourceMap)
+        ));
+        assert_eq!(config_dev.public_path, "/");
+        assert_eq!(config_dev.copy, vec!["public", "assets"]);
+        assert!(config_dev.minify.is_none());
+        assert_eq!(
+            config_dev.define.get("NODE_ENV").unwrap(),
+            "\"development\""
+        );
+
+        let config_prod = config(r#"{"mode":"production"}"#);
+        assert!(matches!(
+            config_prod.devtool,
+            Some(DevtoolConfig::SourceMap)
+        ));
+        assert_eq!(config_prod.public_path, "https://cdn.example.com/");
+        // arrays are replaced, and objects are merged
+        assert_eq!(config_prod.copy, vec!["public"]);
+        let px2rem = config_prod.px2rem.unwrap();
+        assert_eq!(px2rem.root, 50.0);
+        assert_eq!(px2rem.prop_blacklist, vec!["border"]);
+        assert!(config_prod.minify.is_some());
+        assert_eq!(
+            config_prod.define.get("NODE_ENV").unwrap(),
+            "\"production\""
+        );
+    }
+
+    #[test]
+    fn test_env_config_overridden() {
+        let root = std::env::current_dir().unwrap().join("test/config/env");
+        let config = Config::new(
+            &root,
+            Some(r#"{"minify":{"dropConsole":true}}"#),
+            Some(r#"{"mode":"production","publicPath":"/static/","minify":false}"#),
+        )
+        .unwrap();
+        // explicit values override the env config and the mode defaults
+        assert_eq!(config.public_path, "/static/");
+        assert!(config.minify.is_none());
+
+        let config = Config::new(
+            &root,
+            Some(r#"{"minify":{"dropConsole":true}}"#),
+            Some(r#"{"mode":"development"}"#),
+        )
+        .unwrap();
+        assert!(matches!(
+            config.minify.unwrap().drop_console,
+            DropConsoleConfig::Bool(true)
+        ));
+    }
+
+    #[test]
+    fn test_runtime_chunk() {
+        let current_dir = std::env::current_dir().unwrap();
+        let config = Config::new(
+            &current_dir.join("test/config/normal"),
+            None,
+            Some(r#"{"platform":"browser","optimization":{"runtimeChunk":"single"}}"#),
+        )
+        .unwrap();
+        assert_eq!(config.runtime_chunk(), Some(RuntimeChunk::Single));
+        // other optimization options are kept
+        assert_eq!(config.optimization.unwrap().skip_modules, Some(true));
+    }
+
+    #[test]
+    fn test_optimization_module_ids() {
+        let current_dir = std::env::current_dir().unwrap();
+        let config = Config::new(
+            &current_dir.join("test/config/normal"),
+            None,
+            Some(r#"{"moduleIdStrategy":"hashed","optimization":{"moduleIds":"deterministic"}}"#),
+        )
+        .unwrap();
+        assert_eq!(config.module_id_strategy, ModuleIdStrategy::Deterministic);
+        let config = Config::new(
+            &current_dir.join("test/config/normal"),
+            None,
+            Some(r#"{"moduleIdStrategy":"hashed"}"#),
+        )
+        .unwrap();
+        assert_eq!(config.module_id_strategy, ModuleIdStrategy::Hashed);
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "optimization.runtimeChunk can only be used with browser platform, without umd and cjs"
+    )]
+    fn test_runtime_chunk_with_umd() {
+        let current_dir = std::env::current_dir().unwrap();
+        Config::new(
+            &current_dir.join("test/config/normal"),
+            None,
+            Some(r#"{"umd":"foo","optimization":{"runtimeChunk":"single"}}"#),
+        )
+        .unwrap();
+    }
+
+    #[test]
+    #[should_panic(expected = "output.format esm can not be used with umd")]
+    fn test_esm_format_with_umd() {
+        let current_dir = std::env::current_dir().unwrap();
+        Config::new(
+            &current_dir.join("test/config/normal"),
+            None,
+            Some(r#"{"umd":"foo","output":{"format":"esm"}}"#),
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_dev_server_history_api_fallback() {
+        let current_dir = std::env::current_dir().unwrap();
+        let config = Config::new(
+            &current_dir.join("test/config/normal"),
+            None,
+            Some(r#"{"devServer":{"historyApiFallback":true}}"#),
+        )
+        .unwrap();
+        let dev_server = config.dev_server.unwrap();
+        assert!(dev_server.history_api_fallback.unwrap().rewrites.is_empty());
+        assert!(dev_server.proxy.is_empty());
+
+        // true is only allowed for the keys with default fields
+        let err = Config::new(
+            &current_dir.join("test/config/normal"),
+            None,
+            Some(r#"{"devServer":true}"#),
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("invalid `dev_server` value"));
+        let err = Config::new(
+            &current_dir.join("test/config/normal"),
+            None,
+            Some(
+                r#"{"devServer":{"historyApiFallback":{"rewrites":[{"from":"^/a","to":"/a b"}]}}}"#,
+            ),
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("invalid path: /a b"));
+    }
+
     #[test]
     fn test_node_platform() {
         let current_dir = std::env::current_dir().unwrap();
@@ -1034,6 +1843,65 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_analyze_config() {
+        let current_dir = std::env::current_dir().unwrap();
+        let analyze = |cli_config| {
+            Config::new(
+                &current_dir.join("test/config/normal"),
+                None,
+                Some(cli_config),
+            )
+            .unwrap()
+            .analyze
+        };
+        assert!(analyze(r#"{"analyze":true}"#).is_some());
+        assert!(analyze(r#"{"analyze":{}}"#).is_some());
+        assert!(analyze(r#"{"analyze":false}"#).is_none());
+        assert!(analyze(r#"{}"#).is_none());
+    }
+
+    #[test]
+    fn test_warnings_config() {
+        let current_dir = std::env::current_dir().unwrap();
+        let warnings = |cli_config| {
+            Config::new(
+                &current_dir.join("test/config/normal"),
+                None,
+                Some(cli_config),
+            )
+            .unwrap()
+            .warnings
+        };
+        assert!(!warnings(r#"{}"#).circular_dependencies);
+        let enabled = warnings(r#"{"warnings":{"circularDependencies":true}}"#);
+        assert!(enabled.circular_dependencies);
+        assert!(!enabled.cjs_interop);
+    }
+
+    #[test]
+    fn test_browserslist_targets() {
+        let current_dir = std::env::current_dir().unwrap();
+        let config = Config::new(
+            &current_dir.join("test/config/normal"),
+            None,
+            Some(r#"{"platform":"browser","targets":"chrome 49"}"#),
+        )
+        .unwrap();
+        assert_eq!(
+            config.targets,
+            HashMap::from([("chrome".to_string(), 49.0)])
+        );
+        let config = Config::new(
+            &current_dir.join("test/config/normal"),
+            None,
+            Some(r#"{"platform":"browser","targets":["chrome 100", "safari 14"]}"#),
+        )
+        .unwrap();
+        assert_eq!(config.targets.get("chrome"), Some(&100.0));
+        assert_eq!(config.targets.get("safari"), Some(&14.0));
+    }
+
     #[test]
     fn test_generic_usize_default() {
         assert!(GenericUsizeDefault::<100>::value() == 100usize)
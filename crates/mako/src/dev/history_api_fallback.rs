@@ -0,0 +1,117 @@
+use anyhow::Result;
+use hyper::header::ACCEPT;
+use hyper::{Body, Method, Request};
+use regex::Regex;
+
+use crate::config::HistoryApiFallbackConfig;
+
+// devServer.historyApiFallback compiled once when the dev server starts
+pub struct HistoryApiFallback {
+    rewrites: Vec<(Regex, String)>,
+}
+
+impl HistoryApiFallback {
+    pub fn new(config: &HistoryApiFallbackConfig) -> Result<Self> {
+        let rewrites = config
+            .rewrites
+            .iter()
+            .map(|rewrite| Ok((Regex::new(&rewrite.from)?, rewrite.to.clone())))
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Self { rewrites })
+    }
+
+    // returns the path of html to serve for client-side routes, e.g. /some/route => /index.html
+    // requests for existing files, assets with extensions and non html requests are not rewritten
+    pub fn rewrite_path<F>(&self, req: &Request<Body>, path: &str, exists: F) -> Option<String>
+    where
+        F: Fn(&str) -> bool,
+    {
+        if req.method() != Method::GET && req.method() != Method::HEAD {
+            return None;
+        }
+        let accept_html = req
+            .headers()
+            .get(ACCEPT)
+            .and_then(|accept| accept.to_str().ok())
+            .is_some_and(|accept| accept.contains("text/html"));
+        if !accept_html {
+            return None;
+        }
+        let last_segment = path.rsplit('/').next().unwrap_or_default();
+        if last_segment.contains('.') || exists(path) {
+            return None;
+        }
+
+        let to = self
+            .rewrites
+            .iter()
+            .find(|(from, _)| from.is_match(path))
+            .map_or("/index.html", |(_, to)| to.as_str());
+        Some(to.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use hyper::{Body, Request};
+
+    use super::HistoryApiFallback;
+    use crate::config::{HistoryApiFallbackConfig, HistoryApiFallbackRewrite};
+
+    #[test]
+    fn test_rewrite_route() {
+        assert_eq!(
+            run("GET", "/some/route", "text/html,*/*", vec![]),
+            Some("/index.html".to_string())
+        );
+        assert_eq!(
+            run("GET", "/", "text/html", vec![]),
+            Some("/index.html".to_string())
+        );
+    }
+
+    #[test]
+    fn test_rewrites() {
+        let rewrites = vec![HistoryApiFallbackRewrite {
+            from: "^/admin".to_string(),
+            to: "/admin.html".to_string(),
+        }];
+        assert_eq!(
+            run("GET", "/admin/users", "text/html", rewrites.clone()),
+            Some("/admin.html".to_string())
+        );
+        assert_eq!(
+            run("GET", "/users", "text/html", rewrites),
+            Some("/index.html".to_string())
+        );
+    }
+
+    #[test]
+    fn test_ignore() {
+        // assets
+        assert_eq!(run("GET", "/missing.js", "text/html", vec![]), None);
+        // not html
+        assert_eq!(run("GET", "/some/route", "application/json", vec![]), None);
+        assert_eq!(run("POST", "/some/route", "text/html", vec![]), None);
+        // existing file
+        assert_eq!(run("GET", "/exists", "text/html", vec![]), None);
+    }
+
+    fn run(
+        method: &str,
+        path: &str,
+        accept: &str,
+        rewrites: Vec<HistoryApiFallbackRewrite>,
+    ) -> Option<String> {
+        let req = Request::builder()
+            .method(method)
+            .uri(path)
+            .header("accept", accept)
+            .body(Body::empty())
+            .unwrap();
+        let config = HistoryApiFallbackConfig { rewrites };
+        HistoryApiFallback::new(&config)
+            .unwrap()
+            .rewrite_path(&req, path, |p| p == "/exists")
+    }
+}
@@ -1,3 +1,5 @@
+mod history_api_fallback;
+mod proxy;
 pub(crate) mod update;
 mod watch;
 
@@ -20,6 +22,8 @@ use tungstenite::Message;
 use {hyper, hyper_staticfile, hyper_tungstenite, open};
 
 use crate::compiler::{Compiler, Context};
+use crate::dev::history_api_fallback::HistoryApiFallback;
+use crate::dev::proxy::Proxies;
 use crate::plugin::PluginGenerateEndParams;
 use crate::utils::{process_req_url, tokio_runtime};
 
@@ -65,16 +69,46 @@ impl DevServer {
             let addr: SocketAddr = ([127, 0, 0, 1], port).into();
             let context = self.compiler.context.clone();
             let txws = txws.clone();
+            let dev_server = context.config.dev_server.as_ref().unwrap();
+            let routes = Proxies::new(&dev_server.proxy).and_then(|proxies| {
+                let history_api_fallback = dev_server
+                    .history_api_fallback
+                    .as_ref()
+                    .map(HistoryApiFallback::new)
+                    .transpose()?;
+                Ok((Arc::new(proxies), Arc::new(history_api_fallback)))
+            });
+            let (proxies, history_api_fallback) = match routes {
+                Ok(routes) => routes,
+                Err(e) => {
+                    eprintln!("Error starting server: {:?}", e);
+                    return;
+                }
+            };
             let make_svc = make_service_fn(move |_conn| {
                 let context = context.clone();
                 let txws = txws.clone();
+                let proxies = proxies.clone();
+                let history_api_fallback = history_api_fallback.clone();
                 async move {
                     Ok::<_, hyper::Error>(service_fn(move |req| {
                         let context = context.clone();
                         let txws = txws.clone();
+                        let proxies = proxies.clone();
+                        let history_api_fallback = history_api_fallback.clone();
                         let staticfile =
                             hyper_staticfile::Static::new(context.config.output.path.clone());
-                        async move { Self::handle_requests(req, context, staticfile, txws).await }
+                        async move {
+                            Self::handle_requests(
+                                req,
+                                context,
+                                proxies,
+                                history_api_fallback,
+                                staticfile,
+                                txws,
+                            )
+                            .await
+                        }
                     }))
                 }
             });
@@ -116,8 +150,10 @@ impl DevServer {
     }
 
     async fn handle_requests(
-        req: Request<Body>,
+        mut req: Request<Body>,
         context: Arc<Context>,
+        proxies: Arc<Proxies>,
+        history_api_fallback: Arc<Option<HistoryApiFallback>>,
         staticfile: hyper_staticfile::Static,
         txws: broadcast::Sender<WsMessage>,
     ) -> Result<hyper::Response<Body>> {
@@ -134,6 +170,28 @@ impl DevServer {
                 }
             };
         }
+        // for client-side routes, proxied paths are not rewritten
+        if let Some(history_api_fallback) = history_api_fallback.as_ref()
+            && proxies.find(req.uri().path()).is_none()
+            && let Some(to) = history_api_fallback.rewrite_path(&req, &path, |p| {
+                let p = p.trim_start_matches('/');
+                context.get_static_content(p).is_some()
+                    || context.config.output.path.join(p).is_file()
+            })
+        {
+            debug!("history api fallback: {} => {}", path, to);
+            match to.parse() {
+                Ok(uri) => *req.uri_mut() = uri,
+                Err(e) => {
+                    eprintln!("Error rewriting {} to {}: {:?}", path, to, e);
+                    return Ok(hyper::Response::builder()
+                        .status(hyper::StatusCode::INTERNAL_SERVER_ERROR)
+                        .body(hyper::Body::from("Internal Server Error"))
+                        .unwrap());
+                }
+            }
+            path = to;
+        }
         let path_without_slash_start = path.trim_start_matches('/');
         let not_found_response = || {
             hyper::Response::builder()
@@ -157,6 +215,23 @@ impl DevServer {
                 }
             }
             _ => {
+                // for proxied paths
+                if let Some(proxy) = proxies.find(req.uri().path()) {
+                    if hyper_tungstenite::is_upgrade_request(&req) {
+                        return proxy::proxy_websocket(req, proxy);
+                    }
+                    return match proxies.request(req, proxy).await {
+                        Ok(res) => Ok(res),
+                        Err(e) => {
+                            eprintln!("Error proxying request: {:?}", e);
+                            Ok(hyper::Response::builder()
+                                .status(hyper::StatusCode::BAD_GATEWAY)
+                                .body(hyper::Body::from("Bad Gateway"))
+                                .unwrap())
+                        }
+                    };
+                }
+
                 // for bundle outputs
 
                 let ext = path.rsplit('.').next();
@@ -165,6 +240,7 @@ impl DevServer {
                     Some("js") => "application/javascript; charset=utf-8",
                     Some("css") => "text/css; charset=utf-8",
                     Some("map") | Some("json") => "application/json; charset=utf-8",
+                    Some("html") => "text/html; charset=utf-8",
                     Some(_) => "text/plain; charset=utf-8",
                 };
 
@@ -240,7 +316,15 @@ impl DevServer {
             loop {
                 if let Ok(msg) = receiver.recv().await {
                     if sender
-                        .send(Message::text(format!(r#"{{"hash":"{}"}}"#, msg.hash)))
+                        .send(Message::text(format!(
+                            r#"{{"hash":"{}","type":"{}"}}"#,
+                            msg.hash,
+                            if msg.css_only {
+                                "css-update"
+                            } else {
+                                "js-update"
+                            }
+                        )))
                         .await
                         .is_err()
                     {
@@ -274,20 +358,12 @@ impl DevServer {
         let mut snapshot_hash = Box::new(initial_hash);
         let mut hmr_hash = Box::new(initial_hash);
 
-        for result in rx {
-            if result.is_err() {
-                eprintln!("Error watching files: {:?}", result.err().unwrap());
-                continue;
-            }
-            let paths = watch::Watcher::normalize_events(result.unwrap());
-            if !paths.is_empty() {
-                let compiler = compiler.clone();
-                let txws = txws.clone();
-                if let Err(e) =
-                    Self::rebuild(paths, compiler, txws, &mut snapshot_hash, &mut hmr_hash)
-                {
-                    eprintln!("Error rebuilding: {:?}", e);
-                }
+        while let Some(paths) = watcher.recv_changes(&rx) {
+            let compiler = compiler.clone();
+            let txws = txws.clone();
+            if let Err(e) = Self::rebuild(paths, compiler, txws, &mut snapshot_hash, &mut hmr_hash)
+            {
+                eprintln!("Error rebuilding: {:?}", e);
             }
             watcher.refresh_watch()?;
         }
@@ -331,6 +407,8 @@ impl DevServer {
             println!("No changes");
             return Ok(());
         }
+        let css_only = res.is_css_only(&compiler.context.module_graph.read().unwrap());
+        debug!("css_only: {}", css_only);
 
         let t_compiler = Instant::now();
         let start_time = chrono::Local::now().timestamp_millis();
@@ -400,7 +478,11 @@ impl DevServer {
         let receiver_count = txws.receiver_count();
         debug!("receiver count: {}", receiver_count);
         if receiver_count > 0 {
-            txws.send(WsMessage { hash: **hmr_hash }).unwrap();
+            txws.send(WsMessage {
+                hash: **hmr_hash,
+                css_only,
+            })
+            .unwrap();
             debug!("send message to clients");
         }
 
@@ -411,4 +493,6 @@ impl DevServer {
 #[derive(Clone, Debug)]
 struct WsMessage {
     hash: u64,
+    // only css is changed, the client can swap stylesheets without applying js updates
+    css_only: bool,
 }
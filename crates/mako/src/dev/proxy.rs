@@ -0,0 +1,363 @@
+use std::collections::HashMap;
+
+use anyhow::{anyhow, Result};
+use futures::{future, SinkExt, StreamExt};
+use hyper::client::HttpConnector;
+use hyper::header::{CONNECTION, HOST};
+use hyper::{Body, Client, HeaderMap, Request, Response, Uri};
+use hyper_tungstenite::HyperWebsocket;
+use regex::Regex;
+use tracing::debug;
+
+use crate::config::ProxyConfig;
+use crate::utils::tokio_runtime;
+
+// headers which only apply to a single connection, they are not forwarded
+// https://www.rfc-editor.org/rfc/rfc2616#section-13.5.1
+const HOP_BY_HOP_HEADERS: [&str; 8] = [
+    "connection",
+    "keep-alive",
+    "proxy-authenticate",
+    "proxy-authorization",
+    "te",
+    "trailer",
+    "transfer-encoding",
+    "upgrade",
+];
+
+// devServer.proxy compiled once when the dev server starts
+pub struct Proxies {
+    proxies: Vec<Proxy>,
+    // shared by all requests, so connections to the targets are pooled
+    client: Client<HttpConnector>,
+}
+
+pub struct Proxy {
+    prefix: String,
+    target: Uri,
+    change_origin: bool,
+    path_rewrite: Vec<(Regex, String)>,
+}
+
+impl Proxies {
+    pub fn new(proxy: &HashMap<String, ProxyConfig>) -> Result<Self> {
+        let proxies = proxy
+            .iter()
+            .map(|(prefix, config)| Proxy::new(prefix, config))
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Self {
+            proxies,
+            client: Client::new(),
+        })
+    }
+
+    // the longest matched path prefix wins, prefixes match whole path segments,
+    // e.g. /api matches /api and /api/users but not /apis
+    pub fn find(&self, path: &str) -> Option<&Proxy> {
+        self.proxies
+            .iter()
+            .filter(|proxy| is_path_prefix(&proxy.prefix, path))
+            .max_by_key(|proxy| proxy.prefix.len())
+    }
+
+    pub async fn request(&self, mut req: Request<Body>, proxy: &Proxy) -> Result<Response<Body>> {
+        let uri = proxy.target_uri(req.uri(), "http")?;
+        debug!("proxy {} to {}", req.uri(), uri);
+        if proxy.change_origin
+            && let Some(authority) = uri.authority()
+        {
+            req.headers_mut()
+                .insert(HOST, authority.as_str().parse().unwrap());
+        }
+        *req.uri_mut() = uri;
+        remove_hop_by_hop_headers(req.headers_mut());
+        let mut res = self.client.request(req).await?;
+        remove_hop_by_hop_headers(res.headers_mut());
+        Ok(res)
+    }
+}
+
+impl Proxy {
+    fn new(prefix: &str, config: &ProxyConfig) -> Result<Self> {
+        let target = config
+            .target
+            .parse::<Uri>()
+            .map_err(|e| anyhow!("invalid proxy target {}: {}", config.target, e))?;
+        if target.authority().is_none() {
+            return Err(anyhow!("invalid proxy target {}", config.target));
+        }
+        // requests are forwarded by a plain http client, and websockets by ws
+        if target.scheme_str().is_some_and(|scheme| scheme != "http") {
+            return Err(anyhow!(
+                "unsupported proxy target {}, only http targets are supported",
+                config.target
+            ));
+        }
+        // sorted by patterns, so rewrites are applied in a stable order
+        let mut path_rewrite = config
+            .path_rewrite
+            .iter()
+            .map(|(pattern, replacement)| Ok((Regex::new(pattern)?, replacement.clone())))
+            .collect::<Result<Vec<_>>>()?;
+        path_rewrite.sort_by(|(a, _), (b, _)| a.as_str().cmp(b.as_str()));
+        Ok(Self {
+            prefix: prefix.to_string(),
+            target,
+            change_origin: config.change_origin,
+            path_rewrite,
+        })
+    }
+
+    fn target_uri(&self, uri: &Uri, scheme: &str) -> Result<Uri> {
+        // safe, checked in Proxy::new
+        let authority = self.target.authority().unwrap();
+        let path = self
+            .path_rewrite
+            .iter()
+            .fold(uri.path().to_string(), |path, (re, replacement)| {
+                re.replace(&path, replacement.as_str()).to_string()
+            });
+        let path_and_query = format!(
+            "{}{}{}",
+            self.target.path().trim_end_matches('/'),
+            path,
+            uri.query().map(|q| format!("?{}", q)).unwrap_or_default()
+        );
+        Ok(Uri::builder()
+            .scheme(scheme)
+            .authority(authority.as_str())
+            .path_and_query(path_and_query)
+            .build()?)
+    }
+}
+
+fn is_path_prefix(prefix: &str, path: &str) -> bool {
+    path.strip_prefix(prefix)
+        .is_some_and(|rest| rest.is_empty() || rest.starts_with('/') || prefix.ends_with('/'))
+}
+
+fn remove_hop_by_hop_headers(headers: &mut HeaderMap) {
+    // headers listed in connection are hop-by-hop too
+    let listed = headers
+        .get_all(CONNECTION)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .map(|name| name.trim().to_lowercase())
+        .filter(|name| !name.is_empty())
+        .collect::<Vec<_>>();
+    for name in listed.iter().map(String::as_str).chain(HOP_BY_HOP_HEADERS) {
+        headers.remove(name);
+    }
+}
+
+pub fn proxy_websocket(req: Request<Body>, proxy: &Proxy) -> Result<Response<Body>> {
+    let uri = proxy.target_uri(req.uri(), "ws")?;
+    debug!("proxy websocket {} to {}", req.uri(), uri);
+    let (response, websocket) = hyper_tungstenite::upgrade(req, None)?;
+    tokio_runtime::spawn(async move {
+        if let Err(e) = pipe_websocket(websocket, uri).await {
+            eprintln!("Error proxying websocket: {:?}", e);
+        }
+    });
+    Ok(response)
+}
+
+async fn pipe_websocket(websocket: HyperWebsocket, uri: Uri) -> Result<()> {
+    let websocket = websocket.await?;
+    let (upstream, _) = tokio_tungstenite::connect_async(uri).await?;
+    let (mut sender, mut receiver) = websocket.split();
+    let (mut upstream_sender, mut upstream_receiver) = upstream.split();
+    let to_upstream = async {
+        while let Some(Ok(message)) = receiver.next().await {
+            if upstream_sender.send(message).await.is_err() {
+                break;
+            }
+        }
+    };
+    let to_client = async {
+        while let Some(Ok(message)) = upstream_receiver.next().await {
+            if sender.send(message).await.is_err() {
+                break;
+            }
+        }
+    };
+    future::select(Box::pin(to_upstream), Box::pin(to_client)).await;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::net::SocketAddr;
+
+    use hyper::service::{make_service_fn, service_fn};
+    use hyper::{Body, Request, Response, Server};
+
+    use super::Proxies;
+    use crate::config::ProxyConfig;
+    use crate::utils::tokio_runtime;
+
+    #[test]
+    fn test_find_proxy() {
+        let proxies = Proxies::new(&HashMap::from([
+            ("/api".to_string(), proxy_config("http://a", false, &[])),
+            ("/api/v2".to_string(), proxy_config("http://b", false, &[])),
+            ("/static/".to_string(), proxy_config("http://c", false, &[])),
+        ]))
+        .unwrap();
+        let target = |path| {
+            proxies
+                .find(path)
+                .map(|proxy| proxy.target.to_string().trim_end_matches('/').to_string())
+        };
+        assert_eq!(target("/api/users").unwrap(), "http://a");
+        assert_eq!(target("/api").unwrap(), "http://a");
+        assert_eq!(target("/api/v2/users").unwrap(), "http://b");
+        assert_eq!(target("/api/v2").unwrap(), "http://b");
+        assert_eq!(target("/api/v20").unwrap(), "http://a");
+        assert_eq!(target("/static/a.png").unwrap(), "http://c");
+        // prefixes match whole path segments
+        assert!(target("/apis").is_none());
+        assert!(target("/static").is_none());
+        assert!(target("/index.js").is_none());
+    }
+
+    #[test]
+    fn test_https_target_rejected() {
+        let result = Proxies::new(&HashMap::from([(
+            "/api".to_string(),
+            proxy_config("https://example.com", false, &[]),
+        )]));
+        let err = result.err().unwrap().to_string();
+        assert!(err.contains("only http targets are supported"), "{}", err);
+    }
+
+    #[test]
+    fn test_proxy_request() {
+        let addr = start_upstream();
+        let config = proxy_config(&format!("http://{}", addr), false, &[]);
+        let (status, x_upstream, body) = request(&config, "/api/users?id=1");
+        assert_eq!(status, 201);
+        assert_eq!(x_upstream, "1");
+        assert_eq!(
+            body,
+            "POST /api/users?id=1 host=localhost:3000 x-token=foo body=hello"
+        );
+    }
+
+    #[test]
+    fn test_proxy_request_remove_hop_by_hop_headers() {
+        let addr = start_upstream();
+        let config = proxy_config(&format!("http://{}", addr), false, &[]);
+        let req = Request::builder()
+            .method("POST")
+            .uri("/api/hop")
+            .header("host", "localhost:3000")
+            .header("x-token", "foo")
+            .header("connection", "x-hop")
+            .header("x-hop", "1")
+            .header("proxy-authorization", "Basic Zm9vOmJhcg==")
+            .body(Body::from("hello"))
+            .unwrap();
+        let res = send(&config, req);
+        assert_eq!(res.0, 201);
+        // the upstream echoes whether hop-by-hop headers were received
+        assert_eq!(
+            res.2,
+            "POST /api/hop host=localhost:3000 x-token=foo hop=false body=hello"
+        );
+    }
+
+    #[test]
+    fn test_proxy_request_path_rewrite_and_change_origin() {
+        let addr = start_upstream();
+        let config = proxy_config(&format!("http://{}", addr), true, &[("^/api", "/v1")]);
+        let (_, _, body) = request(&config, "/api/users?id=1");
+        assert_eq!(
+            body,
+            format!("POST /v1/users?id=1 host={} x-token=foo body=hello", addr)
+        );
+    }
+
+    fn request(config: &ProxyConfig, path: &str) -> (u16, String, String) {
+        let req = Request::builder()
+            .method("POST")
+            .uri(path)
+            .header("host", "localhost:3000")
+            .header("x-token", "foo")
+            .body(Body::from("hello"))
+            .unwrap();
+        send(config, req)
+    }
+
+    fn send(config: &ProxyConfig, req: Request<Body>) -> (u16, String, String) {
+        let proxies = Proxies::new(&HashMap::from([("/api".to_string(), config.clone())])).unwrap();
+        tokio_runtime::block_on(async {
+            let proxy = proxies.find(req.uri().path()).unwrap();
+            let res = proxies.request(req, proxy).await.unwrap();
+            let status = res.status().as_u16();
+            let x_upstream = res.headers()["x-upstream"].to_str().unwrap().to_string();
+            assert!(!res.headers().contains_key("keep-alive"));
+            let body = hyper::body::to_bytes(res.into_body()).await.unwrap();
+            (
+                status,
+                x_upstream,
+                String::from_utf8(body.to_vec()).unwrap(),
+            )
+        })
+    }
+
+    // echoes the request back
+    fn start_upstream() -> SocketAddr {
+        let make_svc = make_service_fn(|_conn| async {
+            Ok::<_, hyper::Error>(service_fn(|req: Request<Body>| async move {
+                let mut head = format!(
+                    "{} {} host={} x-token={}",
+                    req.method(),
+                    req.uri(),
+                    req.headers()["host"].to_str().unwrap(),
+                    req.headers()["x-token"].to_str().unwrap(),
+                );
+                if req.uri().path() == "/api/hop" {
+                    let hop = ["x-hop", "proxy-authorization"]
+                        .iter()
+                        .any(|name| req.headers().contains_key(*name));
+                    head.push_str(&format!(" hop={}", hop));
+                }
+                let body = hyper::body::to_bytes(req.into_body()).await?;
+                let body = format!("{} body={}", head, String::from_utf8_lossy(&body));
+                Ok::<_, hyper::Error>(
+                    Response::builder()
+                        .status(201)
+                        .header("x-upstream", "1")
+                        .header("keep-alive", "timeout=5")
+                        .body(Body::from(body))
+                        .unwrap(),
+                )
+            }))
+        });
+        let (tx, rx) = std::sync::mpsc::channel();
+        tokio_runtime::spawn(async move {
+            let server = Server::bind(&([127, 0, 0, 1], 0).into()).serve(make_svc);
+            tx.send(server.local_addr()).unwrap();
+            server.await.unwrap();
+        });
+        rx.recv().unwrap()
+    }
+
+    fn proxy_config(
+        target: &str,
+        change_origin: bool,
+        path_rewrite: &[(&str, &str)],
+    ) -> ProxyConfig {
+        ProxyConfig {
+            target: target.to_string(),
+            change_origin,
+            path_rewrite: path_rewrite
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect(),
+        }
+    }
+}
@@ -1,17 +1,21 @@
 use std::collections::{HashMap, HashSet};
 use std::fmt;
 use std::fmt::Debug;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use anyhow::{anyhow, Ok, Result};
+use glob_match::glob_match;
 use rayon::prelude::*;
 use tracing::debug;
 
+use crate::ast::file::parse_path;
 use crate::build::BuildError;
 use crate::compiler::Compiler;
 use crate::generate::transform::transform_modules;
-use crate::module::{Dependency, Module, ModuleId, ResolveTypeFlags};
+use crate::module::{assign_module_ids, Dependency, Module, ModuleAst, ModuleId, ResolveTypeFlags};
 use crate::module_graph::ModuleGraph;
+use crate::plugins::import_meta_glob::{is_glob_matched, GLOB_QUERY};
+use crate::plugins::require_context::VIRTUAL_REQUIRE_CONTEXT_MODULE;
 use crate::resolve::{self, clear_resolver_cache};
 
 #[derive(Debug, Clone)]
@@ -40,6 +44,19 @@ impl UpdateResult {
             || !self.removed.is_empty()
             || !self.dep_changed.is_empty()
     }
+
+    pub fn is_css_only(&self, module_graph: &ModuleGraph) -> bool {
+        !self.modified.is_empty()
+            && self.added.is_empty()
+            && self.removed.is_empty()
+            && self.dep_changed.is_empty()
+            && self.modified.iter().all(|module_id| {
+                module_graph
+                    .get_module(module_id)
+                    .and_then(|module| module.info.as_ref())
+                    .is_some_and(|info| matches!(info.ast, ModuleAst::Css(_)))
+            })
+    }
 }
 
 impl fmt::Display for UpdateResult {
@@ -152,6 +169,11 @@ impl Compiler {
             debug!("checking modules_with_missing_deps...done");
         }
 
+        // glob again since the added or removed files may be matched
+        let context_modules = self.get_affected_context_modules(&paths);
+        debug!("  > affected context modules: {:?}", &context_modules);
+        modified.extend(context_modules);
+
         // watch 到变化的文件，如果不在之前的 module graph 中，需过滤掉
         debug!("filtering paths...");
         let paths: Vec<(PathBuf, UpdateType)> = {
@@ -236,11 +258,81 @@ impl Compiler {
                 .collect::<HashSet<_>>(),
         );
         update_result.added.extend(added_module_ids);
+        assign_module_ids(&self.context);
 
         debug!("update_result: {:?}", &update_result);
         Result::Ok(update_result)
     }
 
+    // context modules whose dirs contain the added or removed files,
+    // e.g. import(`./i18n/${lang}.json`), which is built as ./i18n?context&glob=**/*.json,
+    // and require.context('./i18n'), they are rebuilt with the files globbed again,
+    // and the importers of the import.meta.glob modules whose patterns match the files,
+    // e.g. /src/index.js?import-meta-glob=./dir/*.js&files=1a2b3c4d, which are rebuilt to be
+    // resolved to the glob modules of the new files
+    fn get_affected_context_modules(&self, paths: &[(PathBuf, UpdateType)]) -> Vec<PathBuf> {
+        let changed = paths
+            .iter()
+            .filter(|(_, update_type)| matches!(update_type, UpdateType::Add | UpdateType::Remove))
+            .map(|(path, _)| path)
+            .collect::<Vec<_>>();
+        if changed.is_empty() {
+            return vec![];
+        }
+        let module_graph = self.context.module_graph.read().unwrap();
+        let mut affected = vec![];
+        for module in module_graph.modules() {
+            let Result::Ok((pathname, _, params, _)) = parse_path(&module.id.id) else {
+                continue;
+            };
+            let param = |key: &str| {
+                params
+                    .iter()
+                    .find_map(|(k, v)| (k == key).then_some(v.as_str()))
+            };
+            if module.id.id.starts_with(VIRTUAL_REQUIRE_CONTEXT_MODULE) {
+                let Some(root) = param("root") else {
+                    continue;
+                };
+                let dir = self.context.root.join(root);
+                let use_subdirectories = param("sub") == Some("true");
+                if changed.iter().any(|path| {
+                    if use_subdirectories {
+                        path.starts_with(&dir)
+                    } else {
+                        path.parent() == Some(dir.as_path())
+                    }
+                }) {
+                    affected.push(module.id.to_path());
+                }
+            } else if let (Some(_), Some(glob)) = (param("context"), param("glob")) {
+                if changed.iter().any(|path| {
+                    path.strip_prefix(&pathname).is_ok_and(|relative| {
+                        glob_match(glob, &relative.to_string_lossy().replace('\\', "/"))
+                    })
+                }) {
+                    affected.push(module.id.to_path());
+                }
+            } else if let Some(pattern) = param(GLOB_QUERY) {
+                let importer = Path::new(&pathname);
+                if changed
+                    .iter()
+                    .any(|path| is_glob_matched(importer, pattern, path))
+                {
+                    affected.extend(
+                        module_graph
+                            .get_dependents(&module.id)
+                            .into_iter()
+                            .map(|(dependent, _)| dependent.to_path()),
+                    );
+                }
+            }
+        }
+        affected.sort();
+        affected.dedup();
+        affected
+    }
+
     pub fn transform_for_change(&self, update_result: &UpdateResult) -> Result<()> {
         let mut changes: Vec<ModuleId> = vec![];
         for module_id in &update_result.added {
@@ -344,20 +436,8 @@ impl Compiler {
                 dep_changed_module_ids.insert(modified_module.id.clone());
             }
 
-            // remove bind dependency
-            for remove_module_id in &diff.removed {
-                module_graph.clear_dependency(&modified_module.id, remove_module_id);
-            }
-
-            // add bind dependency
+            // add modules of the new dependencies
             for add_module_id in &diff.added {
-                let mut deps = dependencies
-                    .iter()
-                    .filter(|(module_id, _dep)| module_id == add_module_id)
-                    .map(|(_, dep)| dep)
-                    .collect::<Vec<_>>();
-                deps.sort_by_key(|d| d.order);
-
                 // english: In theory, the add_module_id that add_modules should exist in must exist, but in actual scenarios, an unwrap() error still occurs, so add a guard check here
                 // TODO: Need to find the root cause
                 let add_module = dependence_modules.remove(add_module_id);
@@ -372,25 +452,15 @@ impl Compiler {
                 }
 
                 module_graph.add_module(add_module);
-
-                deps.iter().for_each(|&dep| {
-                    module_graph.add_dependency(&modified_module.id, add_module_id, dep.clone());
-                });
             }
 
-            diff.modified.iter().for_each(|to_module_id| {
-                let deps = dependencies
-                    .iter()
-                    .filter(|(module_id, _dep)| module_id == to_module_id)
-                    .map(|(_, dep)| dep)
-                    .collect::<Vec<_>>();
-
-                module_graph.clear_dependency(&modified_module.id, to_module_id);
-
-                deps.iter().for_each(|&dep| {
-                    module_graph.add_dependency(&modified_module.id, to_module_id, dep.clone());
-                });
-            });
+            // update bind dependencies
+            let dependencies = dependencies
+                .into_iter()
+                .filter(|(module_id, _)| module_graph.has_module(module_id))
+                .collect();
+            let result = module_graph.update_module(&modified_module.id, dependencies);
+            debug!("  > orphan modules: {:?}", result.orphans);
 
             modified_module_ids.insert(modified_module.id.clone());
 
@@ -433,7 +503,6 @@ impl Compiler {
 pub struct Diff {
     added: HashSet<ModuleId>,
     removed: HashSet<ModuleId>,
-    modified: HashSet<ModuleId>,
 }
 
 impl Diff {
@@ -483,7 +552,6 @@ impl Diff {
 }
 
 // 比较两个依赖列表的差异
-// 未变化的模块算作 modified，因为依赖数据必然发生了变化；eg: order，span
 fn diff(origin: &[(ModuleId, Dependency)], new_deps: &[(ModuleId, Dependency)]) -> Diff {
     let origin_module_ids = origin
         .iter()
@@ -504,14 +572,5 @@ fn diff(origin: &[(ModuleId, Dependency)], new_deps: &[(ModuleId, Dependency)])
         .cloned()
         .collect::<HashSet<_>>();
 
-    let modified = origin_module_ids
-        .intersection(&target_module_ids)
-        .cloned()
-        .collect::<HashSet<_>>();
-
-    Diff {
-        added,
-        removed,
-        modified,
-    }
+    Diff { added, removed }
 }
@@ -1,18 +1,22 @@
 use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
+use std::sync::mpsc::{Receiver, RecvTimeoutError};
 use std::sync::Arc;
-use std::time::Instant;
+use std::time::{Duration, Instant};
 
 use anyhow::{self, Ok};
 use colored::Colorize;
+use glob_match::glob_match;
 use notify::{self, EventKind, Watcher as NotifyWatcher};
-use notify_debouncer_full::DebouncedEvent;
+use notify_debouncer_full::{DebounceEventResult, DebouncedEvent};
 use regex::Regex;
 use tracing::debug;
 
 use crate::compiler::Compiler;
 use crate::resolve::ResolverResource;
 
+const MAX_DEBOUNCE_WAIT_FACTOR: u32 = 10;
+
 pub struct Watcher<'a> {
     pub watcher: &'a mut dyn NotifyWatcher,
     pub root: &'a PathBuf,
@@ -165,7 +169,7 @@ impl<'a> Watcher<'a> {
     }
 
     fn watch_file_or_dir(&mut self, path: PathBuf, ignore_list: &[PathBuf]) -> anyhow::Result<()> {
-        if Self::should_ignore_watch(&path, ignore_list) {
+        if Self::should_ignore_watch(&path, ignore_list) || self.is_ignored(&path) {
             return Ok(());
         }
 
@@ -208,6 +212,67 @@ impl<'a> Watcher<'a> {
         ignore_list.iter().any(|ignored| path.ends_with(ignored))
     }
 
+    // blocks until files are changed, and keeps collecting the changes until there are no events
+    // for `watch.debounce` ms, so successive edits within the window are coalesced into one rebuild,
+    // but no longer than MAX_DEBOUNCE_WAIT_FACTOR times of it, so files which keep changing,
+    // e.g. logs written by a dev server, don't block the rebuilds,
+    // changes of ignored paths only are skipped, returns None when the watcher is dropped
+    pub fn recv_changes(&self, rx: &Receiver<DebounceEventResult>) -> Option<Vec<PathBuf>> {
+        let debounce = Duration::from_millis(self.compiler.context.config.watch.debounce);
+        let max_wait = debounce * MAX_DEBOUNCE_WAIT_FACTOR;
+        loop {
+            let mut result = rx.recv().ok()?;
+            let deadline = Instant::now() + max_wait;
+            let mut events = vec![];
+            loop {
+                match result {
+                    Result::Ok(mut debounced_events) => events.append(&mut debounced_events),
+                    Err(errors) => eprintln!("Error watching files: {:?}", errors),
+                }
+                let timeout = debounce.min(deadline.saturating_duration_since(Instant::now()));
+                if timeout.is_zero() {
+                    break;
+                }
+                match rx.recv_timeout(timeout) {
+                    Result::Ok(next) => result = next,
+                    Err(RecvTimeoutError::Timeout) | Err(RecvTimeoutError::Disconnected) => break,
+                }
+            }
+            let mut paths = Self::normalize_events(events);
+            paths.retain(|path| !self.is_ignored(path));
+            if !paths.is_empty() {
+                return Some(paths);
+            }
+        }
+    }
+
+    // paths matched by watch.ignored and the output dir, which may be written by the rebuilds,
+    // node_modules matched by watch._nodeModulesRegexes are still watched
+    fn is_ignored(&self, path: &Path) -> bool {
+        if path.starts_with(&self.compiler.context.config.output.path) {
+            return true;
+        }
+        let Result::Ok(relative) = path.strip_prefix(self.root) else {
+            return false;
+        };
+        let file_path = path.to_string_lossy();
+        if self
+            .node_modules_regexes
+            .iter()
+            .any(|regex| regex.is_match(&file_path))
+        {
+            return false;
+        }
+        let ignored = &self.compiler.context.config.watch.ignored;
+        relative.ancestors().any(|ancestor| {
+            let ancestor = ancestor.to_string_lossy().replace('\\', "/");
+            !ancestor.is_empty()
+                && ignored
+                    .iter()
+                    .any(|pattern| glob_match(pattern.trim_start_matches("./"), &ancestor))
+        })
+    }
+
     pub fn normalize_events(events: Vec<DebouncedEvent>) -> Vec<PathBuf> {
         let mut paths = vec![];
         let mut create_paths = HashMap::new();
@@ -230,3 +295,229 @@ impl<'a> Watcher<'a> {
         paths
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+    use std::sync::mpsc::{self, Sender};
+    use std::sync::Arc;
+    use std::time::{Duration, Instant};
+    use std::{fs, thread};
+
+    use notify::event::{DataChange, ModifyKind};
+    use notify::{Event, EventKind};
+    use notify_debouncer_full::{DebounceEventResult, DebouncedEvent};
+    use tempfile::TempDir;
+
+    use super::Watcher;
+    use crate::ast::file::File;
+    use crate::compiler::Compiler;
+    use crate::module::ModuleId;
+    use crate::utils::test_helper::setup_compiler;
+
+    #[test]
+    fn test_successive_edits_coalesced() {
+        let (compiler, dir) = setup(
+            r#"{"entry":{"index":"./src/index.js"},"watch":{"debounce":100}}"#,
+            &[
+                ("src/index.js", "import './a';"),
+                ("src/a.js", "console.log('a');"),
+            ],
+        );
+        let root = dir.path().to_path_buf();
+        let a = root.join("src/a.js");
+        let mut notify_watcher = notify::recommended_watcher(|_| {}).unwrap();
+        let watcher = Watcher::new(&root, &mut notify_watcher, &compiler);
+
+        let (tx, rx) = mpsc::channel();
+        send(
+            &tx,
+            &a,
+            EventKind::Modify(ModifyKind::Data(DataChange::Any)),
+        );
+        send(
+            &tx,
+            &a,
+            EventKind::Modify(ModifyKind::Data(DataChange::Any)),
+        );
+        drop(tx);
+
+        let paths = watcher.recv_changes(&rx).unwrap();
+        assert_eq!(paths, vec![a.clone()]);
+        // no more changes for another rebuild
+        assert!(watcher.recv_changes(&rx).is_none());
+
+        let update_result = compiler.update(paths).unwrap();
+        assert_eq!(
+            update_result.modified.into_iter().collect::<Vec<_>>(),
+            vec![ModuleId::from_path(a)]
+        );
+    }
+
+    #[test]
+    fn test_debounce_max_wait() {
+        let (compiler, dir) = setup(
+            r#"{"entry":{"index":"./src/index.js"},"watch":{"debounce":20}}"#,
+            &[("src/index.js", "console.log('index');")],
+        );
+        let root = dir.path().to_path_buf();
+        let log = root.join("src/index.js");
+        let mut notify_watcher = notify::recommended_watcher(|_| {}).unwrap();
+        let watcher = Watcher::new(&root, &mut notify_watcher, &compiler);
+
+        // a file keeps changing within the debounce window
+        let (tx, rx) = mpsc::channel();
+        let sender = thread::spawn(move || {
+            for _ in 0..100 {
+                send(
+                    &tx,
+                    &log,
+                    EventKind::Modify(ModifyKind::Data(DataChange::Any)),
+                );
+                thread::sleep(Duration::from_millis(10));
+            }
+        });
+        let start = Instant::now();
+        assert!(watcher.recv_changes(&rx).is_some());
+        assert!(start.elapsed() < Duration::from_millis(800));
+        sender.join().unwrap();
+    }
+
+    #[test]
+    fn test_ignored_paths() {
+        let (compiler, dir) = setup(
+            r#"{"entry":{"index":"./src/index.js"},"watch":{"ignored":["**/node_modules","src/generated"]}}"#,
+            &[
+                ("src/index.js", "import './a'; import './generated/g';"),
+                ("src/a.js", "console.log('a');"),
+                ("src/generated/g.js", "console.log('g');"),
+                ("src/vendor/node_modules/v/index.js", "console.log('v');"),
+                ("dist/index.js", "console.log('dist');"),
+            ],
+        );
+        let root = dir.path().to_path_buf();
+        let a = root.join("src/a.js");
+        let mut notify_watcher = notify::recommended_watcher(|_| {}).unwrap();
+        let watcher = Watcher::new(&root, &mut notify_watcher, &compiler);
+
+        let (tx, rx) = mpsc::channel();
+        let modify = || EventKind::Modify(ModifyKind::Data(DataChange::Any));
+        send(&tx, &root.join("src/generated/g.js"), modify());
+        send(
+            &tx,
+            &root.join("src/vendor/node_modules/v/index.js"),
+            modify(),
+        );
+        send(&tx, &root.join("dist/index.js"), modify());
+        send(&tx, &a, modify());
+        drop(tx);
+
+        assert_eq!(watcher.recv_changes(&rx).unwrap(), vec![a]);
+    }
+
+    #[test]
+    fn test_added_and_removed_file_in_context_module() {
+        let (compiler, dir) = setup(
+            r#"{"entry":{"index":"./src/index.js"}}"#,
+            &[
+                (
+                    "src/index.js",
+                    "const lang = 'en'; import(`./locales/${lang}.js`);",
+                ),
+                ("src/locales/en.js", "export default 'en';"),
+                ("src/locales/fr.js", "export default 'fr';"),
+            ],
+        );
+        let root = dir.path().to_path_buf();
+        let zh = root.join("src/locales/zh.js");
+        fs::write(&zh, "export default 'zh';").unwrap();
+        // not matched by the glob **/*.js
+        fs::write(root.join("src/locales/zh.json"), "{}").unwrap();
+
+        let update_result = compiler
+            .update(vec![zh.clone(), root.join("src/locales/zh.json")])
+            .unwrap();
+        assert!(update_result.added.contains(&ModuleId::from_path(zh)));
+        assert!(!update_result
+            .added
+            .contains(&ModuleId::from_path(root.join("src/locales/zh.json"))));
+        assert!(update_result
+            .modified
+            .iter()
+            .any(|id| id.id.contains("?context&glob=")));
+
+        let fr = root.join("src/locales/fr.js");
+        fs::remove_file(&fr).unwrap();
+        let update_result = compiler.update(vec![fr.clone()]).unwrap();
+        assert!(update_result.removed.contains(&ModuleId::from_path(fr)));
+        assert!(update_result
+            .modified
+            .iter()
+            .any(|id| id.id.contains("?context&glob=")));
+    }
+
+    #[test]
+    fn test_added_and_removed_file_in_glob_module() {
+        let (compiler, dir) = setup(
+            r#"{"entry":{"index":"./src/index.js"}}"#,
+            &[
+                (
+                    "src/index.js",
+                    "console.log(import.meta.glob('./pages/*.js'));",
+                ),
+                ("src/pages/a.js", "export default 'a';"),
+            ],
+        );
+        let root = dir.path().to_path_buf();
+        let index = ModuleId::from_path(root.join("src/index.js"));
+        let glob_module = || {
+            let module_graph = compiler.context.module_graph.read().unwrap();
+            module_graph
+                .get_dependencies(&index)
+                .into_iter()
+                .map(|(id, _)| id.id.clone())
+                .find(|id| id.contains("?import-meta-glob="))
+                .unwrap()
+        };
+        let before = glob_module();
+
+        let b = root.join("src/pages/b.js");
+        fs::write(&b, "export default 'b';").unwrap();
+        let update_result = compiler.update(vec![b.clone()]).unwrap();
+        assert!(update_result.modified.contains(&index));
+        let added = glob_module();
+        assert_ne!(before, added);
+        assert!(update_result.added.contains(&ModuleId::new(added.clone())));
+
+        fs::remove_file(&b).unwrap();
+        let update_result = compiler.update(vec![b]).unwrap();
+        assert!(update_result.modified.contains(&index));
+        assert_eq!(glob_module(), before);
+    }
+
+    fn send(tx: &Sender<DebounceEventResult>, path: &Path, kind: EventKind) {
+        let event = Event::new(kind).add_path(path.to_path_buf());
+        let _ = tx.send(Ok(vec![DebouncedEvent::new(event, Instant::now())]));
+    }
+
+    fn setup(config: &str, files: &[(&str, &str)]) -> (Arc<Compiler>, TempDir) {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        for (path, content) in files {
+            let path = root.join(path);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, content).unwrap();
+        }
+        fs::write(root.join("mako.config.json"), config).unwrap();
+        let compiler = setup_compiler(&root.to_string_lossy(), false);
+        let context = compiler.context.clone();
+        let files = context
+            .config
+            .entry
+            .values()
+            .map(|entry| File::new_entry(entry.to_string_lossy().to_string(), context.clone()))
+            .collect();
+        compiler.build(files).unwrap();
+        (Arc::new(compiler), dir)
+    }
+}
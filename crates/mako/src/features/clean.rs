@@ -0,0 +1,152 @@
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use anyhow::Result;
+use glob_match::glob_match;
+
+use crate::config::OutputCleanConfig;
+
+// paths written to the output dir by the current build
+#[derive(Default)]
+pub struct EmittedFiles {
+    files: Mutex<HashSet<PathBuf>>,
+}
+
+impl EmittedFiles {
+    pub fn add<P: AsRef<Path>>(&self, path: P) {
+        self.files
+            .lock()
+            .unwrap()
+            .insert(normalize_path(path.as_ref()));
+    }
+
+    fn contains(&self, path: &Path) -> bool {
+        self.files.lock().unwrap().contains(&normalize_path(path))
+    }
+}
+
+// files under the output dir before building,
+// the stale ones are the files which are not emitted by the current build
+pub struct OutputSnapshot {
+    root: PathBuf,
+    files: Vec<PathBuf>,
+    keep: Vec<String>,
+}
+
+impl OutputSnapshot {
+    pub fn new(root: &Path, config: &OutputCleanConfig) -> Result<Self> {
+        let mut files = vec![];
+        if root.is_dir() {
+            collect_files(root, &mut files)?;
+        }
+        Ok(Self {
+            root: root.to_path_buf(),
+            files,
+            keep: config.keep.clone(),
+        })
+    }
+
+    // removes the files of the previous builds which are not emitted by the current build,
+    // except the ones matched by clean.keep, returns the removed files relative to the output dir
+    pub fn clean_stale(&self, emitted: &EmittedFiles) -> Result<Vec<String>> {
+        let mut removed = vec![];
+        for path in &self.files {
+            if emitted.contains(path) {
+                continue;
+            }
+            let relative = path
+                .strip_prefix(&self.root)?
+                .to_string_lossy()
+                .replace('\\', "/");
+            if self
+                .keep
+                .iter()
+                .any(|pattern| glob_match(pattern.trim_start_matches("./"), &relative))
+            {
+                continue;
+            }
+            // symlinks are removed themselves instead of their targets
+            match fs::remove_file(path) {
+                Ok(_) => {}
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => continue,
+                Err(e) => return Err(e.into()),
+            }
+            remove_empty_parents(path, &self.root);
+            removed.push(relative);
+        }
+        removed.sort();
+        Ok(removed)
+    }
+}
+
+// e.g. dist/./static/a.png => dist/static/a.png
+fn normalize_path(path: &Path) -> PathBuf {
+    path.components().collect()
+}
+
+// dirs are not followed when they are symlinks, which may point to outside of the output dir
+fn collect_files(dir: &Path, files: &mut Vec<PathBuf>) -> Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        if entry.file_type()?.is_dir() {
+            collect_files(&entry.path(), files)?;
+        } else {
+            files.push(entry.path());
+        }
+    }
+    Ok(())
+}
+
+// the output dir itself is never removed
+fn remove_empty_parents(path: &Path, root: &Path) {
+    let mut dir = path.parent();
+    while let Some(current) = dir {
+        if current == root || !current.starts_with(root) || fs::remove_dir(current).is_err() {
+            break;
+        }
+        dir = current.parent();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use crate::utils::test_helper::setup_compiler;
+
+    #[test]
+    fn test_output_clean() {
+        let compiler = setup_compiler("test/build/output-clean", false);
+        let dist = compiler.context.config.output.path.clone();
+        if dist.exists() {
+            fs::remove_dir_all(&dist).unwrap();
+        }
+        fs::create_dir_all(dist.join("static")).unwrap();
+        fs::create_dir_all(dist.join("old")).unwrap();
+        fs::write(dist.join("index.js"), "stale index").unwrap();
+        fs::write(dist.join("old/chunk.js"), "stale chunk").unwrap();
+        fs::write(dist.join("static/robots.txt"), "kept").unwrap();
+        // outputs of the previous build which are emitted again
+        fs::write(dist.join("robots.txt"), "User-agent: *\n").unwrap();
+        fs::write(dist.join("stats.json"), "{}").unwrap();
+
+        compiler.compile().unwrap();
+
+        assert!(!dist.join("old/chunk.js").exists());
+        assert!(!dist.join("old").exists());
+        // emitted files are kept, including the copied ones whose mtimes may not change
+        assert!(dist.join("robots.txt").exists());
+        assert!(dist.join("stats.json").exists());
+        assert_eq!(
+            fs::read_to_string(dist.join("static/robots.txt")).unwrap(),
+            "kept"
+        );
+        // rewritten by the current build
+        assert!(fs::read_to_string(dist.join("index.js"))
+            .unwrap()
+            .contains("hello output clean"));
+        fs::remove_dir_all(&dist).unwrap();
+    }
+}
@@ -1,2 +1,3 @@
+pub mod clean;
 pub mod node;
 pub mod rsc;
@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::path::PathBuf;
 use std::sync::Arc;
 
@@ -33,23 +33,29 @@ impl Node {
             config
                 .define
                 .insert("__filename".into(), Value::String("'/index.js'".into()));
-            // polyfill with equivalent modules
-            for name in Self::get_polyfill_modules().iter() {
-                config.resolve.alias.push((
-                    name.to_string(),
-                    format!("node-libs-browser-okam/polyfill/{}", name),
-                ));
+            // polyfill with equivalent modules, which can be replaced by resolve.nodeBuiltins,
+            // the others like fs are not resolvable unless they are configured
+            let mut shims: BTreeMap<String, String> = Self::get_polyfill_modules()
+                .into_iter()
+                .map(|name| {
+                    let shim = format!("node-libs-browser-okam/polyfill/{}", name);
+                    (name, shim)
+                })
+                .collect();
+            for (name, shim) in config.resolve.node_builtins.iter() {
+                shims.insert(Self::normalize_builtin(name).to_string(), shim.clone());
             }
-            // polyfill with empty modules
-            for name in Self::get_empty_modules().iter() {
-                // e.g. support fs and fs/promise
-                config
-                    .externals
-                    .insert(name.to_string(), ExternalConfig::Basic("".to_string()));
-                config.externals.insert(
-                    format!("{name}/promise"),
-                    ExternalConfig::Basic("".to_string()),
-                );
+            for (name, shim) in shims {
+                // e.g. node:path is the same as path
+                for source in [format!("node:{}", name), name] {
+                    if shim.is_empty() {
+                        config
+                            .externals
+                            .insert(source, ExternalConfig::Basic("".to_string()));
+                    } else {
+                        config.resolve.alias.push((source, shim.clone()));
+                    }
+                }
             }
             // polyfill identifiers
             config
@@ -65,6 +71,33 @@ impl Node {
         }
     }
 
+    // e.g. node:fs => fs
+    fn normalize_builtin(source: &str) -> &str {
+        source.strip_prefix("node:").unwrap_or(source)
+    }
+
+    // hint for Node.js builtin modules which can't be resolved in browser builds
+    pub fn unshimmed_builtin_hint(source: &str, config: &Config) -> Option<String> {
+        if config.platform != Platform::Browser {
+            return None;
+        }
+        let name = Self::normalize_builtin(source);
+        let module = name.split('/').next().unwrap();
+        if !Self::get_empty_modules().iter().any(|m| m == module)
+            || config
+                .resolve
+                .node_builtins
+                .keys()
+                .any(|k| Self::normalize_builtin(k) == name)
+        {
+            return None;
+        }
+        Some(format!(
+            "Node.js builtin module '{}' is not available in browsers, configure a shim with `resolve.nodeBuiltins`, e.g. {{ \"{}\": \"\" }} for an empty module",
+            name, name
+        ))
+    }
+
     fn get_polyfill_modules() -> Vec<String> {
         vec![
             "assert",
@@ -1,5 +1,5 @@
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 use anyhow::Result;
 
@@ -8,7 +8,7 @@ use crate::stats::StatsJsonMap;
 pub struct Analyze {}
 
 impl Analyze {
-    pub fn write_analyze(stats: &StatsJsonMap, path: &Path) -> Result<()> {
+    pub fn write_analyze(stats: &StatsJsonMap, path: &Path) -> Result<PathBuf> {
         let stats_json = serde_json::to_string_pretty(&stats).unwrap();
         let html_str = format!(
             r#"<!DOCTYPE html>
@@ -36,6 +36,32 @@ impl Analyze {
             "Analyze report generated at: {}",
             report_path.to_string_lossy()
         );
-        Ok(())
+        Ok(report_path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use crate::utils::test_helper::setup_compiler;
+
+    #[test]
+    fn test_analyze_report() {
+        let compiler = setup_compiler("test/build/analyze", false);
+        compiler.compile().unwrap();
+        let report_path = compiler
+            .context
+            .config
+            .output
+            .path
+            .join("analyze-report.html");
+        let report = fs::read_to_string(report_path).unwrap();
+        assert!(report.contains("window.chartData = {"));
+        // modules of the entry chunk and the async chunk
+        for module in ["src/index.js", "src/foo.js", "src/lazy.js"] {
+            let module_id = compiler.context.root.join(module);
+            assert!(report.contains(&format!(r#""{}""#, module_id.to_string_lossy())));
+        }
     }
 }
@@ -0,0 +1,75 @@
+use anyhow::Result;
+
+use crate::config::OutputConfig;
+use crate::generate::generate_chunks::ChunkFile;
+
+// prepend output.banner and append output.footer to the js and css files, the
+// sourceMappingURL comment is appended after the footer when the files are emitted
+pub fn add_banner_and_footer(chunk_files: &mut [ChunkFile], output: &OutputConfig) -> Result<()> {
+    if output.banner.is_none() && output.footer.is_none() {
+        return Ok(());
+    }
+    for chunk_file in chunk_files.iter_mut() {
+        if let Some(banner) = &output.banner {
+            let mut content = format!("{}\n", banner).into_bytes();
+            content.append(&mut chunk_file.content);
+            chunk_file.content = content;
+            if let Some(source_map) = &chunk_file.source_map {
+                let lines = banner.matches('\n').count() + 1;
+                chunk_file.source_map = Some(shift_source_map(source_map, lines)?);
+            }
+        }
+        if let Some(footer) = &output.footer {
+            chunk_file
+                .content
+                .extend_from_slice(format!("\n{}", footer).as_bytes());
+        }
+    }
+    Ok(())
+}
+
+// empty lines are prepended to the mappings, the positions of the segments are
+// relative to the previous ones, so they are kept
+fn shift_source_map(source_map: &[u8], lines: usize) -> Result<Vec<u8>> {
+    let mut source_map: serde_json::Value = serde_json::from_slice(source_map)?;
+    if let Some(serde_json::Value::String(mappings)) = source_map.get_mut("mappings") {
+        mappings.insert_str(0, &";".repeat(lines));
+    }
+    Ok(serde_json::to_vec(&source_map)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use swc_core::base::sourcemap::SourceMap;
+
+    use crate::utils::test_helper::setup_compiler;
+
+    #[test]
+    fn test_banner_and_footer() {
+        let compiler = setup_compiler("test/build/banner", false);
+        compiler.compile().unwrap();
+        let dist = compiler.context.config.output.path.clone();
+        let index = fs::read_to_string(dist.join("index.js")).unwrap();
+        let source_map = fs::read(dist.join("index.js.map")).unwrap();
+        let css = fs::read_to_string(dist.join("index.css")).unwrap();
+        fs::remove_dir_all(&dist).unwrap();
+
+        assert!(index.starts_with("/*!\n * banner v1.0.0\n */\n"));
+        assert!(css.starts_with("/*!\n * banner v1.0.0\n */\n"));
+        // the footer is above the sourceMappingURL comment
+        assert!(index.contains("\n/* footer */\n//# sourceMappingURL=index.js.map"));
+
+        // the generated position of the marker is still mapped to its original position
+        let source_map = SourceMap::from_slice(&source_map).unwrap();
+        let (line, column) = index
+            .lines()
+            .enumerate()
+            .find_map(|(line, code)| code.find("banner marker").map(|column| (line, column)))
+            .unwrap();
+        let token = source_map.lookup_token(line as u32, column as u32).unwrap();
+        assert!(token.get_source().unwrap().ends_with("src/index.js"));
+        assert_eq!(token.get_src_line(), 2);
+    }
+}
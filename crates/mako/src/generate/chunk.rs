@@ -31,6 +31,8 @@ pub enum ChunkType {
 pub struct Chunk {
     pub id: ChunkId,
     pub chunk_type: ChunkType,
+    // user specified name of async chunk, e.g. from webpackChunkName
+    pub name: Option<String>,
     pub modules: LinkedHashSet<ModuleId>,
     pub content: Option<String>,
     pub source_map: Option<String>,
@@ -55,6 +57,7 @@ impl Chunk {
             modules: LinkedHashSet::new(),
             id,
             chunk_type,
+            name: None,
             content: None,
             source_map: None,
         }
@@ -65,25 +68,16 @@ impl Chunk {
             ChunkType::Runtime => "runtime.js".into(),
             // foo/bar.tsx -> bar.js
             ChunkType::Entry(_, name, _) => format!("{}.js", name),
-            // foo/bar.tsx -> foo_bar_tsx-async.js
             ChunkType::Async | ChunkType::Sync | ChunkType::Worker(_) => {
+                // webpackChunkName: "foo" -> foo-async.js
+                // it's sanitized as the path, e.g. "../x" -> pd__x-async.js
+                if let (ChunkType::Async, Some(name)) = (&self.chunk_type, &self.name) {
+                    return named_async_filename(name);
+                }
+
+                // foo/bar.tsx -> foo_bar_tsx-async.js
                 let (path, search, ..) = parse_path(&self.id.id).unwrap();
-                let path = Path::new(&path);
-
-                let mut name = path
-                    .components()
-                    .filter(|c| !matches!(c, Component::RootDir | Component::CurDir))
-                    .map(|c| match c {
-                        Component::ParentDir => "pd_".to_string(),
-                        Component::Prefix(_) => "ps_".to_string(),
-                        Component::RootDir => "".to_string(),
-                        Component::CurDir => "".to_string(),
-                        Component::Normal(seg) => {
-                            seg.to_string_lossy().replace(['.', '?', '@'], "_")
-                        }
-                    })
-                    .collect::<Vec<String>>()
-                    .join("_");
+                let mut name = sanitize_path(Path::new(&path));
 
                 if !search.is_empty() {
                     let search_hash =
@@ -140,6 +134,26 @@ impl Chunk {
     }
 }
 
+// e.g. webpackChunkName: "pages/a" -> pages_a-async.js
+pub(crate) fn named_async_filename(name: &str) -> String {
+    format!("{}-async.js", sanitize_path(Path::new(name)))
+}
+
+// the components are joined with _, so the file name never escapes the output dir
+fn sanitize_path(path: &Path) -> String {
+    path.components()
+        .filter(|c| !matches!(c, Component::RootDir | Component::CurDir))
+        .map(|c| match c {
+            Component::ParentDir => "pd_".to_string(),
+            Component::Prefix(_) => "ps_".to_string(),
+            Component::RootDir => "".to_string(),
+            Component::CurDir => "".to_string(),
+            Component::Normal(seg) => seg.to_string_lossy().replace(['.', '?', '@'], "_"),
+        })
+        .collect::<Vec<String>>()
+        .join("_")
+}
+
 #[cfg(test)]
 mod tests {
     use crate::generate::chunk::{Chunk, ChunkType};
@@ -154,8 +168,12 @@ mod tests {
         );
         assert_eq!(chunk.filename(), "foo_bar.js");
 
-        let chunk = Chunk::new(ModuleId::new("./foo/bar.tsx".into()), ChunkType::Async);
+        let mut chunk = Chunk::new(ModuleId::new("./foo/bar.tsx".into()), ChunkType::Async);
         assert_eq!(chunk.filename(), "foo_bar_tsx-async.js");
+        chunk.name = Some("foo".to_string());
+        assert_eq!(chunk.filename(), "foo-async.js");
+        chunk.name = Some("../../x/y".to_string());
+        assert_eq!(chunk.filename(), "pd__pd__x_y-async.js");
 
         let chunk = Chunk::new(ModuleId::new("foo/bar.tsx".into()), ChunkType::Runtime);
         assert_eq!(chunk.filename(), "runtime.js");
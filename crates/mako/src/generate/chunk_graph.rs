@@ -3,14 +3,39 @@ use std::collections::{HashMap, HashSet};
 use std::hash::Hasher;
 
 use petgraph::stable_graph::{DefaultIx, NodeIndex, StableDiGraph};
-use petgraph::visit::Dfs;
+use petgraph::visit::{Dfs, EdgeRef, IntoEdgeReferences};
 use petgraph::Direction;
+use serde::Serialize;
 use twox_hash::XxHash64;
 
 use crate::generate::chunk::{Chunk, ChunkId, ChunkType};
 use crate::module::ModuleId;
 use crate::module_graph::ModuleGraph;
 
+// json schema of the chunk graph, keep it stable as ModuleGraphJson
+#[derive(Serialize, Debug)]
+pub struct ChunkGraphJson {
+    pub chunks: Vec<ChunkJson>,
+    pub dependencies: Vec<ChunkDependencyJson>,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ChunkJson {
+    pub id: String,
+    // entry, async, sync, worker or runtime
+    pub chunk_type: &'static str,
+    pub name: Option<String>,
+    pub filename: String,
+    pub modules: Vec<String>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct ChunkDependencyJson {
+    pub from: String,
+    pub to: String,
+}
+
 pub struct ChunkGraph {
     pub(crate) graph: StableDiGraph<Chunk, ()>,
     id_index_map: HashMap<ChunkId, NodeIndex<DefaultIx>>,
@@ -188,10 +213,167 @@ impl ChunkGraph {
         ret
     }
 
+    // chunks and dependencies are sorted by id, modules are in chunk order
+    pub fn to_json(&self) -> serde_json::Value {
+        let mut chunks = self
+            .get_chunks()
+            .into_iter()
+            .map(|chunk| ChunkJson {
+                id: chunk.id.id.clone(),
+                chunk_type: match chunk.chunk_type {
+                    ChunkType::Runtime => "runtime",
+                    ChunkType::Entry(..) => "entry",
+                    ChunkType::Async => "async",
+                    ChunkType::Sync => "sync",
+                    ChunkType::Worker(_) => "worker",
+                },
+                name: match &chunk.chunk_type {
+                    ChunkType::Entry(_, name, _) => Some(name.clone()),
+                    _ => chunk.name.clone(),
+                },
+                filename: chunk.filename(),
+                modules: chunk.modules.iter().map(|id| id.id.clone()).collect(),
+            })
+            .collect::<Vec<_>>();
+        chunks.sort_by(|a, b| a.id.cmp(&b.id));
+
+        let mut dependencies = self
+            .graph
+            .edge_references()
+            .map(|edge| ChunkDependencyJson {
+                from: self.graph[edge.source()].id.id.clone(),
+                to: self.graph[edge.target()].id.id.clone(),
+            })
+            .collect::<Vec<_>>();
+        dependencies.sort_by(|a, b| (&a.from, &a.to).cmp(&(&b.from, &b.to)));
+
+        serde_json::to_value(ChunkGraphJson {
+            chunks,
+            dependencies,
+        })
+        .unwrap()
+    }
+
     pub fn remove_chunk(&mut self, chunk_id: &ChunkId) {
         let idx = self.id_index_map.remove(chunk_id).unwrap();
         self.graph.remove_node(idx);
     }
+
+    pub fn chunk_size<F>(&self, chunk: &Chunk, module_size: F) -> usize
+    where
+        F: Fn(&ModuleId) -> usize,
+    {
+        chunk.modules.iter().map(module_size).sum()
+    }
+
+    // merge async chunks smaller than min_size into the chunk which imports them,
+    // chunks imported by more than one chunk are kept to avoid duplicated modules
+    pub fn merge_small_async_chunks<F>(&mut self, min_size: usize, module_size: F)
+    where
+        F: Fn(&ModuleId) -> usize,
+    {
+        let mut chunk_ids = self
+            .get_all_chunks()
+            .into_iter()
+            .filter(|c| c.chunk_type == ChunkType::Async)
+            .map(|c| c.id.clone())
+            .collect::<Vec<_>>();
+        chunk_ids.sort_by_key(|c| c.id.clone());
+
+        for chunk_id in chunk_ids {
+            let chunk = self.chunk(&chunk_id).unwrap();
+            if self.chunk_size(chunk, &module_size) >= min_size {
+                continue;
+            }
+            let dependents = self.dependents_chunk(&chunk_id);
+            if dependents.len() != 1
+                || !matches!(
+                    self.chunk(&dependents[0]).unwrap().chunk_type,
+                    ChunkType::Entry(_, _, _) | ChunkType::Async
+                )
+            {
+                continue;
+            }
+            let parent_id = &dependents[0];
+
+            // move modules and dependencies to the parent chunk
+            let modules = chunk.modules.clone();
+            let dependencies = self.dependencies_chunk(&chunk_id);
+            let parent = self.mut_chunk(parent_id).unwrap();
+            modules.into_iter().for_each(|m| parent.add_module(m));
+            for dependency in dependencies {
+                if dependency != *parent_id && !self.has_edge(parent_id, &dependency) {
+                    self.add_edge(parent_id, &dependency);
+                }
+            }
+            self.remove_chunk(&chunk_id);
+        }
+    }
+
+    // split async chunks larger than max_size into sync chunks at module boundaries,
+    // the root module (the last one) always stays in the async chunk
+    pub fn split_large_async_chunks<F>(&mut self, max_size: usize, module_size: F)
+    where
+        F: Fn(&ModuleId) -> usize,
+    {
+        let mut chunk_ids = self
+            .get_all_chunks()
+            .into_iter()
+            .filter(|c| {
+                c.chunk_type == ChunkType::Async && self.chunk_size(c, &module_size) > max_size
+            })
+            .map(|c| c.id.clone())
+            .collect::<Vec<_>>();
+        chunk_ids.sort_by_key(|c| c.id.clone());
+
+        for chunk_id in chunk_ids {
+            // collect modules greedily until the next one exceeds max_size
+            let mut groups: Vec<(usize, Vec<ModuleId>)> = vec![];
+            for module_id in self.chunk(&chunk_id).unwrap().modules.iter() {
+                let size = module_size(module_id);
+                match groups.last_mut() {
+                    Some((group_size, modules)) if *group_size + size <= max_size => {
+                        *group_size += size;
+                        modules.push(module_id.clone());
+                    }
+                    _ => groups.push((size, vec![module_id.clone()])),
+                }
+            }
+            // the last group contains the root module
+            groups.pop();
+
+            let mut split_count = 0;
+            for (_, modules) in groups {
+                let mut split_chunk_id = ChunkId::new(format!("{}_{}", chunk_id.id, split_count));
+                while self.has_chunk(&split_chunk_id) {
+                    split_count += 1;
+                    split_chunk_id = ChunkId::new(format!("{}_{}", chunk_id.id, split_count));
+                }
+                split_count += 1;
+
+                let chunk = self.mut_chunk(&chunk_id).unwrap();
+                modules.iter().for_each(|m| chunk.remove_module(m));
+                let mut split_chunk = Chunk::new(split_chunk_id.clone(), ChunkType::Sync);
+                modules.into_iter().for_each(|m| split_chunk.add_module(m));
+                self.add_chunk(split_chunk);
+                self.add_edge(&chunk_id, &split_chunk_id);
+            }
+        }
+    }
+
+    fn dependencies_chunk(&self, chunk_id: &ChunkId) -> Vec<ChunkId> {
+        let idx = self.id_index_map.get(chunk_id).unwrap();
+        self.graph
+            .neighbors_directed(*idx, Direction::Outgoing)
+            .map(|idx| self.graph[idx].id.clone())
+            .collect::<Vec<ChunkId>>()
+    }
+
+    fn has_edge(&self, from: &ChunkId, to: &ChunkId) -> bool {
+        let from = self.id_index_map.get(from).unwrap();
+        let to = self.id_index_map.get(to).unwrap();
+        self.graph.contains_edge(*from, *to)
+    }
 }
 
 impl Default for ChunkGraph {
@@ -211,3 +393,173 @@ impl fmt::Display for ChunkGraph {
         write!(f, "graph\n nodes:{:?}", &nodes)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_json() {
+        let mut chunk_graph = ChunkGraph::new();
+        let entry_id = ModuleId::new("index.js".into());
+        let mut entry = Chunk::new(
+            entry_id.clone(),
+            ChunkType::Entry(entry_id.clone(), "index".to_string(), false),
+        );
+        entry.add_module(entry_id.clone());
+        entry.add_module(ModuleId::new("a.js".into()));
+        chunk_graph.add_chunk(entry);
+        let async_id = ModuleId::new("b.js".into());
+        let mut async_chunk = Chunk::new(async_id.clone(), ChunkType::Async);
+        async_chunk.name = Some("b".to_string());
+        async_chunk.add_module(async_id.clone());
+        chunk_graph.add_chunk(async_chunk);
+        // empty chunks are ignored
+        chunk_graph.add_chunk(Chunk::new(ModuleId::new("c.js".into()), ChunkType::Sync));
+        chunk_graph.add_edge(&entry_id, &async_id);
+
+        assert_eq!(
+            serde_json::to_string(&chunk_graph.to_json()).unwrap(),
+            r#"{"chunks":[{"id":"b.js","chunkType":"async","name":"b","filename":"b-async.js","modules":["b.js"]},{"id":"index.js","chunkType":"entry","name":"index","filename":"index.js","modules":["index.js","a.js"]}],"dependencies":[{"from":"index.js","to":"b.js"}]}"#
+        );
+    }
+
+    #[test]
+    fn test_merge_small_async_chunks() {
+        // index -> small -> nested
+        //       -> big
+        // shared is imported by both small and big
+        let mut chunk_graph = build_chunk_graph(vec![
+            ("small.js", vec!["small.js"]),
+            ("nested.js", vec!["nested.js"]),
+            ("big.js", vec!["big_dep.js", "big.js"]),
+            ("shared.js", vec!["shared.js"]),
+        ]);
+        chunk_graph.add_edge(&"index.js".into(), &"small.js".into());
+        chunk_graph.add_edge(&"index.js".into(), &"big.js".into());
+        chunk_graph.add_edge(&"small.js".into(), &"nested.js".into());
+        chunk_graph.add_edge(&"small.js".into(), &"shared.js".into());
+        chunk_graph.add_edge(&"big.js".into(), &"shared.js".into());
+        let sizes = module_sizes(vec![
+            ("index.js", 100),
+            ("small.js", 10),
+            ("nested.js", 100),
+            ("big.js", 100),
+            ("big_dep.js", 100),
+            ("shared.js", 10),
+        ]);
+
+        chunk_graph.merge_small_async_chunks(50, |m| sizes[&m.id]);
+
+        assert_eq!(
+            chunk_modules(&chunk_graph),
+            vec![
+                ("big.js".to_string(), vec!["big_dep.js", "big.js"]),
+                ("index.js".to_string(), vec!["index.js", "small.js"]),
+                ("nested.js".to_string(), vec!["nested.js"]),
+                ("shared.js".to_string(), vec!["shared.js"]),
+            ]
+        );
+        // dependencies of the merged chunk are moved to the parent chunk
+        let mut dependencies = chunk_graph.dependencies_chunk(&"index.js".into());
+        dependencies.sort_by_key(|c| c.id.clone());
+        assert_eq!(
+            dependencies,
+            vec!["big.js".into(), "nested.js".into(), "shared.js".into()]
+        );
+        assert_no_module_lost_or_duplicated(&chunk_graph, &sizes);
+    }
+
+    #[test]
+    fn test_split_large_async_chunks() {
+        let mut chunk_graph = build_chunk_graph(vec![(
+            "big.js",
+            vec!["a.js", "b.js", "c.js", "d.js", "big.js"],
+        )]);
+        chunk_graph.add_edge(&"index.js".into(), &"big.js".into());
+        let sizes = module_sizes(vec![
+            ("index.js", 100),
+            ("a.js", 40),
+            ("b.js", 50),
+            ("c.js", 120),
+            ("d.js", 30),
+            ("big.js", 10),
+        ]);
+
+        chunk_graph.split_large_async_chunks(100, |m| sizes[&m.id]);
+
+        assert_eq!(
+            chunk_modules(&chunk_graph),
+            vec![
+                ("big.js".to_string(), vec!["d.js", "big.js"]),
+                ("big.js_0".to_string(), vec!["a.js", "b.js"]),
+                // a module larger than max_size is kept as a whole
+                ("big.js_1".to_string(), vec!["c.js"]),
+                ("index.js".to_string(), vec!["index.js"]),
+            ]
+        );
+        let mut dependencies = chunk_graph.sync_dependencies_chunk(&"big.js".into());
+        dependencies.sort_by_key(|c| c.id.clone());
+        assert_eq!(dependencies, vec!["big.js_0".into(), "big.js_1".into()]);
+        assert!(matches!(
+            chunk_graph.chunk(&"big.js_0".into()).unwrap().chunk_type,
+            ChunkType::Sync
+        ));
+        assert_no_module_lost_or_duplicated(&chunk_graph, &sizes);
+    }
+
+    // index.js is the entry chunk, the others are async chunks
+    fn build_chunk_graph(async_chunks: Vec<(&str, Vec<&str>)>) -> ChunkGraph {
+        let mut chunk_graph = ChunkGraph::new();
+        let entry_id: ModuleId = "index.js".into();
+        let mut entry = Chunk::new(
+            entry_id.clone(),
+            ChunkType::Entry(entry_id.clone(), "index".to_string(), false),
+        );
+        entry.add_module(entry_id);
+        chunk_graph.add_chunk(entry);
+        for (id, modules) in async_chunks {
+            let mut chunk = Chunk::new(id.into(), ChunkType::Async);
+            modules.into_iter().for_each(|m| chunk.add_module(m.into()));
+            chunk_graph.add_chunk(chunk);
+        }
+        chunk_graph
+    }
+
+    fn module_sizes(sizes: Vec<(&str, usize)>) -> HashMap<String, usize> {
+        sizes
+            .into_iter()
+            .map(|(id, size)| (id.to_string(), size))
+            .collect()
+    }
+
+    fn chunk_modules(chunk_graph: &ChunkGraph) -> Vec<(String, Vec<&str>)> {
+        let mut chunks = chunk_graph
+            .get_all_chunks()
+            .into_iter()
+            .map(|c| {
+                (
+                    c.id.id.clone(),
+                    c.modules.iter().map(|m| m.id.as_str()).collect::<Vec<_>>(),
+                )
+            })
+            .collect::<Vec<_>>();
+        chunks.sort();
+        chunks
+    }
+
+    fn assert_no_module_lost_or_duplicated(
+        chunk_graph: &ChunkGraph,
+        sizes: &HashMap<String, usize>,
+    ) {
+        let mut modules = chunk_graph
+            .get_all_chunks()
+            .into_iter()
+            .flat_map(|c| c.modules.iter().map(|m| m.id.clone()))
+            .collect::<Vec<_>>();
+        modules.sort();
+        let mut expected = sizes.keys().cloned().collect::<Vec<_>>();
+        expected.sort();
+        assert_eq!(modules, expected);
+    }
+}
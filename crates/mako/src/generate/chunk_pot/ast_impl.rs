@@ -65,14 +65,15 @@ pub(crate) fn render_css_chunk(
         transform_css_generate(&mut stylesheet, context);
     }
 
-    if context.config.minify && matches!(context.config.mode, Mode::Production) {
+    if context.config.minify.is_some() && matches!(context.config.mode, Mode::Production) {
         minify_css(&mut stylesheet, context)?;
     }
 
     let mut gen = CodeGenerator::new(
         css_writer,
         CodegenConfig {
-            minify: context.config.minify && matches!(context.config.mode, Mode::Production),
+            minify: context.config.minify.is_some()
+                && matches!(context.config.mode, Mode::Production),
         },
     );
     gen.emit(&stylesheet)?;
@@ -115,6 +116,7 @@ pub(crate) fn render_css_chunk(
         file_name: get_css_chunk_filename(&chunk_pot.js_name),
         chunk_id: chunk_pot.chunk_id.clone(),
         file_type: ChunkFileType::Css,
+        file_name_template: context.config.output.filename.clone(),
     })
 }
 
@@ -145,11 +147,12 @@ pub(crate) fn render_normal_js_chunk(
         path: "".to_string(),
     });
 
-    if context.config.minify && matches!(context.config.mode, Mode::Production) {
+    if context.config.minify.is_some() && matches!(context.config.mode, Mode::Production) {
         minify_js(&mut ast, context)?;
     }
 
     let (buf, source_map) = util::render_module_js(&ast.ast, context)?;
+    let source_map = util::merge_chunk_input_source_maps(source_map, chunk_pot, context);
 
     let hash = if context.config.hash {
         Some(file_content_hash(&buf))
@@ -165,6 +168,7 @@ pub(crate) fn render_normal_js_chunk(
         file_name: chunk_pot.js_name.clone(),
         chunk_id: chunk_pot.chunk_id.clone(),
         file_type: ChunkFileType::JS,
+        file_name_template: context.config.output.filename.clone(),
     })
 }
 
@@ -204,6 +208,7 @@ pub(crate) fn render_entry_js_chunk(
         file_name: pot.js_name.clone(),
         chunk_id: pot.chunk_id.clone(),
         file_type: ChunkFileType::JS,
+        file_name_template: context.config.output.filename.clone(),
     })
 }
 
@@ -300,11 +305,12 @@ fn render_entry_chunk_js_without_full_hash(
         ast.ast = wrap_in_iife(ast.ast);
     }
 
-    if context.config.minify && matches!(context.config.mode, Mode::Production) {
+    if context.config.minify.is_some() && matches!(context.config.mode, Mode::Production) {
         minify_js(&mut ast, context)?;
     }
 
     let (buf, source_map_buf) = util::render_module_js(&ast.ast, context)?;
+    let source_map_buf = util::merge_chunk_input_source_maps(source_map_buf, pot, context);
 
     let hash = if context.config.hash {
         crate::mako_profile_scope!("entryHash");
@@ -399,3 +405,27 @@ fn wrap_in_iife(module: SwcModule) -> SwcModule {
         span: DUMMY_SP,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use crate::utils::test_helper::setup_compiler;
+
+    #[test]
+    fn test_css_chunk_cache_of_different_builds() {
+        // the render cache is shared by the compilers of a process, chunks with the same id
+        // and the same number of css modules must not be served from the other build
+        let mut contents = vec![];
+        for fixture in ["a", "b"] {
+            let compiler =
+                setup_compiler(&format!("test/build/css-chunk-cache/{}", fixture), false);
+            compiler.compile().unwrap();
+            let output = compiler.context.config.output.path.clone();
+            contents.push(fs::read_to_string(output.join("index.css")).unwrap());
+            fs::remove_dir_all(&output).unwrap();
+        }
+        assert!(contents[0].contains(".a"), "{}", contents[0]);
+        assert!(contents[1].contains(".b"), "{}", contents[1]);
+    }
+}
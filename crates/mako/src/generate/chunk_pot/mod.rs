@@ -2,6 +2,7 @@ mod ast_impl;
 mod str_impl;
 pub mod util;
 
+use std::borrow::Cow;
 use std::collections::HashMap;
 use std::sync::Arc;
 use std::vec;
@@ -19,6 +20,7 @@ use crate::generate::generate_chunks::ChunkFile;
 use crate::module::{Module, ModuleAst, ModuleId};
 use crate::module_graph::ModuleGraph;
 use crate::ternary;
+use crate::visitors::css_imports::with_import_conditions;
 
 pub struct ChunkPot<'a> {
     pub chunk_id: String,
@@ -136,6 +138,29 @@ impl<'cp> ChunkPot<'cp> {
         Ok(files)
     }
 
+    // css file of the chunk rendered without the runtime, e.g. output.format esm
+    pub(crate) fn to_css_chunk_file(
+        &self,
+        chunk: &Chunk,
+        context: &Arc<Context>,
+    ) -> Result<Option<ChunkFile>> {
+        if self.stylesheet.is_none() {
+            return Ok(None);
+        }
+        ast_impl::render_css_chunk(self, chunk, context).map(Some)
+    }
+
+    // entry chunks are pushed with the entry module id when the runtime chunk is enabled,
+    // and executed by the runtime chunk
+    pub fn runtime_entry_module_id(&self, context: &Arc<Context>) -> Option<String> {
+        match &self.chunk_type {
+            ChunkType::Entry(module_id, _, false) if context.config.runtime_chunk().is_some() => {
+                Some(module_id.generate(context))
+            }
+            _ => None,
+        }
+    }
+
     fn use_chunk_parallel(&self, context: &Arc<Context>) -> bool {
         // parallel emit chunk when in watch mode
         context.config.chunk_parallel
@@ -150,7 +175,7 @@ impl<'cp> ChunkPot<'cp> {
     ) -> (JsModules<'a>, Option<CssModules<'a>>) {
         crate::mako_profile_function!(module_ids.len().to_string());
         let mut module_map: HashMap<String, (&Module, u64)> = Default::default();
-        let mut merged_css_modules: Vec<(String, &Stylesheet)> = vec![];
+        let mut merged_css_modules: Vec<(String, Cow<Stylesheet>)> = vec![];
 
         let mut module_raw_hash_map: HashMap<String, u64> = Default::default();
         let mut css_raw_hashes = vec![];
@@ -165,7 +190,7 @@ impl<'cp> ChunkPot<'cp> {
             let module_info = module.info.as_ref().unwrap();
             let ast = &module_info.ast;
 
-            if let ModuleAst::Script(_) = ast {
+            if let ModuleAst::Script(_) | ModuleAst::Json(_) = ast {
                 module_raw_hash_map.insert(module.id.id.clone(), module_info.raw_hash);
                 module_map.insert(module.id.generate(context), (module, module_info.raw_hash));
             }
@@ -173,8 +198,14 @@ impl<'cp> ChunkPot<'cp> {
             if let ModuleAst::Css(ast) = ast {
                 // not add empty css to chunk
                 if !ast.ast.rules.is_empty() {
-                    merged_css_modules.push((module.id.id.clone(), &ast.ast));
-                    css_raw_hashes.push(module_info.raw_hash);
+                    merged_css_modules.push((
+                        module.id.id.clone(),
+                        with_import_conditions(&module.id, &ast.ast, module_graph, context),
+                    ));
+                    // raw_hash is 0 when watch is off, the module id is hashed as well so that
+                    // the css render cache, which is shared by the builds of a process, doesn't
+                    // serve a chunk with the same id from another build
+                    css_raw_hashes.push((module.id.id.clone(), module_info.raw_hash));
                 }
             }
         });
@@ -220,7 +251,7 @@ struct JsModules<'a> {
 }
 
 pub struct CssModules<'a> {
-    stylesheets: Vec<&'a Stylesheet>,
+    stylesheets: Vec<Cow<'a, Stylesheet>>,
     raw_hash: u64,
 }
 
@@ -14,7 +14,7 @@ use crate::ast::sourcemap::{build_source_map, RawSourceMap};
 use crate::compiler::Context;
 use crate::generate::chunk::Chunk;
 use crate::generate::chunk_pot::ast_impl::{render_css_chunk, render_css_chunk_no_cache};
-use crate::generate::chunk_pot::util::runtime_code;
+use crate::generate::chunk_pot::util::{merge_chunk_input_source_maps, runtime_code};
 use crate::generate::chunk_pot::ChunkPot;
 use crate::generate::generate_chunks::{ChunkFile, ChunkFileType};
 use crate::module::{Module, ModuleAst};
@@ -93,10 +93,11 @@ pub(super) fn render_entry_js_chunk(
         raw_hash: hmr_hash,
         content,
         hash: None,
-        source_map: Some(source_map_buf),
+        source_map: merge_chunk_input_source_maps(Some(source_map_buf), pot, context),
         file_name: pot.js_name.clone(),
         chunk_id: pot.chunk_id.clone(),
         file_type: ChunkFileType::JS,
+        file_name_template: context.config.output.filename.clone(),
     })
 }
 
@@ -130,8 +131,18 @@ pub(super) fn render_normal_js_chunk(
         let mut source_map_buf: Vec<u8> = vec![];
         sourcemap::SourceMap::from(chunk_raw_sourcemap).to_writer(&mut source_map_buf)?;
 
+        let chunk_suffix_code = pot
+            .runtime_entry_module_id(context)
+            .map(|entry_module_id| {
+                format!(",\n{}", serde_json::to_string(&entry_module_id).unwrap())
+            })
+            .unwrap_or_default();
+
         (
-            format!("{}\n{}]);", chunk_prefix_code, chunk_content),
+            format!(
+                "{}\n{}{}]);",
+                chunk_prefix_code, chunk_content, chunk_suffix_code
+            ),
             source_map_buf,
         )
     };
@@ -140,10 +151,11 @@ pub(super) fn render_normal_js_chunk(
         raw_hash: chunk_pot.js_hash,
         content: content_buf.into(),
         hash: None,
-        source_map: Some(source_map_buf),
+        source_map: merge_chunk_input_source_maps(Some(source_map_buf), chunk_pot, context),
         file_name: chunk_pot.js_name.clone(),
         chunk_id: chunk_pot.chunk_id.clone(),
         file_type: ChunkFileType::JS,
+        file_name_template: context.config.output.filename.clone(),
     })
 }
 
@@ -208,6 +220,18 @@ fn emit_module_with_mapping(
             None,
         )),
 
+        ModuleAst::Json(value) => Ok((
+            format!(
+                r#""{}": function (module, exports, __mako_require__){{
+module.exports = {};
+}},
+"#,
+                module_id,
+                serde_json::to_string(value)?
+            ),
+            None,
+        )),
+
         ModuleAst::None => Err(anyhow!("ModuleAst::None({}) not supported", module_id)),
     }
 }
@@ -326,7 +350,7 @@ mod tests {
         let context = Arc::new(Context {
             config: Config {
                 mode: Mode::Development,
-                minify: true,
+                minify: Some(Default::default()),
                 ..Default::default()
             },
             args: Args { watch: true },
@@ -423,7 +447,7 @@ mod tests {
         let swc_comments = comments.get_swc_comments();
         {
             let with_minify =
-                context.config.minify && matches!(context.config.mode, Mode::Production);
+                context.config.minify.is_some() && matches!(context.config.mode, Mode::Production);
             let mut emitter = Emitter {
                 cfg: JsCodegenConfig::default()
                     .with_minify(with_minify)
@@ -19,12 +19,14 @@ use swc_core::ecma::codegen::{Config as JsCodegenConfig, Emitter};
 use swc_core::ecma::utils::{quote_ident, quote_str, ExprFactory};
 use twox_hash::XxHash64;
 
-use crate::ast::sourcemap::build_source_map_to_buf;
+use crate::ast::json_ast::json_to_module_stmt;
+use crate::ast::sourcemap::{build_source_map_to_buf, merge_input_source_maps};
 use crate::compiler::Context;
 use crate::config::{get_pkg_name, Mode};
 use crate::generate::chunk_pot::ChunkPot;
-use crate::generate::runtime::AppRuntimeTemplate;
+use crate::generate::runtime::{AppRuntimeTemplate, UmdExternals};
 use crate::module::{relative_to_root, Module, ModuleAst};
+use crate::resolve::get_global_externals;
 
 pub(crate) fn render_module_js(
     ast: &SwcModule,
@@ -35,7 +37,8 @@ pub(crate) fn render_module_js(
     let mut buf = vec![];
     let mut source_map_buf = Vec::new();
     let cm = context.meta.script.cm.clone();
-    let with_minify = context.config.minify && matches!(context.config.mode, Mode::Production);
+    let with_minify =
+        context.config.minify.is_some() && matches!(context.config.mode, Mode::Production);
     let comments = context.meta.script.origin_comments.read().unwrap();
     let swc_comments = comments.get_swc_comments();
 
@@ -67,6 +70,30 @@ pub(crate) fn render_module_js(
     Ok((buf, source_map))
 }
 
+// chain the chunk source map with input source maps of its modules
+pub(crate) fn merge_chunk_input_source_maps(
+    source_map: Option<Vec<u8>>,
+    pot: &ChunkPot,
+    context: &Arc<Context>,
+) -> Option<Vec<u8>> {
+    let source_map = source_map?;
+    let input_source_maps = pot
+        .module_map
+        .values()
+        .filter_map(|(module, _)| module.info.as_ref())
+        .flat_map(|info| info.input_source_maps())
+        .collect::<HashMap<_, _>>();
+    if input_source_maps.is_empty() {
+        return Some(source_map);
+    }
+    crate::mako_profile_scope!("merge_input_source_maps");
+    Some(merge_input_source_maps(
+        &source_map,
+        &input_source_maps,
+        &context.root,
+    ))
+}
+
 pub(crate) fn empty_module_fn_expr() -> FnExpr {
     let func = Function {
         span: DUMMY_SP,
@@ -94,21 +121,31 @@ pub(crate) fn empty_module_fn_expr() -> FnExpr {
 pub(crate) fn runtime_code(context: &Arc<Context>) -> Result<String> {
     let umd = context.config.umd.clone();
     let chunk_graph = context.chunk_graph.read().unwrap();
-    let has_dynamic_chunks = chunk_graph.get_all_chunks().len() > 1;
+    let runtime_chunk = context.config.runtime_chunk().is_some();
+    // entry chunks are registered with jsonp when the runtime chunk is enabled
+    let has_dynamic_chunks = chunk_graph.get_all_chunks().len() > 1 || runtime_chunk;
     let has_hmr = context.args.watch;
     let app_runtime = AppRuntimeTemplate {
         has_dynamic_chunks,
         has_hmr,
+        umd_externals: if umd.is_some() {
+            UmdExternals::new(&get_global_externals(&context.config.externals))
+        } else {
+            UmdExternals::default()
+        },
         umd,
         is_browser: matches!(context.config.platform, crate::config::Platform::Browser),
         cjs: context.config.cjs,
         chunk_loading_global: context.config.output.chunk_loading_global.clone(),
+        chunk_load_timeout: context.config.output.chunk_load_timeout,
+        chunk_load_retries: context.config.output.chunk_load_retries,
         pkg_name: get_pkg_name(&context.root),
         concatenate_enabled: context
             .config
             .optimization
             .as_ref()
             .map_or(false, |o| o.concatenate_modules.unwrap_or(false)),
+        runtime_chunk,
     };
     let app_runtime = app_runtime.render_once()?;
     let app_runtime = app_runtime.replace(
@@ -243,16 +280,16 @@ pub(crate) fn pot_to_chunk_module(
         .make_assign_to(AssignOp::Assign, chunk_global_expr.clone().as_pat_or_expr())
         .wrap_with_paren()
         .make_member::<Ident>(quote_ident!("push"));
+    // [[ "module id"], { module object }, "entry module id"?]
+    let mut chunk_data = vec![
+        to_array_lit(vec![quote_str!(pot.chunk_id.clone()).as_arg()]).as_arg(),
+        module_object.as_arg(),
+    ];
+    if let Some(entry_module_id) = pot.runtime_entry_module_id(context) {
+        chunk_data.push(quote_str!(entry_module_id).as_arg());
+    }
     let chunk_register_stmt = chunk_global_obj
-        .as_call(
-            DUMMY_SP,
-            // [[ "module id"], { module object }]
-            vec![to_array_lit(vec![
-                to_array_lit(vec![quote_str!(pot.chunk_id.clone()).as_arg()]).as_arg(),
-                module_object.as_arg(),
-            ])
-            .as_arg()],
-        )
+        .as_call(DUMMY_SP, vec![to_array_lit(chunk_data).as_arg()])
         .into_stmt();
 
     Ok(SwcModule {
@@ -315,15 +352,21 @@ fn to_module_fn_expr(module: &Module) -> Result<FnExpr> {
         }
         // TODO: css module should be removed
         ModuleAst::Css(_) => Ok(empty_module_fn_expr()),
+        ModuleAst::Json(value) => {
+            let mut fn_expr = empty_module_fn_expr();
+            if let Some(body) = fn_expr.function.body.as_mut() {
+                body.stmts.push(json_to_module_stmt(value));
+            }
+            Ok(fn_expr)
+        }
         ModuleAst::None => Err(anyhow!("ModuleAst::None({}) cannot concert", module.id.id)),
     }
 }
 
 pub const CHUNK_FILE_NAME_HASH_LENGTH: usize = 8;
 
+// the full hash is kept, it's truncated when rendering the file name
 pub fn file_content_hash<T: AsRef<[u8]>>(content: T) -> String {
     let digest = md5::compute(content);
-    let mut hash = format!("{:x}", digest);
-    hash.truncate(CHUNK_FILE_NAME_HASH_LENGTH);
-    hash
+    format!("{:x}", digest)
 }
@@ -0,0 +1,1597 @@
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::path::Path;
+use std::sync::Arc;
+
+use anyhow::{anyhow, Result};
+use indexmap::IndexMap;
+use nanoid::nanoid;
+use pathdiff::diff_paths;
+use swc_core::common::{Mark, SyntaxContext, DUMMY_SP, GLOBALS};
+use swc_core::ecma::ast::{
+    ArrowExpr, BlockStmt, BlockStmtOrExpr, CallExpr, Callee, ClassDecl, Decl, DefaultDecl,
+    ExportNamedSpecifier, ExportSpecifier, Expr, ExprOrSpread, FnDecl, FnExpr, Function,
+    GetterProp, Id, Ident, Import, ImportDecl, ImportNamedSpecifier, ImportSpecifier,
+    ImportStarAsSpecifier, KeyValueProp, Lit, MemberProp, Module as SwcModule, ModuleDecl,
+    ModuleExportName, ModuleItem, NamedExport, Null, ObjectLit, Param, Pat, Prop, PropName,
+    PropOrSpread, ReturnStmt, Stmt, Str, VarDeclKind,
+};
+use swc_core::ecma::transforms::base::fixer::fixer;
+use swc_core::ecma::transforms::base::hygiene::hygiene;
+use swc_core::ecma::transforms::base::resolver;
+use swc_core::ecma::utils::{
+    find_pat_ids, member_expr, private_ident, quote_ident, quote_str, undefined, ExprFactory,
+};
+use swc_core::ecma::visit::{Visit, VisitMut, VisitMutWith, VisitWith};
+use swc_core::quote;
+
+use crate::ast::js_ast::JsAst;
+use crate::ast::json_ast::json_to_expr;
+use crate::ast::utils::{
+    get_first_str_arg, is_commonjs_require, is_commonjs_require_resolve, is_dynamic_import,
+    is_import_meta_url,
+};
+use crate::compiler::{Compiler, Context};
+use crate::config::{Mode, Platform};
+use crate::generate::chunk::{Chunk, ChunkId, ChunkType};
+use crate::generate::chunk_graph::ChunkGraph;
+use crate::generate::chunk_pot::util::{
+    file_content_hash, merge_chunk_input_source_maps, render_module_js,
+};
+use crate::generate::chunk_pot::ChunkPot;
+use crate::generate::generate_chunks::{
+    hash_file_name, ChunkFile, ChunkFileType, CONTENT_HASH_LENGTH,
+};
+use crate::generate::minify::minify_js;
+use crate::module::{Module, ModuleAst, ModuleId, ResolveType};
+use crate::module_graph::ModuleGraph;
+use crate::resolve::is_global_external;
+
+// output.format "esm"
+// modules of a chunk are hoisted into one scope with their imports linked to the bindings,
+// chunks import the bindings of each other with import statements,
+// and dynamic imports are kept as native import() of the chunk files
+impl Compiler {
+    pub fn generate_esm_chunk_files(&self) -> Result<Vec<ChunkFile>> {
+        crate::mako_profile_function!();
+        let context = &self.context;
+        let module_graph = context.module_graph.read().unwrap();
+        let chunk_graph = context.chunk_graph.read().unwrap();
+
+        let mut chunk_files = GLOBALS.set(&context.meta.script.globals, || -> Result<_> {
+            let linker = Linker::new(&module_graph, &chunk_graph, context)?;
+            let mut chunk_files = vec![];
+            for chunk in chunk_graph.get_chunks() {
+                chunk_files.extend(linker.render_chunk(chunk)?);
+            }
+            Ok(chunk_files)
+        })?;
+
+        // chunks may import each other, so the content hash is computed with the file names of
+        // the imported chunks hashed by their modules instead of the random placeholders
+        if context.config.hash {
+            let stable_names = chunk_files
+                .iter()
+                .filter(|chunk_file| matches!(chunk_file.file_type, ChunkFileType::JS))
+                .map(|chunk_file| {
+                    let stable_name = hash_file_name(
+                        &chunk_file.file_name,
+                        &chunk_file.chunk_id,
+                        Some(&format!("{:x}", chunk_file.raw_hash)),
+                        chunk_file.file_name_template.as_deref(),
+                    );
+                    (chunk_file.disk_name(), stable_name)
+                })
+                .collect::<Vec<_>>();
+            let mut replacer = vec![];
+            for chunk_file in chunk_files.iter_mut() {
+                if let ChunkFileType::JS = chunk_file.file_type {
+                    let placeholder = chunk_file.disk_name();
+                    let content = replace_names(&chunk_file.content, &stable_names)?;
+                    chunk_file.hash = Some(file_content_hash(content));
+                    replacer.push((placeholder, chunk_file.disk_name()));
+                }
+            }
+            for chunk_file in chunk_files.iter_mut() {
+                if let ChunkFileType::JS = chunk_file.file_type {
+                    chunk_file.content = replace_names(&chunk_file.content, &replacer)?;
+                }
+            }
+        }
+        Ok(chunk_files)
+    }
+}
+
+fn replace_names(content: &[u8], names: &[(String, String)]) -> Result<Vec<u8>> {
+    let mut content = String::from_utf8(content.to_vec())?;
+    for (from, to) in names {
+        content = content.replace(from, to);
+    }
+    Ok(content.into_bytes())
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+enum Source {
+    Module(ModuleId),
+    // externals and unresolved sources are kept as imports
+    External(String),
+}
+
+impl Source {
+    fn name(&self) -> &str {
+        match self {
+            Source::Module(module_id) => &module_id.id,
+            Source::External(source) => source,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+enum Export {
+    Local(Ident),
+    // source and the export name in it, * is the namespace
+    ReExport(Source, String),
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+enum Target {
+    // a top-level binding of the module
+    Binding(ModuleId, Id),
+    Namespace(ModuleId),
+    // the require function of a commonjs module
+    Require(ModuleId),
+    // an export of a commonjs module, which is read from module.exports, * is the namespace
+    CommonJs(ModuleId, String),
+    External(String, String),
+    Missing,
+}
+
+impl Target {
+    // the binding shared between the chunks for the target
+    fn binding(&self) -> Target {
+        match self {
+            Target::CommonJs(module_id, _) => Target::Require(module_id.clone()),
+            target => target.clone(),
+        }
+    }
+}
+
+#[derive(Default)]
+struct EsModule {
+    // statements without imports and exports
+    items: Vec<ModuleItem>,
+    // modules without imports and exports are wrapped in a factory like the runtime does
+    is_commonjs: bool,
+    unresolved_mark: Mark,
+    exports: IndexMap<String, Export>,
+    stars: Vec<Source>,
+    // local => (source, imported name), * is the namespace
+    imports: IndexMap<Id, (Source, String)>,
+    // static dependencies in order
+    requires: Vec<Source>,
+    dynamic_imports: IndexMap<String, Source>,
+    // require() and eager import.meta.glob, which are evaluated when they are called
+    require_calls: IndexMap<String, Source>,
+    require_resolves: HashMap<String, ModuleId>,
+    workers: HashMap<String, ModuleId>,
+    url_assets: HashMap<String, String>,
+}
+
+struct Linker<'a> {
+    context: &'a Arc<Context>,
+    module_graph: &'a ModuleGraph,
+    chunk_graph: &'a ChunkGraph,
+    modules: HashMap<ModuleId, EsModule>,
+    // placeholders of the content hash, which are replaced after all chunks are rendered
+    placeholders: HashMap<ChunkId, String>,
+    // disk names of the chunks with the placeholders
+    file_names: HashMap<ChunkId, String>,
+    // namespace objects of the modules
+    namespaces: IndexMap<ModuleId, Ident>,
+    // es modules required by commonjs modules, whose namespaces are marked with __esModule
+    required: HashSet<ModuleId>,
+    // require functions of the commonjs modules
+    factories: HashMap<ModuleId, Ident>,
+    // commonjs modules imported statically, which are evaluated in the order of the imports
+    eager: HashSet<ModuleId>,
+    // aliases exported by the chunks for the bindings used in other chunks
+    aliases: HashMap<ChunkId, IndexMap<Target, String>>,
+}
+
+impl<'a> Linker<'a> {
+    fn new(
+        module_graph: &'a ModuleGraph,
+        chunk_graph: &'a ChunkGraph,
+        context: &'a Arc<Context>,
+    ) -> Result<Self> {
+        let chunks = chunk_graph.get_chunks();
+        let mut modules = HashMap::new();
+        let mut placeholders = HashMap::new();
+        let mut file_names = HashMap::new();
+        for chunk in &chunks {
+            // the placeholder has the same length as the content hash
+            let placeholder = context.config.hash.then(|| nanoid!(CONTENT_HASH_LENGTH));
+            file_names.insert(
+                chunk.id.clone(),
+                hash_file_name(
+                    &chunk.filename(),
+                    &chunk.id.id,
+                    placeholder.as_deref(),
+                    context.config.output.filename.as_deref(),
+                ),
+            );
+            if let Some(placeholder) = placeholder {
+                placeholders.insert(chunk.id.clone(), placeholder);
+            }
+            for module_id in chunk.get_modules() {
+                if modules.contains_key(module_id) {
+                    continue;
+                }
+                let module = module_graph.get_module(module_id).unwrap();
+                modules.insert(
+                    module_id.clone(),
+                    to_es_module(module, module_graph, context)?,
+                );
+            }
+        }
+
+        let factories = modules
+            .iter()
+            .filter(|(_, module)| module.is_commonjs)
+            .map(|(module_id, _)| {
+                let require = format!("require_{}", module_alias(module_id, context));
+                (module_id.clone(), private_ident!(require))
+            })
+            .collect::<HashMap<_, _>>();
+        let mut eager = modules
+            .values()
+            .flat_map(|module| &module.requires)
+            .filter_map(|source| match source {
+                Source::Module(module_id) if factories.contains_key(module_id) => {
+                    Some(module_id.clone())
+                }
+                _ => None,
+            })
+            .collect::<HashSet<_>>();
+        let mut linker = Self {
+            context,
+            module_graph,
+            chunk_graph,
+            modules,
+            placeholders,
+            file_names,
+            namespaces: Default::default(),
+            required: Default::default(),
+            factories,
+            eager: Default::default(),
+            aliases: Default::default(),
+        };
+        for chunk in &chunks {
+            if let Some(root) = linker.chunk_root(chunk)
+                && linker.factories.contains_key(root)
+            {
+                eager.insert(root.clone());
+            }
+        }
+        linker.eager = eager;
+        // collect the namespaces and aliases to be exported before rendering
+        for chunk in &chunks {
+            for module_id in chunk.get_modules() {
+                let module = &linker.modules[module_id];
+                let mut targets = vec![];
+                for (source, name) in module
+                    .imports
+                    .values()
+                    .map(|(source, name)| (source, name))
+                    .chain(module.exports.values().filter_map(|export| match export {
+                        Export::ReExport(source, name) => Some((source, name)),
+                        Export::Local(_) => None,
+                    }))
+                {
+                    let target = linker.resolve(source, name);
+                    if target == Target::Missing {
+                        return Err(anyhow!(
+                            "\"{}\" is not exported by {}, imported by {}",
+                            name,
+                            source.name(),
+                            module_id.id
+                        ));
+                    }
+                    targets.push(target);
+                }
+                let requires = module
+                    .require_calls
+                    .values()
+                    .map(|source| linker.resolve(source, "*"))
+                    .collect::<Vec<_>>();
+                let dynamic_imports = module
+                    .dynamic_imports
+                    .values()
+                    .filter_map(|source| match source {
+                        Source::Module(module_id) => Some(module_id.clone()),
+                        Source::External(_) => None,
+                    })
+                    .collect::<Vec<_>>();
+                for target in targets {
+                    linker.request(chunk, &target)?;
+                }
+                for target in requires {
+                    if let Target::Namespace(module_id) = &target {
+                        linker.required.insert(module_id.clone());
+                    }
+                    linker.request(chunk, &target)?;
+                }
+                for module_id in dynamic_imports {
+                    let owner = linker.owner(&module_id, chunk)?;
+                    if owner == chunk.id || !linker.is_esm_root(&owner, &module_id) {
+                        let target = linker.resolve(&Source::Module(module_id), "*");
+                        linker.request(chunk, &target)?;
+                    }
+                }
+            }
+            if let Some(root) = linker.chunk_root(chunk) {
+                let root = Source::Module(root.clone());
+                for name in linker.export_names(&root, &mut HashSet::new()) {
+                    let target = linker.resolve(&root, &name);
+                    linker.request(chunk, &target)?;
+                }
+            }
+        }
+        Ok(linker)
+    }
+
+    // the target is used in the chunk
+    fn request(&mut self, chunk: &Chunk, target: &Target) -> Result<()> {
+        let target = &target.binding();
+        let module_id = match target {
+            Target::Binding(module_id, _) | Target::Require(module_id) => module_id,
+            Target::Namespace(module_id) => {
+                if !self.namespaces.contains_key(module_id) {
+                    self.namespaces
+                        .insert(module_id.clone(), private_ident!("namespace"));
+                    let source = Source::Module(module_id.clone());
+                    let targets = self
+                        .export_names(&source, &mut HashSet::new())
+                        .into_iter()
+                        .map(|name| self.resolve(&source, &name))
+                        .collect::<Vec<_>>();
+                    // the namespace object is declared in the chunks of the module
+                    let chunks = self
+                        .chunk_graph
+                        .get_chunks()
+                        .into_iter()
+                        .filter(|chunk| chunk.has_module(module_id))
+                        .collect::<Vec<_>>();
+                    for chunk in chunks {
+                        for target in &targets {
+                            self.request(chunk, target)?;
+                        }
+                    }
+                }
+                module_id
+            }
+            Target::CommonJs(..) | Target::External(..) | Target::Missing => return Ok(()),
+        };
+        let owner = self.owner(module_id, chunk)?;
+        if owner == chunk.id {
+            return Ok(());
+        }
+        let aliases = self.aliases.entry(owner).or_default();
+        if !aliases.contains_key(target) {
+            let base = match target {
+                Target::Binding(_, (sym, _)) => {
+                    format!("{}${}", module_alias(module_id, self.context), sym)
+                }
+                Target::Require(_) => format!("{}$require", module_alias(module_id, self.context)),
+                _ => format!("{}$namespace", module_alias(module_id, self.context)),
+            };
+            let mut alias = base.clone();
+            let mut i = 1;
+            while aliases.values().any(|a| a == &alias) {
+                alias = format!("{}{}", base, i);
+                i += 1;
+            }
+            aliases.insert(target.clone(), alias);
+        }
+        Ok(())
+    }
+
+    // the module whose exports are the exports of the chunk
+    fn chunk_root<'c>(&self, chunk: &'c Chunk) -> Option<&'c ModuleId> {
+        match &chunk.chunk_type {
+            ChunkType::Entry(module_id, ..) | ChunkType::Worker(module_id) => Some(module_id),
+            ChunkType::Async => chunk
+                .get_modules()
+                .iter()
+                .find(|module_id| module_id.generate(self.context) == chunk.id.id),
+            _ => None,
+        }
+    }
+
+    fn chunk(&self, chunk_id: &ChunkId) -> &Chunk {
+        self.chunk_graph.chunk(chunk_id).unwrap()
+    }
+
+    // the es module whose exports are the exports of the chunk, which is imported as is
+    fn is_esm_root(&self, chunk_id: &ChunkId, module_id: &ModuleId) -> bool {
+        self.chunk_root(self.chunk(chunk_id)) == Some(module_id)
+            && !self.factories.contains_key(module_id)
+    }
+
+    // the chunk where the module is used from
+    fn owner(&self, module_id: &ModuleId, chunk: &Chunk) -> Result<ChunkId> {
+        if chunk.has_module(module_id) {
+            return Ok(chunk.id.clone());
+        }
+        self.chunk_graph
+            .get_chunk_for_module(module_id)
+            .map(|chunk| chunk.id.clone())
+            .ok_or_else(|| anyhow!("{} is not in any chunk", module_id.id))
+    }
+
+    fn resolve(&self, source: &Source, name: &str) -> Target {
+        self.resolve_export(source, name, &mut HashSet::new())
+    }
+
+    fn resolve_export(
+        &self,
+        source: &Source,
+        name: &str,
+        visited: &mut HashSet<(ModuleId, String)>,
+    ) -> Target {
+        let module_id = match source {
+            Source::External(source) => {
+                return Target::External(source.clone(), name.to_string());
+            }
+            Source::Module(module_id) => module_id,
+        };
+        if self.factories.contains_key(module_id) {
+            return Target::CommonJs(module_id.clone(), name.to_string());
+        }
+        if name == "*" {
+            return Target::Namespace(module_id.clone());
+        }
+        if !visited.insert((module_id.clone(), name.to_string())) {
+            return Target::Missing;
+        }
+        let Some(module) = self.modules.get(module_id) else {
+            return Target::Missing;
+        };
+        match module.exports.get(name) {
+            Some(Export::Local(ident)) => match module.imports.get(&ident.to_id()) {
+                Some((source, name)) => self.resolve_export(source, name, visited),
+                None => Target::Binding(module_id.clone(), ident.to_id()),
+            },
+            Some(Export::ReExport(source, name)) => self.resolve_export(source, name, visited),
+            None if name != "default" => module
+                .stars
+                .iter()
+                .map(|source| self.resolve_export(source, name, visited))
+                .find(|target| target != &Target::Missing)
+                .unwrap_or(Target::Missing),
+            None => Target::Missing,
+        }
+    }
+
+    fn export_names(&self, source: &Source, visited: &mut HashSet<ModuleId>) -> BTreeSet<String> {
+        let mut names = BTreeSet::new();
+        if let Source::Module(module_id) = source
+            && visited.insert(module_id.clone())
+            && let Some(module) = self.modules.get(module_id)
+        {
+            // module.exports of the commonjs modules
+            if module.is_commonjs {
+                names.insert("default".to_string());
+            }
+            names.extend(module.exports.keys().cloned());
+            for star in &module.stars {
+                names.extend(
+                    self.export_names(star, visited)
+                        .into_iter()
+                        .filter(|name| name != "default"),
+                );
+            }
+        }
+        names
+    }
+
+    // specifier to import the chunk from another chunk
+    fn specifier(&self, from: &ChunkId, to: &ChunkId) -> String {
+        relative_specifier(&self.file_names[from], &self.file_names[to])
+    }
+
+    // url of the asset for new URL(url, import.meta.url) in the chunk
+    fn asset_url(&self, chunk: &Chunk, file_name: &str) -> String {
+        let public_path = &self.context.config.public_path;
+        if matches!(self.context.config.platform, Platform::Browser) && public_path != "auto" {
+            format!("{}{}", public_path, file_name)
+        } else {
+            relative_specifier(&self.file_names[&chunk.id], file_name)
+        }
+    }
+
+    fn target_ident(
+        &self,
+        chunk: &Chunk,
+        target: &Target,
+        imports: &mut ChunkImports,
+    ) -> Result<Option<Ident>> {
+        let module_id = match target {
+            Target::Binding(module_id, _)
+            | Target::Namespace(module_id)
+            | Target::Require(module_id) => module_id,
+            Target::External(source, name) => return Ok(Some(imports.import(source, name))),
+            Target::CommonJs(..) | Target::Missing => return Ok(None),
+        };
+        if chunk.has_module(module_id) {
+            return Ok(Some(match target {
+                Target::Binding(_, (sym, ctxt)) => {
+                    Ident::new(sym.clone(), DUMMY_SP.with_ctxt(*ctxt))
+                }
+                Target::Require(_) => self.factories[module_id].clone(),
+                _ => self.namespaces[module_id].clone(),
+            }));
+        }
+        let owner = self.owner(module_id, chunk)?;
+        let alias = self
+            .aliases
+            .get(&owner)
+            .and_then(|aliases| aliases.get(target))
+            .ok_or_else(|| anyhow!("{:?} is not exported by chunk {}", target, owner.id))?;
+        Ok(Some(
+            imports.import(&self.specifier(&chunk.id, &owner), alias),
+        ))
+    }
+
+    fn target_expr(
+        &self,
+        chunk: &Chunk,
+        target: &Target,
+        imports: &mut ChunkImports,
+    ) -> Result<Expr> {
+        let Target::CommonJs(_, name) = target else {
+            return Ok(self
+                .target_ident(chunk, target, imports)?
+                .map_or_else(|| *undefined(DUMMY_SP), Expr::Ident));
+        };
+        // interop of the module.exports like the runtime does,
+        // the named exports are read from module.exports directly
+        let exports = self.require_expr(chunk, target, imports)?;
+        Ok(match name.as_str() {
+            "*" | "default" => {
+                let namespace = imports
+                    .helper(Helper::ToEsm)
+                    .as_call(DUMMY_SP, vec![exports.as_arg()]);
+                if name == "*" {
+                    namespace
+                } else {
+                    namespace.make_member(quote_ident!("default"))
+                }
+            }
+            name if is_identifier_name(name) => exports.make_member(quote_ident!(name)),
+            name => exports.computed_member(quote_str!(name)),
+        })
+    }
+
+    // require() of the target, which is the module.exports of the commonjs modules
+    // and the namespaces of the es modules
+    fn require_expr(
+        &self,
+        chunk: &Chunk,
+        target: &Target,
+        imports: &mut ChunkImports,
+    ) -> Result<Expr> {
+        match target {
+            Target::CommonJs(module_id, _) => {
+                let require = self
+                    .target_ident(chunk, &Target::Require(module_id.clone()), imports)?
+                    .unwrap();
+                Ok(require.as_call(DUMMY_SP, vec![]))
+            }
+            target => self.target_expr(chunk, target, imports),
+        }
+    }
+
+    fn dynamic_import_expr(
+        &self,
+        chunk: &Chunk,
+        module_id: &ModuleId,
+        imports: &mut ChunkImports,
+    ) -> Result<Expr> {
+        let owner = self.owner(module_id, chunk)?;
+        let target = self.resolve(&Source::Module(module_id.clone()), "*");
+        // Promise.resolve(namespace)
+        if owner == chunk.id {
+            let namespace = self.target_expr(chunk, &target, imports)?;
+            return Ok(
+                member_expr!(DUMMY_SP, Promise.resolve).as_call(DUMMY_SP, vec![namespace.as_arg()])
+            );
+        }
+        let import = Expr::Call(CallExpr {
+            span: DUMMY_SP,
+            callee: Callee::Import(Import { span: DUMMY_SP }),
+            args: vec![quote_str!(self.specifier(&chunk.id, &owner)).as_arg()],
+            type_args: None,
+        });
+        if self.is_esm_root(&owner, module_id) {
+            return Ok(import);
+        }
+        // import("./chunk.js").then((m) => m.alias)
+        // or import("./chunk.js").then((m) => toEsm(m.alias())) for the commonjs modules
+        let alias = &self.aliases[&owner][&target.binding()];
+        let m = private_ident!("m");
+        let mut value = m.clone().make_member(quote_ident!(alias.as_str()));
+        if let Target::CommonJs(..) = target {
+            value = imports
+                .helper(Helper::ToEsm)
+                .as_call(DUMMY_SP, vec![value.as_call(DUMMY_SP, vec![]).as_arg()]);
+        }
+        let then = ArrowExpr {
+            span: DUMMY_SP,
+            params: vec![m.into()],
+            body: Box::new(BlockStmtOrExpr::Expr(Box::new(value))),
+            is_async: false,
+            is_generator: false,
+            type_params: None,
+            return_type: None,
+        };
+        Ok(import
+            .make_member(quote_ident!("then"))
+            .as_call(DUMMY_SP, vec![then.as_arg()]))
+    }
+
+    // modules are evaluated after their dependencies in the chunk
+    fn evaluation_order(&self, chunk: &Chunk) -> Vec<ModuleId> {
+        fn visit(
+            linker: &Linker,
+            module_id: &ModuleId,
+            chunk: &Chunk,
+            visited: &mut HashSet<ModuleId>,
+            order: &mut Vec<ModuleId>,
+        ) {
+            if !chunk.has_module(module_id) || !visited.insert(module_id.clone()) {
+                return;
+            }
+            let module = &linker.modules[module_id];
+            for source in module.requires.iter().chain(module.require_calls.values()) {
+                if let Source::Module(dep) = source {
+                    visit(linker, dep, chunk, visited, order);
+                }
+            }
+            order.push(module_id.clone());
+        }
+
+        let mut visited = HashSet::new();
+        let mut order = vec![];
+        for module_id in self
+            .chunk_root(chunk)
+            .into_iter()
+            .chain(chunk.get_modules())
+        {
+            visit(self, module_id, chunk, &mut visited, &mut order);
+        }
+        order
+    }
+
+    fn render_chunk(&self, chunk: &Chunk) -> Result<Vec<ChunkFile>> {
+        crate::mako_profile_function!(&chunk.id.id);
+        let mut imports = ChunkImports::default();
+        let mut body = vec![];
+
+        for module_id in self.evaluation_order(chunk) {
+            let module = &self.modules[&module_id];
+            // dependencies in other chunks are evaluated first
+            for source in &module.requires {
+                match source {
+                    Source::Module(dep) if !chunk.has_module(dep) => {
+                        let owner = self.owner(dep, chunk)?;
+                        imports.require(&self.specifier(&chunk.id, &owner));
+                    }
+                    Source::External(source) => imports.require(source),
+                    _ => {}
+                }
+            }
+
+            let mut bindings = HashMap::new();
+            for (local, (source, name)) in &module.imports {
+                let target = self.resolve(source, name);
+                bindings.insert(
+                    local.clone(),
+                    self.target_expr(chunk, &target, &mut imports)?,
+                );
+            }
+            let mut dynamic_imports = HashMap::new();
+            for (source, dep) in &module.dynamic_imports {
+                if let Source::Module(dep) = dep {
+                    dynamic_imports.insert(
+                        source.clone(),
+                        self.dynamic_import_expr(chunk, dep, &mut imports)?,
+                    );
+                }
+            }
+            let mut requires = HashMap::new();
+            for (source, dep) in &module.require_calls {
+                let target = self.resolve(dep, "*");
+                requires.insert(
+                    source.clone(),
+                    self.require_expr(chunk, &target, &mut imports)?,
+                );
+            }
+            let require_resolves = module
+                .require_resolves
+                .iter()
+                .map(|(source, dep)| (source.clone(), quote_str!(dep.generate(self.context))))
+                .collect::<HashMap<_, _>>();
+            let mut urls = HashMap::new();
+            for (source, dep) in &module.workers {
+                let worker = ChunkId::from(dep.generate(self.context));
+                let file_name = self
+                    .file_names
+                    .get(&worker)
+                    .ok_or_else(|| anyhow!("worker chunk of {} is not found", dep.id))?;
+                urls.insert(
+                    source.clone(),
+                    relative_specifier(&self.file_names[&chunk.id], file_name),
+                );
+            }
+            for (source, file_name) in &module.url_assets {
+                urls.insert(source.clone(), self.asset_url(chunk, file_name));
+            }
+            let mut items = module.items.clone();
+            items.visit_mut_with(&mut LinkReplacer {
+                unresolved_mark: module.unresolved_mark,
+                bindings: &bindings,
+                dynamic_imports: &dynamic_imports,
+                requires: &requires,
+                require_resolves: &require_resolves,
+                urls: &urls,
+            });
+            if let Some(require) = self.factories.get(&module_id) {
+                body.push(factory_decl(
+                    require,
+                    items,
+                    module.unresolved_mark,
+                    &mut imports,
+                ));
+                if self.eager.contains(&module_id) {
+                    body.push(require.clone().as_call(DUMMY_SP, vec![]).into_stmt().into());
+                }
+            } else {
+                body.extend(items);
+            }
+
+            if let Some(namespace) = self.namespaces.get(&module_id) {
+                body.push(self.namespace_decl(chunk, &module_id, namespace, &mut imports)?);
+            }
+        }
+
+        let mut exports = vec![];
+        if let Some(root) = self.chunk_root(chunk) {
+            let root = Source::Module(root.clone());
+            for name in self.export_names(&root, &mut HashSet::new()) {
+                let target = self.resolve(&root, &name);
+                if let Target::CommonJs(..) = target {
+                    // const _default = toEsm(require()).default
+                    let local = private_ident!("_default");
+                    let value = self.target_expr(chunk, &target, &mut imports)?;
+                    body.push(const_decl(local.clone(), value));
+                    exports.push((local, name));
+                } else if let Some(local) = self.target_ident(chunk, &target, &mut imports)? {
+                    exports.push((local, name));
+                }
+            }
+        }
+        if let Some(aliases) = self.aliases.get(&chunk.id) {
+            for (target, alias) in aliases {
+                if let Some(local) = self.target_ident(chunk, target, &mut imports)? {
+                    exports.push((local, alias.clone()));
+                }
+            }
+        }
+        if !exports.is_empty() {
+            body.push(ModuleItem::ModuleDecl(ModuleDecl::ExportNamed(
+                NamedExport {
+                    span: DUMMY_SP,
+                    specifiers: exports
+                        .into_iter()
+                        .map(|(local, name)| {
+                            ExportSpecifier::Named(ExportNamedSpecifier {
+                                span: DUMMY_SP,
+                                orig: ModuleExportName::Ident(local),
+                                exported: Some(export_name(&name)),
+                                is_type_only: false,
+                            })
+                        })
+                        .collect(),
+                    src: None,
+                    type_only: false,
+                    with: None,
+                },
+            )));
+        }
+        body.splice(0..0, imports.into_items());
+
+        let mut ast = JsAst {
+            ast: SwcModule {
+                span: DUMMY_SP,
+                body,
+                shebang: None,
+            },
+            unresolved_mark: Mark::new(),
+            top_level_mark: Mark::new(),
+            contains_top_level_await: false,
+            path: "".to_string(),
+        };
+        ast.ast.visit_mut_with(&mut hygiene());
+        ast.ast.visit_mut_with(&mut fixer(None));
+        let context = self.context;
+        if context.config.minify.is_some() && matches!(context.config.mode, Mode::Production) {
+            ast.ast.visit_mut_with(&mut CleanSyntaxContext);
+            minify_js(&mut ast, context)?;
+        }
+
+        let pot = ChunkPot::from(chunk, self.module_graph, context);
+        let (content, source_map) = render_module_js(&ast.ast, context)?;
+        let source_map = merge_chunk_input_source_maps(source_map, &pot, context);
+        let mut files = vec![ChunkFile {
+            raw_hash: pot.js_hash,
+            content,
+            hash: self.placeholders.get(&chunk.id).cloned(),
+            source_map,
+            file_name: pot.js_name.clone(),
+            chunk_id: pot.chunk_id.clone(),
+            file_type: ChunkFileType::JS,
+            file_name_template: context.config.output.filename.clone(),
+        }];
+        if let Some(css) = pot.to_css_chunk_file(chunk, context)? {
+            // dynamic imports are emitted as plain import(), nothing loads the css of async chunks
+            if matches!(chunk.chunk_type, ChunkType::Async) {
+                return Err(anyhow!(
+                    "css in async chunks is not supported with output.format esm: {}",
+                    chunk.id.id
+                ));
+            }
+            files.push(css);
+        }
+        Ok(files)
+    }
+
+    // const namespace = Object.freeze({ __proto__: null, get name() { return binding; } })
+    fn namespace_decl(
+        &self,
+        chunk: &Chunk,
+        module_id: &ModuleId,
+        namespace: &Ident,
+        imports: &mut ChunkImports,
+    ) -> Result<ModuleItem> {
+        let source = Source::Module(module_id.clone());
+        let mut props = vec![PropOrSpread::Prop(Box::new(Prop::KeyValue(KeyValueProp {
+            key: PropName::Ident(quote_ident!("__proto__")),
+            value: Expr::Lit(Lit::Null(Null { span: DUMMY_SP })).into(),
+        })))];
+        // for the interop of the commonjs modules which require it
+        if self.required.contains(module_id) {
+            props.push(PropOrSpread::Prop(Box::new(Prop::KeyValue(KeyValueProp {
+                key: PropName::Ident(quote_ident!("__esModule")),
+                value: Expr::Lit(Lit::Bool(true.into())).into(),
+            }))));
+        }
+        for name in self.export_names(&source, &mut HashSet::new()) {
+            let target = self.resolve(&source, &name);
+            let value = self.target_expr(chunk, &target, imports)?;
+            props.push(PropOrSpread::Prop(Box::new(Prop::Getter(GetterProp {
+                span: DUMMY_SP,
+                key: prop_name(&name),
+                type_ann: None,
+                body: Some(BlockStmt {
+                    span: DUMMY_SP,
+                    stmts: vec![Stmt::Return(ReturnStmt {
+                        span: DUMMY_SP,
+                        arg: Some(Box::new(value)),
+                    })],
+                }),
+            }))));
+        }
+        let object = ObjectLit {
+            span: DUMMY_SP,
+            props,
+        };
+        let stmt: Stmt = member_expr!(DUMMY_SP, Object.freeze)
+            .as_call(DUMMY_SP, vec![object.as_arg()])
+            .into_var_decl(VarDeclKind::Const, namespace.clone().into())
+            .into();
+        Ok(stmt.into())
+    }
+}
+
+fn to_es_module(
+    module: &Module,
+    module_graph: &ModuleGraph,
+    context: &Arc<Context>,
+) -> Result<EsModule> {
+    let mut es_module = EsModule::default();
+    let Some(info) = module.info.as_ref() else {
+        return Ok(es_module);
+    };
+    // imported from the source instead
+    if module.is_external() && !is_global_external(&context.config.externals, &module.id.id) {
+        return Ok(es_module);
+    }
+    let path = &module.id.id;
+    let mut sources = HashMap::new();
+    for (dep_id, dep) in module_graph.get_dependencies(&module.id) {
+        match dep.resolve_type {
+            ResolveType::RequireResolve => {
+                es_module
+                    .require_resolves
+                    .insert(dep.source.clone(), dep_id.clone());
+                continue;
+            }
+            ResolveType::Worker => {
+                es_module.workers.insert(dep.source.clone(), dep_id.clone());
+                continue;
+            }
+            _ => {}
+        }
+        // externals read from a global variable are modules of module.exports = global,
+        // and the others are kept as imports
+        let is_external = module_graph.get_module(dep_id).is_some_and(|module| {
+            module.is_external() && !is_global_external(&context.config.externals, &module.id.id)
+        });
+        let source = if is_external {
+            Source::External(dep.source.clone())
+        } else {
+            Source::Module(dep_id.clone())
+        };
+        if matches!(dep.resolve_type, ResolveType::Require | ResolveType::Glob) {
+            es_module
+                .require_calls
+                .insert(dep.source.clone(), source.clone());
+        }
+        sources.insert(dep.source.clone(), source);
+    }
+    es_module.url_assets = info.deps.url_assets.clone();
+    let get_source = |src: &Str| {
+        sources
+            .get(&src.value.to_string())
+            .cloned()
+            .unwrap_or_else(|| Source::External(src.value.to_string()))
+    };
+
+    let ast = match &info.ast {
+        ModuleAst::Script(ast) => ast,
+        ModuleAst::Json(value) => {
+            let ident = private_ident!("_default");
+            es_module
+                .items
+                .push(const_decl(ident.clone(), json_to_expr(value)));
+            es_module
+                .exports
+                .insert("default".to_string(), Export::Local(ident));
+            return Ok(es_module);
+        }
+        // css is emitted to the css file of the chunk
+        ModuleAst::Css(_) | ModuleAst::None => return Ok(es_module),
+    };
+    let unresolved_mark = ast.unresolved_mark;
+    let mut swc_module = ast.ast.clone();
+    // externals are not transformed when they are built
+    if module.is_external() {
+        swc_module.visit_mut_with(&mut resolver(unresolved_mark, ast.top_level_mark, false));
+    }
+    swc_module.visit_mut_with(&mut PublicPathReplacer {
+        unresolved_mark,
+        public_path: &context.config.public_path,
+    });
+    let mut finder = ModuleSystemFinder {
+        unresolved_mark,
+        commonjs: false,
+        mako_require: false,
+    };
+    swc_module.visit_with(&mut finder);
+    es_module.unresolved_mark = unresolved_mark;
+    es_module.is_commonjs = finder.commonjs && !crate::ast::utils::is_esm(&swc_module);
+    if finder.mako_require {
+        return Err(anyhow!(
+            "__mako_require__ is referenced in {}, which is not supported by output.format esm",
+            path
+        ));
+    }
+
+    let mut collector = DynamicImportCollector { sources: vec![] };
+    swc_module.visit_with(&mut collector);
+    for src in collector.sources {
+        if let Some(source) = sources.get(&src) {
+            es_module.dynamic_imports.insert(src, source.clone());
+        }
+    }
+
+    let require = |es_module: &mut EsModule, source: &Source| {
+        if !es_module.requires.contains(source) {
+            es_module.requires.push(source.clone());
+        }
+    };
+    for item in swc_module.body {
+        let module_decl = match item {
+            ModuleItem::ModuleDecl(module_decl) => module_decl,
+            ModuleItem::Stmt(stmt) => {
+                es_module.items.push(stmt.into());
+                continue;
+            }
+        };
+        match module_decl {
+            ModuleDecl::Import(ImportDecl {
+                specifiers,
+                src,
+                type_only: false,
+                ..
+            }) => {
+                let source = get_source(&src);
+                require(&mut es_module, &source);
+                for specifier in specifiers {
+                    let (local, name) = match specifier {
+                        ImportSpecifier::Named(ImportNamedSpecifier {
+                            local, imported, ..
+                        }) => {
+                            let name = imported
+                                .map_or(local.sym.to_string(), |name| module_export_name(&name));
+                            (local, name)
+                        }
+                        ImportSpecifier::Default(specifier) => {
+                            (specifier.local, "default".to_string())
+                        }
+                        ImportSpecifier::Namespace(specifier) => (specifier.local, "*".to_string()),
+                    };
+                    es_module
+                        .imports
+                        .insert(local.to_id(), (source.clone(), name));
+                }
+            }
+            ModuleDecl::ExportDecl(export_decl) => {
+                let idents = match &export_decl.decl {
+                    Decl::Fn(FnDecl { ident, .. }) | Decl::Class(ClassDecl { ident, .. }) => {
+                        vec![ident.clone()]
+                    }
+                    Decl::Var(var_decl) => find_pat_ids::<_, Ident>(&var_decl.decls),
+                    _ => vec![],
+                };
+                for ident in idents {
+                    es_module
+                        .exports
+                        .insert(ident.sym.to_string(), Export::Local(ident));
+                }
+                es_module
+                    .items
+                    .push(ModuleItem::Stmt(Stmt::Decl(export_decl.decl)));
+            }
+            ModuleDecl::ExportNamed(NamedExport {
+                specifiers,
+                src,
+                type_only: false,
+                ..
+            }) => {
+                let source = src.as_ref().map(|src| get_source(src));
+                if let Some(source) = &source {
+                    require(&mut es_module, source);
+                }
+                for specifier in specifiers {
+                    let (name, export) = match (specifier, &source) {
+                        (ExportSpecifier::Named(specifier), Some(source)) => {
+                            let orig = module_export_name(&specifier.orig);
+                            let name = specifier
+                                .exported
+                                .map_or(orig.clone(), |name| module_export_name(&name));
+                            (name, Export::ReExport(source.clone(), orig))
+                        }
+                        (ExportSpecifier::Named(specifier), None) => {
+                            let ModuleExportName::Ident(orig) = specifier.orig else {
+                                continue;
+                            };
+                            let name = specifier
+                                .exported
+                                .map_or(orig.sym.to_string(), |name| module_export_name(&name));
+                            (name, Export::Local(orig))
+                        }
+                        (ExportSpecifier::Namespace(specifier), Some(source)) => (
+                            module_export_name(&specifier.name),
+                            Export::ReExport(source.clone(), "*".to_string()),
+                        ),
+                        (ExportSpecifier::Default(specifier), Some(source)) => (
+                            specifier.exported.sym.to_string(),
+                            Export::ReExport(source.clone(), "default".to_string()),
+                        ),
+                        _ => continue,
+                    };
+                    es_module.exports.insert(name, export);
+                }
+            }
+            ModuleDecl::ExportDefaultDecl(export_default) => {
+                let decl = match export_default.decl {
+                    DefaultDecl::Fn(fn_expr) => Decl::Fn(FnDecl {
+                        ident: fn_expr.ident.unwrap_or_else(|| private_ident!("_default")),
+                        declare: false,
+                        function: fn_expr.function,
+                    }),
+                    DefaultDecl::Class(class_expr) => Decl::Class(ClassDecl {
+                        ident: class_expr
+                            .ident
+                            .unwrap_or_else(|| private_ident!("_default")),
+                        declare: false,
+                        class: class_expr.class,
+                    }),
+                    DefaultDecl::TsInterfaceDecl(_) => continue,
+                };
+                let ident = match &decl {
+                    Decl::Fn(FnDecl { ident, .. }) | Decl::Class(ClassDecl { ident, .. }) => {
+                        ident.clone()
+                    }
+                    _ => unreachable!(),
+                };
+                es_module
+                    .exports
+                    .insert("default".to_string(), Export::Local(ident));
+                es_module.items.push(ModuleItem::Stmt(Stmt::Decl(decl)));
+            }
+            ModuleDecl::ExportDefaultExpr(export_default) => {
+                let ident = private_ident!("_default");
+                es_module
+                    .items
+                    .push(const_decl(ident.clone(), *export_default.expr));
+                es_module
+                    .exports
+                    .insert("default".to_string(), Export::Local(ident));
+            }
+            ModuleDecl::ExportAll(export_all) if !export_all.type_only => {
+                let source = get_source(&export_all.src);
+                require(&mut es_module, &source);
+                es_module.stars.push(source);
+            }
+            _ => {}
+        }
+    }
+    Ok(es_module)
+}
+
+// const require = commonjs(function (module, exports) { ... })
+fn factory_decl(
+    require: &Ident,
+    items: Vec<ModuleItem>,
+    unresolved_mark: Mark,
+    imports: &mut ChunkImports,
+) -> ModuleItem {
+    // module and exports of the module are unresolved references
+    let param = |name: &str| {
+        Param::from(Pat::from(Ident::new(
+            name.into(),
+            DUMMY_SP.apply_mark(unresolved_mark),
+        )))
+    };
+    let factory = FnExpr {
+        ident: None,
+        function: Box::new(Function {
+            params: vec![param("module"), param("exports")],
+            decorators: vec![],
+            span: DUMMY_SP,
+            body: Some(BlockStmt {
+                span: DUMMY_SP,
+                stmts: items.into_iter().filter_map(|item| item.stmt()).collect(),
+            }),
+            is_generator: false,
+            is_async: false,
+            type_params: None,
+            return_type: None,
+        }),
+    };
+    let value = imports
+        .helper(Helper::CommonJs)
+        .as_call(DUMMY_SP, vec![factory.as_arg()]);
+    const_decl(require.clone(), value)
+}
+
+fn relative_specifier(from: &str, to: &str) -> String {
+    let to = Path::new(to);
+    let path = diff_paths(to, Path::new(from).parent().unwrap_or(Path::new("")))
+        .unwrap_or(to.to_path_buf())
+        .to_string_lossy()
+        .replace('\\', "/");
+    if path.starts_with("../") {
+        path
+    } else {
+        format!("./{}", path)
+    }
+}
+
+// const ident = expr
+fn const_decl(ident: Ident, expr: Expr) -> ModuleItem {
+    let var_decl = expr.into_var_decl(VarDeclKind::Const, ident.into());
+    ModuleItem::Stmt(Stmt::Decl(Decl::Var(Box::new(var_decl))))
+}
+
+fn module_export_name(name: &ModuleExportName) -> String {
+    match name {
+        ModuleExportName::Ident(ident) => ident.sym.to_string(),
+        ModuleExportName::Str(str) => str.value.to_string(),
+    }
+}
+
+fn export_name(name: &str) -> ModuleExportName {
+    if is_identifier_name(name) {
+        ModuleExportName::Ident(quote_ident!(name))
+    } else {
+        ModuleExportName::Str(quote_str!(name))
+    }
+}
+
+fn prop_name(name: &str) -> PropName {
+    if is_identifier_name(name) {
+        PropName::Ident(quote_ident!(name))
+    } else {
+        PropName::Str(quote_str!(name))
+    }
+}
+
+fn is_identifier_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_alphabetic() || c == '_' || c == '$')
+        && chars.all(|c| c.is_alphanumeric() || c == '_' || c == '$')
+}
+
+// e.g. src/utils/index.ts => src_utils_index_ts
+fn module_alias(module_id: &ModuleId, context: &Arc<Context>) -> String {
+    let alias = module_id
+        .generate(context)
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect::<String>();
+    let alias = alias.trim_matches('_');
+    if alias.starts_with(|c: char| c.is_ascii_digit()) || alias.is_empty() {
+        format!("_{}", alias)
+    } else {
+        alias.to_string()
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+enum Helper {
+    CommonJs,
+    ToEsm,
+}
+
+impl Helper {
+    fn decl(&self, ident: Ident) -> ModuleItem {
+        match self {
+            // the module.exports is created when it's required for the first time
+            Helper::CommonJs => quote!(
+                "const $ident = function (factory) {
+                    var module;
+                    return function () {
+                        if (!module) {
+                            module = { exports: {} };
+                            factory.call(module.exports, module, module.exports);
+                        }
+                        return module.exports;
+                    };
+                };" as ModuleItem,
+                ident: Ident = ident
+            ),
+            // same as the interop of the runtime,
+            // module.exports is the default export unless it's compiled from es module
+            Helper::ToEsm => quote!(
+                "const $ident = (exports) =>
+                    exports && exports.__esModule
+                        ? exports
+                        : Object.assign({}, exports, { default: exports });" as ModuleItem,
+                ident: Ident = ident
+            ),
+        }
+    }
+}
+
+// import declarations of the chunk, specifier => (imported name => local),
+// and the helpers used by the chunk
+#[derive(Default)]
+struct ChunkImports {
+    imports: IndexMap<String, IndexMap<String, Ident>>,
+    helpers: IndexMap<Helper, Ident>,
+}
+
+impl ChunkImports {
+    fn helper(&mut self, helper: Helper) -> Expr {
+        Expr::Ident(
+            self.helpers
+                .entry(helper)
+                .or_insert_with(|| match helper {
+                    Helper::CommonJs => private_ident!("__commonJS"),
+                    Helper::ToEsm => private_ident!("__toESM"),
+                })
+                .clone(),
+        )
+    }
+
+    fn import(&mut self, specifier: &str, name: &str) -> Ident {
+        self.imports
+            .entry(specifier.to_string())
+            .or_default()
+            .entry(name.to_string())
+            .or_insert_with(|| match name {
+                "*" => private_ident!("namespace"),
+                "default" => private_ident!("_default"),
+                name if is_identifier_name(name) => private_ident!(name),
+                _ => private_ident!("_import"),
+            })
+            .clone()
+    }
+
+    // import "specifier"
+    fn require(&mut self, specifier: &str) {
+        self.imports.entry(specifier.to_string()).or_default();
+    }
+
+    fn into_items(self) -> Vec<ModuleItem> {
+        let mut items = vec![];
+        for (specifier, imports) in self.imports {
+            let import_decl = |specifiers| {
+                ModuleItem::ModuleDecl(ModuleDecl::Import(ImportDecl {
+                    span: DUMMY_SP,
+                    specifiers,
+                    src: Box::new(quote_str!(specifier.as_str())),
+                    type_only: false,
+                    with: None,
+                }))
+            };
+            let mut named = vec![];
+            for (name, local) in imports {
+                if name == "*" {
+                    items.push(import_decl(vec![ImportSpecifier::Namespace(
+                        ImportStarAsSpecifier {
+                            span: DUMMY_SP,
+                            local,
+                        },
+                    )]));
+                } else {
+                    named.push(ImportSpecifier::Named(ImportNamedSpecifier {
+                        span: DUMMY_SP,
+                        local,
+                        imported: Some(export_name(&name)),
+                        is_type_only: false,
+                    }));
+                }
+            }
+            if !named.is_empty()
+                || !items.iter().any(|item| {
+                    matches!(item, ModuleItem::ModuleDecl(ModuleDecl::Import(decl)) if *decl.src.value == *specifier)
+                })
+            {
+                items.push(import_decl(named));
+            }
+        }
+        for (helper, ident) in self.helpers {
+            items.push(helper.decl(ident));
+        }
+        items
+    }
+}
+
+// replace the imported bindings, dynamic imports, require() and urls of the module
+struct LinkReplacer<'a> {
+    unresolved_mark: Mark,
+    bindings: &'a HashMap<Id, Expr>,
+    dynamic_imports: &'a HashMap<String, Expr>,
+    requires: &'a HashMap<String, Expr>,
+    require_resolves: &'a HashMap<String, Str>,
+    // new URL(url, import.meta.url) of the workers and assets
+    urls: &'a HashMap<String, String>,
+}
+
+impl VisitMut for LinkReplacer<'_> {
+    fn visit_mut_expr(&mut self, expr: &mut Expr) {
+        if let Expr::Ident(ident) = expr
+            && let Some(replacement) = self.bindings.get(&ident.to_id())
+        {
+            *expr = replacement.clone();
+            return;
+        }
+        if let Expr::Call(call) = expr
+            && let Some(src) = get_first_str_arg(call)
+        {
+            let replacement = if is_dynamic_import(call) {
+                self.dynamic_imports.get(&src)
+            } else if is_commonjs_require(call, &self.unresolved_mark) {
+                self.requires.get(&src)
+            } else {
+                None
+            };
+            if let Some(replacement) = replacement {
+                *expr = replacement.clone();
+                return;
+            }
+            if is_commonjs_require_resolve(call, &self.unresolved_mark)
+                && let Some(id) = self.require_resolves.get(&src)
+            {
+                *expr = id.clone().into();
+                return;
+            }
+        }
+        if let Expr::New(new_expr) = expr
+            && new_expr.callee.as_ident().is_some_and(|ident| {
+                ident.sym == *"URL" && ident.span.ctxt.outer() == self.unresolved_mark
+            })
+            && let Some(args) = new_expr.args.as_mut()
+            && args.get(1).is_some_and(|arg| is_import_meta_url(&arg.expr))
+            && let Some(Lit::Str(src)) = args[0].expr.as_mut_lit()
+            && let Some(url) = self.urls.get(&src.value.to_string())
+        {
+            *src = quote_str!(src.span, url.as_str());
+            return;
+        }
+        expr.visit_mut_children_with(self);
+    }
+
+    fn visit_mut_prop(&mut self, prop: &mut Prop) {
+        if let Prop::Shorthand(ident) = prop
+            && let Some(replacement) = self.bindings.get(&ident.to_id())
+        {
+            *prop = Prop::KeyValue(KeyValueProp {
+                key: PropName::Ident(quote_ident!(ident.sym.clone())),
+                value: Box::new(replacement.clone()),
+            });
+            return;
+        }
+        prop.visit_mut_children_with(self);
+    }
+}
+
+// require.publicPath in the urls of assets
+// => "/public/path/", or new URL("./", import.meta.url).href if it's auto
+struct PublicPathReplacer<'a> {
+    unresolved_mark: Mark,
+    public_path: &'a str,
+}
+
+impl VisitMut for PublicPathReplacer<'_> {
+    fn visit_mut_expr(&mut self, expr: &mut Expr) {
+        if let Expr::Member(member) = expr
+            && let Expr::Ident(obj) = &*member.obj
+            && (obj.sym == *"require" || obj.sym == *"__mako_require__")
+            && obj.span.ctxt.outer() == self.unresolved_mark
+            && let MemberProp::Ident(prop) = &member.prop
+            && prop.sym == *"publicPath"
+        {
+            *expr = if self.public_path == "auto" {
+                let url = member_expr!(DUMMY_SP, import.meta.url);
+                let url = Expr::New(swc_core::ecma::ast::NewExpr {
+                    span: DUMMY_SP,
+                    callee: quote_ident!("URL").into(),
+                    args: Some(vec![quote_str!("./").as_arg(), url.as_arg()]),
+                    type_args: None,
+                });
+                url.make_member(quote_ident!("href"))
+            } else {
+                quote_str!(self.public_path).into()
+            };
+            return;
+        }
+        expr.visit_mut_children_with(self);
+    }
+}
+
+// references of the module system, the commonjs ones are provided by the factory
+// and the runtime is not available in esm output
+struct ModuleSystemFinder {
+    unresolved_mark: Mark,
+    commonjs: bool,
+    mako_require: bool,
+}
+
+impl Visit for ModuleSystemFinder {
+    fn visit_ident(&mut self, ident: &Ident) {
+        if ident.span.ctxt.outer() != self.unresolved_mark {
+            return;
+        }
+        match &*ident.sym {
+            "require" | "module" | "exports" => self.commonjs = true,
+            "__mako_require__" => self.mako_require = true,
+            _ => {}
+        }
+    }
+}
+
+struct DynamicImportCollector {
+    sources: Vec<String>,
+}
+
+impl Visit for DynamicImportCollector {
+    fn visit_call_expr(&mut self, call: &CallExpr) {
+        if is_dynamic_import(call)
+            && let Some(ExprOrSpread {
+                expr: box Expr::Lit(Lit::Str(src)),
+                ..
+            }) = call.args.first()
+        {
+            self.sources.push(src.value.to_string());
+        }
+        call.visit_children_with(self);
+    }
+}
+
+// idents are renamed by hygiene, so the contexts of the modules can be cleaned
+// before the chunk is resolved again by the minifier
+struct CleanSyntaxContext;
+
+impl VisitMut for CleanSyntaxContext {
+    fn visit_mut_span(&mut self, span: &mut swc_core::common::Span) {
+        span.ctxt = SyntaxContext::empty();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use crate::utils::test_helper::setup_compiler;
+
+    #[test]
+    fn test_esm_format() {
+        let compiler = setup_compiler("test/build/esm-format", false);
+        compiler.compile().unwrap();
+        let output = &compiler.context.config.output.path;
+        let index = fs::read_to_string(output.join("index.js")).unwrap();
+        let lazy = fs::read_to_string(output.join("lazy_ts-async.js")).unwrap();
+
+        // modules are linked without the module registry of the runtime
+        for content in [&index, &lazy] {
+            assert!(!content.contains("__mako_require__"), "{}", content);
+            assert!(!content.contains("registerModules"), "{}", content);
+        }
+        assert!(index.contains("export { add as add, area as area, main as default"));
+        assert!(index.contains("console.log(namespace1.name, namespace1.PI, _default.name)"));
+        assert!(index.contains(r#"import("./lazy_ts-async.js").then"#));
+        // the bindings shared with the async chunk are imported from the entry chunk
+        assert!(index.contains("add as math_ts$add"));
+        assert!(lazy.contains(r#"import { math_ts$add as math_ts$add } from "./index.js";"#));
+        assert!(lazy.contains("export { lazy as lazy };"));
+
+        // commonjs modules are wrapped in a factory, and required es modules get __esModule
+        assert!(
+            index.contains("const require_cjs_js = __commonJS(function(module, exports) {"),
+            "{}",
+            index
+        );
+        assert!(index.contains("const { PI } = namespace;"));
+        assert!(index.contains("__esModule: true"));
+        // evaluated where it's imported
+        assert!(index.contains("});\nrequire_cjs_js();"));
+        assert!(
+            index.contains("__toESM(require_cjs_js()).default.name, require_cjs_js().circle(1)")
+        );
+        // externals read from a global variable are not imported
+        assert!(index.contains(
+            "module.exports = (typeof globalThis !== 'undefined' ? globalThis : self)['React'];"
+        ));
+        assert!(index.contains("__toESM(require_react()).default.version"));
+        assert!(!index.contains(r#"from "react""#));
+        assert!(index.contains(r#"import { debounce as debounce } from "lodash";"#));
+        assert!(!index.contains(r#"require("lodash")"#));
+    }
+
+    #[test]
+    fn test_esm_format_missing_export() {
+        let compiler = setup_compiler("test/build/esm-format-missing-export", false);
+        let err = compiler.compile().unwrap_err().to_string();
+        assert!(err.contains(r#""b" is not exported by"#), "{}", err);
+        assert!(err.contains("a.js, imported by"), "{}", err);
+    }
+
+    #[test]
+    fn test_esm_format_async_css() {
+        let compiler = setup_compiler("test/build/esm-format-async-css", false);
+        let err = compiler.compile().unwrap_err().to_string();
+        assert!(
+            err.contains("css in async chunks is not supported with output.format esm"),
+            "{}",
+            err
+        );
+        assert!(err.contains("lazy.js"), "{}", err);
+    }
+}
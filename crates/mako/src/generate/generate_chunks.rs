@@ -1,23 +1,30 @@
 use std::collections::HashMap;
 use std::path::Path;
-use std::sync::Arc;
+use std::sync::{Arc, OnceLock};
 
 use anyhow::{anyhow, Result};
 use indexmap::IndexSet;
 use nanoid::nanoid;
 use rayon::prelude::*;
+use regex::{Captures, Regex};
 use swc_core::common::DUMMY_SP;
 use swc_core::css::ast::Stylesheet;
 use swc_core::ecma::ast::{Expr, KeyValueProp, Prop, PropName, PropOrSpread, Str};
 use tracing::warn;
 
 use crate::compiler::{Compiler, Context};
-use crate::generate::chunk::{Chunk, ChunkType};
-use crate::generate::chunk_pot::util::file_content_hash;
+use crate::generate::chunk::{Chunk, ChunkId, ChunkType};
+use crate::generate::chunk_pot::util::{file_content_hash, hash_hashmap};
 use crate::generate::chunk_pot::{get_css_chunk_filename, ChunkPot, CHUNK_FILE_NAME_HASH_LENGTH};
 use crate::generate::transform::transform_css_generate;
 use crate::module::{ModuleAst, ModuleId};
 use crate::utils::thread_pool;
+use crate::visitors::css_imports::with_import_conditions;
+
+// length of the md5 hex digest
+pub(crate) const CONTENT_HASH_LENGTH: usize = 32;
+
+pub const RUNTIME_CHUNK_ID: &str = "runtime";
 
 #[derive(Clone)]
 pub enum ChunkFileType {
@@ -34,15 +41,18 @@ pub struct ChunkFile {
     pub file_name: String,
     pub chunk_id: String,
     pub file_type: ChunkFileType,
+    // output.filename, e.g. [name].[contenthash:8].js
+    pub file_name_template: Option<String>,
 }
 
 impl ChunkFile {
     pub fn disk_name(&self) -> String {
-        if let Some(hash) = &self.hash {
-            hash_file_name(&self.file_name, hash)
-        } else {
-            self.file_name.clone()
-        }
+        hash_file_name(
+            &self.file_name,
+            &self.chunk_id,
+            self.hash.as_deref(),
+            self.file_name_template.as_deref(),
+        )
     }
 
     pub fn source_map_disk_name(&self) -> String {
@@ -62,15 +72,34 @@ impl Compiler {
         crate::mako_profile_function!();
         let chunk_graph = self.context.chunk_graph.read().unwrap();
         let chunks = chunk_graph.get_chunks();
+        let has_runtime_chunk = self.context.config.runtime_chunk().is_some();
 
         let (entry_chunks, normal_chunks): (Vec<&Chunk>, Vec<&Chunk>) = chunks
             .into_iter()
             .partition(|chunk| match chunk.chunk_type {
-                ChunkType::Entry(_, _, false) | ChunkType::Worker(_) => true,
+                // entries are pushed to the runtime chunk like normal chunks
+                ChunkType::Entry(_, _, false) => !has_runtime_chunk,
+                ChunkType::Worker(_) => true,
                 ChunkType::Entry(_, _, true) => false,
                 _ => false,
             });
 
+        let mut entry_chunks = entry_chunks
+            .into_iter()
+            .map(|chunk| (chunk, chunk_graph.installable_descendants_chunk(&chunk.id)))
+            .collect::<Vec<_>>();
+        let runtime_chunk = Chunk::new(RUNTIME_CHUNK_ID.into(), ChunkType::Runtime);
+        if has_runtime_chunk {
+            // the runtime chunk installs chunks of all entries
+            let descendants = chunk_graph
+                .get_chunks()
+                .into_iter()
+                .filter(|chunk| matches!(chunk.chunk_type, ChunkType::Entry(_, _, false)))
+                .flat_map(|chunk| chunk_graph.installable_descendants_chunk(&chunk.id))
+                .collect::<IndexSet<_>>();
+            entry_chunks.push((&runtime_chunk, descendants.into_iter().collect()));
+        }
+
         let (entry_chunk_files_with_placeholder, normal_chunk_files) = thread_pool::join(
             || self.generate_entry_chunk_files(entry_chunks, hmr_hash),
             || self.generate_normal_chunk_files(normal_chunks),
@@ -128,22 +157,21 @@ impl Compiler {
         Ok([entry_chunk_files, normal_chunk_files].concat())
     }
 
+    // chunks with the chunks they install
     fn generate_entry_chunk_files(
         &self,
-        chunks: Vec<&Chunk>,
+        chunks: Vec<(&Chunk, Vec<ChunkId>)>,
         hmr_hash: u64,
     ) -> Result<Vec<(Vec<ChunkFile>, ChunksHashPlaceholder, ChunksHashPlaceholder)>> {
         let chunk_file_results: Vec<_> = chunks
             .par_iter()
-            .map(|chunk| {
+            .map(|(chunk, descendants)| {
                 let context = self.context.clone();
                 let module_graph = context.module_graph.read().unwrap();
                 let chunk_graph = self.context.chunk_graph.read().unwrap();
 
-                let (js_chunks_hash_placeholder, css_chunks_hash_placeholder) = chunk_graph
-                    .installable_descendants_chunk(&chunk.id)
-                    .iter()
-                    .fold(
+                let (js_chunks_hash_placeholder, css_chunks_hash_placeholder) =
+                    descendants.iter().fold(
                         (ChunksHashPlaceholder::new(), ChunksHashPlaceholder::new()),
                         |(mut acc_js, mut acc_css), descendant_chunk_id| {
                             let descendant_chunk = chunk_graph.chunk(descendant_chunk_id).unwrap();
@@ -152,39 +180,49 @@ impl Compiler {
                             let chunk_pot =
                                 ChunkPot::from(descendant_chunk, &module_graph, &context);
 
-                            if self.context.config.hash {
-                                let placeholder = nanoid!(CHUNK_FILE_NAME_HASH_LENGTH);
-
-                                let js_filename = chunk_pot.js_name;
-
-                                if chunk_pot.stylesheet.is_some() {
-                                    let css_filename = get_css_chunk_filename(&js_filename);
-                                    acc_css.insert(
-                                        descendant_chunk_id.id.clone(),
-                                        hash_file_name(&css_filename, &placeholder),
-                                    );
-                                }
-
-                                acc_js.insert(
+                            // the placeholder has the same length as the content hash,
+                            // so that it can be replaced in place after normal chunks are generated
+                            let placeholder = if self.context.config.hash {
+                                Some(nanoid!(CONTENT_HASH_LENGTH))
+                            } else {
+                                None
+                            };
+                            let template = self.context.config.output.filename.as_deref();
+                            let js_filename = chunk_pot.js_name;
+
+                            if chunk_pot.stylesheet.is_some() {
+                                let css_filename = get_css_chunk_filename(&js_filename);
+                                acc_css.insert(
                                     descendant_chunk_id.id.clone(),
-                                    hash_file_name(&js_filename, &placeholder),
+                                    hash_file_name(
+                                        &css_filename,
+                                        &descendant_chunk_id.id,
+                                        placeholder.as_deref(),
+                                        template,
+                                    ),
                                 );
-                            } else {
-                                let js_filename = chunk_pot.js_name;
-
-                                if chunk_pot.stylesheet.is_some() {
-                                    let css_filename = get_css_chunk_filename(&js_filename);
-                                    acc_css.insert(descendant_chunk_id.id.clone(), css_filename);
-                                }
-
-                                acc_js.insert(descendant_chunk_id.id.clone(), js_filename);
                             }
+
+                            acc_js.insert(
+                                descendant_chunk_id.id.clone(),
+                                hash_file_name(
+                                    &js_filename,
+                                    &descendant_chunk_id.id,
+                                    placeholder.as_deref(),
+                                    template,
+                                ),
+                            );
                             (acc_js, acc_css)
                         },
                     );
 
                 let chunk_files = {
-                    let chunk_pot = ChunkPot::from(chunk, &module_graph, &context);
+                    let mut chunk_pot = ChunkPot::from(chunk, &module_graph, &context);
+                    if matches!(chunk.chunk_type, ChunkType::Runtime) {
+                        // the runtime chunk has no modules, it changes with the chunks it installs
+                        chunk_pot.js_hash = hash_hashmap(&js_chunks_hash_placeholder)
+                            ^ hash_hashmap(&css_chunks_hash_placeholder);
+                    }
                     chunk_pot
                         .to_entry_chunk_files(
                             &context,
@@ -360,7 +398,10 @@ pub fn modules_to_js_stmts(
             {
                 merged_css_modules.remove(index);
             }
-            merged_css_modules.push((module.id.id.clone(), ast.ast.clone()));
+            merged_css_modules.push((
+                module.id.id.clone(),
+                with_import_conditions(&module.id, &ast.ast, module_graph, context).into_owned(),
+            ));
         }
     }
     if !merged_css_modules.is_empty() {
@@ -380,10 +421,196 @@ pub fn modules_to_js_stmts(
     }
 }
 
-fn hash_file_name(file_name: &String, hash: &String) -> String {
-    let path = Path::new(&file_name);
+fn content_hash_regex() -> &'static Regex {
+    static CONTENT_HASH_REGEXP: OnceLock<Regex> = OnceLock::new();
+
+    CONTENT_HASH_REGEXP.get_or_init(|| Regex::new(r"(\.)?\[contenthash(?::(\d+))?\]").unwrap())
+}
+
+// e.g.
+// index.js => index.1a2b3c4d.js
+// index.js with template [name].[contenthash:4].js => index.1a2b.js
+pub(crate) fn hash_file_name(
+    file_name: &str,
+    chunk_id: &str,
+    hash: Option<&str>,
+    template: Option<&str>,
+) -> String {
+    let path = Path::new(file_name);
     let file_stem = path.file_stem().unwrap().to_str().unwrap();
     let file_extension = path.extension().unwrap().to_str().unwrap();
 
-    format!("{}.{}.{}", file_stem, hash, file_extension)
+    let Some(template) = template else {
+        return match hash {
+            Some(hash) => format!(
+                "{}.{}.{}",
+                file_stem,
+                &hash[..CHUNK_FILE_NAME_HASH_LENGTH.min(hash.len())],
+                file_extension
+            ),
+            None => file_name.to_string(),
+        };
+    };
+
+    let name = content_hash_regex().replace_all(template, |caps: &Captures| match hash {
+        Some(hash) => {
+            let len = caps
+                .get(2)
+                .and_then(|len| len.as_str().parse::<usize>().ok())
+                .unwrap_or(CHUNK_FILE_NAME_HASH_LENGTH)
+                .min(hash.len());
+            format!("{}{}", caps.get(1).map_or("", |m| m.as_str()), &hash[..len])
+        }
+        // no hash in development mode
+        None => "".to_string(),
+    });
+    // the extension of the chunk id is stripped, e.g. src/a.js => src_a
+    let id = Path::new(chunk_id).with_extension("");
+    let name = name
+        .replace("[name]", file_stem)
+        .replace("[id]", &id.to_string_lossy().replace(['/', '\\'], "_"));
+
+    // templates are written for js files, e.g. [name].js => [name].css
+    let name = name
+        .strip_suffix(".js")
+        .or_else(|| name.strip_suffix(&format!(".{}", file_extension)))
+        .unwrap_or(&name);
+    format!("{}.{}", name, file_extension)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::path::Path;
+
+    use super::hash_file_name;
+    use crate::compiler::Compiler;
+    use crate::config::{Config, Mode};
+    use crate::utils::test_helper::setup_compiler;
+
+    #[test]
+    fn test_hash_file_name() {
+        let hash = Some("0123456789abcdef0123456789abcdef");
+        assert_eq!(hash_file_name("a.js", "a", hash, None), "a.01234567.js");
+        assert_eq!(hash_file_name("a.js", "a", None, None), "a.js");
+        assert_eq!(
+            hash_file_name("a.js", "a", hash, Some("[name].[contenthash:4].js")),
+            "a.0123.js"
+        );
+        assert_eq!(
+            hash_file_name("a.css", "a", hash, Some("[name].[contenthash].js")),
+            "a.01234567.css"
+        );
+        assert_eq!(
+            hash_file_name("src_a_js-async.js", "src/a.js", hash, Some("chunk-[id].js")),
+            "chunk-src_a.js"
+        );
+        // no hash in development mode
+        assert_eq!(
+            hash_file_name("a.js", "a", None, Some("[name].[contenthash:8].js")),
+            "a.js"
+        );
+    }
+
+    #[test]
+    fn test_content_hash_is_stable() {
+        let base = "test/build/tmp/content-hash";
+        let root = std::env::current_dir().unwrap().join(base);
+        if root.exists() {
+            fs::remove_dir_all(&root).unwrap();
+        }
+        fs::create_dir_all(root.join("src")).unwrap();
+        fs::write(
+            root.join("mako.config.json"),
+            r#"{"mode":"production","entry":{"a":"./src/a.js","b":"./src/b.js"},"output":{"filename":"[name].[contenthash:8].js"}}"#,
+        )
+        .unwrap();
+        fs::write(root.join("src/a.js"), "console.log('a');").unwrap();
+        fs::write(root.join("src/b.js"), "console.log('b');").unwrap();
+
+        let first = build(base, &root);
+        assert_eq!(first.len(), 2);
+        assert!(first.iter().all(|f| f.len() == "a.12345678.js".len()));
+        assert_eq!(build(base, &root), first);
+
+        fs::write(root.join("src/a.js"), "console.log('a changed');").unwrap();
+        let changed = build(base, &root);
+        assert_ne!(changed[0], first[0]);
+        assert_eq!(changed[1], first[1]);
+    }
+
+    #[test]
+    fn test_runtime_chunk() {
+        let root = std::env::current_dir()
+            .unwrap()
+            .join("test/build/runtime-chunk");
+        let mut config = Config::new(&root, None, None).unwrap();
+        config.minify = None;
+        config.mode = Mode::Production;
+        let compiler = Compiler::new(config, root.clone(), Default::default(), None).unwrap();
+        compiler.compile().unwrap();
+
+        let dist = root.join("dist");
+        let runtime = fs::read_to_string(dist.join("runtime.js")).unwrap();
+        assert_eq!(runtime.matches("function createRuntime").count(), 1);
+        for entry in ["a", "b"] {
+            let content = fs::read_to_string(dist.join(format!("{}.js", entry))).unwrap();
+            assert!(!content.contains("createRuntime"), "{}", content);
+            // pushed with the entry module id to be executed by the runtime
+            assert!(
+                content.contains(&format!("    }},\n    \"{}.ts\"\n]);", entry)),
+                "{}",
+                content
+            );
+        }
+        let stats = serde_json::to_value(compiler.create_stats_info()).unwrap();
+        assert_eq!(
+            stats["entrypoints"]["a"]["chunks"],
+            serde_json::json!(["runtime", "a.ts"])
+        );
+    }
+
+    #[test]
+    fn test_worker() {
+        let root = std::env::current_dir().unwrap().join("test/build/worker");
+        let files = build("test/build/worker", &root);
+        assert_eq!(
+            files,
+            vec![
+                "index.js",
+                "shared_ts_q_s57j-worker.js",
+                "worker_ts_q_s57j-worker.js"
+            ]
+        );
+        let content = fs::read_to_string(root.join("dist/index.js")).unwrap();
+        // urls are rewritten to the worker outputs
+        assert!(
+            content.contains("new Worker(new URL(\"worker_ts_q_s57j-worker.js\", "),
+            "{}",
+            content
+        );
+        assert!(
+            content.contains("new SharedWorker(new URL(\"shared_ts_q_s57j-worker.js\", "),
+            "{}",
+            content
+        );
+        let worker = fs::read_to_string(root.join("dist/worker_ts_q_s57j-worker.js")).unwrap();
+        assert!(worker.contains("worker: "));
+    }
+
+    fn build(base: &str, root: &Path) -> Vec<String> {
+        let dist = root.join("dist");
+        if dist.exists() {
+            fs::remove_dir_all(&dist).unwrap();
+        }
+        let compiler = setup_compiler(base, false);
+        compiler.compile().unwrap();
+        let mut files = fs::read_dir(dist)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().to_string_lossy().to_string())
+            .filter(|file| file.ends_with(".js"))
+            .collect::<Vec<_>>();
+        files.sort();
+        files
+    }
 }
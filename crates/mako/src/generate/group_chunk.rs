@@ -6,7 +6,7 @@ use tracing::debug;
 use crate::ast::file::parse_path;
 use crate::compiler::Compiler;
 use crate::dev::update::UpdateResult;
-use crate::generate::chunk::{Chunk, ChunkId, ChunkType};
+use crate::generate::chunk::{named_async_filename, Chunk, ChunkId, ChunkType};
 use crate::generate::chunk_graph::ChunkGraph;
 use crate::module::{ModuleId, ResolveType};
 
@@ -94,12 +94,13 @@ impl Compiler {
         visited_workers: &mut HashSet<ModuleId>,
     ) {
         visit_modules(dynamic_dependencies, Some(visited.clone()), |head| {
-            let (chunk, dynamic_dependencies, mut worker_dependencies) = self.create_chunk(
+            let (mut chunk, dynamic_dependencies, mut worker_dependencies) = self.create_chunk(
                 head,
                 ChunkType::Async,
                 chunk_graph,
                 vec![chunk_name.to_string()],
             );
+            chunk.name = self.get_chunk_name_hint(head, chunk_graph);
 
             worker_dependencies.retain(|w| !visited_workers.contains(w));
 
@@ -241,7 +242,7 @@ impl Compiler {
                     module_graph
                         .get_dependents(module_id)
                         .iter()
-                        .any(|(_, dep)| dep.resolve_type == ResolveType::DynamicImport)
+                        .any(|(_, dep)| dep.resolve_type.is_dynamic_esm())
                 })
                 .cloned()
                 .collect::<_>();
@@ -314,8 +315,7 @@ impl Compiler {
                 .get_dependencies(head)
                 .into_iter()
                 .filter(|(_, dep)| {
-                    dep.resolve_type != ResolveType::DynamicImport
-                        && dep.resolve_type != ResolveType::Worker
+                    !dep.resolve_type.is_dynamic_esm() && dep.resolve_type != ResolveType::Worker
                 })
                 .collect::<Vec<_>>();
             let mut next_module_ids = vec![];
@@ -375,6 +375,30 @@ impl Compiler {
         })
     }
 
+    // get chunk name from the webpackChunkName of dynamic imports which import the module,
+    // fallback to the auto-generated name if the file name is already taken by another chunk,
+    // the file names are compared after sanitizing, e.g. pages/a and pages_a are the same
+    fn get_chunk_name_hint(
+        &self,
+        module_id: &ModuleId,
+        chunk_graph: &ChunkGraph,
+    ) -> Option<String> {
+        let module_graph = self.context.module_graph.read().unwrap();
+        let mut names = module_graph
+            .get_dependents(module_id)
+            .into_iter()
+            .filter(|(_, dep)| dep.resolve_type.is_dynamic_esm())
+            .filter_map(|(_, dep)| dep.chunk_hint.as_ref().and_then(|h| h.name.clone()))
+            .collect::<Vec<_>>();
+        // make it stable when multiple names are specified
+        names.sort();
+        names.into_iter().next().filter(|name| {
+            chunk_graph
+                .get_chunk_by_name(&named_async_filename(name))
+                .is_none()
+        })
+    }
+
     fn create_chunk(
         &self,
         entry_module_id: &ModuleId,
@@ -402,7 +426,7 @@ impl Compiler {
 
             for (dep_module_id, dep) in module_graph.get_dependencies(head) {
                 match dep.resolve_type {
-                    ResolveType::DynamicImport => {
+                    ResolveType::DynamicImport | ResolveType::DynamicImportGlob { .. } => {
                         dynamic_entries.push(dep_module_id.clone());
                     }
                     ResolveType::Worker => {
@@ -448,12 +472,13 @@ impl Compiler {
         let mut new_chunks = vec![];
 
         visit_modules(async_module_ids, None, |head| {
-            let (new_chunk, dynamic_dependencies, worker_dependencies) = self.create_chunk(
+            let (mut new_chunk, dynamic_dependencies, worker_dependencies) = self.create_chunk(
                 head,
                 ChunkType::Async,
                 chunk_graph,
                 shared_chunk_names.clone(),
             );
+            new_chunk.name = self.get_chunk_name_hint(head, chunk_graph);
             let chunk_id = new_chunk.id.clone();
 
             // record edges and add chunk to graph
@@ -508,3 +533,32 @@ where
         queue.extend(callback(&id));
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use crate::utils::test_helper::setup_compiler;
+
+    #[test]
+    fn test_chunk_name_collision_after_sanitizing() {
+        let compiler = setup_compiler("test/build/chunk-name-collision", false);
+        compiler.compile().unwrap();
+        let output = compiler.context.config.output.path.clone();
+        let chunks = fs::read_dir(&output)
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .filter(|path| path.to_string_lossy().ends_with("-async.js"))
+            .map(|path| fs::read_to_string(path).unwrap())
+            .collect::<Vec<_>>();
+        let named = fs::read_to_string(output.join("pages_a-async.js")).unwrap();
+        fs::remove_dir_all(&output).unwrap();
+
+        // pages/a and pages_a are both sanitized to pages_a, the latter falls back to
+        // the auto-generated name instead of overwriting the former
+        assert_eq!(chunks.len(), 2);
+        assert!(chunks.iter().any(|chunk| chunk.contains("chunk a")));
+        assert!(chunks.iter().any(|chunk| chunk.contains("chunk b")));
+        assert!(named.contains("chunk a") ^ named.contains("chunk b"));
+    }
+}
@@ -6,7 +6,7 @@ use swc_core::common::GLOBALS;
 use swc_core::css::ast::Stylesheet;
 use swc_core::css::minifier;
 use swc_core::ecma::minifier::optimize;
-use swc_core::ecma::minifier::option::{ExtraOptions, MinifyOptions};
+use swc_core::ecma::minifier::option::{ExtraOptions, MangleOptions, MinifyOptions};
 use swc_core::ecma::transforms::base::fixer::fixer;
 use swc_core::ecma::transforms::base::helpers::{Helpers, HELPERS};
 use swc_core::ecma::transforms::base::resolver;
@@ -15,6 +15,7 @@ use swc_error_reporters::handler::try_with_handler;
 
 use crate::ast::js_ast::JsAst;
 use crate::compiler::Context;
+use crate::visitors::drop_console::DropConsole;
 
 pub fn minify_js(ast: &mut JsAst, context: &Arc<Context>) -> Result<()> {
     crate::mako_profile_function!();
@@ -34,6 +35,13 @@ pub fn minify_js(ast: &mut JsAst, context: &Arc<Context>) -> Result<()> {
                             false,
                         ));
 
+                        if let Some(config) = &context.config.minify {
+                            ast.ast.visit_mut_with(&mut DropConsole {
+                                config,
+                                unresolved_mark,
+                            });
+                        }
+
                         let mut minified = optimize(
                             ast.ast.clone().into(),
                             context.meta.script.cm.clone(),
@@ -49,7 +57,17 @@ pub fn minify_js(ast: &mut JsAst, context: &Arc<Context>) -> Result<()> {
                             None,
                             &MinifyOptions {
                                 compress: Some(Default::default()),
-                                mangle: Some(Default::default()),
+                                mangle: context.config.mangle.as_ref().map(|mangle| {
+                                    MangleOptions {
+                                        top_level: Some(mangle.toplevel),
+                                        reserved: mangle
+                                            .reserved
+                                            .iter()
+                                            .map(|name| name.as_str().into())
+                                            .collect(),
+                                        ..Default::default()
+                                    }
+                                }),
                                 ..Default::default()
                             },
                             &ExtraOptions {
@@ -91,3 +109,135 @@ pub fn minify_css(stylesheet: &mut Stylesheet, context: &Arc<Context>) -> Result
         })
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::{minify_css, minify_js};
+    use crate::ast::tests::{TestUtils, TestUtilsOpts};
+    use crate::compiler::Context;
+    use crate::config::{DropConsoleConfig, MangleConfig, MinifyConfig};
+
+    const JS_CODE: &str = r#"
+export function add(first, second) {
+    const result = first + second;
+    const keepMe = result * 2;
+    return keepMe + result;
+}
+"#;
+
+    #[test]
+    fn test_mangle_locals() {
+        let code = run_js(JS_CODE, Some(MangleConfig::default()));
+        assert!(code.contains("function add("), "{}", code);
+        assert!(!code.contains("first"), "{}", code);
+        assert!(!code.contains("result"), "{}", code);
+    }
+
+    #[test]
+    fn test_mangle_reserved() {
+        let code = run_js(
+            JS_CODE,
+            Some(MangleConfig {
+                reserved: vec!["first".to_string(), "keepMe".to_string()],
+                ..Default::default()
+            }),
+        );
+        assert!(code.contains("first"), "{}", code);
+        assert!(!code.contains("second"), "{}", code);
+    }
+
+    #[test]
+    fn test_mangle_toplevel() {
+        let js_code = "const helper = () => 1; export const value = helper();";
+        let code = run_js(js_code, Some(MangleConfig::default()));
+        assert!(code.contains("helper"), "{}", code);
+        assert!(code.contains("value"), "{}", code);
+        let code = run_js(
+            js_code,
+            Some(MangleConfig {
+                toplevel: true,
+                ..Default::default()
+            }),
+        );
+        assert!(!code.contains("helper"), "{}", code);
+        // the exported name should be kept
+        assert!(code.contains("value"), "{}", code);
+    }
+
+    #[test]
+    fn test_drop_console_and_debugger() {
+        let mut context = Context::default();
+        context.config.minify = Some(MinifyConfig {
+            drop_console: DropConsoleConfig::Bool(true),
+            drop_debugger: true,
+        });
+        let code = run_js_with_context(
+            "export function f(x) { console.log(sideEffect()); debugger; return x; }",
+            context,
+        );
+        assert!(!code.contains("console"), "{}", code);
+        assert!(!code.contains("sideEffect"), "{}", code);
+        assert!(!code.contains("debugger"), "{}", code);
+    }
+
+    #[test]
+    fn test_mangle_false() {
+        let code = run_js(JS_CODE, None);
+        assert!(code.contains("first"), "{}", code);
+    }
+
+    fn run_js(js_code: &str, mangle: Option<MangleConfig>) -> String {
+        let mut context = Context::default();
+        context.config.mangle = mangle;
+        run_js_with_context(js_code, context)
+    }
+
+    fn run_js_with_context(js_code: &str, context: Context) -> String {
+        let mut test_utils = TestUtils::with_context(
+            TestUtilsOpts {
+                file: Some("test.js".to_string()),
+                content: Some(js_code.to_string()),
+            },
+            Arc::new(context),
+        );
+        minify_js(test_utils.ast.js_mut(), &test_utils.context).unwrap();
+        test_utils.js_ast_to_code()
+    }
+
+    #[test]
+    fn test_minify_css() {
+        let css = r#"
+/* comment */
+.a {
+    color: #ffffff;
+    margin: 0px 0px 0px 0px;
+}
+.b {
+    color: red;
+}
+.b {
+    background: blue;
+}
+"#;
+        let code = run(css);
+        assert_eq!(code, ".a{color:#fff;margin:0}.b{color:red;background:blue}");
+        assert!(code.len() < css.len());
+    }
+
+    #[test]
+    fn test_minify_css_calc() {
+        assert_eq!(
+            run(".a { width: calc(100% - 10px); height: calc(1px + 2em); }"),
+            ".a{width:calc(100% - 10px);height:calc(2em + 1px)}"
+        );
+    }
+
+    fn run(css_code: &str) -> String {
+        let mut test_utils = TestUtils::gen_css_ast(css_code.to_string(), true);
+        let ast = test_utils.ast.css_mut();
+        minify_css(&mut ast.ast, &test_utils.context).unwrap();
+        test_utils.css_ast_to_code()
+    }
+}
@@ -1,7 +1,9 @@
 pub(crate) mod analyze;
+pub(crate) mod banner;
 pub(crate) mod chunk;
 pub(crate) mod chunk_graph;
 pub(crate) mod chunk_pot;
+pub(crate) mod esm;
 pub(crate) mod generate_chunks;
 pub(crate) mod group_chunk;
 pub(crate) mod hmr;
@@ -10,8 +12,10 @@ pub(crate) mod optimize_chunk;
 pub(crate) mod runtime;
 pub(crate) mod swc_helpers;
 pub(crate) mod transform;
-use std::collections::{HashMap, HashSet};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::fs;
+use std::hash::{Hash, Hasher};
 use std::ops::DerefMut;
 use std::path::PathBuf;
 use std::sync::Arc;
@@ -25,13 +29,15 @@ use serde::Serialize;
 use tracing::debug;
 
 use crate::compiler::{Compiler, Context};
-use crate::config::{DevtoolConfig, OutputMode, TreeShakingStrategy};
+use crate::config::{DevtoolConfig, OutputFormat, OutputMode, TreeShakingStrategy};
 use crate::dev::update::UpdateResult;
+use crate::generate::banner::add_banner_and_footer;
 use crate::generate::generate_chunks::{ChunkFile, ChunkFileType};
 use crate::module::{Dependency, ModuleId};
 use crate::plugins::bundless_compiler::BundlessCompiler;
-use crate::stats::StatsJsonMap;
+use crate::stats::{write_stats, StatsJsonMap};
 use crate::utils::base64_encode;
+use crate::utils::profiler::Phase;
 use crate::visitors::async_module::mark_async;
 
 #[derive(Clone)]
@@ -42,6 +48,13 @@ pub struct EmitFile {
     pub hashname: String,
 }
 
+// output files by their names relative to the output dir, plugins can add, modify
+// or remove files in the generate_bundle hook
+#[derive(Default)]
+pub struct Bundle {
+    pub files: BTreeMap<String, Vec<u8>>,
+}
+
 #[derive(Serialize)]
 struct ChunksUrlMap {
     js: HashMap<String, String>,
@@ -76,6 +89,10 @@ impl Compiler {
     pub fn generate(&self) -> Result<StatsJsonMap> {
         debug!("generate");
         let t_generate = Instant::now();
+        let is_esm = self.context.config.output.format == Some(OutputFormat::Esm);
+        if is_esm && self.context.args.watch {
+            return Err(anyhow!("output.format esm is not supported in watch mode"));
+        }
 
         if self
             .context
@@ -100,9 +117,11 @@ impl Compiler {
                     let mut module_graph = self.context.module_graph.write().unwrap();
 
                     crate::mako_profile_scope!("tree shake");
-                    self.context
-                        .plugin_driver
-                        .optimize_module_graph(module_graph.deref_mut(), &self.context)?;
+                    self.context.profiler.record(Phase::TreeShake, || {
+                        self.context
+                            .plugin_driver
+                            .optimize_module_graph(module_graph.deref_mut(), &self.context)
+                    })?;
                     let t_tree_shaking = t_tree_shaking.elapsed();
                     debug!("basic optimize in {}ms.", t_tree_shaking.as_millis());
                 }
@@ -119,35 +138,44 @@ impl Compiler {
             return Ok(stats);
         }
 
-        let t_group_chunks = Instant::now();
-        self.group_chunk();
-        let t_group_chunks = t_group_chunks.elapsed();
+        let (t_group_chunks, t_optimize_chunks) =
+            self.context
+                .profiler
+                .record(Phase::Chunk, || -> Result<_> {
+                    let t_group_chunks = Instant::now();
+                    self.group_chunk();
+                    let t_group_chunks = t_group_chunks.elapsed();
 
-        let t_optimize_chunks = Instant::now();
+                    let t_optimize_chunks = Instant::now();
 
-        self.context
-            .plugin_driver
-            .before_optimize_chunk(&self.context)?;
+                    self.context
+                        .plugin_driver
+                        .before_optimize_chunk(&self.context)?;
 
-        self.optimize_chunk();
-        let t_optimize_chunks = t_optimize_chunks.elapsed();
+                    self.optimize_chunk();
+                    let t_optimize_chunks = t_optimize_chunks.elapsed();
 
-        {
-            let mut module_graph = self.context.module_graph.write().unwrap();
-            let mut chunk_graph = self.context.chunk_graph.write().unwrap();
-
-            self.context.plugin_driver.optimize_chunk(
-                &mut chunk_graph,
-                &mut module_graph,
-                &self.context,
-            )?;
-        }
+                    let mut module_graph = self.context.module_graph.write().unwrap();
+                    let mut chunk_graph = self.context.chunk_graph.write().unwrap();
+
+                    self.context.plugin_driver.optimize_chunk(
+                        &mut chunk_graph,
+                        &mut module_graph,
+                        &self.context,
+                    )?;
+                    Ok((t_group_chunks, t_optimize_chunks))
+                })?;
 
         // 为啥单独提前 transform modules？
         // 因为放 chunks 的循环里，一个 module 可能存在于多个 chunk 里，可能会被编译多遍
         let t_transform_modules = Instant::now();
         debug!("transform all modules");
-        self.transform_all(async_dep_map)?;
+        // es modules are linked when the chunks are generated
+        if !is_esm {
+            self.context
+                .profiler
+                .record(Phase::Generate, || self.transform_all(async_dep_map))?;
+        }
         let t_transform_modules = t_transform_modules.elapsed();
 
         // ensure output dir exists
@@ -163,18 +191,21 @@ impl Compiler {
         if config.emit_assets {
             let t_write_assets = Instant::now();
             debug!("write assets");
-            {
+            self.context.profiler.record(Phase::Write, || {
                 let assets_info = &(*self.context.assets_info.lock().unwrap());
                 for (k, v) in assets_info {
                     let asset_path = &self.context.root.join(k);
                     let asset_output_path = &config.output.path.join(v);
                     if asset_path.exists() {
+                        fs::create_dir_all(asset_output_path.parent().unwrap())?;
                         fs::copy(asset_path, asset_output_path)?;
+                        self.context.emitted_files.add(asset_output_path);
                     } else {
                         return Err(anyhow!("asset not found: {}", asset_path.display()));
                     }
                 }
-            }
+                Ok(())
+            })?;
             let t_write_assets = t_write_assets.elapsed();
             debug!("  - write assets: {}ms", t_write_assets.as_millis());
         }
@@ -192,8 +223,14 @@ impl Compiler {
             self.print_stats();
         }
 
+        if self.context.config.stats.is_some() {
+            let path = write_stats(&self.context.config.output.path, &stats);
+            self.context.emitted_files.add(path);
+        }
+
         if self.context.config.analyze.is_some() {
-            Analyze::write_analyze(&stats, &self.context.config.output.path)?;
+            let path = Analyze::write_analyze(&stats, &self.context.config.output.path)?;
+            self.context.emitted_files.add(path);
         }
 
         debug!("generate done in {}ms", t_generate.elapsed().as_millis());
@@ -217,22 +254,62 @@ impl Compiler {
         // generate chunks
         let t_generate_chunks = Instant::now();
         debug!("generate chunks");
-        let chunk_files = self.generate_chunk_files(full_hash)?;
-        self.context
-            .plugin_driver
-            .after_generate_chunk_files(&chunk_files, &self.context)?;
+        let chunk_files = self.context.profiler.record(Phase::Generate, || {
+            let mut chunk_files = match self.context.config.output.format {
+                Some(OutputFormat::Esm) => self.generate_esm_chunk_files()?,
+                None => self.generate_chunk_files(full_hash)?,
+            };
+            add_banner_and_footer(&mut chunk_files, &self.context.config.output)?;
+            self.context
+                .plugin_driver
+                .after_generate_chunk_files(&chunk_files, &self.context)?;
+            self.generate_bundle(chunk_files)
+        })?;
 
         let t_generate_chunks = t_generate_chunks.elapsed();
 
-        let t_ast_to_code_and_write = if self.context.args.watch {
-            self.generate_chunk_mem_file(&chunk_files)?
-        } else {
-            self.generate_chunk_disk_file(&chunk_files)?
-        };
+        let t_ast_to_code_and_write = self.context.profiler.record(Phase::Write, || {
+            if self.context.args.watch {
+                self.generate_chunk_mem_file(&chunk_files)
+            } else {
+                self.generate_chunk_disk_file(&chunk_files)
+            }
+        })?;
 
         Ok((t_generate_chunks, t_ast_to_code_and_write))
     }
 
+    // the chunk files are replaced by the files of the bundle with the same names after
+    // the generate_bundle hook, and the other files of the bundle are added
+    fn generate_bundle(&self, mut chunk_files: Vec<ChunkFile>) -> Result<Vec<ChunkFile>> {
+        let mut bundle = Bundle::default();
+        for chunk_file in chunk_files.iter_mut() {
+            bundle.files.insert(
+                chunk_file.disk_name(),
+                std::mem::take(&mut chunk_file.content),
+            );
+        }
+        self.context
+            .plugin_driver
+            .generate_bundle(&mut bundle, &self.context)?;
+        chunk_files.retain_mut(
+            |chunk_file| match bundle.files.remove(&chunk_file.disk_name()) {
+                Some(content) => {
+                    chunk_file.content = content;
+                    true
+                }
+                None => false,
+            },
+        );
+        for (name, content) in bundle.files {
+            let mut hasher = DefaultHasher::new();
+            content.hash(&mut hasher);
+            self.context
+                .write_static_content(name, content, hasher.finish())?;
+        }
+        Ok(chunk_files)
+    }
+
     fn generate_chunk_disk_file(&self, chunk_files: &Vec<ChunkFile>) -> Result<Duration> {
         let t_ast_to_code_and_write = Instant::now();
         debug!("ast to code and write");
@@ -291,7 +368,8 @@ impl Compiler {
 
         // generate chunks
         let t_generate_chunks = Instant::now();
-        let chunk_files = self.generate_chunk_files(current_hmr_hash)?;
+        let mut chunk_files = self.generate_chunk_files(current_hmr_hash)?;
+        add_banner_and_footer(&mut chunk_files, &self.context.config.output)?;
 
         if config.hmr.is_some() {
             let mut chunk_id_url_map = ChunksUrlMap {
@@ -321,6 +399,7 @@ impl Compiler {
         self.context
             .plugin_driver
             .after_generate_chunk_files(&chunk_files, &self.context)?;
+        let chunk_files = self.generate_bundle(chunk_files)?;
 
         let t_generate_chunks = t_generate_chunks.elapsed();
 
@@ -337,6 +416,7 @@ impl Compiler {
                 let asset_path = &self.context.root.join(k);
                 let asset_output_path = &config.output.path.join(v);
                 if asset_path.exists() {
+                    fs::create_dir_all(asset_output_path.parent().unwrap())?;
                     fs::copy(asset_path, asset_output_path)?;
                 } else {
                     panic!("asset not found: {}", asset_path.display());
@@ -503,7 +583,8 @@ impl Compiler {
         content: C,
     ) {
         let to = self.context.config.output.path.join(filename);
-        std::fs::write(to, content).unwrap();
+        std::fs::write(&to, content).unwrap();
+        self.context.emitted_files.add(to);
     }
 }
 
@@ -573,15 +654,13 @@ fn emit_chunk_file(context: &Arc<Context>, chunk_file: &ChunkFile) {
                     to.to_string_lossy().to_string(),
                     chunk_file.source_map_disk_name(),
                 );
-                fs::write(
-                    context
-                        .config
-                        .output
-                        .path
-                        .join(chunk_file.source_map_disk_name()),
-                    source_map,
-                )
-                .unwrap();
+                let source_map_path = context
+                    .config
+                    .output
+                    .path
+                    .join(chunk_file.source_map_disk_name());
+                fs::write(&source_map_path, source_map).unwrap();
+                context.emitted_files.add(source_map_path);
 
                 let source_map_url_line = match chunk_file.file_type {
                     ChunkFileType::JS => {
@@ -608,20 +687,22 @@ fn emit_chunk_file(context: &Arc<Context>, chunk_file: &ChunkFile) {
                 to.to_string_lossy().to_string(),
                 dist_name.clone(),
             );
-            fs::write(to, &code).unwrap();
+            fs::write(&to, &code).unwrap();
         }
         Some(DevtoolConfig::InlineSourceMap) => {
             let mut code = Vec::new();
             code.extend_from_slice(&chunk_file.content);
 
             if let Some(source_map) = &chunk_file.source_map {
-                code.extend_from_slice(
-                    format!(
-                        "\n//# sourceMappingURL=data:application/json;charset=utf-8;base64,{}",
-                        base64_encode(source_map)
-                    )
-                    .as_bytes(),
+                let source_map_url = format!(
+                    "data:application/json;charset=utf-8;base64,{}",
+                    base64_encode(source_map)
                 );
+                let source_map_url_line = match chunk_file.file_type {
+                    ChunkFileType::JS => format!("\n//# sourceMappingURL={}", source_map_url),
+                    ChunkFileType::Css => format!("\n/*# sourceMappingURL={}*/", source_map_url),
+                };
+                code.extend_from_slice(source_map_url_line.as_bytes());
             }
 
             let size = code.len() as u64;
@@ -632,7 +713,7 @@ fn emit_chunk_file(context: &Arc<Context>, chunk_file: &ChunkFile) {
                 to.to_string_lossy().to_string(),
                 dist_name.clone(),
             );
-            fs::write(to, code).unwrap();
+            fs::write(&to, code).unwrap();
         }
         None => {
             stats_info.add_assets(
@@ -643,9 +724,10 @@ fn emit_chunk_file(context: &Arc<Context>, chunk_file: &ChunkFile) {
                 dist_name,
             );
 
-            fs::write(to, &chunk_file.content).unwrap();
+            fs::write(&to, &chunk_file.content).unwrap();
         }
     }
+    context.emitted_files.add(to);
 }
 
 fn to_hot_update_chunk_name(chunk_name: &String, hash: u64) -> String {
@@ -670,3 +752,217 @@ struct HotUpdateManifest {
     // #[serde(rename(serialize = "c"))]
     // removed_modules: Vec<String>,
 }
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::fs;
+
+    use base64::engine::general_purpose;
+    use base64::Engine;
+    use merge_source_map::sourcemap::SourceMap;
+
+    use crate::module::ModuleId;
+    use crate::utils::test_helper::setup_compiler;
+
+    #[test]
+    fn test_devtool_source_map() {
+        let output = compile("test/build/devtool-source-map");
+        let js = fs::read_to_string(output.join("index.js")).unwrap();
+        assert!(js.ends_with("\n//# sourceMappingURL=index.js.map"));
+        let css = fs::read_to_string(output.join("index.css")).unwrap();
+        assert!(css.ends_with("\n/*# sourceMappingURL=index.css.map*/"));
+        for map in ["index.js.map", "index.css.map"] {
+            let source_map = fs::read_to_string(output.join(map)).unwrap();
+            assert!(serde_json::from_str::<serde_json::Value>(&source_map).is_ok());
+        }
+    }
+
+    #[test]
+    fn test_devtool_inline_source_map() {
+        let output = compile("test/build/devtool-inline-source-map");
+        let prefix = "sourceMappingURL=data:application/json;charset=utf-8;base64,";
+        let js = fs::read_to_string(output.join("index.js")).unwrap();
+        let (_, js_map) = js.rsplit_once(&format!("\n//# {}", prefix)).unwrap();
+        let css = fs::read_to_string(output.join("index.css")).unwrap();
+        let (_, css_map) = css.rsplit_once(&format!("\n/*# {}", prefix)).unwrap();
+        let css_map = css_map.strip_suffix("*/").unwrap();
+        for source_map in [js_map, css_map] {
+            let source_map = general_purpose::STANDARD.decode(source_map).unwrap();
+            let source_map: serde_json::Value = serde_json::from_slice(&source_map).unwrap();
+            assert!(source_map["mappings"].is_string());
+        }
+        assert!(!output.join("index.js.map").exists());
+        assert!(!output.join("index.css.map").exists());
+    }
+
+    #[test]
+    fn test_devtool_false() {
+        let output = compile("test/build/devtool-false");
+        for file in ["index.js", "index.css"] {
+            let content = fs::read_to_string(output.join(file)).unwrap();
+            assert!(!content.contains("sourceMappingURL"));
+            assert!(!output.join(format!("{}.map", file)).exists());
+        }
+    }
+
+    #[test]
+    fn test_css_import_conditions() {
+        let output = compile("test/build/css-import-conditions");
+        let css = fs::read_to_string(output.join("index.css")).unwrap();
+        fs::remove_dir_all(&output).unwrap();
+        assert!(css.contains("@media print {\n  .print {"));
+        assert!(css.contains("@supports (display: grid) {\n  @media screen {\n    .grid {"));
+        // shared.css is imported by index.ts without conditions as well
+        assert!(css.contains("\n.shared {"));
+        assert!(!css.contains("@import"));
+    }
+
+    #[test]
+    fn test_input_source_map() {
+        let output = compile("test/build/input-source-map");
+        let js = fs::read_to_string(output.join("index.js")).unwrap();
+        let source_map = fs::read(output.join("index.js.map")).unwrap();
+        let source_map = SourceMap::from_slice(&source_map).unwrap();
+        let sources = source_map.sources().collect::<Vec<_>>();
+        assert!(sources.contains(&"lib/original.ts"));
+        assert!(!sources.contains(&"lib/compiled.js"));
+        // `return a + b;` is on the second line of lib/original.ts
+        let line = js
+            .lines()
+            .position(|l| l.contains("return a + b;"))
+            .unwrap() as u32;
+        let token = source_map
+            .tokens()
+            .find(|t| t.get_dst_line() == line && t.get_source() == Some("lib/original.ts"))
+            .unwrap();
+        assert_eq!(token.get_src_line(), 1);
+        assert!(token
+            .get_source_view()
+            .unwrap()
+            .source()
+            .starts_with("export function add(a: number, b: number)"));
+    }
+
+    #[test]
+    fn test_deterministic_module_ids() {
+        let base = "test/build/tmp/deterministic-module-ids";
+        let before = build_with_module_ids(base, "deterministic", false);
+        let after = build_with_module_ids(base, "deterministic", true);
+        assert_eq!(after.len(), 4);
+        // adding a module doesn't change the ids of the existing ones
+        for (path, id) in &before {
+            assert_eq!(after.get(path), Some(id));
+            assert_eq!(id.len(), 8);
+        }
+    }
+
+    #[test]
+    fn test_numeric_module_ids() {
+        let base = "test/build/tmp/numeric-module-ids";
+        let before = build_with_module_ids(base, "numeric", false);
+        assert_eq!(before["src/b.js"], "0");
+        assert_eq!(before["src/c.js"], "1");
+        assert_eq!(before["src/index.js"], "2");
+        // the ids are assigned in the order of the module paths
+        let after = build_with_module_ids(base, "numeric", true);
+        assert_eq!(after["src/a.js"], "0");
+        assert_eq!(after["src/index.js"], "3");
+    }
+
+    #[test]
+    fn test_require_context_module_ids() {
+        let root = std::env::current_dir()
+            .unwrap()
+            .join("test/build/tmp/require-context-module-ids");
+        if root.exists() {
+            fs::remove_dir_all(&root).unwrap();
+        }
+        fs::create_dir_all(root.join("src/locales")).unwrap();
+        fs::write(
+            root.join("mako.config.json"),
+            r#"{"entry":{"index":"./src/index.js"},"optimization":{"moduleIds":"numeric"}}"#,
+        )
+        .unwrap();
+        fs::write(
+            root.join("src/index.js"),
+            "const ctx = require.context('./locales', false, /\\.js$/);\nconsole.log(ctx.resolve('./en.js'));\n",
+        )
+        .unwrap();
+        fs::write(root.join("src/locales/en.js"), "export default 'en';").unwrap();
+        fs::write(root.join("src/locales/zh.js"), "export default 'zh';").unwrap();
+
+        let compiler = setup_compiler(root.to_str().unwrap(), false);
+        compiler.compile().unwrap();
+        let context = compiler.context.clone();
+        let js = fs::read_to_string(context.config.output.path.join("index.js")).unwrap();
+        fs::remove_dir_all(&root).unwrap();
+
+        // the ids of the context module map are the ones assigned after the build
+        let en = ModuleId::from_path(root.join("src/locales/en.js")).generate(&context);
+        let zh = ModuleId::from_path(root.join("src/locales/zh.js")).generate(&context);
+        assert_eq!((en.as_str(), zh.as_str()), ("1", "2"));
+        assert!(js.contains(r#""./en.js": "1""#), "{}", js);
+        assert!(js.contains(r#""./zh.js": "2""#), "{}", js);
+    }
+
+    // returns the ids of the modules by their paths relative to root,
+    // the ids are asserted to be used by the output
+    fn build_with_module_ids(
+        base: &str,
+        module_ids: &str,
+        with_new_module: bool,
+    ) -> HashMap<String, String> {
+        let root = std::env::current_dir().unwrap().join(base);
+        if root.exists() {
+            fs::remove_dir_all(&root).unwrap();
+        }
+        fs::create_dir_all(root.join("src")).unwrap();
+        fs::write(
+            root.join("mako.config.json"),
+            format!(
+                r#"{{"entry":{{"index":"./src/index.js"}},"optimization":{{"moduleIds":"{}"}}}}"#,
+                module_ids
+            ),
+        )
+        .unwrap();
+        let mut index = "import './b';\nimport('./c');\n".to_string();
+        if with_new_module {
+            fs::write(root.join("src/a.js"), "console.log('a');").unwrap();
+            index.push_str("import './a';\n");
+        }
+        fs::write(root.join("src/b.js"), "console.log('b');").unwrap();
+        fs::write(root.join("src/c.js"), "console.log('c');").unwrap();
+        fs::write(root.join("src/index.js"), index).unwrap();
+
+        let compiler = setup_compiler(base, false);
+        compiler.compile().unwrap();
+        let context = compiler.context.clone();
+        let output = fs::read_dir(&context.config.output.path)
+            .unwrap()
+            .map(|entry| fs::read_to_string(entry.unwrap().path()).unwrap_or_default())
+            .collect::<String>();
+        let module_graph = context.module_graph.read().unwrap();
+        let ids = module_graph
+            .modules()
+            .into_iter()
+            .map(|module| {
+                let id = module.id.generate(&context);
+                assert!(output.contains(&format!("\"{}\": function", id)));
+                let path = module
+                    .id
+                    .id
+                    .replace(&format!("{}/", root.to_str().unwrap()), "");
+                (path, id)
+            })
+            .collect();
+        fs::remove_dir_all(&root).unwrap();
+        ids
+    }
+
+    fn compile(base: &str) -> std::path::PathBuf {
+        let compiler = setup_compiler(base, false);
+        compiler.compile().unwrap();
+        compiler.context.config.output.path.clone()
+    }
+}
@@ -10,9 +10,9 @@ use tracing::debug;
 
 use crate::compiler::Compiler;
 use crate::config::{
-    CodeSplitting, CodeSplittingAdvancedOptions, CodeSplittingGranularOptions,
-    CodeSplittingStrategy, CodeSplittingStrategyOptions, GenericUsizeDefault, OptimizeAllowChunks,
-    OptimizeChunkGroup, OptimizeChunkNameSuffixStrategy,
+    CodeSplitting, CodeSplittingAdvancedOptions, CodeSplittingAutoOptions,
+    CodeSplittingGranularOptions, CodeSplittingStrategy, CodeSplittingStrategyOptions,
+    GenericUsizeDefault, OptimizeAllowChunks, OptimizeChunkGroup, OptimizeChunkNameSuffixStrategy,
 };
 use crate::generate::chunk::{Chunk, ChunkId, ChunkType};
 use crate::generate::group_chunk::GroupUpdateResult;
@@ -42,6 +42,9 @@ impl Compiler {
 
             optimize_chunks_infos.sort_by_key(|o| -o.group_options.priority);
 
+            // stage: merge minimal async chunks
+            self.merge_small_async_chunks(&optimize_options);
+
             // stage: modules
             self.module_to_optimize_infos(&mut optimize_chunks_infos, None);
 
@@ -54,6 +57,15 @@ impl Compiler {
             // stage: apply
             self.apply_optimize_infos(&optimize_chunks_infos);
 
+            // stage: split large async chunks
+            if let Some(max_size) = optimize_options.max_size {
+                self.context
+                    .chunk_graph
+                    .write()
+                    .unwrap()
+                    .split_large_async_chunks(max_size, |m| self.get_module_size(m).unwrap_or(0));
+            }
+
             // save optimize infos for hot update
             if let Ok(mut optimize_info) = self.context.optimize_infos.lock() {
                 *optimize_info = Some(optimize_chunks_infos);
@@ -112,9 +124,27 @@ impl Compiler {
 
             // stage: apply
             self.apply_hot_update_optimize_infos(&optimize_infos);
+
+            // stage: merge minimal async chunks, new async chunks may be small too
+            if let Some(optimize_options) = self.get_optimize_chunk_options() {
+                self.merge_small_async_chunks(&optimize_options);
+            }
         }
     }
 
+    fn merge_small_async_chunks(&self, optimize_options: &CodeSplittingAdvancedOptions) {
+        if optimize_options.min_size == 0 {
+            return;
+        }
+        self.context
+            .chunk_graph
+            .write()
+            .unwrap()
+            .merge_small_async_chunks(optimize_options.min_size, |m| {
+                self.get_module_size(m).unwrap_or(0)
+            });
+    }
+
     fn module_to_optimize_infos<'a>(
         &'a self,
         optimize_chunks_infos: &'a mut Vec<OptimizeChunksInfo>,
@@ -216,6 +246,7 @@ impl Compiler {
                         .collect::<LinkedHashSet<_>>(),
                     id: ChunkId { id: "".to_string() },
                     chunk_type: ChunkType::Sync,
+                    name: None,
                     content: None,
                     source_map: None,
                 };
@@ -441,6 +472,7 @@ impl Compiler {
                     .collect::<LinkedHashSet<_>>(),
                 id: info_chunk_id.clone(),
                 chunk_type: info_chunk_type,
+                name: None,
                 content: None,
                 source_map: None,
             };
@@ -564,7 +596,13 @@ impl Compiler {
             Some(CodeSplitting {
                 strategy: CodeSplittingStrategy::Auto,
                 options: None,
-            }) => Some(code_splitting_strategy_auto()),
+            }) => Some(code_splitting_strategy_auto(
+                &CodeSplittingAutoOptions::default(),
+            )),
+            Some(CodeSplitting {
+                strategy: CodeSplittingStrategy::Auto,
+                options: Some(CodeSplittingStrategyOptions::Auto(auto_options)),
+            }) => Some(code_splitting_strategy_auto(auto_options)),
             Some(CodeSplitting {
                 strategy: CodeSplittingStrategy::Granular,
                 options:
@@ -592,24 +630,31 @@ impl Compiler {
     }
 }
 
-fn code_splitting_strategy_auto() -> CodeSplittingAdvancedOptions {
+fn code_splitting_strategy_auto(
+    options: &CodeSplittingAutoOptions,
+) -> CodeSplittingAdvancedOptions {
+    let mut groups = vec![];
+    if options.vendor {
+        groups.push(OptimizeChunkGroup {
+            name: "vendors".to_string(),
+            allow_chunks: options.allow_chunks.clone(),
+            test: Regex::new(r"[/\\]node_modules[/\\]").ok(),
+            min_size: options.min_size,
+            priority: -10,
+            ..Default::default()
+        });
+    }
+    groups.push(OptimizeChunkGroup {
+        name: "common".to_string(),
+        allow_chunks: options.allow_chunks.clone(),
+        min_chunks: options.min_chunks,
+        // always split, to avoid multi-instance risk
+        min_size: 1,
+        priority: -20,
+        ..Default::default()
+    });
     CodeSplittingAdvancedOptions {
-        groups: vec![
-            OptimizeChunkGroup {
-                name: "vendors".to_string(),
-                test: Regex::new(r"[/\\]node_modules[/\\]").ok(),
-                priority: -10,
-                ..Default::default()
-            },
-            OptimizeChunkGroup {
-                name: "common".to_string(),
-                min_chunks: 2,
-                // always split, to avoid multi-instance risk
-                min_size: 1,
-                priority: -20,
-                ..Default::default()
-            },
-        ],
+        groups,
         ..Default::default()
     }
 }
@@ -666,3 +711,100 @@ fn md5_chunk_ids(chunk_ids: &[ChunkId]) -> String {
     let hash = general_purpose::URL_SAFE.encode(digest.0);
     hash[..8].to_string()
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::compiler::Compiler;
+    use crate::config::{Config, Mode};
+    use crate::generate::chunk::ChunkId;
+    use crate::module::ModuleId;
+    use crate::utils::test_helper::setup_compiler;
+
+    #[test]
+    fn test_auto_vendor() {
+        let compiler = setup_compiler("test/build/code-splitting-vendor", false);
+        compiler.compile().unwrap();
+        let chunk_graph = compiler.context.chunk_graph.read().unwrap();
+        let chunk_of = |path: &str| {
+            let module_id = ModuleId::from(compiler.context.root.join(path));
+            chunk_graph
+                .get_chunk_for_module(&module_id)
+                .map(|chunk| chunk.id.id.clone())
+        };
+        assert_eq!(
+            chunk_of("node_modules/react/index.js"),
+            Some("vendors".to_string())
+        );
+        assert_eq!(chunk_of("src/shared.js"), Some("common".to_string()));
+        assert_eq!(chunk_of("src/a.js"), Some("src/a.js".to_string()));
+    }
+
+    #[test]
+    fn test_common_chunk() {
+        let compiler = setup_compiler("test/build/code-splitting-common", false);
+        compiler.compile().unwrap();
+        let chunk_graph = compiler.context.chunk_graph.read().unwrap();
+        let shared = ModuleId::from(compiler.context.root.join("src/shared.js"));
+        let chunks = chunk_graph
+            .get_chunks()
+            .into_iter()
+            .filter(|chunk| chunk.has_module(&shared))
+            .map(|chunk| chunk.id.id.clone())
+            .collect::<Vec<_>>();
+        assert_eq!(chunks, vec!["common".to_string()]);
+        for name in ["src/a.js", "src/b.js", "src/c.js"] {
+            assert_eq!(
+                chunk_graph.sync_dependencies_chunk(&ChunkId::new(name.to_string())),
+                vec![ChunkId::new("common".to_string())]
+            );
+        }
+        // the common chunk is ensured before the async chunk
+        let index = std::fs::read_to_string(compiler.context.root.join("dist/index.js"))
+            .unwrap()
+            .replace(char::is_whitespace, "");
+        assert!(index.contains(
+            r#"Promise.all([__mako_require__.ensure("common"),__mako_require__.ensure("src/a.js")])"#
+        ));
+    }
+
+    #[test]
+    fn test_merge_small_async_chunks() {
+        let async_chunk = ChunkId::new("src/small.js".to_string());
+        // merging is disabled by default
+        let compiler = setup_compiler("test/build/code-splitting-merge", false);
+        compiler.compile().unwrap();
+        assert!(compiler
+            .context
+            .chunk_graph
+            .read()
+            .unwrap()
+            .chunk(&async_chunk)
+            .is_some());
+
+        let root = std::env::current_dir()
+            .unwrap()
+            .join("test/build/code-splitting-merge");
+        let mut config = Config::new(
+            &root,
+            None,
+            Some(
+                r#"{"codeSplitting":{"strategy":"advanced","options":{"minSize":1000,"groups":[]}}}"#,
+            ),
+        )
+        .unwrap();
+        config.minify = None;
+        config.mode = Mode::Production;
+        config.optimization = None;
+        let compiler = Compiler::new(config, root, Default::default(), None).unwrap();
+        compiler.compile().unwrap();
+        let chunk_graph = compiler.context.chunk_graph.read().unwrap();
+        assert!(chunk_graph.chunk(&async_chunk).is_none());
+        let small = ModuleId::from(compiler.context.root.join("src/small.js"));
+        assert_eq!(
+            chunk_graph
+                .get_chunk_for_module(&small)
+                .map(|chunk| chunk.id.id.clone()),
+            Some("src/index.js".to_string())
+        );
+    }
+}
@@ -1,14 +1,145 @@
 use sailfish::TemplateOnce;
 
+use crate::resolve::get_umd_external_root;
+
 #[derive(TemplateOnce)]
 #[template(path = "app_runtime.stpl")]
 pub struct AppRuntimeTemplate {
     pub has_dynamic_chunks: bool,
     pub has_hmr: bool,
     pub umd: Option<String>,
+    pub umd_externals: UmdExternals,
     pub cjs: bool,
     pub pkg_name: Option<String>,
     pub chunk_loading_global: String,
+    pub chunk_load_timeout: u64,
+    pub chunk_load_retries: u32,
     pub is_browser: bool,
     pub concatenate_enabled: bool,
+    // the runtime is emitted as a separate chunk, which executes entry chunks
+    pub runtime_chunk: bool,
+}
+
+// arguments of the umd wrapper, e.g. for react => React
+// require_args: require("react")
+// amd_deps: "react"
+// root_args: root['React']
+// params: __mako_umd_external_0__
+// object: "react": __mako_umd_external_0__
+#[derive(Default)]
+pub struct UmdExternals {
+    pub require_args: String,
+    pub amd_deps: String,
+    pub root_args: String,
+    pub params: String,
+    pub object: String,
+}
+
+impl UmdExternals {
+    pub fn new(externals: &[(String, String)]) -> Self {
+        let mut umd_externals = Self::default();
+        if externals.is_empty() {
+            return umd_externals;
+        }
+        let sources = externals
+            .iter()
+            .map(|(source, _)| serde_json::to_string(source).unwrap())
+            .collect::<Vec<_>>();
+        let params = (0..externals.len())
+            .map(|i| format!("__mako_umd_external_{}__", i))
+            .collect::<Vec<_>>();
+        umd_externals.require_args = sources
+            .iter()
+            .map(|source| format!("require({})", source))
+            .collect::<Vec<_>>()
+            .join(", ");
+        umd_externals.amd_deps = sources.join(", ");
+        umd_externals.root_args = externals
+            .iter()
+            .map(|(_, global)| get_umd_external_root(global))
+            .collect::<Vec<_>>()
+            .join(", ");
+        umd_externals.object = sources
+            .iter()
+            .zip(params.iter())
+            .map(|(source, param)| format!("{}: {}", source, param))
+            .collect::<Vec<_>>()
+            .join(", ");
+        umd_externals.params = params.join(", ");
+        umd_externals
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use sailfish::TemplateOnce;
+
+    use super::{AppRuntimeTemplate, UmdExternals};
+
+    #[test]
+    fn test_umd_externals_in_runtime() {
+        let externals = vec![
+            ("react".to_string(), "React".to_string()),
+            ("lodash".to_string(), "_".to_string()),
+        ];
+        let runtime = AppRuntimeTemplate {
+            umd: Some("lib".to_string()),
+            umd_externals: UmdExternals::new(&externals),
+            ..template()
+        }
+        .render_once()
+        .unwrap();
+
+        // commonjs
+        assert!(
+            runtime.contains(r#"module.exports = factory(require("react"), require("lodash"));"#),
+            "{}",
+            runtime
+        );
+        assert!(
+            runtime.contains(r#"exports['lib'] = factory(require("react"), require("lodash"));"#)
+        );
+        // amd
+        assert!(runtime.contains(r#"define(["react", "lodash"], factory);"#));
+        // globals
+        assert!(runtime.contains("root['lib'] = factory(root['React'], root['_']);"));
+        assert!(runtime.contains("function (__mako_umd_external_0__, __mako_umd_external_1__) {"));
+        assert!(runtime.contains(
+            r#"makoCreateRuntime({"react": __mako_umd_external_0__, "lodash": __mako_umd_external_1__});"#
+        ));
+    }
+
+    #[test]
+    fn test_chunk_load_timeout_in_ms() {
+        let runtime = AppRuntimeTemplate {
+            has_dynamic_chunks: true,
+            chunk_load_timeout: 500,
+            ..template()
+        }
+        .render_once()
+        .unwrap();
+        assert!(
+            runtime.contains("{ type: 'timeout', target: script }),\n        500,"),
+            "{}",
+            runtime
+        );
+        assert!(!runtime.contains("script.timeout"));
+    }
+
+    fn template() -> AppRuntimeTemplate {
+        AppRuntimeTemplate {
+            has_dynamic_chunks: false,
+            has_hmr: false,
+            umd: None,
+            umd_externals: UmdExternals::default(),
+            cjs: false,
+            pkg_name: None,
+            chunk_loading_global: "makoChunk_lib".to_string(),
+            chunk_load_timeout: 120000,
+            chunk_load_retries: 0,
+            is_browser: true,
+            concatenate_enabled: false,
+            runtime_chunk: false,
+        }
+    }
 }
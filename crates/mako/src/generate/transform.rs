@@ -21,7 +21,7 @@ use tracing::debug;
 
 use crate::ast::js_ast::JsAst;
 use crate::compiler::{Compiler, Context};
-use crate::module::{Dependency, ModuleAst, ModuleId, ModuleType, ResolveType};
+use crate::module::{ChunkHint, Dependency, ModuleAst, ModuleId, ModuleType, ResolveType};
 use crate::utils::thread_pool;
 use crate::visitors::async_module::{mark_async, AsyncModule};
 use crate::visitors::common_js::common_js;
@@ -79,9 +79,17 @@ pub fn transform_modules_in_thread(
             .get(&module_id)
             .expect(&module_id.id)
             .clone();
-        thread_pool::spawn(move || {
+        thread_pool::spawn_catch(rs, move |rs| {
             let module_graph = context.module_graph.read().unwrap();
             let deps = module_graph.get_dependencies(&module_id);
+            let chunk_hints: HashMap<String, ChunkHint> = deps
+                .iter()
+                .filter_map(|(id, dep)| {
+                    dep.chunk_hint
+                        .as_ref()
+                        .map(|hint| (id.generate(&context), hint.clone()))
+                })
+                .collect();
             let mut resolved_deps: HashMap<String, (String, String)> = deps
                 .into_iter()
                 .map(|(id, dep)| {
@@ -107,6 +115,8 @@ pub fn transform_modules_in_thread(
             let deps_to_replace = DependenciesToReplace {
                 resolved: resolved_deps,
                 missing: info.deps.missing_deps.clone(),
+                url_assets: info.deps.url_assets.clone(),
+                chunk_hints,
             };
             if let ModuleAst::Script(mut ast) = ast {
                 let wrap_async = info.is_async && info.external.is_none();
@@ -59,10 +59,15 @@ async fn run() -> Result<()> {
     let cli_args = format!(
         r#"
         {{
-            "mode": "{}"
+            "mode": "{}"{}
         }}
         "#,
-        cli.mode
+        cli.mode,
+        if cli.trace.is_some() {
+            r#", "profile": true"#
+        } else {
+            ""
+        }
     );
     let mut config = config::Config::new(&root, None, Some(cli_args.as_str()))
         .map_err(|e| anyhow!(format!("Load config failed: {}", e)))?;
@@ -93,6 +98,18 @@ async fn run() -> Result<()> {
             eprintln!("{}", e);
             std::process::exit(1);
         }
+        if let Some(trace) = &cli.trace {
+            compiler.write_trace(trace)?;
+        }
+        if let Some(why) = &cli.why {
+            let chains = compiler.why(why);
+            if chains.is_empty() {
+                println!("{:?} is not in the module graph", why);
+            }
+            for chain in chains {
+                println!("{}", chain);
+            }
+        }
         if cli.watch {
             let d = dev::DevServer::new(root.clone(), compiler);
             // TODO: when in Dev Mode, Dev Server should start asap, and provider a loading  while in first compiling
@@ -1,4 +1,4 @@
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::fmt::{Debug, Formatter};
 use std::path::PathBuf;
 use std::sync::Arc;
@@ -17,6 +17,8 @@ use swc_core::ecma::utils::quote_ident;
 use crate::ast::css_ast::CssAst;
 use crate::ast::file::File;
 use crate::ast::js_ast::JsAst;
+use crate::ast::json_ast::json_to_module_stmt;
+use crate::ast::sourcemap::InputSourceMap;
 use crate::build::analyze_deps::AnalyzeDepsResult;
 use crate::compiler::Context;
 use crate::config::ModuleIdStrategy;
@@ -31,6 +33,27 @@ pub struct Dependency {
     pub resolve_type: ResolveType,
     pub order: usize,
     pub span: Option<Span>,
+    pub chunk_hint: Option<ChunkHint>,
+    // import attributes, e.g. import a from './a.json' with { type: 'json' }
+    pub attributes: Option<Vec<(String, String)>>,
+    // conditions of css imports, e.g. @import 'a.css' supports(display: grid) print
+    pub import_conditions: Option<CssImportConditions>,
+}
+
+// the imported css is wrapped in @supports and @media with them, see wrap_with_import_conditions
+#[derive(Debug, Clone, Hash, Eq, PartialEq, Default)]
+pub struct CssImportConditions {
+    pub supports: Option<String>,
+    pub media: Option<String>,
+}
+
+// hints for the async chunk from webpack magic comments
+// e.g. import(/* webpackChunkName: "foo", webpackPrefetch: true */ './foo')
+#[derive(Debug, Clone, Hash, Eq, PartialEq, Default)]
+pub struct ChunkHint {
+    pub name: Option<String>,
+    pub prefetch: bool,
+    pub preload: bool,
 }
 
 bitflags! {
@@ -59,7 +82,9 @@ bitflags! {
 impl From<&ResolveType> for ResolveTypeFlags {
     fn from(value: &ResolveType) -> Self {
         match value {
-            ResolveType::DynamicImport | ResolveType::Worker => Self::Async,
+            ResolveType::DynamicImport
+            | ResolveType::DynamicImportGlob { .. }
+            | ResolveType::Worker => Self::Async,
             _ => Self::Sync,
         }
     }
@@ -126,18 +151,43 @@ impl From<&NamedExport> for NamedExportType {
     }
 }
 
-#[derive(Eq, Hash, PartialEq, Serialize, Debug, Clone, Copy)]
+#[derive(Eq, Hash, PartialEq, Serialize, Debug, Clone)]
 pub enum ResolveType {
     Import(ImportType),
     ExportNamed(NamedExportType),
     ExportAll,
     Require,
+    RequireResolve,
     DynamicImport,
+    // dynamic import with a static prefix and suffix, e.g. import(`./locales/${lang}.js`),
+    // which is loaded from a context module with all the matching files, see ContextModuleVisitor
+    DynamicImportGlob { prefix: String, suffix: String },
+    // import.meta.glob('./dir/*.js'), which is required from a virtual module of the matching
+    // files, the pattern is expanded when it's resolved, see ImportMetaGlobPlugin
+    Glob,
     Css,
     Worker,
+    UrlAsset,
 }
 
 impl ResolveType {
+    // stable name used in stats.json
+    pub fn name(&self) -> &'static str {
+        match self {
+            ResolveType::Import(_) => "import",
+            ResolveType::ExportNamed(_) => "exportNamed",
+            ResolveType::ExportAll => "exportAll",
+            ResolveType::Require => "require",
+            ResolveType::RequireResolve => "requireResolve",
+            ResolveType::DynamicImport => "dynamicImport",
+            ResolveType::DynamicImportGlob { .. } => "dynamicImportGlob",
+            ResolveType::Glob => "glob",
+            ResolveType::Css => "css",
+            ResolveType::Worker => "worker",
+            ResolveType::UrlAsset => "urlAsset",
+        }
+    }
+
     pub fn same_enum(&self, other: &Self) -> bool {
         match (self, other) {
             (Self::Import(_), Self::Import(_)) => true,
@@ -159,7 +209,10 @@ impl ResolveType {
     }
 
     pub fn is_dynamic_esm(&self) -> bool {
-        matches!(self, ResolveType::DynamicImport)
+        matches!(
+            self,
+            ResolveType::DynamicImport | ResolveType::DynamicImportGlob { .. }
+        )
     }
 }
 
@@ -180,6 +233,28 @@ pub struct ModuleInfo {
     pub resolved_resource: Option<ResolverResource>,
     /// The transformed source map chain of this module
     pub source_map_chain: Vec<Vec<u8>>,
+    /// Input source maps of the modules concatenated into this module
+    pub concatenated_source_maps: HashMap<String, InputSourceMap>,
+}
+
+impl ModuleInfo {
+    /// Input source maps of this module and the modules concatenated into it,
+    /// keyed by the source in the generated source map
+    pub fn input_source_maps(&self) -> HashMap<String, InputSourceMap> {
+        let mut input_source_maps = self.concatenated_source_maps.clone();
+        if !self.source_map_chain.is_empty()
+            && let Some(dir) = self.file.pathname.parent()
+        {
+            input_source_maps.insert(
+                self.file.relative_path.to_string_lossy().to_string(),
+                InputSourceMap {
+                    dir: dir.to_path_buf(),
+                    source_map_chain: self.source_map_chain.clone(),
+                },
+            );
+        }
+        input_source_maps
+    }
 }
 
 impl Default for ModuleInfo {
@@ -195,6 +270,7 @@ impl Default for ModuleInfo {
             is_async: false,
             resolved_resource: None,
             source_map_chain: vec![],
+            concatenated_source_maps: Default::default(),
             is_ignored: false,
         }
     }
@@ -216,9 +292,70 @@ pub fn generate_module_id(origin_module_id: String, context: &Arc<Context>) -> S
             let relative_path = diff_paths(&absolute_path, &context.root).unwrap_or(absolute_path);
             relative_path.to_string_lossy().to_string()
         }
+        ModuleIdStrategy::Deterministic | ModuleIdStrategy::Numeric => {
+            if let Some(id) = context.module_ids.read().unwrap().get(&origin_module_id) {
+                return id.clone();
+            }
+            // modules out of the module graph, e.g. the client components referenced by
+            // rsc server modules, are not assigned by assign_module_ids
+            md5_hash(&deterministic_source(&origin_module_id, &context.root), 8)
+        }
+    }
+}
+
+// the absolute path differs between machines, and so does the path separator
+fn deterministic_source(module_id: &str, root: &PathBuf) -> String {
+    let absolute_path = PathBuf::from(module_id);
+    let relative_path = diff_paths(&absolute_path, root).unwrap_or(absolute_path);
+    relative_path.to_string_lossy().replace('\\', "/")
+}
+
+// assign ids to the modules without one in the order of their paths relative to root,
+// it's called after the module graph is complete, so the ids don't depend on the build order,
+// deterministic ids are lengthened on collisions, and numeric ids are the next numbers,
+// the assigned ids are kept, so the ids of hot updates are consistent with the loaded chunks
+pub fn assign_module_ids(context: &Arc<Context>) {
+    let strategy = context.config.module_id_strategy;
+    if !matches!(
+        strategy,
+        ModuleIdStrategy::Deterministic | ModuleIdStrategy::Numeric
+    ) {
+        return;
+    }
+    let module_graph = context.module_graph.read().unwrap();
+    let mut module_ids = context.module_ids.write().unwrap();
+    let mut unassigned = module_graph
+        .modules()
+        .into_iter()
+        .filter(|module| !module_ids.contains_key(&module.id.id))
+        .map(|module| {
+            (
+                deterministic_source(&module.id.id, &context.root),
+                module.id.id.clone(),
+            )
+        })
+        .collect::<Vec<_>>();
+    unassigned.sort();
+    let mut used = module_ids.values().cloned().collect::<HashSet<_>>();
+    for (source, id) in unassigned {
+        let module_id = match strategy {
+            ModuleIdStrategy::Numeric => module_ids.len().to_string(),
+            _ => deterministic_id(&source, &used),
+        };
+        used.insert(module_id.clone());
+        module_ids.insert(id, module_id);
     }
 }
 
+// the shortest prefix of the hash, at least 8 chars, which is not used by the other modules
+fn deterministic_id(source: &str, used: &HashSet<String>) -> String {
+    let hash = md5_hash(source, 32);
+    (8..hash.len())
+        .map(|len| hash[..len].to_string())
+        .find(|module_id| !used.contains(module_id))
+        .unwrap_or(hash)
+}
+
 pub fn relative_to_root(module_path: &String, root: &PathBuf) -> String {
     let absolute_path = PathBuf::from(module_path);
     let relative_path = diff_paths(&absolute_path, root).unwrap_or(absolute_path);
@@ -297,6 +434,7 @@ impl From<PathBuf> for ModuleId {
 pub enum ModuleAst {
     Script(JsAst),
     Css(CssAst),
+    Json(serde_json::Value),
     None,
 }
 
@@ -353,6 +491,7 @@ impl ModuleAst {
 pub enum ModuleType {
     Script,
     Css,
+    Json,
     Raw,
     PlaceHolder,
 }
@@ -408,6 +547,7 @@ impl Module {
             .map_or(ModuleType::PlaceHolder, |info| match info.ast {
                 ModuleAst::Script(_) => ModuleType::Script,
                 ModuleAst::Css(_) => ModuleType::Css,
+                ModuleAst::Json(_) => ModuleType::Json,
                 ModuleAst::None => ModuleType::Raw,
             })
     }
@@ -467,6 +607,13 @@ impl Module {
             }
             // TODO: css modules will be removed in the future
             ModuleAst::Css(_) => Ok(empty_module_fn_expr()),
+            ModuleAst::Json(value) => {
+                let mut fn_expr = empty_module_fn_expr();
+                if let Some(body) = fn_expr.function.body.as_mut() {
+                    body.stmts.push(json_to_module_stmt(value));
+                }
+                Ok(fn_expr)
+            }
             ModuleAst::None => Err(anyhow!("ModuleAst::None({}) cannot concert", self.id.id)),
         }
     }
@@ -501,3 +648,19 @@ fn empty_module_fn_expr() -> FnExpr {
         function: func.into(),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::{deterministic_id, md5_hash};
+
+    #[test]
+    fn test_deterministic_id_collision() {
+        let hash = md5_hash("src/a.js", 32);
+        assert_eq!(deterministic_id("src/a.js", &HashSet::new()), hash[..8]);
+        // lengthened until it's not used
+        let used = HashSet::from([hash[..8].to_string(), hash[..9].to_string()]);
+        assert_eq!(deterministic_id("src/a.js", &used), hash[..10]);
+    }
+}
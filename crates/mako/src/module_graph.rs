@@ -1,16 +1,63 @@
-use std::collections::{HashMap, HashSet};
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::fmt;
 
 use fixedbitset::FixedBitSet;
+use petgraph::algo::tarjan_scc;
 use petgraph::graph::{DefaultIx, NodeIndex};
 use petgraph::prelude::{Dfs, EdgeRef};
 use petgraph::stable_graph::{StableDiGraph, WalkNeighbors};
 use petgraph::visit::IntoEdgeReferences;
 use petgraph::Direction;
+use serde::Serialize;
+use thiserror::Error;
 use tracing::debug;
 
 use crate::module::{Dependencies, Dependency, Module, ModuleId, ResolveType};
 
+#[derive(Debug, Error)]
+#[error("circular dependency found: {}", .cycle.iter().map(|m| m.id.clone()).collect::<Vec<_>>().join(" -> "))]
+pub struct CycleError {
+    pub cycle: Vec<ModuleId>,
+}
+
+// cap of dependency_chains, a chain is searched for each importer
+const MAX_CHAINS: usize = 100;
+
+#[derive(Debug, Default)]
+pub struct UpdateModuleResult {
+    // non-entry modules which have no dependents after update
+    pub orphans: HashSet<ModuleId>,
+}
+
+// json schema of the module graph, it's used by tools like bundle analyzers,
+// so keep it stable
+#[derive(Serialize, Debug)]
+pub struct ModuleGraphJson {
+    pub modules: Vec<ModuleJson>,
+    pub dependencies: Vec<DependencyJson>,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ModuleJson {
+    pub id: String,
+    // resolved path, None for placeholder modules
+    pub path: Option<String>,
+    // size of the raw content in bytes
+    pub size: usize,
+    pub is_entry: bool,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct DependencyJson {
+    pub from: String,
+    pub to: String,
+    pub source: String,
+    pub resolve_type: &'static str,
+    pub order: usize,
+}
+
 #[derive(Debug)]
 pub struct ModuleGraph {
     id_index_map: HashMap<ModuleId, NodeIndex<DefaultIx>>,
@@ -180,6 +227,61 @@ impl ModuleGraph {
         }
     }
 
+    // set the order of all dependencies from `from` to `to`
+    pub fn set_dependencies_order(&mut self, from: &ModuleId, to: &ModuleId, order: usize) {
+        let from = self.id_index_map.get(from).unwrap();
+        let to = self.id_index_map.get(to).unwrap();
+        if let Some(edge) = self.graph.find_edge(*from, *to) {
+            let deps = self.graph.edge_weight_mut(edge).unwrap();
+            *deps = deps
+                .drain()
+                .map(|dep| Dependency { order, ..dep })
+                .collect();
+        }
+    }
+
+    // diff the dependencies of module_id with new_deps, and only update the changed edges
+    // modules of new_deps should be added to the module graph before
+    pub fn update_module(
+        &mut self,
+        module_id: &ModuleId,
+        new_deps: Vec<(ModuleId, Dependency)>,
+    ) -> UpdateModuleResult {
+        let mut origin: HashMap<ModuleId, Dependencies> = HashMap::new();
+        for (to, dep) in self.get_dependencies(module_id) {
+            origin.entry(to.clone()).or_default().insert(dep.clone());
+        }
+        let mut target: HashMap<ModuleId, Dependencies> = HashMap::new();
+        for (to, dep) in new_deps {
+            target.entry(to).or_default().insert(dep);
+        }
+
+        let mut result = UpdateModuleResult::default();
+        for to in origin.keys() {
+            if !target.contains_key(to) {
+                self.clear_dependency(module_id, to);
+                let is_entry = self.get_module(to).is_some_and(|m| m.is_entry);
+                if !is_entry && self.dependant_module_ids(to).is_empty() {
+                    result.orphans.insert(to.clone());
+                }
+            }
+        }
+        for (to, deps) in target {
+            match origin.get(&to) {
+                Some(origin_deps) if *origin_deps == deps => {}
+                origin_deps => {
+                    if origin_deps.is_some() {
+                        self.clear_dependency(module_id, &to);
+                    }
+                    for dep in deps {
+                        self.add_dependency(module_id, &to, dep);
+                    }
+                }
+            }
+        }
+        result
+    }
+
     // 公共方法抽出, InComing 找 targets, Outing 找 dependencies
     fn get_edges(&self, module_id: &ModuleId, direction: Direction) -> WalkNeighbors<u32> {
         let i = self
@@ -368,6 +470,251 @@ impl ModuleGraph {
         (result, cyclic)
     }
 
+    // kahn's algorithm, dependents come before their dependencies
+    // roots are sorted by module id, and dependencies are visited by
+    // Dependency.order then module id, so the result is deterministic
+    pub fn topo_sort(&self) -> Result<Vec<ModuleId>, CycleError> {
+        let mut in_degrees: HashMap<&ModuleId, usize> = self
+            .graph
+            .node_weights()
+            .map(|module| (&module.id, 0))
+            .collect();
+        for edge in self.graph.edge_references() {
+            *in_degrees.get_mut(&self.graph[edge.target()].id).unwrap() += 1;
+        }
+
+        let mut roots = in_degrees
+            .iter()
+            .filter(|(_, degree)| **degree == 0)
+            .map(|(id, _)| *id)
+            .collect::<Vec<_>>();
+        roots.sort();
+        let mut queue = VecDeque::from(roots);
+
+        let mut result = vec![];
+        while let Some(module_id) = queue.pop_front() {
+            result.push(module_id.clone());
+            for dep_module_id in self.sorted_dependence_module_ids(module_id) {
+                let degree = in_degrees.get_mut(dep_module_id).unwrap();
+                *degree -= 1;
+                if *degree == 0 {
+                    queue.push_back(dep_module_id);
+                }
+            }
+        }
+
+        if result.len() == in_degrees.len() {
+            Ok(result)
+        } else {
+            Err(CycleError {
+                cycle: self.find_cycle(&in_degrees),
+            })
+        }
+    }
+
+    // import paths from entries to module_id, e.g. [entry, a, module_id], one shortest path
+    // for each importer of module_id, so each search is linear in the size of the graph
+    pub fn dependency_chains(&self, module_id: &ModuleId) -> Vec<Vec<ModuleId>> {
+        if !self.has_module(module_id) {
+            return vec![];
+        }
+        if self.entries.contains(module_id) {
+            return vec![vec![module_id.clone()]];
+        }
+        self.sorted_dependant_module_ids(module_id)
+            .into_iter()
+            .filter(|importer| importer != module_id)
+            .take(MAX_CHAINS)
+            .filter_map(|importer| {
+                let mut chain = self.shortest_import_chain(&importer, module_id)?;
+                chain.push(module_id.clone());
+                Some(chain)
+            })
+            .collect()
+    }
+
+    // bfs over the importers from module_id to the closest entry, without passing excluded,
+    // the chain is rebuilt from the module each visited importer was reached from
+    fn shortest_import_chain(
+        &self,
+        module_id: &ModuleId,
+        excluded: &ModuleId,
+    ) -> Option<Vec<ModuleId>> {
+        let mut reached_from: HashMap<ModuleId, ModuleId> = HashMap::new();
+        let mut visited = HashSet::from([module_id.clone(), excluded.clone()]);
+        let mut queue = VecDeque::from([module_id.clone()]);
+        while let Some(current) = queue.pop_front() {
+            if self.entries.contains(&current) {
+                let mut chain = vec![current.clone()];
+                while let Some(next) = reached_from.get(chain.last().unwrap()) {
+                    chain.push(next.clone());
+                }
+                return Some(chain);
+            }
+            for dependant in self.sorted_dependant_module_ids(&current) {
+                if visited.insert(dependant.clone()) {
+                    reached_from.insert(dependant.clone(), current.clone());
+                    queue.push_back(dependant);
+                }
+            }
+        }
+        None
+    }
+
+    fn sorted_dependant_module_ids(&self, module_id: &ModuleId) -> Vec<ModuleId> {
+        let mut dependants = self.dependant_module_ids(module_id);
+        dependants.sort();
+        dependants.dedup();
+        dependants
+    }
+
+    // strongly connected components with more than one module or a self import,
+    // modules of each cycle are in dependency order starting from the min module id
+    pub fn find_cycles(&self) -> Vec<Vec<ModuleId>> {
+        let mut cycles = tarjan_scc(&self.graph)
+            .into_iter()
+            .filter(|scc| scc.len() > 1 || self.graph.contains_edge(scc[0], scc[0]))
+            .map(|scc| {
+                let members = scc
+                    .iter()
+                    .map(|i| &self.graph[*i].id)
+                    .collect::<HashSet<_>>();
+                self.sort_cycle_members(&members)
+            })
+            .collect::<Vec<_>>();
+        cycles.sort();
+        cycles
+    }
+
+    // pre-order dfs inside the cycle
+    fn sort_cycle_members(&self, members: &HashSet<&ModuleId>) -> Vec<ModuleId> {
+        let start = *members.iter().min().unwrap();
+        let mut visited = HashSet::new();
+        let mut result = vec![];
+        let mut stack = vec![start];
+        while let Some(module_id) = stack.pop() {
+            if !visited.insert(module_id) {
+                continue;
+            }
+            result.push(module_id.clone());
+            // reversed so that the first dependency is visited first
+            for dep_module_id in self
+                .sorted_dependence_module_ids(module_id)
+                .into_iter()
+                .rev()
+            {
+                if members.contains(dep_module_id) && !visited.contains(dep_module_id) {
+                    stack.push(dep_module_id);
+                }
+            }
+        }
+        result
+    }
+
+    // unique dependence module ids, sorted by the min Dependency.order then module id
+    fn sorted_dependence_module_ids(&self, module_id: &ModuleId) -> Vec<&ModuleId> {
+        let mut edges = self.get_edges(module_id, Direction::Outgoing);
+        let mut deps = vec![];
+        while let Some((edge_index, node_index)) = edges.next(&self.graph) {
+            let order = self
+                .graph
+                .edge_weight(edge_index)
+                .unwrap()
+                .iter()
+                .map(|dep| dep.order)
+                .min()
+                .unwrap_or_default();
+            deps.push((order, &self.graph[node_index].id));
+        }
+        deps.sort();
+        deps.into_iter().map(|(_, id)| id).collect()
+    }
+
+    // modules left with in degree > 0 after kahn's algorithm always has
+    // a dependent left, walk back through the dependents until a module is visited twice
+    fn find_cycle(&self, in_degrees: &HashMap<&ModuleId, usize>) -> Vec<ModuleId> {
+        let mut remaining = in_degrees
+            .iter()
+            .filter(|(_, degree)| **degree > 0)
+            .map(|(id, _)| *id)
+            .collect::<Vec<_>>();
+        remaining.sort();
+
+        let mut path: Vec<&ModuleId> = vec![];
+        let mut current = remaining[0];
+        while !path.contains(&current) {
+            path.push(current);
+            let mut dependents = self
+                .dependant_module_ids(current)
+                .into_iter()
+                .filter(|id| in_degrees.get(id).is_some_and(|degree| *degree > 0))
+                .collect::<Vec<_>>();
+            dependents.sort();
+            current = in_degrees.get_key_value(&dependents[0]).unwrap().0;
+        }
+
+        let pos = path.iter().position(|id| *id == current).unwrap();
+        let mut cycle = path[pos..]
+            .iter()
+            .map(|id| (*id).clone())
+            .collect::<Vec<_>>();
+        // path is walked through dependents, reverse it to the import order
+        // and start from the min module id
+        cycle.reverse();
+        let min_pos = (0..cycle.len()).min_by_key(|i| &cycle[*i]).unwrap();
+        cycle.rotate_left(min_pos);
+        cycle
+    }
+
+    // modules are sorted by id, dependencies are sorted by from, order and to
+    pub fn to_json(&self) -> serde_json::Value {
+        let mut modules = self
+            .graph
+            .node_weights()
+            .map(|module| ModuleJson {
+                id: module.id.id.clone(),
+                path: module
+                    .info
+                    .as_ref()
+                    .map(|info| info.file.path.to_string_lossy().to_string()),
+                size: module.get_module_size(),
+                is_entry: module.is_entry,
+            })
+            .collect::<Vec<_>>();
+        modules.sort_by(|a, b| a.id.cmp(&b.id));
+
+        let mut dependencies = self
+            .graph
+            .edge_references()
+            .flat_map(|edge| {
+                let from = &self.graph[edge.source()].id.id;
+                let to = &self.graph[edge.target()].id.id;
+                edge.weight().iter().map(|dep| DependencyJson {
+                    from: from.clone(),
+                    to: to.clone(),
+                    source: dep.source.clone(),
+                    resolve_type: dep.resolve_type.name(),
+                    order: dep.order,
+                })
+            })
+            .collect::<Vec<_>>();
+        dependencies.sort_by(|a, b| {
+            (&a.from, a.order, &a.to, &a.source, a.resolve_type).cmp(&(
+                &b.from,
+                b.order,
+                &b.to,
+                &b.source,
+                b.resolve_type,
+            ))
+        });
+
+        serde_json::to_value(ModuleGraphJson {
+            modules,
+            dependencies,
+        })
+        .unwrap()
+    }
+
     pub fn get_reference(&self) -> Vec<String> {
         let mut references = self
             .graph
@@ -409,3 +756,258 @@ impl Default for ModuleGraph {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::module::ResolveType;
+
+    fn build_graph(modules: &[&str], deps: &[(&str, &str, usize)]) -> ModuleGraph {
+        let mut module_graph = ModuleGraph::new();
+        for id in modules {
+            module_graph.add_module(Module::new((*id).into(), false, None));
+        }
+        for (from, to, order) in deps {
+            let (to, dep) = dep(to, *order);
+            module_graph.add_dependency(&(*from).into(), &to, dep);
+        }
+        module_graph
+    }
+
+    fn ids(module_ids: Vec<ModuleId>) -> Vec<String> {
+        module_ids.into_iter().map(|id| id.id).collect()
+    }
+
+    #[test]
+    fn test_topo_sort_chain() {
+        let module_graph = build_graph(&["c", "a", "b"], &[("a", "b", 1), ("b", "c", 1)]);
+        assert_eq!(ids(module_graph.topo_sort().unwrap()), vec!["a", "b", "c"]);
+    }
+
+    #[test]
+    fn test_topo_sort_diamond() {
+        // a imports c before b, b and c both import d
+        let module_graph = build_graph(
+            &["a", "b", "c", "d"],
+            &[("a", "b", 2), ("a", "c", 1), ("b", "d", 1), ("c", "d", 1)],
+        );
+        assert_eq!(
+            ids(module_graph.topo_sort().unwrap()),
+            vec!["a", "c", "b", "d"]
+        );
+    }
+
+    #[test]
+    fn test_topo_sort_multiple_roots() {
+        let module_graph = build_graph(&["z", "y", "x"], &[("y", "x", 1)]);
+        assert_eq!(ids(module_graph.topo_sort().unwrap()), vec!["y", "z", "x"]);
+    }
+
+    #[test]
+    fn test_find_cycles() {
+        // acyclic
+        let module_graph = build_graph(
+            &["a", "b", "c", "d"],
+            &[("a", "b", 1), ("a", "c", 2), ("b", "d", 1), ("c", "d", 1)],
+        );
+        assert!(module_graph.find_cycles().is_empty());
+
+        // self import
+        let module_graph = build_graph(&["a", "b"], &[("a", "b", 1), ("b", "b", 1)]);
+        assert_eq!(
+            module_graph
+                .find_cycles()
+                .into_iter()
+                .map(ids)
+                .collect::<Vec<_>>(),
+            vec![vec!["b"]]
+        );
+
+        // nested cycles, b -> c -> b inside b -> c -> d -> b, and a separate e <-> f
+        let module_graph = build_graph(
+            &["a", "b", "c", "d", "e", "f"],
+            &[
+                ("a", "b", 1),
+                ("b", "c", 1),
+                ("c", "b", 2),
+                ("c", "d", 1),
+                ("d", "b", 1),
+                ("f", "e", 1),
+                ("e", "f", 1),
+            ],
+        );
+        assert_eq!(
+            module_graph
+                .find_cycles()
+                .into_iter()
+                .map(ids)
+                .collect::<Vec<_>>(),
+            vec![vec!["b", "c", "d"], vec!["e", "f"]]
+        );
+    }
+
+    #[test]
+    fn test_dependency_chains() {
+        let mut module_graph = build_graph(
+            &["b", "c", "d"],
+            &[("b", "d", 1), ("c", "d", 1), ("d", "c", 2)],
+        );
+        module_graph.add_module(Module::new("a".into(), true, None));
+        module_graph.add_dependency(&"a".into(), &"b".into(), dep("b", 1).1);
+        module_graph.add_dependency(&"a".into(), &"c".into(), dep("c", 2).1);
+
+        let chains = |id: &str| {
+            module_graph
+                .dependency_chains(&id.into())
+                .into_iter()
+                .map(ids)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(chains("d"), vec![vec!["a", "b", "d"], vec!["a", "c", "d"]]);
+        // c <-> d is a cycle
+        assert_eq!(chains("c"), vec![vec!["a", "c"], vec!["a", "b", "d", "c"]]);
+        assert_eq!(chains("a"), vec![vec!["a"]]);
+        assert!(chains("not_exists").is_empty());
+    }
+
+    #[test]
+    fn test_dependency_chains_dense_graph() {
+        // modules not imported by the entry import all the modules after them,
+        // which are 2^n dead end paths for a search of all the paths
+        let names = (0..40).map(|i| format!("m{:02}", i)).collect::<Vec<_>>();
+        let mut module_graph = build_graph(&[], &[]);
+        for name in &names {
+            module_graph.add_module(Module::new(name.as_str().into(), false, None));
+        }
+        for (i, from) in names.iter().enumerate() {
+            for (j, to) in names.iter().enumerate().skip(i + 1) {
+                module_graph.add_dependency(
+                    &from.as_str().into(),
+                    &to.as_str().into(),
+                    dep(to, j).1,
+                );
+            }
+        }
+        module_graph.add_module(Module::new("entry".into(), true, None));
+        module_graph.add_dependency(&"entry".into(), &"m39".into(), dep("m39", 1).1);
+        let chains = module_graph
+            .dependency_chains(&"m39".into())
+            .into_iter()
+            .map(ids)
+            .collect::<Vec<_>>();
+        assert_eq!(chains, vec![vec!["entry", "m39"]]);
+    }
+
+    #[test]
+    fn test_to_json() {
+        let mut module_graph = build_graph(&["b", "c"], &[("b", "c", 1)]);
+        module_graph.add_module(Module::new("a".into(), true, None));
+        let (to, mut dep) = dep("b", 1);
+        dep.resolve_type = ResolveType::DynamicImport;
+        module_graph.add_dependency(&"a".into(), &to, dep);
+        assert_eq!(
+            serde_json::to_string_pretty(&module_graph.to_json()).unwrap(),
+            r#"{
+  "modules": [
+    {
+      "id": "a",
+      "path": null,
+      "size": 0,
+      "isEntry": true
+    },
+    {
+      "id": "b",
+      "path": null,
+      "size": 0,
+      "isEntry": false
+    },
+    {
+      "id": "c",
+      "path": null,
+      "size": 0,
+      "isEntry": false
+    }
+  ],
+  "dependencies": [
+    {
+      "from": "a",
+      "to": "b",
+      "source": "b",
+      "resolveType": "dynamicImport",
+      "order": 1
+    },
+    {
+      "from": "b",
+      "to": "c",
+      "source": "c",
+      "resolveType": "import",
+      "order": 1
+    }
+  ]
+}"#
+        );
+    }
+
+    #[test]
+    fn test_update_module() {
+        let mut module_graph = build_graph(
+            &["a", "b", "c", "d"],
+            &[("a", "b", 1), ("a", "c", 2), ("d", "c", 1)],
+        );
+
+        // add d, remove b and c
+        let result = module_graph.update_module(&"a".into(), vec![(dep("d", 1))]);
+        // c is still imported by d
+        assert_eq!(sorted(&result.orphans), vec!["b"]);
+        assert_eq!(module_graph.get_reference(), vec!["a -> d", "d -> c"]);
+
+        // change the order only
+        let result = module_graph.update_module(&"a".into(), vec![(dep("d", 2))]);
+        assert!(result.orphans.is_empty());
+        assert_eq!(module_graph.get_dependencies(&"a".into())[0].1.order, 2);
+
+        // add b back
+        let result = module_graph.update_module(&"a".into(), vec![dep("b", 1), dep("d", 2)]);
+        assert!(result.orphans.is_empty());
+        assert_eq!(
+            module_graph.get_reference(),
+            vec!["a -> b", "a -> d", "d -> c"]
+        );
+    }
+
+    fn dep(to: &str, order: usize) -> (ModuleId, Dependency) {
+        (
+            to.into(),
+            Dependency {
+                source: to.to_string(),
+                resolve_as: None,
+                resolve_type: ResolveType::Import(Default::default()),
+                order,
+                span: None,
+                chunk_hint: None,
+                attributes: None,
+                import_conditions: None,
+            },
+        )
+    }
+
+    fn sorted(module_ids: &HashSet<ModuleId>) -> Vec<String> {
+        let mut ids = module_ids
+            .iter()
+            .map(|id| id.id.clone())
+            .collect::<Vec<_>>();
+        ids.sort();
+        ids
+    }
+
+    #[test]
+    fn test_topo_sort_cycle() {
+        let module_graph = build_graph(
+            &["a", "b", "c", "d"],
+            &[("a", "b", 1), ("b", "c", 1), ("c", "b", 1), ("c", "d", 2)],
+        );
+        let err = module_graph.topo_sort().unwrap_err();
+        assert_eq!(ids(err.cycle.clone()), vec!["b", "c"]);
+        assert_eq!(err.to_string(), "circular dependency found: b -> c");
+    }
+}
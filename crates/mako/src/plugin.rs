@@ -2,7 +2,7 @@ use std::any::Any;
 use std::path::Path;
 use std::sync::Arc;
 
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 use serde::Serialize;
 use swc_core::common::errors::Handler;
 use swc_core::common::Mark;
@@ -13,6 +13,7 @@ use crate::compiler::{Args, Compiler, Context};
 use crate::config::Config;
 use crate::generate::chunk_graph::ChunkGraph;
 use crate::generate::generate_chunks::ChunkFile;
+use crate::generate::Bundle;
 use crate::module::{Dependency, ModuleAst, ModuleId};
 use crate::module_graph::ModuleGraph;
 use crate::resolve::ResolverResource;
@@ -34,6 +35,19 @@ pub struct PluginTransformJsParam<'a> {
     pub unresolved_mark: Mark,
 }
 
+pub enum PluginResolvedId {
+    // loaded by the load hooks of plugins, the id should start with virtual:, e.g. virtual:config
+    Virtual(String),
+    // resolved by the resolver, e.g. an absolute path for a custom scheme
+    Path(String),
+}
+
+pub struct PluginTransformResult {
+    pub code: String,
+    // skip the transform hooks of the following plugins
+    pub stop: bool,
+}
+
 #[derive(Clone, Serialize)]
 pub struct PluginGenerateEndParams {
     pub is_first_compile: bool,
@@ -54,6 +68,15 @@ pub trait Plugin: Any + Send + Sync {
         Ok(())
     }
 
+    // part of the keys of the module cache, which should change when the plugin transforms
+    // modules differently, e.g. "name@2", None to disable the module cache
+    fn module_cache_key(&self) -> Option<String> {
+        Some(self.name().to_string())
+    }
+
+    // the content of the file with its module type, e.g. for virtual modules resolved by resolve_id,
+    // which is parsed, transformed and analyzed like files, the relative imports of virtual modules
+    // are resolved from the root, or the path param if it's in the id, e.g. virtual:routes?path=/a.js
     fn load(&self, _param: &PluginLoadParam, _context: &Arc<Context>) -> Result<Option<Content>> {
         Ok(None)
     }
@@ -62,6 +85,16 @@ pub trait Plugin: Any + Send + Sync {
         true
     }
 
+    // intercept the resolving of the specifier imported by the importer
+    fn resolve_id(
+        &self,
+        _specifier: &str,
+        _importer: &str,
+        _context: &Arc<Context>,
+    ) -> Result<Option<PluginResolvedId>> {
+        Ok(None)
+    }
+
     fn parse(
         &self,
         _param: &PluginParseParam,
@@ -70,6 +103,16 @@ pub trait Plugin: Any + Send + Sync {
         Ok(None)
     }
 
+    // preprocess the js source before it's parsed, e.g. strip flow types
+    fn transform(
+        &self,
+        _code: &str,
+        _path: &str,
+        _context: &Arc<Context>,
+    ) -> Result<Option<PluginTransformResult>> {
+        Ok(None)
+    }
+
     fn transform_js(
         &self,
         _param: &PluginTransformJsParam,
@@ -104,6 +147,12 @@ pub trait Plugin: Any + Send + Sync {
         Ok(())
     }
 
+    // add, modify or remove the output files after the chunk files are generated,
+    // e.g. a LICENSE file or a service worker
+    fn generate_bundle(&self, _bundle: &mut Bundle, _context: &Arc<Context>) -> Result<()> {
+        Ok(())
+    }
+
     fn build_success(&self, _stats: &StatsJsonMap, _context: &Arc<Context>) -> Result<()> {
         Ok(())
     }
@@ -189,6 +238,10 @@ impl PluginDriver {
         Ok(())
     }
 
+    pub fn module_cache_keys(&self) -> Option<Vec<String>> {
+        self.plugins.iter().map(|p| p.module_cache_key()).collect()
+    }
+
     pub fn load(&self, param: &PluginLoadParam, context: &Arc<Context>) -> Result<Option<Content>> {
         for plugin in &self.plugins {
             let ret = plugin.load(param, context)?;
@@ -199,6 +252,30 @@ impl PluginDriver {
         Ok(None)
     }
 
+    pub fn resolve_id(
+        &self,
+        specifier: &str,
+        importer: &str,
+        context: &Arc<Context>,
+    ) -> Result<Option<PluginResolvedId>> {
+        for plugin in &self.plugins {
+            let ret = plugin.resolve_id(specifier, importer, context)?;
+            if let Some(PluginResolvedId::Virtual(id)) = &ret
+                && !id.starts_with("virtual:")
+            {
+                return Err(anyhow!(
+                    "virtual id {} resolved by plugin {} should start with virtual:",
+                    id,
+                    plugin.name()
+                ));
+            }
+            if ret.is_some() {
+                return Ok(ret);
+            }
+        }
+        Ok(None)
+    }
+
     pub fn parse(
         &self,
         param: &PluginParseParam,
@@ -213,6 +290,27 @@ impl PluginDriver {
         Ok(None)
     }
 
+    // plugins are called in order with the code transformed by the previous ones,
+    // until one of them returns a result with stop
+    pub fn transform(
+        &self,
+        code: &str,
+        path: &str,
+        context: &Arc<Context>,
+    ) -> Result<Option<String>> {
+        let mut transformed: Option<String> = None;
+        for plugin in &self.plugins {
+            let code = transformed.as_deref().unwrap_or(code);
+            if let Some(result) = plugin.transform(code, path, context)? {
+                transformed = Some(result.code);
+                if result.stop {
+                    break;
+                }
+            }
+        }
+        Ok(transformed)
+    }
+
     #[allow(dead_code)]
     pub fn transform_js(
         &self,
@@ -269,6 +367,13 @@ impl PluginDriver {
         Ok(())
     }
 
+    pub fn generate_bundle(&self, bundle: &mut Bundle, context: &Arc<Context>) -> Result<()> {
+        for plugin in &self.plugins {
+            plugin.generate_bundle(bundle, context)?;
+        }
+        Ok(())
+    }
+
     pub fn build_start(&self, context: &Arc<Context>) -> Result<()> {
         for plugin in &self.plugins {
             plugin.build_start(context)?;
@@ -354,3 +459,248 @@ impl PluginDriver {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::sync::Arc;
+
+    use anyhow::Result;
+
+    use super::{
+        Bundle, Plugin, PluginDriver, PluginLoadParam, PluginResolvedId, PluginTransformResult,
+    };
+    use crate::ast::file::{Content, File, JsContent};
+    use crate::compiler::{Compiler, Context};
+    use crate::config::Config;
+    use crate::module::ModuleId;
+
+    struct ReplacePlugin {
+        from: &'static str,
+        to: &'static str,
+        stop: bool,
+    }
+
+    impl Plugin for ReplacePlugin {
+        fn name(&self) -> &str {
+            "replace"
+        }
+
+        fn transform(
+            &self,
+            code: &str,
+            _path: &str,
+            _context: &Arc<Context>,
+        ) -> Result<Option<PluginTransformResult>> {
+            Ok(code.contains(self.from).then(|| PluginTransformResult {
+                code: code.replace(self.from, self.to),
+                stop: self.stop,
+            }))
+        }
+    }
+
+    struct VirtualConfigPlugin {}
+
+    impl Plugin for VirtualConfigPlugin {
+        fn name(&self) -> &str {
+            "virtual_config"
+        }
+
+        fn resolve_id(
+            &self,
+            specifier: &str,
+            _importer: &str,
+            context: &Arc<Context>,
+        ) -> Result<Option<PluginResolvedId>> {
+            Ok(match specifier {
+                "virtual:config" => Some(PluginResolvedId::Virtual(specifier.to_string())),
+                "env:mode" => Some(PluginResolvedId::Path(
+                    context
+                        .root
+                        .join("src/mode.js")
+                        .to_string_lossy()
+                        .to_string(),
+                )),
+                _ => None,
+            })
+        }
+
+        fn load(
+            &self,
+            param: &PluginLoadParam,
+            _context: &Arc<Context>,
+        ) -> Result<Option<Content>> {
+            Ok(
+                (param.file.path.to_string_lossy() == "virtual:config").then(|| {
+                    Content::Js(JsContent {
+                        content: "export default { name: 'generated config' };".to_string(),
+                        is_jsx: false,
+                    })
+                }),
+            )
+        }
+    }
+
+    struct VirtualRoutesPlugin {}
+
+    impl Plugin for VirtualRoutesPlugin {
+        fn name(&self) -> &str {
+            "virtual_routes"
+        }
+
+        fn load(
+            &self,
+            param: &PluginLoadParam,
+            _context: &Arc<Context>,
+        ) -> Result<Option<Content>> {
+            Ok(
+                (param.file.path.to_string_lossy() == "virtual:routes").then(|| {
+                    Content::Js(JsContent {
+                        content: r#"
+export default [
+  { path: '/', component: () => import('./src/pages/home.js') },
+  { path: '/about', component: () => import('./src/pages/about.js') },
+];
+"#
+                        .to_string(),
+                        is_jsx: false,
+                    })
+                }),
+            )
+        }
+    }
+
+    struct LicensePlugin {}
+
+    impl Plugin for LicensePlugin {
+        fn name(&self) -> &str {
+            "license"
+        }
+
+        fn generate_bundle(&self, bundle: &mut Bundle, _context: &Arc<Context>) -> Result<()> {
+            let index = bundle.files.get_mut("index.js").unwrap();
+            index.splice(0..0, b"/*! MIT */\n".iter().copied());
+            bundle
+                .files
+                .insert("LICENSE".to_string(), b"MIT License".to_vec());
+            Ok(())
+        }
+    }
+
+    fn replace(from: &'static str, to: &'static str, stop: bool) -> Arc<dyn Plugin> {
+        Arc::new(ReplacePlugin { from, to, stop })
+    }
+
+    #[test]
+    fn test_transform_chain() {
+        let context = Arc::new(Context::default());
+        let transform = |plugins| {
+            PluginDriver::new(plugins)
+                .transform("a", "/a.js", &context)
+                .unwrap()
+        };
+        assert_eq!(
+            transform(vec![replace("a", "b", false), replace("b", "c", false)]),
+            Some("c".to_string())
+        );
+        assert_eq!(
+            transform(vec![replace("a", "b", true), replace("b", "c", false)]),
+            Some("b".to_string())
+        );
+        assert_eq!(transform(vec![replace("x", "y", false)]), None);
+    }
+
+    #[test]
+    fn test_resolve_id() {
+        let output = compile(
+            "test/build/plugin-resolve-id",
+            vec![Arc::new(VirtualConfigPlugin {})],
+        );
+        assert!(output.contains("generated config"));
+        assert!(output.contains("mode from env"));
+    }
+
+    #[test]
+    fn test_load_virtual_module() {
+        let root = std::env::current_dir()
+            .unwrap()
+            .join("test/build/plugin-load");
+        let compiler = Compiler::new(
+            Config::new(&root, None, None).unwrap(),
+            root.clone(),
+            Default::default(),
+            Some(vec![Arc::new(VirtualRoutesPlugin {})]),
+        )
+        .unwrap();
+        let context = compiler.context.clone();
+        compiler
+            .build(vec![File::new_entry(
+                root.join("src/index.js").to_string_lossy().to_string(),
+                context.clone(),
+            )])
+            .unwrap();
+        let module_graph = context.module_graph.read().unwrap();
+        let mut deps = module_graph
+            .get_dependencies(&ModuleId::new("virtual:routes".to_string()))
+            .into_iter()
+            .map(|(id, dep)| {
+                (
+                    id.id.replace(root.to_str().unwrap(), ""),
+                    dep.resolve_type.name(),
+                )
+            })
+            .collect::<Vec<_>>();
+        deps.sort();
+        assert_eq!(
+            deps,
+            vec![
+                ("/src/pages/about.js".to_string(), "dynamicImport"),
+                ("/src/pages/home.js".to_string(), "dynamicImport"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_generate_bundle() {
+        let root = std::env::current_dir()
+            .unwrap()
+            .join("test/build/plugin-generate-bundle");
+        let compiler = Compiler::new(
+            Config::new(&root, None, None).unwrap(),
+            root.clone(),
+            Default::default(),
+            Some(vec![Arc::new(LicensePlugin {})]),
+        )
+        .unwrap();
+        compiler.compile().unwrap();
+        let dist = root.join("dist");
+        let license = fs::read_to_string(dist.join("LICENSE")).unwrap();
+        let index = fs::read_to_string(dist.join("index.js")).unwrap();
+        fs::remove_dir_all(dist).unwrap();
+        assert_eq!(license, "MIT License");
+        assert!(index.starts_with("/*! MIT */\n"));
+    }
+
+    #[test]
+    fn test_transform_before_parse() {
+        let output = compile(
+            "test/build/plugin-transform",
+            vec![replace("mako-marker", "MAKO-MARKER", false)],
+        );
+        assert!(output.contains("MAKO-MARKER"));
+        assert!(!output.contains("mako-marker"));
+    }
+
+    fn compile(base: &str, plugins: Vec<Arc<dyn Plugin>>) -> String {
+        let root = std::env::current_dir().unwrap().join(base);
+        let mut config = Config::new(&root, None, None).unwrap();
+        config.minify = None;
+        let compiler =
+            Compiler::new(config, root.clone(), Default::default(), Some(plugins)).unwrap();
+        compiler.compile().unwrap();
+        let dist = root.join("dist");
+        let output = fs::read_to_string(dist.join("index.js")).unwrap();
+        fs::remove_dir_all(dist).unwrap();
+        output
+    }
+}
@@ -99,6 +99,8 @@ impl BundlessCompiler {
                 let deps_to_replace = DependenciesToReplace {
                     resolved: resolved_deps,
                     missing: info.deps.missing_deps.clone(),
+                    url_assets: info.deps.url_assets.clone(),
+                    chunk_hints: Default::default(),
                 };
 
                 if let ModuleAst::Script(ast) = ast {
@@ -127,7 +129,8 @@ impl BundlessCompiler {
             .unwrap();
 
         if !self.context.config.output.skip_write {
-            fs::write(to, content).unwrap();
+            fs::write(&to, content).unwrap();
+            self.context.emitted_files.add(to);
         }
     }
 
@@ -161,6 +164,10 @@ impl BundlessCompiler {
                     }
                 }
                 ModuleAst::Css(_style) => {}
+                ModuleAst::Json(_) => {
+                    // nothing
+                    // todo: generate resolved AJSON
+                }
                 ModuleAst::None => {
                     let target = to_dist_path(&id.id, &self.context);
                     self.write_to_dist(target, &info.raw);
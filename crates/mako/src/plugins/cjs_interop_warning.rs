@@ -0,0 +1,202 @@
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use anyhow::Result;
+use colored::Colorize;
+use pathdiff::diff_paths;
+use swc_core::common::{Mark, GLOBALS};
+use swc_core::ecma::ast::{Expr, Ident, MemberExpr, MemberProp, ModuleItem, Str};
+use swc_core::ecma::visit::{Visit, VisitWith};
+
+use crate::ast::js_ast::JsAst;
+use crate::ast::utils::is_ident_undefined;
+use crate::compiler::Context;
+use crate::module::{ImportType, ModuleAst, ModuleId, NamedExportType, ResolveType};
+use crate::module_graph::ModuleGraph;
+use crate::plugin::Plugin;
+
+pub struct CjsInteropWarning {}
+
+impl Plugin for CjsInteropWarning {
+    fn name(&self) -> &str {
+        "cjs_interop_warning"
+    }
+
+    fn generate_begin(&self, context: &Arc<Context>) -> Result<()> {
+        if context.config.warnings.cjs_interop && !context.args.watch {
+            let relative = |id: &ModuleId| {
+                let absolute_path = PathBuf::from(id.id.clone());
+                diff_paths(&absolute_path, &context.root)
+                    .unwrap_or(absolute_path)
+                    .to_string_lossy()
+                    .to_string()
+            };
+            for (importer, cjs) in find_cjs_default_imports(context) {
+                println!(
+                    "{} CommonJS Interop: \"{}\" imports the default export of \"{}\", which is a CommonJS module without __esModule, so the default import is the whole module.exports",
+                    "Warning".yellow(),
+                    relative(&importer),
+                    relative(&cjs)
+                );
+            }
+        }
+        Ok(())
+    }
+}
+
+// default imports of CommonJS modules without __esModule, e.g.
+// import foo from './cjs'; while cjs.js is `exports.default = 1`,
+// foo is { default: 1 } rather than 1, returns (importer, cjs module) pairs
+// notice: importers under node_modules are skipped since they can't be fixed by users
+pub fn find_cjs_default_imports(context: &Arc<Context>) -> Vec<(ModuleId, ModuleId)> {
+    let module_graph = context.module_graph.read().unwrap();
+    let mut hazards = GLOBALS.set(&context.meta.script.globals, || {
+        collect_cjs_default_imports(&module_graph)
+    });
+    hazards.sort_by(|a, b| (&a.0.id, &a.1.id).cmp(&(&b.0.id, &b.1.id)));
+    hazards
+}
+
+fn collect_cjs_default_imports(module_graph: &ModuleGraph) -> Vec<(ModuleId, ModuleId)> {
+    let mut hazards = vec![];
+    for module in module_graph.modules() {
+        let Some(info) = module.info.as_ref() else {
+            continue;
+        };
+        if info.file.is_under_node_modules {
+            continue;
+        }
+        for (dep_id, dep) in module_graph.get_dependencies(&module.id) {
+            let is_default_import = match dep.resolve_type {
+                ResolveType::Import(import_type) => import_type.contains(ImportType::Default),
+                ResolveType::ExportNamed(export_type) => {
+                    export_type.contains(NamedExportType::Default)
+                }
+                _ => false,
+            };
+            if !is_default_import {
+                continue;
+            }
+            let is_cjs = module_graph
+                .get_module(dep_id)
+                .and_then(|m| m.info.as_ref())
+                .is_some_and(|info| {
+                    info.external.is_none()
+                        && matches!(&info.ast, ModuleAst::Script(ast) if is_cjs_without_es_module(ast))
+                });
+            if is_cjs {
+                hazards.push((module.id.clone(), dep_id.clone()));
+            }
+        }
+    }
+    hazards
+}
+
+// modules without import and export which assign to module.exports or exports,
+// and never mention __esModule
+fn is_cjs_without_es_module(ast: &JsAst) -> bool {
+    if ast
+        .ast
+        .body
+        .iter()
+        .any(|item| matches!(item, ModuleItem::ModuleDecl(_)))
+    {
+        return false;
+    }
+    let mut detector = CjsDetector {
+        unresolved_mark: ast.unresolved_mark,
+        has_exports: false,
+        has_es_module: false,
+    };
+    ast.ast.visit_with(&mut detector);
+    detector.has_exports && !detector.has_es_module
+}
+
+struct CjsDetector {
+    unresolved_mark: Mark,
+    has_exports: bool,
+    has_es_module: bool,
+}
+
+impl Visit for CjsDetector {
+    fn visit_member_expr(&mut self, expr: &MemberExpr) {
+        // e.g. module.exports, exports.foo
+        if let Expr::Ident(obj) = &*expr.obj {
+            let is_module_exports = is_ident_undefined(obj, "module", &self.unresolved_mark)
+                && matches!(&expr.prop, MemberProp::Ident(prop) if prop.sym == *"exports");
+            if is_module_exports || is_ident_undefined(obj, "exports", &self.unresolved_mark) {
+                self.has_exports = true;
+            }
+        }
+        expr.visit_children_with(self);
+    }
+
+    // e.g. exports.__esModule = true
+    fn visit_ident(&mut self, ident: &Ident) {
+        if ident.sym == *"__esModule" {
+            self.has_es_module = true;
+        }
+    }
+
+    // e.g. Object.defineProperty(exports, "__esModule", { value: true })
+    fn visit_str(&mut self, str: &Str) {
+        if str.value == *"__esModule" {
+            self.has_es_module = true;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use swc_core::common::GLOBALS;
+
+    use super::{find_cjs_default_imports, is_cjs_without_es_module};
+    use crate::ast::file::File;
+    use crate::ast::tests::TestUtils;
+    use crate::module::ModuleId;
+    use crate::utils::test_helper::setup_compiler;
+
+    #[test]
+    fn test_is_cjs_without_es_module() {
+        let is_cjs = |code| {
+            let test_utils = TestUtils::gen_js_ast(code);
+            GLOBALS.set(&test_utils.context.meta.script.globals, || {
+                is_cjs_without_es_module(test_utils.ast.js())
+            })
+        };
+        assert!(is_cjs(r#"module.exports = function() {};"#));
+        assert!(is_cjs(r#"exports.default = 1;"#));
+        assert!(!is_cjs(
+            r#"exports.__esModule = true; exports.default = 1;"#
+        ));
+        assert!(!is_cjs(
+            r#"Object.defineProperty(exports, "__esModule", { value: true }); exports.default = 1;"#
+        ));
+        assert!(!is_cjs(r#"export default 1;"#));
+        assert!(!is_cjs(r#"const exports = {}; exports.default = 1;"#));
+        assert!(!is_cjs(r#"console.log(1);"#));
+    }
+
+    #[test]
+    fn test_find_cjs_default_imports() {
+        let compiler = setup_compiler("test/build/cjs-interop", false);
+        let context = compiler.context.clone();
+        let files = context
+            .config
+            .entry
+            .values()
+            .map(|entry| File::new_entry(entry.to_string_lossy().to_string(), context.clone()))
+            .collect();
+        compiler.build(files).unwrap();
+        let relative = |id: ModuleId| id.id.replace(context.root.to_str().unwrap(), "");
+        let hazards = find_cjs_default_imports(&context)
+            .into_iter()
+            .map(|(importer, cjs)| (relative(importer), relative(cjs)))
+            .collect::<Vec<_>>();
+        // es-module.js defines __esModule, and the named import of cjs-named.js is fine
+        assert_eq!(
+            hazards,
+            vec![("/src/index.js".to_string(), "/src/cjs.js".to_string())]
+        );
+    }
+}
@@ -25,7 +25,7 @@ impl Plugin for ContextModulePlugin {
         "context_module"
     }
 
-    fn load(&self, param: &PluginLoadParam, _context: &Arc<Context>) -> Result<Option<Content>> {
+    fn load(&self, param: &PluginLoadParam, context: &Arc<Context>) -> Result<Option<Content>> {
         if let (Some(glob_pattern), true) = (
             param
                 .file
@@ -54,7 +54,9 @@ impl Plugin for ContextModulePlugin {
                 keys.push(format!("./{}", rlt_path.to_string_lossy()));
                 // omit ext `./i18n/zh_CN`
                 if let Some(ext) = rlt_path.extension() {
-                    if get_module_extensions().contains(&format!(".{}", ext.to_string_lossy())) {
+                    if get_module_extensions(&context.config)
+                        .contains(&format!(".{}", ext.to_string_lossy()))
+                    {
                         keys.push(format!(
                             "./{}",
                             rlt_path.with_extension("").to_string_lossy()
@@ -189,6 +191,14 @@ impl VisitMut for ContextModuleVisitor {
     }
 }
 
+// get the static prefix and suffix of the async context request made by ContextModuleVisitor
+// e.g. ./i18n/?context&glob=**/*.json&async => ("./i18n/", ".json")
+pub fn parse_async_context_request(source: &str) -> Option<(String, String)> {
+    let (prefix, query) = source.split_once("?context&glob=")?;
+    let suffix = query.strip_suffix("&async")?.strip_prefix("**/*")?;
+    Some((prefix.to_string(), suffix.to_string()))
+}
+
 /**
  * try to find valid context arg
  * and return prefix, suffix and replace first string literal with `./`
@@ -227,7 +237,8 @@ fn try_replace_context_arg(
         }
 
         // handle prefix of `'./foo/' + bar + '.ext'`
-        Expr::Lit(Lit::Str(str)) => {
+        // notice: `'' + bar` has no static prefix to find the files
+        Expr::Lit(Lit::Str(str)) if !str.value.is_empty() => {
             let mut prefix = str.value.to_string();
             // replace first str with relative prefix
             let (pre_quasis, remainder) = if let Some(pos) = prefix.rfind('/') {
@@ -244,8 +255,9 @@ fn try_replace_context_arg(
         }
 
         // handle `./foo/${bar}.ext`
+        // notice: `${bar}.ext` has no static prefix to find the files
         Expr::Tpl(tpl) => {
-            if !tpl.exprs.is_empty() {
+            if !tpl.exprs.is_empty() && !tpl.quasis[0].raw.is_empty() {
                 let pre_quasis = tpl.quasis.first().unwrap().raw.to_string();
                 let (prefix, remainder) = if let Some(pos) = pre_quasis.rfind('/') {
                     (
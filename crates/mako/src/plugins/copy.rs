@@ -1,4 +1,4 @@
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
 use anyhow::Result;
@@ -64,7 +64,9 @@ impl CopyPlugin {
         for src in context.config.copy.iter() {
             let src = context.root.join(src);
             debug!("copy {:?} to {:?}", src, dest);
-            copy(src.as_path(), dest)?;
+            for file in copy(src.as_path(), dest)? {
+                context.emitted_files.add(file);
+            }
         }
         Ok(())
     }
@@ -84,8 +86,10 @@ impl Plugin for CopyPlugin {
     }
 }
 
-fn copy(src: &Path, dest: &Path) -> Result<()> {
+// returns the copied files in the dest
+fn copy(src: &Path, dest: &Path) -> Result<Vec<PathBuf>> {
     let paths = glob(src.to_str().unwrap())?;
+    let mut files = vec![];
 
     for entry in paths {
         let entry = entry.unwrap();
@@ -96,12 +100,27 @@ fn copy(src: &Path, dest: &Path) -> Result<()> {
                 .skip_exist(false)
                 .overwrite(true);
             fs_extra::dir::copy(&entry, dest, &options)?;
+            collect_copied_files(&entry, dest, &mut files)?;
         } else {
             let file_name = entry.file_name().unwrap();
             let options = fs_extra::file::CopyOptions::new()
                 .skip_exist(false)
                 .overwrite(true);
             fs_extra::file::copy(&entry, dest.join(file_name), &options)?;
+            files.push(dest.join(file_name));
+        }
+    }
+    Ok(files)
+}
+
+fn collect_copied_files(dir: &Path, dest: &Path, files: &mut Vec<PathBuf>) -> Result<()> {
+    for entry in std::fs::read_dir(dir)? {
+        let entry = entry?;
+        let dest = dest.join(entry.file_name());
+        if entry.file_type()?.is_dir() {
+            collect_copied_files(&entry.path(), &dest, files)?;
+        } else {
+            files.push(dest);
         }
     }
     Ok(())
@@ -16,11 +16,13 @@ impl Plugin for LoopDetector {
     }
 
     fn generate_begin(&self, context: &Arc<Context>) -> Result<()> {
+        let warn = context.config.warnings.circular_dependencies;
         if let Some(detect_loop) = &context.config.experimental.detect_circular_dependence
+            && (warn || detect_loop.graphviz)
             && !context.args.watch
         {
             let module_graph = context.module_graph.read().unwrap();
-            let (_, loops) = module_graph.toposort();
+            let loops = module_graph.find_cycles();
 
             let ignore_regexes = detect_loop
                 .ignores
@@ -62,8 +64,10 @@ impl Plugin for LoopDetector {
                 .collect::<Vec<_>>();
 
             if !loop_lines.is_empty() {
-                for l in &loop_lines {
-                    println!("{} Circular Dependencies: {}", "Warning".yellow(), l);
+                if warn {
+                    for l in &loop_lines {
+                        println!("{} Circular Dependencies: {}", "Warning".yellow(), l);
+                    }
                 }
 
                 if detect_loop.graphviz {
@@ -4,11 +4,13 @@ use anyhow::Ok;
 use swc_core::common::comments::NoopComments;
 use swc_core::common::sync::Lrc;
 use swc_core::common::SourceMap;
-use swc_core::ecma::ast::Module;
+use swc_core::ecma::ast::{
+    CallExpr, Expr, Ident, KeyValueProp, Lit, Module, ObjectLit, Prop, PropName, PropOrSpread, Str,
+};
 use swc_core::ecma::visit::{Fold, VisitMut, VisitMutWith};
 use swc_emotion::{emotion, EmotionOptions};
 
-use crate::config::{Mode, ReactConfig};
+use crate::config::{CssInJsConfig, Mode, ReactConfig};
 use crate::plugin::Plugin;
 
 pub struct EmotionPlugin {}
@@ -41,11 +43,12 @@ impl Plugin for EmotionPlugin {
         ast: &mut swc_core::ecma::ast::Module,
         context: &std::sync::Arc<crate::compiler::Context>,
     ) -> anyhow::Result<()> {
-        if context.config.emotion {
+        if context.config.emotion || context.config.css_in_js.is_some() {
             ast.visit_mut_with(&mut Emotion {
                 mode: context.config.mode.clone(),
                 cm: context.meta.script.cm.clone(),
                 path: param.path.into(),
+                css_in_js: context.config.css_in_js.clone(),
             });
         }
 
@@ -57,6 +60,7 @@ struct Emotion {
     cm: Lrc<SourceMap>,
     path: String,
     mode: Mode,
+    css_in_js: Option<CssInJsConfig>,
 }
 
 impl VisitMut for Emotion {
@@ -68,9 +72,13 @@ impl VisitMut for Emotion {
             EmotionOptions {
                 enabled: Some(true),
                 sourcemap: Some(is_dev),
-                auto_label: Some(is_dev),
+                // labels are always added when cssInJs is configured, in dev mode otherwise
+                auto_label: Some(is_dev || self.css_in_js.is_some()),
+                label_format: self
+                    .css_in_js
+                    .as_ref()
+                    .map(|css_in_js| css_in_js.label_format.clone()),
                 import_map: None,
-                ..Default::default()
             },
             Path::new(&self.path),
             hash,
@@ -78,7 +86,124 @@ impl VisitMut for Emotion {
             NoopComments,
         );
         module.body = folder.fold_module(module.clone()).body;
+        // labels of anonymous usages are left as the raw format, e.g. `label: "[local]"`
+        module.visit_mut_with(&mut AnonymousLabelRemover {
+            label_format: self
+                .css_in_js
+                .as_ref()
+                .map_or("[local]".to_string(), |css_in_js| {
+                    css_in_js.label_format.clone()
+                }),
+        });
 
         module.visit_mut_children_with(self);
     }
 }
+
+struct AnonymousLabelRemover {
+    label_format: String,
+}
+
+impl AnonymousLabelRemover {
+    fn is_raw_label(&self, expr: &Expr) -> bool {
+        matches!(expr, Expr::Lit(Lit::Str(Str { value, .. }))
+            if value == &self.label_format
+                || value.strip_prefix("label:") == Some(self.label_format.as_str()))
+    }
+}
+
+impl VisitMut for AnonymousLabelRemover {
+    fn visit_mut_call_expr(&mut self, call: &mut CallExpr) {
+        call.args
+            .retain(|arg| arg.spread.is_some() || !self.is_raw_label(&arg.expr));
+        call.visit_mut_children_with(self);
+    }
+
+    fn visit_mut_object_lit(&mut self, obj: &mut ObjectLit) {
+        obj.props.retain(|prop| {
+            !matches!(prop, PropOrSpread::Prop(box Prop::KeyValue(KeyValueProp {
+                key: PropName::Ident(Ident { sym, .. }),
+                value,
+            })) if sym == "label" && self.is_raw_label(value))
+        });
+        obj.visit_mut_children_with(self);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use swc_core::common::GLOBALS;
+    use swc_core::ecma::visit::VisitMutWith;
+
+    use super::Emotion;
+    use crate::ast::tests::{TestUtils, TestUtilsOpts};
+    use crate::compiler::Context;
+    use crate::config::{CssInJsConfig, Mode};
+
+    #[test]
+    fn test_label() {
+        let code = run(
+            r#"
+import styled from '@emotion/styled';
+const Button = styled.button`color: red;`;
+const Link = styled(Button)`color: blue;`;
+            "#,
+            "[local]",
+        );
+        assert!(code.contains(r#"label: "Button""#));
+        assert!(code.contains(r#"label: "Link""#));
+    }
+
+    #[test]
+    fn test_label_format() {
+        let code = run(
+            r#"
+import { css } from '@emotion/react';
+const title = css`color: red;`;
+            "#,
+            "[filename]-[local]",
+        );
+        assert!(code.contains(r#"css("color:red;", "test-title")"#));
+    }
+
+    #[test]
+    fn test_anonymous() {
+        let code = run(
+            r#"
+import styled from '@emotion/styled';
+export default styled.div`color: red;`;
+            "#,
+            "[local]",
+        );
+        assert!(!code.contains("label"));
+    }
+
+    fn run(js_code: &str, label_format: &str) -> String {
+        let mut context = Context::default();
+        context.config.devtool = None;
+        context.config.mode = Mode::Production;
+        context.config.minify = None;
+        context.config.css_in_js = Some(CssInJsConfig {
+            label_format: label_format.to_string(),
+        });
+        let mut test_utils = TestUtils::with_context(
+            TestUtilsOpts {
+                file: Some("test.js".to_string()),
+                content: Some(js_code.to_string()),
+            },
+            Arc::new(context),
+        );
+        let ast = test_utils.ast.js_mut();
+        GLOBALS.set(&test_utils.context.meta.script.globals, || {
+            ast.ast.visit_mut_with(&mut Emotion {
+                cm: test_utils.context.meta.script.cm.clone(),
+                path: "test.js".to_string(),
+                mode: test_utils.context.config.mode.clone(),
+                css_in_js: test_utils.context.config.css_in_js.clone(),
+            });
+        });
+        test_utils.js_ast_to_code()
+    }
+}
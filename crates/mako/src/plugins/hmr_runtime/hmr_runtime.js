@@ -8,6 +8,8 @@
 !(function () {
   let currentParents = [];
   let currentChildModule;
+  // data passed from dispose handlers to the next module instance
+  const hotData = {};
   requireModule.hmrC = {};
   const createHmrRequire = (require, moduleId) => {
     const me = modulesRegistry[moduleId];
@@ -67,9 +69,11 @@
     }
     for (const moduleId of outdatedModules) {
       const module = modulesRegistry[moduleId];
+      const data = {};
       for (const handler of module.hot._disposeHandlers) {
-        handler();
+        handler(data);
       }
+      hotData[moduleId] = data;
       module.hot.active = false;
       delete modulesRegistry[moduleId];
       for (const childModule of module.children) {
@@ -83,7 +87,10 @@
     }
     registerModules(modules);
     for (const module of outdatedSelfAcceptedModules) {
-      module.hot._requireSelf();
+      const exports = module.hot._requireSelf();
+      for (const callback of module.hot._acceptCallbacks) {
+        callback(exports);
+      }
     }
   };
   const createModuleHotObject = (moduleId, me) => {
@@ -95,15 +102,20 @@
       _selfDeclined: false,
       _selfInvalidated: false,
       _disposeHandlers: [],
+      _acceptCallbacks: [],
       _requireSelf: function () {
         currentParents = me.parents.slice();
         currentChildModule = _main ? undefined : moduleId;
-        requireModule(moduleId);
+        return requireModule(moduleId);
       },
       _main,
       active: true,
-      accept() {
+      data: hotData[moduleId],
+      accept(callback) {
         this._selfAccepted = true;
+        if (typeof callback === 'function') {
+          this._acceptCallbacks.push(callback);
+        }
       },
       dispose(callback) {
         this._disposeHandlers.push(callback);
@@ -152,8 +164,17 @@
       apply(update) {
         return applyHotUpdate(update);
       },
+      reloadStylesheets() {
+        return Promise.all(
+          Object.keys(cssChunksIdToUrlMap).reduce((promises, chunkId) => {
+            requireModule.hmrC.css(chunkId, null, promises);
+            return promises;
+          }, []),
+        );
+      },
     };
     currentChildModule = undefined;
+    delete hotData[moduleId];
     return hot;
   };
   requireModule.hmrC.jsonp = (chunkId, update, promises) => {
@@ -186,6 +207,8 @@
             );
             newLink.rel = 'prereload';
             newLink.as = 'style';
+          } else {
+            resolve();
           }
         }),
       );
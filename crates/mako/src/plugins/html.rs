@@ -0,0 +1,354 @@
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashSet;
+use std::fs;
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, OnceLock};
+
+use anyhow::{anyhow, Result};
+use regex::Regex;
+
+use crate::ast::utils::is_remote_or_data;
+use crate::compiler::{Args, Context};
+use crate::config::{Config, OutputFormat};
+use crate::generate::chunk::ChunkType;
+use crate::generate::generate_chunks::RUNTIME_CHUNK_ID;
+use crate::plugin::{Plugin, PluginGenerateEndParams};
+use crate::stats::StatsJsonMap;
+
+// html entries, e.g. { "index": "./index.html" }
+// local <script src> and <link rel="stylesheet" href> are bundled as entries,
+// and the html is emitted to output.path with the tags rewritten to the outputs
+#[derive(Default)]
+pub struct HtmlPlugin {
+    entries: Mutex<Vec<HtmlEntry>>,
+}
+
+struct HtmlEntry {
+    name: String,
+    content: String,
+    tags: Vec<HtmlTag>,
+}
+
+#[derive(Debug)]
+struct HtmlTag {
+    // byte range of the tag in the html
+    start: usize,
+    end: usize,
+    src: String,
+    entry: String,
+    is_style: bool,
+    // attributes except src and href, which are kept in the rewritten tags
+    attrs: Vec<(String, Option<String>)>,
+}
+
+impl Plugin for HtmlPlugin {
+    fn name(&self) -> &str {
+        "html"
+    }
+
+    fn modify_config(&self, config: &mut Config, root: &Path, _args: &Args) -> Result<()> {
+        let html_entries = config
+            .entry
+            .iter()
+            .filter(|(_, path)| path.extension().is_some_and(|ext| ext == "html"))
+            .map(|(name, path)| (name.clone(), path.clone()))
+            .collect::<Vec<_>>();
+        let mut entries = self.entries.lock().unwrap();
+        for (name, path) in html_entries {
+            config.entry.remove(&name);
+            let content = fs::read_to_string(&path)
+                .map_err(|e| anyhow!("read {} failed: {}", path.display(), e))?;
+            let mut tags = parse_html(&content);
+            for (i, tag) in tags.iter_mut().enumerate() {
+                tag.entry = if i == 0 {
+                    name.clone()
+                } else {
+                    format!("{}_{}", name, i)
+                };
+                let file = resolve_src(root, &path, &tag.src)?;
+                config.entry.insert(tag.entry.clone(), file);
+            }
+            entries.push(HtmlEntry {
+                name,
+                content,
+                tags,
+            });
+        }
+        Ok(())
+    }
+
+    fn build_success(&self, _stats: &StatsJsonMap, context: &Arc<Context>) -> Result<()> {
+        self.emit_html(context)
+    }
+
+    // the chunk hashes change with rebuilds in watch mode
+    fn generate_end(&self, params: &PluginGenerateEndParams, context: &Arc<Context>) -> Result<()> {
+        if params.is_first_compile {
+            return Ok(());
+        }
+        self.emit_html(context)
+    }
+}
+
+impl HtmlPlugin {
+    fn emit_html(&self, context: &Arc<Context>) -> Result<()> {
+        for entry in self.entries.lock().unwrap().iter() {
+            let html = render_html(entry, context);
+            let mut hasher = DefaultHasher::new();
+            html.hash(&mut hasher);
+            context.write_static_content(
+                format!("{}.html", entry.name),
+                html.into_bytes(),
+                hasher.finish(),
+            )?;
+        }
+        Ok(())
+    }
+}
+
+fn tag_regex() -> &'static Regex {
+    static TAG_REGEXP: OnceLock<Regex> = OnceLock::new();
+
+    // comments are matched first, so tags in them are skipped
+    TAG_REGEXP.get_or_init(|| {
+        Regex::new(r"(?is)<!--.*?-->|<script\b([^>]*)>.*?</script\s*>|<link\b([^>]*)>").unwrap()
+    })
+}
+
+fn attr_regex() -> &'static Regex {
+    static ATTR_REGEXP: OnceLock<Regex> = OnceLock::new();
+
+    ATTR_REGEXP.get_or_init(|| {
+        Regex::new(r#"([^\s"'<>/=]+)(?:\s*=\s*(?:"([^"]*)"|'([^']*)'|([^\s"'=<>`]+)))?"#).unwrap()
+    })
+}
+
+// the local scripts and stylesheets, remote and inline ones are left untouched
+fn parse_html(content: &str) -> Vec<HtmlTag> {
+    let mut tags = vec![];
+    for cap in tag_regex().captures_iter(content) {
+        let tag = cap.get(0).unwrap();
+        let (attrs, is_style) = match (cap.get(1), cap.get(2)) {
+            (Some(attrs), _) => (parse_attrs(attrs.as_str()), false),
+            (_, Some(attrs)) => (parse_attrs(attrs.as_str()), true),
+            // comments
+            _ => continue,
+        };
+        if is_style
+            && !get_attr(&attrs, "rel").is_some_and(|rel| {
+                rel.split_whitespace()
+                    .any(|rel| rel.eq_ignore_ascii_case("stylesheet"))
+            })
+        {
+            continue;
+        }
+        let src_attr = if is_style { "href" } else { "src" };
+        let Some(src) = get_attr(&attrs, src_attr) else {
+            continue;
+        };
+        if is_remote_or_data(&src) {
+            continue;
+        }
+        tags.push(HtmlTag {
+            start: tag.start(),
+            end: tag.end(),
+            src,
+            entry: String::new(),
+            is_style,
+            attrs: attrs
+                .into_iter()
+                .filter(|(name, _)| !name.eq_ignore_ascii_case(src_attr))
+                .collect(),
+        });
+    }
+    tags
+}
+
+// e.g. src="./a.js" defer => [("src", Some("./a.js")), ("defer", None)]
+fn parse_attrs(attrs: &str) -> Vec<(String, Option<String>)> {
+    attr_regex()
+        .captures_iter(attrs)
+        .map(|cap| {
+            let value = cap
+                .get(2)
+                .or(cap.get(3))
+                .or(cap.get(4))
+                .map(|value| value.as_str().to_string());
+            (cap[1].to_string(), value)
+        })
+        .collect()
+}
+
+fn get_attr(attrs: &[(String, Option<String>)], name: &str) -> Option<String> {
+    attrs
+        .iter()
+        .find(|(attr, _)| attr.eq_ignore_ascii_case(name))
+        .and_then(|(_, value)| value.clone())
+}
+
+fn render_attrs(attrs: &[(String, Option<String>)]) -> String {
+    attrs
+        .iter()
+        .map(|(name, value)| match value {
+            Some(value) => format!(" {}=\"{}\"", name, value.replace('"', "&quot;")),
+            None => format!(" {}", name),
+        })
+        .collect()
+}
+
+// src is relative to the html, or the root if it starts with /
+fn resolve_src(root: &Path, html: &Path, src: &str) -> Result<PathBuf> {
+    let src = src.split(['?', '#']).next().unwrap();
+    let path = if let Some(src) = src.strip_prefix('/') {
+        root.join(src)
+    } else {
+        html.parent().unwrap().join(src)
+    };
+    path.canonicalize()
+        .map_err(|_| anyhow!("{} in {} not found", src, html.display()))
+}
+
+fn render_html(entry: &HtmlEntry, context: &Arc<Context>) -> String {
+    let public_path = match context.config.public_path.as_str() {
+        "runtime" => "/",
+        "auto" => "",
+        public_path => public_path,
+    };
+    // chunks of esm output import each other, which needs module scripts
+    let is_esm = matches!(context.config.output.format, Some(OutputFormat::Esm));
+    let assets = context.stats_info.get_assets();
+    let chunk_graph = context.chunk_graph.read().unwrap();
+    // chunks shared by multiple tags are only injected once
+    let mut injected = HashSet::new();
+    let mut head_links = String::new();
+    let mut html = String::new();
+    let mut last = 0;
+    for tag in &entry.tags {
+        let Some(entry_chunk_id) = chunk_graph.get_chunks().into_iter().find_map(|chunk| {
+            matches!(&chunk.chunk_type, ChunkType::Entry(_, name, _) if name == &tag.entry)
+                .then(|| chunk.id.clone())
+        }) else {
+            continue;
+        };
+        let mut chunk_ids = vec![];
+        if context.config.runtime_chunk().is_some() {
+            chunk_ids.push(RUNTIME_CHUNK_ID.to_string());
+        }
+        chunk_ids.extend(
+            chunk_graph
+                .entry_dependencies_chunk(&entry_chunk_id)
+                .into_iter()
+                .map(|id| id.id),
+        );
+        chunk_ids.push(entry_chunk_id.id);
+
+        let attrs = render_attrs(&tag.attrs);
+        let script_type = if is_esm && get_attr(&tag.attrs, "type").is_none() {
+            " type=\"module\""
+        } else {
+            ""
+        };
+        let mut links = String::new();
+        let mut scripts = String::new();
+        for chunk_id in chunk_ids {
+            if !injected.insert(chunk_id.clone()) {
+                continue;
+            }
+            for asset in assets.iter().filter(|asset| asset.chunk_id == chunk_id) {
+                let url = format!("{}{}", public_path, asset.hashname);
+                if asset.hashname.ends_with(".css") {
+                    // the attributes of stylesheets are kept, e.g. media
+                    if tag.is_style {
+                        links.push_str(&format!("<link{} href=\"{}\">", attrs, url));
+                    } else {
+                        links.push_str(&format!("<link rel=\"stylesheet\" href=\"{}\">", url));
+                    }
+                } else if asset.hashname.ends_with(".js") && !tag.is_style {
+                    scripts.push_str(&format!(
+                        "<script{}{} src=\"{}\"></script>",
+                        attrs, script_type, url
+                    ));
+                }
+            }
+        }
+
+        html.push_str(&entry.content[last..tag.start]);
+        // css extracted from scripts are injected into head
+        if tag.is_style {
+            html.push_str(&links);
+        } else {
+            head_links.push_str(&links);
+        }
+        html.push_str(&scripts);
+        last = tag.end;
+    }
+    html.push_str(&entry.content[last..]);
+
+    if head_links.is_empty() {
+        return html;
+    }
+    match html.find("</head>") {
+        Some(idx) => {
+            html.insert_str(idx, &head_links);
+            html
+        }
+        None => format!("{}{}", head_links, html),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::parse_html;
+    use crate::utils::test_helper::setup_compiler;
+
+    #[test]
+    fn test_html_entry() {
+        let compiler = setup_compiler("test/build/html-entry", false);
+        compiler.compile().unwrap();
+        let output = &compiler.context.config.output.path;
+        let html = fs::read_to_string(output.join("index.html")).unwrap();
+        crate::assert_display_snapshot!(html);
+    }
+
+    #[test]
+    fn test_parse_html() {
+        let tags = parse_html(
+            r#"<!-- <script src="./commented.js"></script> -->
+<script data-src="./data.js" src='./a.js' type="module" defer></script>
+<script src="https://example.com/remote.js"></script>
+<script>window.inline = 1;</script>
+<link rel="icon" href="./favicon.ico">
+<link rel="stylesheet" href="./a.css" media="print" />"#,
+        );
+        let tags = tags
+            .iter()
+            .map(|tag| (tag.src.as_str(), tag.is_style, tag.attrs.clone()))
+            .collect::<Vec<_>>();
+        let attr = |name: &str, value: Option<&str>| (name.to_string(), value.map(String::from));
+        assert_eq!(
+            tags,
+            vec![
+                (
+                    "./a.js",
+                    false,
+                    vec![
+                        attr("data-src", Some("./data.js")),
+                        attr("type", Some("module")),
+                        attr("defer", None),
+                    ]
+                ),
+                (
+                    "./a.css",
+                    true,
+                    vec![
+                        attr("rel", Some("stylesheet")),
+                        attr("media", Some("print"))
+                    ]
+                ),
+            ]
+        );
+    }
+}
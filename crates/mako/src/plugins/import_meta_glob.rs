@@ -0,0 +1,254 @@
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use anyhow::{anyhow, Result};
+use glob::{glob, MatchOptions, Pattern};
+use pathdiff::diff_paths;
+use percent_encoding::{percent_decode_str, utf8_percent_encode, AsciiSet, CONTROLS};
+
+use crate::ast::file::{Content, JsContent};
+use crate::compiler::Context;
+use crate::plugin::{Plugin, PluginLoadParam};
+use crate::resolve::ResolverResource;
+
+pub(crate) const GLOB_QUERY: &str = "import-meta-glob";
+
+// the chars which break the query of the request and the module id, i.e. the pattern is
+// written as a query value, `+` is decoded as a space in the query too
+const PATTERN_ENCODE_SET: &AsciiSet = &CONTROLS
+    .add(b' ')
+    .add(b'#')
+    .add(b'%')
+    .add(b'&')
+    .add(b'+')
+    .add(b'=')
+    .add(b'?');
+
+// import.meta.glob('./dir/*.js') is transformed to require('./dir/*.js?import-meta-glob')
+// by ImportMetaGlob, the pattern is expanded when the dependency is resolved, and resolved to
+// a virtual module of the importer, e.g. /src/index.js?import-meta-glob=./dir/*.js&files=1a2b3c4d
+// files is the hash of the matching files, so a different module is resolved when files are
+// added or removed, and it's loaded as
+// module.exports = { "./dir/a.js": () => import("/src/dir/a.js") }
+pub struct ImportMetaGlobPlugin {}
+
+impl Plugin for ImportMetaGlobPlugin {
+    fn name(&self) -> &str {
+        "import_meta_glob"
+    }
+
+    fn load(&self, param: &PluginLoadParam, _context: &Arc<Context>) -> Result<Option<Content>> {
+        let Some(pattern) = param
+            .file
+            .params
+            .iter()
+            .find_map(|(k, v)| (k == GLOB_QUERY).then_some(v))
+        else {
+            return Ok(None);
+        };
+        let eager = param.file.has_param("eager");
+        let load_by = if eager { "require" } else { "() => import" };
+        let key_values = expand_glob(&param.file.pathname, pattern)?
+            .into_iter()
+            .map(|(key, path)| {
+                format!(
+                    "    {}: {}({})",
+                    serde_json::to_string(&key).unwrap(),
+                    load_by,
+                    serde_json::to_string(&path.to_string_lossy()).unwrap()
+                )
+            })
+            .collect::<Vec<_>>();
+        Ok(Some(Content::Js(JsContent {
+            content: format!("module.exports = {{\n{}\n}};\n", key_values.join(",\n")),
+            ..Default::default()
+        })))
+    }
+}
+
+// e.g. ./dir/*.js?import-meta-glob&eager => ("./dir/*.js", true)
+pub fn parse_glob_request(source: &str) -> Option<(String, bool)> {
+    let (pattern, query) = source.split_once('?')?;
+    let mut params = query.split('&');
+    if params.next() != Some(GLOB_QUERY) {
+        return None;
+    }
+    let pattern = percent_decode_str(pattern).decode_utf8().ok()?;
+    Some((pattern.to_string(), params.any(|p| p == "eager")))
+}
+
+pub fn create_glob_request(pattern: &str, eager: bool) -> String {
+    let pattern = encode_pattern(pattern);
+    if eager {
+        format!("{}?{}&eager", pattern, GLOB_QUERY)
+    } else {
+        format!("{}?{}", pattern, GLOB_QUERY)
+    }
+}
+
+pub fn resolve_glob(importer: &str, source: &str) -> Result<ResolverResource> {
+    let (pattern, eager) =
+        parse_glob_request(source).ok_or_else(|| anyhow!("invalid glob request {}", source))?;
+    let files = expand_glob(Path::new(importer), &pattern)?
+        .into_iter()
+        .map(|(key, _)| key)
+        .collect::<Vec<_>>()
+        .join("\n");
+    let hash = format!("{:x}", md5::compute(files));
+    let mut id = format!(
+        "{}?{}={}&files={}",
+        importer,
+        GLOB_QUERY,
+        encode_pattern(&pattern),
+        &hash[..8]
+    );
+    if eager {
+        id.push_str("&eager");
+    }
+    Ok(ResolverResource::Virtual(PathBuf::from(id)))
+}
+
+fn encode_pattern(pattern: &str) -> String {
+    utf8_percent_encode(pattern, PATTERN_ENCODE_SET).to_string()
+}
+
+// the matching files except the importer itself, keyed by the paths relative to the importer,
+// sorted by the keys
+fn expand_glob(importer: &Path, pattern: &str) -> Result<Vec<(String, PathBuf)>> {
+    let dir = importer
+        .parent()
+        .ok_or_else(|| anyhow!("invalid importer {:?} of glob {}", importer, pattern))?;
+    // the dir may contain glob metacharacters, e.g. /src/[id]/index.js
+    let full_pattern = Path::new(&Pattern::escape(&dir.to_string_lossy())).join(pattern);
+    let mut files = glob(&full_pattern.to_string_lossy())?
+        .filter_map(|path| path.ok())
+        .filter(|path| path.is_file() && path != importer)
+        .filter_map(|path| Some((to_relative_source(&path, dir)?, path)))
+        .collect::<Vec<_>>();
+    files.sort();
+    Ok(files)
+}
+
+// whether the file is matched by the glob of the importer, the same as expand_glob,
+// so added or removed files which change the matching files can be found
+pub fn is_glob_matched(importer: &Path, pattern: &str, path: &Path) -> bool {
+    let Some(dir) = importer.parent() else {
+        return false;
+    };
+    let Ok(pattern) = Pattern::new(pattern) else {
+        return false;
+    };
+    path != importer
+        && to_relative_source(path, dir).is_some_and(|source| {
+            pattern.matches_with(
+                &source,
+                MatchOptions {
+                    require_literal_separator: true,
+                    ..Default::default()
+                },
+            )
+        })
+}
+
+fn to_relative_source(path: &Path, dir: &Path) -> Option<String> {
+    let relative = diff_paths(path, dir)?;
+    let relative = relative.to_string_lossy().replace('\\', "/");
+    if relative.starts_with("../") {
+        Some(relative)
+    } else {
+        Some(format!("./{}", relative))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::{
+        create_glob_request, expand_glob, is_glob_matched, parse_glob_request, resolve_glob,
+    };
+    use crate::ast::file::parse_path;
+
+    #[test]
+    fn test_parse_glob_request() {
+        assert_eq!(
+            parse_glob_request("./dir/*.js?import-meta-glob"),
+            Some(("./dir/*.js".to_string(), false))
+        );
+        assert_eq!(
+            parse_glob_request("./dir/*.js?import-meta-glob&eager"),
+            Some(("./dir/*.js".to_string(), true))
+        );
+        assert_eq!(parse_glob_request("./dir/a.js?raw"), None);
+        // the query chars of the pattern are encoded
+        let request = create_glob_request("./dir/?(a|b)=&c+d.js", true);
+        assert_eq!(
+            parse_glob_request(&request),
+            Some(("./dir/?(a|b)=&c+d.js".to_string(), true))
+        );
+    }
+
+    #[test]
+    fn test_expand_glob() {
+        let importer = fixture("index.js");
+        let keys = |pattern| {
+            expand_glob(&importer, pattern)
+                .unwrap()
+                .into_iter()
+                .map(|(key, _)| key)
+                .collect::<Vec<_>>()
+        };
+        // the importer is excluded
+        assert_eq!(keys("./*.js"), vec!["./a.js", "./b.js"]);
+        assert_eq!(keys("./**/*.css"), vec!["./dir/c.css"]);
+        // glob metacharacters of the dir are escaped
+        let importer = fixture("[id]/index.js");
+        assert_eq!(
+            expand_glob(&importer, "./*.js").unwrap().len(),
+            1,
+            "only [id]/d.js is matched"
+        );
+    }
+
+    #[test]
+    fn test_is_glob_matched() {
+        let importer = fixture("index.js");
+        assert!(is_glob_matched(&importer, "./*.js", &fixture("e.js")));
+        assert!(is_glob_matched(
+            &importer,
+            "./**/*.css",
+            &fixture("dir/e.css")
+        ));
+        assert!(!is_glob_matched(&importer, "./*.js", &fixture("dir/e.js")));
+        assert!(!is_glob_matched(&importer, "./*.js", &importer));
+    }
+
+    #[test]
+    fn test_resolve_glob() {
+        let importer = fixture("index.js").to_string_lossy().to_string();
+        let id = resolve_glob(&importer, "./*.js?import-meta-glob&eager")
+            .unwrap()
+            .get_resolved_path();
+        assert!(id.starts_with(&format!("{}?import-meta-glob=./*.js&files=", importer)));
+        assert!(id.ends_with("&eager"));
+        // the id changes with the matching files
+        let other = resolve_glob(&importer, "./**/*.css?import-meta-glob&eager")
+            .unwrap()
+            .get_resolved_path();
+        assert_ne!(id.split("&files=").nth(1), other.split("&files=").nth(1));
+        // the pattern is read back from the params of the id
+        let id = resolve_glob(&importer, &create_glob_request("./?.js", false))
+            .unwrap()
+            .get_resolved_path();
+        let (pathname, _, params, _) = parse_path(&id).unwrap();
+        assert_eq!(pathname, importer);
+        assert!(params.contains(&("import-meta-glob".to_string(), "./?.js".to_string())));
+    }
+
+    fn fixture(path: &str) -> std::path::PathBuf {
+        std::env::current_dir()
+            .unwrap()
+            .join("src/visitors/fixtures/import_meta_glob")
+            .join(Path::new(path))
+    }
+}
@@ -31,7 +31,14 @@ impl Plugin for ManifestPlugin {
             let path = normalize_path(base_path);
 
             for asset in assets {
-                let key = format!("{}{}", path, remove_key_hash(&asset.hashname));
+                // chunk files are keyed by the file name before hashing,
+                // e.g. index.js, index.css, src_a_js-async.js
+                let name = if asset.chunk_id.is_empty() {
+                    remove_key_hash(&asset.hashname)
+                } else {
+                    asset.name.clone()
+                };
+                let key = format!("{}{}", path, name);
                 manifest.insert(key, asset.hashname.clone());
             }
 
@@ -39,7 +46,8 @@ impl Plugin for ManifestPlugin {
 
             let output_path = context.config.output.path.join(file_name);
 
-            fs::write(output_path, manifest_json).unwrap();
+            fs::write(&output_path, manifest_json).unwrap();
+            context.emitted_files.add(output_path);
         }
         Ok(())
     }
@@ -58,3 +66,41 @@ fn remove_key_hash(key: &str) -> String {
     let val = reg.replace_all(key, "").to_string();
     val
 }
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+    use std::fs;
+
+    use crate::utils::test_helper::setup_compiler;
+
+    #[test]
+    fn test_manifest() {
+        let compiler = setup_compiler("test/build/manifest", false);
+        compiler.compile().unwrap();
+        let output = &compiler.context.config.output.path;
+        let manifest: BTreeMap<String, String> =
+            serde_json::from_str(&fs::read_to_string(output.join("manifest.json")).unwrap())
+                .unwrap();
+        assert_eq!(
+            manifest.keys().collect::<Vec<_>>(),
+            vec![
+                "/static/a.css",
+                "/static/a.css.map",
+                "/static/a.js",
+                "/static/a.js.map",
+                "/static/b.js",
+                "/static/b.js.map",
+                "/static/src_c_js-async.css",
+                "/static/src_c_js-async.css.map",
+                "/static/src_c_js-async.js",
+                "/static/src_c_js-async.js.map",
+            ]
+        );
+        // values are the hashed files in the output directory
+        for (key, value) in manifest {
+            assert_ne!(key, format!("/static/{}", value));
+            assert!(output.join(value).exists());
+        }
+    }
+}
@@ -186,7 +186,7 @@ impl Plugin for MinifishPlugin {
                         .iter()
                         .map(|dep| Dependency {
                             module: dep.0.id.clone(),
-                            import_type: dep.1.resolve_type,
+                            import_type: dep.1.resolve_type.clone(),
                         })
                         .collect();
 
@@ -215,6 +215,7 @@ impl Plugin for MinifishPlugin {
 
             std::fs::write(meta_path, meta)
                 .map_err(|e| anyhow!("write meta file({}) error: {}", meta_path.display(), e))?;
+            context.emitted_files.add(meta_path);
         }
 
         Ok(())
@@ -1,13 +1,16 @@
 pub mod async_runtime;
 pub mod bundless_compiler;
+pub mod cjs_interop_warning;
 pub mod context_module;
 pub mod copy;
 pub mod detect_circular_dependence;
 pub mod emotion;
 pub mod graphviz;
 pub mod hmr_runtime;
+pub mod html;
 pub mod ignore;
 pub mod import;
+pub mod import_meta_glob;
 pub mod invalid_webpack_syntax;
 pub mod manifest;
 pub mod minifish;
@@ -72,4 +72,4 @@ impl Plugin for RequireContextPlugin {
     }
 }
 
-const VIRTUAL_REQUIRE_CONTEXT_MODULE: &str = "virtual:context";
+pub(crate) const VIRTUAL_REQUIRE_CONTEXT_MODULE: &str = "virtual:context";
@@ -9,7 +9,6 @@ use regex::RegexBuilder;
 
 use super::param::ContextLoadMode;
 use crate::compiler::Context;
-use crate::module::ModuleId;
 
 pub struct VirtualContextModuleRender {
     mode: ContextLoadMode,
@@ -20,36 +19,49 @@ pub struct VirtualContextModuleRender {
 }
 
 impl ContextLoadMode {
-    fn render_module_import(&self, map: &BTreeMap<String, String>) -> String {
+    fn render_module_import(&self, map: &BTreeMap<String, String>) -> Result<String> {
         let mut map_str = String::from(r#"var _map_lazy = {"#);
-        map.iter().for_each(|(key, value)| match self {
-            ContextLoadMode::Sync => {
-                map_str.push_str(&format!(
-                    r#"
+        for (key, value) in map.iter() {
+            match self {
+                ContextLoadMode::Sync => {
+                    map_str.push_str(&format!(
+                        r#"
   "{}": ()=> require("{}"),"#,
-                    key, value
-                ));
-            }
+                        key, value
+                    ));
+                }
 
-            ContextLoadMode::Lazy => {
-                map_str.push_str(&format!(
-                    r#"
+                ContextLoadMode::Lazy => {
+                    map_str.push_str(&format!(
+                        r#"
   "{}": ()=> import("{}"),"#,
-                    key, value
-                ));
-            }
-            ContextLoadMode::Eager | ContextLoadMode::Weak | ContextLoadMode::LazyOnce => {
-                unimplemented!("{} Mode not implemented", self);
+                        key, value
+                    ));
+                }
+                // modules are bundled into the current chunk, but still returns a promise
+                ContextLoadMode::Eager => {
+                    map_str.push_str(&format!(
+                        r#"
+  "{}": ()=> Promise.resolve(require("{}")),"#,
+                        key, value
+                    ));
+                }
+                ContextLoadMode::Weak | ContextLoadMode::LazyOnce => {
+                    return Err(anyhow!(
+                        "{} mode of require.context is not supported yet",
+                        self
+                    ));
+                }
             }
-        });
+        }
 
         map_str.push_str("\n};\n");
-        map_str
+        Ok(map_str)
     }
 
-    fn module_context(self) -> String {
+    fn module_context(self) -> Result<String> {
         match self {
-            ContextLoadMode::Sync => r#"
+            ContextLoadMode::Sync => Ok(r#"
   module.exports = function contextRequire(req){{
     var call  = _map_lazy[req];
     if(call){{
@@ -61,8 +73,8 @@ impl ContextLoadMode {
     }}
   }};
 "#
-            .to_string(),
-            ContextLoadMode::Lazy => r#"
+            .to_string()),
+            ContextLoadMode::Lazy | ContextLoadMode::Eager => Ok(r#"
   module.exports = function contextRequire(req){{
     var call  = _map_lazy[req];
     if(call){{
@@ -74,10 +86,11 @@ impl ContextLoadMode {
     }} 
   }};  
 "#
-            .to_string(),
-            ContextLoadMode::Eager | ContextLoadMode::Weak | ContextLoadMode::LazyOnce => {
-                unimplemented!("{} Mode not implemented", self)
-            }
+            .to_string()),
+            ContextLoadMode::Weak | ContextLoadMode::LazyOnce => Err(anyhow!(
+                "{} mode of require.context is not supported yet",
+                self
+            )),
         }
     }
 }
@@ -144,14 +157,14 @@ impl VirtualContextModuleRender {
         Ok(source_to_path)
     }
 
-    pub fn module_id_map(&self, map: &BTreeMap<String, String>, context: &Arc<Context>) -> String {
+    // the module ids are replaced when generating, since they're assigned after the build
+    pub fn module_id_map(&self, map: &BTreeMap<String, String>) -> String {
         let mut map_str = String::from(r#"var _map = {"#);
         for (key, value) in map.iter() {
             map_str.push_str(&format!(
                 r#"
-  "{}": "{}","#,
-                key,
-                ModuleId::from(value.as_str()).generate(context)
+  "{}": require.resolve("{}"),"#,
+                key, value
             ));
         }
         map_str.push_str("\n};\n");
@@ -159,11 +172,11 @@ impl VirtualContextModuleRender {
         map_str
     }
 
-    pub fn module_import(&self, map: &BTreeMap<String, String>) -> String {
+    pub fn module_import(&self, map: &BTreeMap<String, String>) -> Result<String> {
         self.mode.render_module_import(map)
     }
 
-    pub fn module_context(&self) -> String {
+    pub fn module_context(&self) -> Result<String> {
         self.mode.module_context()
     }
 
@@ -194,9 +207,9 @@ module.exports.keys = function() {{ return Object.keys(_map); }}
             
 module.exports.id = "{id}";            
 "#,
-            self.module_id_map(&source_to_path, &context),
-            self.module_import(&source_to_path),
-            self.module_context(),
+            self.module_id_map(&source_to_path),
+            self.module_import(&source_to_path)?,
+            self.module_context()?,
         ))
     }
 }
@@ -237,3 +250,33 @@ impl TryFrom<HashMap<String, String>> for VirtualContextModuleRender {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use super::*;
+
+    #[test]
+    fn test_eager_module_import() {
+        let map = BTreeMap::from([("./a.js".to_string(), "/project/a.js".to_string())]);
+        assert_eq!(
+            ContextLoadMode::Eager.render_module_import(&map).unwrap(),
+            r#"var _map_lazy = {
+  "./a.js": ()=> Promise.resolve(require("/project/a.js")),
+};
+"#
+        );
+        assert!(ContextLoadMode::Eager
+            .module_context()
+            .unwrap()
+            .contains("Promise.reject(e)"));
+    }
+
+    #[test]
+    fn test_unsupported_mode() {
+        let map = BTreeMap::from([("./a.js".to_string(), "/project/a.js".to_string())]);
+        assert!(ContextLoadMode::Weak.render_module_import(&map).is_err());
+        assert!(ContextLoadMode::LazyOnce.module_context().is_err());
+    }
+}
@@ -28,6 +28,24 @@ impl MakoRuntime {
         let public_path = context.config.public_path.clone();
         let public_path = if public_path == "runtime" {
             "(typeof globalThis !== 'undefined' ? globalThis : self).publicPath || '/'".to_string()
+        } else if public_path == "auto" {
+            // the dir of the current script, e.g. https://cdn.com/app/index.js => https://cdn.com/app/
+            r#"(function () {
+      var scriptUrl;
+      if (typeof document !== 'undefined') {
+        var currentScript = document.currentScript;
+        if (currentScript && currentScript.tagName.toUpperCase() === 'SCRIPT') scriptUrl = currentScript.src;
+        if (!scriptUrl) {
+          var scripts = document.getElementsByTagName('script');
+          if (scripts.length) scriptUrl = scripts[scripts.length - 1].src;
+        }
+      }
+      // worker
+      if (!scriptUrl && typeof self !== 'undefined' && self.location) scriptUrl = self.location.href;
+      if (!scriptUrl) return '/';
+      return scriptUrl.replace(/#.*$/, '').replace(/\?.*$/, '').replace(/\/[^\/]+$/, '/');
+    })()"#
+                .to_string()
         } else {
             format!("\"{}\"", public_path)
         };
@@ -177,3 +195,38 @@ function(module, exports, __mako_require__) {
         Ok(code.to_string())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::MakoRuntime;
+    use crate::compiler::Context;
+    use crate::config::Config;
+
+    #[test]
+    fn test_public_path() {
+        assert!(run("/cdn/").contains(r#"requireModule.publicPath= "/cdn/";"#));
+        assert!(run("runtime").contains(
+            "requireModule.publicPath= (typeof globalThis !== 'undefined' ? globalThis : self).publicPath || '/';"
+        ));
+    }
+
+    #[test]
+    fn test_public_path_auto() {
+        let code = run("auto");
+        assert!(code.contains("document.currentScript"));
+        assert!(code.contains("self.location.href"));
+    }
+
+    fn run(public_path: &str) -> String {
+        let context = Arc::new(Context {
+            config: Config {
+                public_path: public_path.to_string(),
+                ..Default::default()
+            },
+            ..Default::default()
+        });
+        MakoRuntime {}.public_path(&context)
+    }
+}
@@ -163,6 +163,7 @@ impl Plugin for SUPlus {
             options: Some(CodeSplittingStrategyOptions::Advanced(
                 CodeSplittingAdvancedOptions {
                     min_size: 0,
+                    max_size: None,
                     groups: vec![
                         OptimizeChunkGroup {
                             name: "node_modules".to_string(),
@@ -94,6 +94,7 @@ mod tests {
 
     use super::*;
     use crate::ast::tests::TestUtils;
+    use crate::utils::test_helper::setup_compiler;
 
     #[test]
     fn test_split_multi_declarator_decl() {
@@ -126,6 +127,23 @@ var b = 2;
         );
     }
 
+    #[test]
+    fn test_remove_unused_exports() {
+        // shares the fixture of the e2e test
+        let compiler = setup_compiler("../../e2e/fixtures/tree-shaking.unused-exports", false);
+        compiler.compile().unwrap();
+        let output = &compiler.context.config.output.path;
+        let js = std::fs::read_to_string(output.join("index.js")).unwrap();
+        std::fs::remove_dir_all(output).unwrap();
+        assert!(js.contains("a used"));
+        assert!(!js.contains("a unused"));
+        // declarations only used by unused exports are removed too
+        assert!(!js.contains("a helper"));
+        // exports used through re-export chains are kept
+        assert!(js.contains("b chained"));
+        assert!(!js.contains("b not chained"));
+    }
+
     fn split_top_decl(code: &str) -> String {
         let mut tu = TestUtils::gen_js_ast(code);
 
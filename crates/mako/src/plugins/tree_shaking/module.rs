@@ -3,6 +3,7 @@ use std::collections::{BTreeMap, HashSet};
 use swc_core::common::SyntaxContext;
 use swc_core::ecma::ast::{Module as SwcModule, ModuleItem};
 
+use crate::ast::comments::Comments;
 use crate::module::{Module, ModuleId};
 use crate::plugins::tree_shaking::statement_graph::{
     ExportInfo, ExportInfoMatch, ExportSource, ExportSpecifierInfo, ImportInfo, StatementGraph,
@@ -176,8 +177,8 @@ pub struct TreeShakeModule {
 }
 
 impl TreeShakeModule {
-    pub fn update_stmt_graph(&mut self, module: &SwcModule) {
-        let stmt_graph = StatementGraph::new(module, self.unresolved_ctxt);
+    pub fn update_stmt_graph(&mut self, module: &SwcModule, comments: &Comments) {
+        let stmt_graph = StatementGraph::new(module, self.unresolved_ctxt, comments);
 
         self.stmt_graph = stmt_graph;
     }
@@ -277,7 +278,7 @@ impl TreeShakeModule {
         self.used_exports.is_empty()
     }
 
-    pub fn new(module: &Module, order: usize) -> Self {
+    pub fn new(module: &Module, order: usize, comments: &Comments) -> Self {
         let module_info = module.info.as_ref().unwrap();
 
         let mut unresolved_ctxt = SyntaxContext::empty();
@@ -293,7 +294,7 @@ impl TreeShakeModule {
                 if is_esm {
                     module_system = ModuleSystem::ESModule;
                     unresolved_ctxt = unresolved_ctxt.apply_mark(module.unresolved_mark);
-                    StatementGraph::new(&module.ast, unresolved_ctxt)
+                    StatementGraph::new(&module.ast, unresolved_ctxt, comments)
                 } else {
                     StatementGraph::empty()
                 }
@@ -302,7 +303,7 @@ impl TreeShakeModule {
                 module_system = ModuleSystem::Custom;
                 StatementGraph::empty()
             }
-            crate::module::ModuleAst::None => {
+            crate::module::ModuleAst::Json(_) | crate::module::ModuleAst::None => {
                 module_system = ModuleSystem::Custom;
                 StatementGraph::empty()
             }
@@ -96,7 +96,10 @@ fn match_glob_pattern(pattern: &str, path: &str) -> bool {
 
 #[cfg(test)]
 mod tests {
+    use std::fs;
+
     use super::match_glob_pattern;
+    use crate::module::ModuleId;
     use crate::utils::test_helper::{get_module, setup_compiler};
 
     #[test]
@@ -145,4 +148,29 @@ mod tests {
         assert!(!four.info.unwrap().get_side_effects_flag());
         assert!(four_s.info.unwrap().get_side_effects_flag());
     }
+
+    #[test]
+    fn test_side_effects_prune() {
+        let compiler = setup_compiler("test/build/side-effects-prune", false);
+        compiler.compile().unwrap();
+        let has_module = |path: &str| {
+            let module_graph = compiler.context.module_graph.read().unwrap();
+            module_graph.has_module(&ModuleId::from(compiler.context.root.join(path)))
+        };
+        // unused modules of side effects free packages are removed
+        assert!(has_module("node_modules/pure/index.ts"));
+        assert!(!has_module("node_modules/pure/unused.ts"));
+        assert!(!has_module("node_modules/globs/no-effect.ts"));
+        // modules matched by sideEffects globs and css are kept
+        assert!(has_module("node_modules/globs/effect.ts"));
+        assert!(has_module("node_modules/pure/style.css"));
+
+        let output = &compiler.context.config.output.path;
+        let js = fs::read_to_string(output.join("index.js")).unwrap();
+        assert!(js.contains("globs effect"));
+        assert!(!js.contains("pure unused"));
+        assert!(!js.contains("globs no effect"));
+        let css = fs::read_to_string(output.join("index.css")).unwrap();
+        assert!(css.contains(".pure"));
+    }
 }
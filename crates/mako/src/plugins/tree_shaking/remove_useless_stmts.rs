@@ -39,6 +39,7 @@ pub fn remove_useless_stmts(
             module_item,
             Some(used_defined_idents.clone()),
             tree_shake_module.unresolved_ctxt,
+            None,
         );
 
         if let Some(import_info) = import_info {
@@ -60,6 +60,7 @@ pub fn optimize_modules(module_graph: &mut ModuleGraph, context: &Arc<Context>)
 
     let tree_shake_modules_map = {
         mako_profile_scope!("init_tree_shake_modules_map");
+        let comments = context.meta.script.origin_comments.read().unwrap();
         tree_shake_modules_ids
             .par_iter()
             .enumerate()
@@ -69,13 +70,21 @@ pub fn optimize_modules(module_graph: &mut ModuleGraph, context: &Arc<Context>)
                 let module = module_graph.get_module(module_id).unwrap();
 
                 let tree_shake_module = GLOBALS.set(&context.meta.script.globals, || {
-                    TreeShakeModule::new(module, index)
+                    TreeShakeModule::new(module, index, &comments)
                 });
 
                 (module_id.clone(), RefCell::new(tree_shake_module))
             })
             .collect::<HashMap<_, _>>()
     };
+    for tsm in tree_shake_modules_map.values() {
+        unmark_side_effects_free_bare_imports(
+            tsm.borrow_mut().deref_mut(),
+            module_graph,
+            &tree_shake_modules_map,
+        );
+    }
+
     let mut current_index = (tree_shake_modules_ids.len() - 1) as i64;
 
     // update tree-shake module side_effects flag in reversed topo-sort order
@@ -174,11 +183,14 @@ pub fn optimize_modules(module_graph: &mut ModuleGraph, context: &Arc<Context>)
         }
     }
 
+    // exports of the concatenated modules are defined with the runtime,
+    // es modules are linked without it
     if context
         .config
         .optimization
         .as_ref()
         .map_or(false, |o| o.concatenate_modules.unwrap_or(false))
+        && context.config.output.format.is_none()
     {
         optimize_module_graph(module_graph, &tree_shake_modules_map, context)?;
     }
@@ -186,6 +198,38 @@ pub fn optimize_modules(module_graph: &mut ModuleGraph, context: &Arc<Context>)
     Ok(())
 }
 
+// import "x" is not a side effect statement when "x" is described as side effects free
+// by the sideEffects field of its package.json, so that "x" can be removed if nothing is used
+fn unmark_side_effects_free_bare_imports(
+    tsm: &mut TreeShakeModule,
+    module_graph: &ModuleGraph,
+    tree_shake_modules_map: &TreeShakingModuleMap,
+) {
+    let module_id = tsm.module_id.clone();
+    let stmt_ids = tsm
+        .stmt_graph
+        .stmts()
+        .iter()
+        .filter_map(|stmt| {
+            let import_info = stmt.import_info.as_ref()?;
+            if !stmt.is_self_executed || !import_info.specifiers.is_empty() {
+                return None;
+            }
+            let dep_id =
+                module_graph.get_dependency_module_by_source(&module_id, &import_info.source)?;
+            if *dep_id == module_id {
+                return None;
+            }
+            let dep_tsm = tree_shake_modules_map.get(dep_id)?.borrow();
+            (dep_tsm.described_side_effects == Some(false)).then_some(stmt.id)
+        })
+        .collect::<Vec<_>>();
+
+    for stmt_id in stmt_ids {
+        tsm.stmt_graph.stmt_mut(&stmt_id).is_self_executed = false;
+    }
+}
+
 // Add all imported to used_exports
 // returns (added, imported_module_topo_order)
 fn add_used_exports_by_import_info(
@@ -575,7 +619,9 @@ fn shake_module(
     // add all dynamic imported dependencies as [UsedExports::All]
     for (dep, edge) in module_graph.get_dependencies(tree_shake_module_id) {
         match edge.resolve_type {
-            ResolveType::DynamicImport | ResolveType::Worker => {
+            ResolveType::DynamicImport
+            | ResolveType::DynamicImportGlob { .. }
+            | ResolveType::Worker => {
                 if let Some(ref_cell) = tree_shake_modules_map.get(dep) {
                     let mut tree_shake_module = ref_cell.borrow_mut();
                     if tree_shake_module.use_all_exports()
@@ -587,7 +633,7 @@ fn shake_module(
                     tree_shake_module.side_effects = true;
                 }
             }
-            ResolveType::Require => {
+            ResolveType::Require | ResolveType::RequireResolve | ResolveType::Glob => {
                 if let Some(ref_cell) = tree_shake_modules_map.get(dep) {
                     let mut tree_shake_module = ref_cell.borrow_mut();
 
@@ -62,10 +62,9 @@ pub fn optimize_module_graph(
             }
 
             let incoming_deps = module_graph.dependant_dependencies(module_id);
-            let dynamic_imported = incoming_deps.iter().any(|&deps| {
-                deps.iter()
-                    .any(|d| matches!(d.resolve_type, ResolveType::DynamicImport))
-            });
+            let dynamic_imported = incoming_deps
+                .iter()
+                .any(|&deps| deps.iter().any(|d| d.resolve_type.is_dynamic_esm()));
 
             if dynamic_imported {
                 can_be_inner = false;
@@ -143,9 +142,13 @@ pub fn optimize_module_graph(
                 ResolveType::ExportNamed(_) => true,
                 ResolveType::ExportAll => true,
                 ResolveType::Require => false,
+                ResolveType::RequireResolve => false,
+                ResolveType::Glob => false,
                 ResolveType::DynamicImport => false,
+                ResolveType::DynamicImportGlob { .. } => false,
                 ResolveType::Css => false,
                 ResolveType::Worker => false,
+                ResolveType::UrlAsset => false,
             };
 
             if candidate.contains(module_id) && esm_import {
@@ -263,7 +266,8 @@ pub fn optimize_module_graph(
             if let Ok(mut concatenate_context) = ConcatenateContext::init(config, module_graph) {
                 let mut module_items = concatenate_context.interop_module_items.clone();
 
-                for id in &config.sorted_modules(module_graph) {
+                // start with 1, 0 for swc helpers
+                for (order, id) in (1..).zip(&config.sorted_modules(module_graph)) {
                     if id.eq(&config.root) {
                         continue;
                     }
@@ -299,10 +303,16 @@ pub fn optimize_module_graph(
                                 source: require_src,
                                 resolve_as: None,
                                 resolve_type: ResolveType::Require,
-                                order: 0,
+                                order,
                                 span: None,
+                                chunk_hint: None,
+                                attributes: None,
+                                import_conditions: None,
                             },
                         );
+                        // externals are executed in the concatenated order, which keeps
+                        // e.g. the order of css imported by the inner modules in the chunk
+                        module_graph.set_dependencies_order(&config.root, id, order);
                         continue;
                     }
 
@@ -429,8 +439,20 @@ pub fn optimize_module_graph(
                 let ast_script = ast.script_mut().unwrap();
                 ast_script.ast = root_module_ast;
 
+                // keep input source maps of inner modules for the chunk source map
+                let mut concatenated_source_maps = HashMap::new();
                 for inner in config.inners.iter() {
-                    module_graph.remove_module(inner);
+                    let inner_module = module_graph.remove_module(inner);
+                    if let Some(info) = inner_module.info.as_ref() {
+                        concatenated_source_maps.extend(info.input_source_maps());
+                    }
+                }
+                if let Some(info) = module_graph
+                    .get_module_mut(&config.root)
+                    .and_then(|root_module| root_module.info.as_mut())
+                {
+                    info.concatenated_source_maps
+                        .extend(concatenated_source_maps);
                 }
             } else {
                 continue;
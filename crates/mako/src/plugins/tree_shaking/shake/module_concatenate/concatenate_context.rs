@@ -238,9 +238,13 @@ impl From<&ResolveType> for EsmDependantFlags {
             ResolveType::ExportNamed(named_export_type) => named_export_type.into(),
             ResolveType::ExportAll => EsmDependantFlags::ExportAll,
             ResolveType::Require => EsmDependantFlags::empty(),
+            ResolveType::RequireResolve => EsmDependantFlags::empty(),
+            ResolveType::Glob => EsmDependantFlags::empty(),
             ResolveType::DynamicImport => EsmDependantFlags::empty(),
+            ResolveType::DynamicImportGlob { .. } => EsmDependantFlags::empty(),
             ResolveType::Css => EsmDependantFlags::empty(),
             ResolveType::Worker => EsmDependantFlags::empty(),
+            ResolveType::UrlAsset => EsmDependantFlags::empty(),
         }
     }
 }
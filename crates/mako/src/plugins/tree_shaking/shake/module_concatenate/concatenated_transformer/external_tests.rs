@@ -277,9 +277,11 @@ fn run_test(code: &str, ccn_ctx: &mut ConcatenateContext) -> String {
             optimization: Some(OptimizationConfig {
                 concatenate_modules: Some(true),
                 skip_modules: Some(true),
+                runtime_chunk: None,
+                module_ids: None,
             }),
             mode: Mode::Production,
-            minify: true,
+            minify: Some(Default::default()),
             ..Default::default()
         },
         ..Default::default()
@@ -673,9 +673,11 @@ fn inner_trans_code(code: &str, concatenate_context: &mut ConcatenateContext) ->
             optimization: Some(OptimizationConfig {
                 concatenate_modules: Some(true),
                 skip_modules: Some(true),
+                runtime_chunk: None,
+                module_ids: None,
             }),
             mode: Mode::Production,
-            minify: false,
+            minify: None,
             ..Default::default()
         },
         ..Default::default()
@@ -66,6 +66,9 @@ impl ReExportReplace {
         Dependency {
             source: self.from_module_id.id.clone(),
             span: Some(span),
+            chunk_hint: None,
+            attributes: None,
+            import_conditions: None,
             order: 0,
             resolve_as: None,
             resolve_type: ResolveType::Import(import_type),
@@ -85,6 +88,9 @@ impl ReExportReplace {
             resolve_type,
             order: 0,
             span: Some(span),
+            chunk_hint: None,
+            attributes: None,
+            import_conditions: None,
         }
     }
 
@@ -165,7 +171,7 @@ pub(super) fn skip_module_optimize(
     module_graph: &mut ModuleGraph,
     tree_shake_modules_ids: &Vec<ModuleId>,
     tree_shake_modules_map: &HashMap<ModuleId, RefCell<TreeShakeModule>>,
-    _context: &Arc<Context>,
+    context: &Arc<Context>,
 ) -> Result<()> {
     mako_profile_function!();
 
@@ -510,7 +516,10 @@ pub(super) fn skip_module_optimize(
                 .ast
                 .as_script_ast();
 
-            tsm.update_stmt_graph(swc_module);
+            tsm.update_stmt_graph(
+                swc_module,
+                &context.meta.script.origin_comments.read().unwrap(),
+            );
         }
     }
 
@@ -603,3 +612,76 @@ fn find_ident_export_source(
 
     None
 }
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use crate::compiler::Compiler;
+    use crate::config::{Config, Mode, OptimizationConfig};
+    use crate::module::ModuleId;
+
+    #[test]
+    fn test_skip_barrel_modules() {
+        let root = std::env::current_dir()
+            .unwrap()
+            .join("test/build/tmp/skip-barrel");
+        if root.exists() {
+            fs::remove_dir_all(&root).unwrap();
+        }
+        fs::create_dir_all(root.join("src/barrel")).unwrap();
+        fs::create_dir_all(root.join("src/effect")).unwrap();
+        fs::write(
+            root.join("mako.config.json"),
+            r#"{"entry":{"index":"./src/index.js"}}"#,
+        )
+        .unwrap();
+        fs::write(
+            root.join("src/index.js"),
+            "import { m0 } from './barrel';\nimport { e0 } from './effect';\nconsole.log(m0, e0);",
+        )
+        .unwrap();
+        let mut barrel = String::new();
+        for i in 0..50 {
+            fs::write(
+                root.join(format!("src/barrel/m{}.js", i)),
+                format!("export const m{} = 'm{}';", i, i),
+            )
+            .unwrap();
+            barrel.push_str(&format!("export {{ m{} }} from './m{}';\n", i, i));
+        }
+        fs::write(root.join("src/barrel/index.js"), barrel).unwrap();
+        fs::write(root.join("src/effect/e0.js"), "export const e0 = 'e0';").unwrap();
+        fs::write(
+            root.join("src/effect/index.js"),
+            "console.log('effect');\nexport { e0 } from './e0';",
+        )
+        .unwrap();
+
+        let mut config = Config::new(&root, None, None).unwrap();
+        config.hmr = None;
+        config.minify = None;
+        config.mode = Mode::Production;
+        config.optimization = Some(OptimizationConfig {
+            skip_modules: Some(true),
+            concatenate_modules: Some(false),
+            runtime_chunk: None,
+            module_ids: None,
+        });
+        let compiler = Compiler::new(config, root.clone(), Default::default(), None).unwrap();
+        compiler.compile().unwrap();
+
+        let module_graph = compiler.context.module_graph.read().unwrap();
+        let has_module = |path: &str| module_graph.has_module(&ModuleId::from(root.join(path)));
+        // the import is redirected to the module which declares it
+        assert!(has_module("src/barrel/m0.js"));
+        assert!(!has_module("src/barrel/index.js"));
+        assert!((1..50).all(|i| !has_module(&format!("src/barrel/m{}.js", i))));
+        // barrels with side effects are kept
+        assert!(has_module("src/effect/index.js"));
+        let index =
+            fs::read_to_string(compiler.context.config.output.path.join("index.js")).unwrap();
+        assert!(index.contains("console.log('effect')"));
+        assert!(!index.contains("'m1'"));
+    }
+}
@@ -11,6 +11,7 @@ pub(crate) mod used_idents_collector;
 use analyze_imports_and_exports::analyze_imports_and_exports;
 use swc_core::common::{Span, SyntaxContext};
 
+use crate::ast::comments::Comments;
 use crate::plugins::tree_shaking::module::{is_ident_equal, UsedIdent};
 use crate::plugins::tree_shaking::shake::strip_context;
 use crate::plugins::tree_shaking::statement_graph::analyze_imports_and_exports::StatementInfo;
@@ -249,7 +250,12 @@ pub struct Statement {
 }
 
 impl Statement {
-    pub fn new(id: StatementId, stmt: &ModuleItem, unresolved_ctxt: SyntaxContext) -> Self {
+    pub fn new(
+        id: StatementId,
+        stmt: &ModuleItem,
+        unresolved_ctxt: SyntaxContext,
+        comments: &Comments,
+    ) -> Self {
         let StatementInfo {
             import_info,
             export_info,
@@ -259,7 +265,7 @@ impl Statement {
             is_self_executed,
             span,
             has_side_effects,
-        } = analyze_imports_and_exports(&id, stmt, None, unresolved_ctxt);
+        } = analyze_imports_and_exports(&id, stmt, None, unresolved_ctxt, Some(comments));
 
         Self {
             id,
@@ -285,12 +291,12 @@ pub struct StatementGraph {
 }
 
 impl StatementGraph {
-    pub fn new(module: &SwcModule, unresolved_ctxt: SyntaxContext) -> Self {
+    pub fn new(module: &SwcModule, unresolved_ctxt: SyntaxContext, comments: &Comments) -> Self {
         let mut g = petgraph::graph::Graph::new();
         let mut id_index_map = HashMap::new();
 
         for (index, stmt) in module.body.iter().enumerate() {
-            let statement = Statement::new(index, stmt, unresolved_ctxt);
+            let statement = Statement::new(index, stmt, unresolved_ctxt, comments);
 
             let node = g.add_node(statement);
             id_index_map.insert(index, node);
@@ -10,6 +10,7 @@ use swc_core::ecma::visit::VisitWith;
 use super::defined_idents_collector::DefinedIdentsCollector;
 use super::used_idents_collector::{self, UsedIdentsCollector};
 use super::{ExportInfo, ExportSpecifierInfo, ImportInfo, ImportSpecifierInfo, StatementId};
+use crate::ast::comments::Comments;
 
 #[derive(Debug)]
 pub struct StatementInfo {
@@ -28,6 +29,7 @@ pub fn analyze_imports_and_exports(
     stmt: &ModuleItem,
     used_defined_idents: Option<HashSet<String>>,
     unresolve_ctxt: SyntaxContext,
+    comments: Option<&Comments>,
 ) -> StatementInfo {
     let mut defined_idents = HashSet::new();
     let mut used_idents = HashSet::new();
@@ -415,7 +417,7 @@ pub fn analyze_imports_and_exports(
                                 .insert(defined_ident.clone(), local_used_idents.clone());
                         }
 
-                        if !is_pure_var_decl(var_decl, unresolve_ctxt) {
+                        if !is_pure_var_decl(var_decl, unresolve_ctxt, comments) {
                             is_self_executed = true;
                         }
                     }
@@ -427,7 +429,7 @@ pub fn analyze_imports_and_exports(
             swc_ecma_ast::Stmt::Expr(expr) => {
                 span = expr.span;
 
-                if !is_pure_expression(&expr.expr, unresolve_ctxt) {
+                if !is_pure_expression(&expr.expr, unresolve_ctxt, comments) {
                     is_self_executed = true;
                 }
                 analyze_and_insert_used_idents(expr, None);
@@ -447,19 +449,56 @@ pub fn analyze_imports_and_exports(
     }
 }
 
-fn is_pure_var_decl(var: &VarDecl, unresolved_ctxt: SyntaxContext) -> bool {
+fn is_pure_var_decl(
+    var: &VarDecl,
+    unresolved_ctxt: SyntaxContext,
+    comments: Option<&Comments>,
+) -> bool {
     var.decls.iter().all(|decl| {
         if let Some(ref init) = decl.init {
-            is_pure_expression(init, unresolved_ctxt)
+            is_pure_expression(init, unresolved_ctxt, comments)
         } else {
             true
         }
     })
 }
 
-fn is_pure_expression(expr: &Expr, unresolved_ctxt: SyntaxContext) -> bool {
-    !expr.may_have_side_effects(&ExprCtx {
-        unresolved_ctxt,
-        is_unresolved_ref_safe: false,
-    })
+fn is_pure_expression(
+    expr: &Expr,
+    unresolved_ctxt: SyntaxContext,
+    comments: Option<&Comments>,
+) -> bool {
+    is_pure_annotated(expr, unresolved_ctxt, comments)
+        || !expr.may_have_side_effects(&ExprCtx {
+            unresolved_ctxt,
+            is_unresolved_ref_safe: false,
+        })
+}
+
+/**
+ * Check for `/*#__PURE__*/ foo()` and `/*#__PURE__*/ new Foo()`,
+ * the arguments are still evaluated, so they must be pure too
+ */
+fn is_pure_annotated(
+    expr: &Expr,
+    unresolved_ctxt: SyntaxContext,
+    comments: Option<&Comments>,
+) -> bool {
+    let Some(comments) = comments else {
+        return false;
+    };
+
+    let (span, args) = match expr {
+        Expr::Call(call) => (call.span, Some(&call.args)),
+        Expr::New(new) => (new.span, new.args.as_ref()),
+        _ => return false,
+    };
+
+    comments.has_pure(span)
+        && args.map_or(true, |args| {
+            args.iter().all(|arg| {
+                arg.spread.is_none()
+                    && is_pure_expression(&arg.expr, unresolved_ctxt, Some(comments))
+            })
+        })
 }
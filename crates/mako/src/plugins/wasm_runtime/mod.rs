@@ -2,6 +2,7 @@ use std::sync::Arc;
 
 use anyhow;
 
+use crate::build::load::is_wasm;
 use crate::compiler::Context;
 use crate::plugin::Plugin;
 
@@ -13,13 +14,15 @@ impl Plugin for WasmRuntimePlugin {
     }
 
     fn runtime_plugins(&self, context: &Arc<Context>) -> anyhow::Result<Vec<String>> {
-        if context
-            .assets_info
-            .lock()
+        // inlined wasm files are not emitted, so check the modules instead of the assets
+        let has_wasm = context
+            .module_graph
+            .read()
             .unwrap()
-            .values()
-            .any(|info| info.ends_with(".wasm"))
-        {
+            .modules()
+            .iter()
+            .any(|module| module.info.as_ref().is_some_and(|info| is_wasm(&info.file)));
+        if has_wasm {
             Ok(vec![include_str!("wasm_runtime.js").to_string()])
         } else {
             Ok(vec![])
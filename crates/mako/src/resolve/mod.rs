@@ -12,7 +12,9 @@ use thiserror::Error;
 use tracing::debug;
 
 mod resource;
+mod tsconfig;
 pub(crate) use resource::{ExternalResource, ResolvedResource, ResolverResource};
+pub(crate) use tsconfig::parse_tsconfig_paths;
 
 use crate::ast::file::parse_path;
 use crate::compiler::Context;
@@ -22,6 +24,8 @@ use crate::config::{
 };
 use crate::features::rsc::Rsc;
 use crate::module::{Dependency, ResolveType};
+use crate::plugin::PluginResolvedId;
+use crate::plugins::import_meta_glob::resolve_glob;
 
 #[derive(Debug, Error)]
 #[error("Resolve {path:?} failed from {from:?}")]
@@ -49,26 +53,42 @@ pub fn resolve(
     crate::mako_profile_function!();
     crate::mako_profile_scope!("resolve", &dep.source);
 
+    let resolved_id = context
+        .plugin_driver
+        .resolve_id(&dep.source, path, context)?;
+    if let Some(PluginResolvedId::Virtual(id)) = &resolved_id {
+        return Ok(ResolverResource::Virtual(PathBuf::from(id)));
+    }
+
     if dep.source.starts_with("virtual:") {
         return Ok(ResolverResource::Virtual(PathBuf::from(&dep.source)));
     }
 
+    if dep.resolve_type == ResolveType::Glob {
+        return resolve_glob(path, &dep.source);
+    }
+
     let has_context_query = parse_path(&dep.source)?
         .2
         .iter()
         .any(|(k, _)| *k == "context");
     let resolver = if has_context_query {
         resolvers.get(&ResolverType::Ctxt)
-    } else if dep.resolve_type == ResolveType::Require {
+    } else if dep.resolve_type == ResolveType::Require
+        || dep.resolve_type == ResolveType::RequireResolve
+    {
         resolvers.get(&ResolverType::Cjs)
-    } else if dep.resolve_type == ResolveType::Css {
+    } else if dep.resolve_type == ResolveType::Css || dep.resolve_type == ResolveType::UrlAsset {
         resolvers.get(&ResolverType::Css)
     } else {
         resolvers.get(&ResolverType::Esm)
     }
     .unwrap();
 
-    let source = dep.resolve_as.as_ref().unwrap_or(&dep.source);
+    let source = match &resolved_id {
+        Some(PluginResolvedId::Path(path)) => path,
+        _ => dep.resolve_as.as_ref().unwrap_or(&dep.source),
+    };
 
     do_resolve(path, source, resolver, Some(&context.config.externals))
 }
@@ -197,11 +217,104 @@ fn get_external_target(
         } else {
             None
         }
+    } else {
+        get_regex_external_target(externals, source, global_obj)
+    }
+}
+
+// keys wrapped with `/` are regex, e.g. "/^@scope\\/(.*)$/"
+pub(crate) fn get_external_regex_key(key: &str) -> Option<&str> {
+    if key.len() > 2 && key.starts_with('/') && key.ends_with('/') {
+        Some(&key[1..key.len() - 1])
     } else {
         None
     }
 }
 
+// e.g.
+// { "/^@scope\\/(.*)$/": "Scope.$1" }, @scope/foo => globalThis.Scope.foo
+// { "/^node:/": "commonjs" }, node:fs => require("node:fs")
+fn get_regex_external_target(
+    externals: &HashMap<String, ExternalConfig>,
+    source: &str,
+    global_obj: &str,
+) -> Option<(String, Option<String>)> {
+    let (target, caps) = externals
+        .iter()
+        .filter_map(|(key, config)| match config {
+            ExternalConfig::Basic(target) => get_external_regex_key(key)
+                .and_then(|re| create_external_regex(re).captures(source))
+                .map(|caps| (key, target, caps)),
+            _ => None,
+        })
+        // make the result stable when multiple regex match
+        .min_by_key(|(key, _, _)| *key)
+        .map(|(_, target, caps)| (target, caps))?;
+
+    // replace $1, $2, ... with captured groups
+    let target = create_external_regex(r"\$(\d+)")
+        .replace_all(target, |target_caps: &Captures| {
+            let i = target_caps[1].parse::<usize>().unwrap();
+            caps.get(i)
+                .map_or("".to_string(), |m| m.as_str().to_string())
+        })
+        .to_string();
+    let target = if target.is_empty() {
+        "''".to_string()
+    } else if target == "commonjs" {
+        format!("require(\"{}\")", source)
+    } else if let Some(target) = target.strip_prefix("commonjs ") {
+        format!("require(\"{}\")", target)
+    } else {
+        get_external_target_from_global_obj(global_obj, &target)
+    };
+    Some((target, None))
+}
+
+// externals read from a global variable as (source, global), excluding the regex, commonjs
+// and script ones, which are loaded the same way for all targets
+pub(crate) fn get_global_externals(
+    externals: &HashMap<String, ExternalConfig>,
+) -> Vec<(String, String)> {
+    let mut global_externals = externals
+        .iter()
+        .filter(|(source, _)| get_external_regex_key(source).is_none())
+        .filter_map(|(source, external)| match external {
+            ExternalConfig::Basic(global)
+                if !global.is_empty() && !global.starts_with("commonjs ") =>
+            {
+                Some((source.clone(), global.clone()))
+            }
+            ExternalConfig::Advanced(config)
+                if !config.root.is_empty()
+                    && config.script.is_none()
+                    && config
+                        .module_type
+                        .as_ref()
+                        .map_or(true, |t| t != "commonjs") =>
+            {
+                Some((source.clone(), config.root.clone()))
+            }
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    global_externals.sort();
+    global_externals
+}
+
+pub(crate) fn is_global_external(
+    externals: &HashMap<String, ExternalConfig>,
+    source: &str,
+) -> bool {
+    get_global_externals(externals)
+        .iter()
+        .any(|(external, _)| external == source)
+}
+
+pub(crate) fn get_umd_external_root(global: &str) -> String {
+    get_external_target_from_global_obj("root", global)
+}
+
 fn get_external_target_from_global_obj(global_obj_name: &str, external: &str) -> String {
     let external = if external.contains('.') || (external.contains('[') && external.contains(']')) {
         /*
@@ -294,22 +407,44 @@ pub fn get_resolvers(config: &Config) -> Resolvers {
     resolvers
 }
 
-pub fn get_module_extensions() -> Vec<String> {
-    vec![
-        ".js".to_string(),
-        ".jsx".to_string(),
-        ".ts".to_string(),
-        ".tsx".to_string(),
-        ".mjs".to_string(),
-        ".cjs".to_string(),
-        ".json".to_string(),
-    ]
+const DEFAULT_MODULE_EXTENSIONS: [&str; 7] =
+    [".js", ".jsx", ".ts", ".tsx", ".mjs", ".cjs", ".json"];
+
+// the leading dot is optional, e.g. "ts" is the same as ".ts",
+// the default extensions which are not configured are tried after the configured ones
+pub fn get_module_extensions(config: &Config) -> Vec<String> {
+    let mut extensions = config
+        .resolve
+        .extensions
+        .iter()
+        .map(|ext| {
+            if ext.starts_with('.') {
+                ext.clone()
+            } else {
+                format!(".{}", ext)
+            }
+        })
+        .collect::<Vec<_>>();
+    for ext in DEFAULT_MODULE_EXTENSIONS {
+        if !extensions.iter().any(|e| e == ext) {
+            extensions.push(ext.to_string());
+        }
+    }
+    extensions
+}
+
+fn get_main_fields(config: &Config, default: &[&str]) -> Vec<String> {
+    config
+        .resolve
+        .main_fields
+        .clone()
+        .unwrap_or_else(|| default.iter().map(|field| field.to_string()).collect())
 }
 
 fn get_resolver(config: &Config, resolver_type: ResolverType) -> Resolver {
-    let alias = parse_alias(config.resolve.alias.clone());
+    let alias = get_alias(config);
     let is_browser = config.platform == Platform::Browser;
-    let extensions = get_module_extensions();
+    let extensions = get_module_extensions(config);
     let options = match (resolver_type, is_browser) {
         (ResolverType::Cjs, true) => ResolveOptions {
             alias,
@@ -323,11 +458,7 @@ fn get_resolver(config: &Config, resolver_type: ResolverType) -> Resolver {
                     "browser".to_string(),
                 ],
             ),
-            main_fields: vec![
-                "browser".to_string(),
-                "module".to_string(),
-                "main".to_string(),
-            ],
+            main_fields: get_main_fields(config, &["browser", "module", "main"]),
             alias_fields: vec![vec!["browser".to_string()]],
             ..Default::default()
         },
@@ -343,11 +474,7 @@ fn get_resolver(config: &Config, resolver_type: ResolverType) -> Resolver {
                     "browser".to_string(),
                 ],
             ),
-            main_fields: vec![
-                "browser".to_string(),
-                "module".to_string(),
-                "main".to_string(),
-            ],
+            main_fields: get_main_fields(config, &["browser", "module", "main"]),
             alias_fields: vec![vec!["browser".to_string()]],
             ..Default::default()
         },
@@ -360,9 +487,10 @@ fn get_resolver(config: &Config, resolver_type: ResolverType) -> Resolver {
                     "import".to_string(),
                     "module".to_string(),
                     "webpack".to_string(),
+                    "node".to_string(),
                 ],
             ),
-            main_fields: vec!["module".to_string(), "main".to_string()],
+            main_fields: get_main_fields(config, &["module", "main"]),
             ..Default::default()
         },
         (ResolverType::Cjs, false) => ResolveOptions {
@@ -374,14 +502,20 @@ fn get_resolver(config: &Config, resolver_type: ResolverType) -> Resolver {
                     "require".to_string(),
                     "module".to_string(),
                     "webpack".to_string(),
+                    "node".to_string(),
                 ],
             ),
-            main_fields: vec!["module".to_string(), "main".to_string()],
+            main_fields: get_main_fields(config, &["module", "main"]),
             ..Default::default()
         },
         // css must be browser
         (ResolverType::Css, _) => ResolveOptions {
-            extensions: vec![".css".to_string(), ".less".to_string(), ".scss".to_string()],
+            extensions: vec![
+                ".css".to_string(),
+                ".less".to_string(),
+                ".scss".to_string(),
+                ".sass".to_string(),
+            ],
             alias,
             main_fields: vec!["css".to_string(), "style".to_string(), "main".to_string()],
             condition_names: vec!["style".to_string()],
@@ -399,12 +533,42 @@ fn get_resolver(config: &Config, resolver_type: ResolverType) -> Resolver {
     Resolver::new(options)
 }
 
-fn parse_alias(alias: Vec<(String, String)>) -> Alias {
-    let mut result = vec![];
-    for (from, to) in alias {
-        let alias_vec = vec![AliasValue::Path(to)];
-        result.push((from, alias_vec));
-    }
+// explicit aliases win over tsconfig paths since the first matched alias is used
+fn get_alias(config: &Config) -> Alias {
+    let mut alias = parse_alias(
+        config
+            .resolve
+            .alias
+            .iter()
+            .map(|(from, to)| (from.clone(), vec![to.clone()]))
+            .collect(),
+    );
+    alias.extend(parse_alias(config.resolve.tsconfig_paths.clone()));
+    alias
+}
+
+// e.g.
+// ["react$", "preact/compat"] only matches `react`
+// ["@/*", "./src/*"] is the same as ["@", "./src"], which matches `@` and `@/xxx`
+// and longer aliases win over shorter ones, e.g. `@/components` over `@`
+fn parse_alias(alias: Vec<(String, Vec<String>)>) -> Alias {
+    let mut result = alias
+        .into_iter()
+        .map(|(from, to)| {
+            let wildcard_to = to
+                .iter()
+                .map(|to| to.strip_suffix("/*").map(|to| to.to_string()))
+                .collect::<Option<Vec<_>>>();
+            let (from, to) = match (from.strip_suffix("/*"), wildcard_to) {
+                (Some(from), Some(to)) => (from.to_string(), to),
+                _ => (from, to),
+            };
+            // multiple values are tried in order
+            (from, to.into_iter().map(AliasValue::Path).collect())
+        })
+        .collect::<Vec<_>>();
+    // stable sort, aliases with the same length keep the config order
+    result.sort_by_key(|(from, _)| std::cmp::Reverse(from.trim_end_matches('$').len()));
     result
 }
 
@@ -420,7 +584,7 @@ mod tests {
 
     use crate::config::{
         Config, ExternalAdvanced, ExternalAdvancedSubpath, ExternalAdvancedSubpathConverter,
-        ExternalAdvancedSubpathRule, ExternalAdvancedSubpathTarget, ExternalConfig,
+        ExternalAdvancedSubpathRule, ExternalAdvancedSubpathTarget, ExternalConfig, Platform,
     };
     use crate::resolve::ResolverType;
 
@@ -430,6 +594,20 @@ mod tests {
         assert_eq!(x, "source.ts".to_string());
     }
 
+    #[test]
+    fn test_resolve_query() {
+        let x = resolve(
+            "test/resolve/normal",
+            None,
+            None,
+            "index.ts",
+            "./source?raw",
+        );
+        assert_eq!(x, "source.ts?raw".to_string());
+        let x = resolve("test/resolve/normal", None, None, "index.ts", "foo?url");
+        assert_eq!(x, "node_modules/foo/index.js?url".to_string());
+    }
+
     #[test]
     fn test_resolve_dep() {
         let x = resolve("test/resolve/normal", None, None, "index.ts", "foo");
@@ -459,6 +637,45 @@ mod tests {
         assert_eq!(x, "node_modules/foo/esm-browser.js".to_string());
     }
 
+    #[test]
+    fn test_resolve_browser_field_string() {
+        let x = browser_field_resolve(Platform::Browser, "index.ts", "str");
+        assert_eq!(x, ("node_modules/str/browser.js".to_string(), false));
+        let x = browser_field_resolve(Platform::Node, "index.ts", "str");
+        assert_eq!(x, ("node_modules/str/main.js".to_string(), false));
+    }
+
+    #[test]
+    fn test_resolve_browser_field_object() {
+        let x = browser_field_resolve(Platform::Browser, "node_modules/obj/main.js", "./lib/node");
+        assert_eq!(x, ("node_modules/obj/lib/browser.js".to_string(), false));
+        let x = browser_field_resolve(Platform::Browser, "node_modules/obj/main.js", "crypto");
+        assert_eq!(x, ("node_modules/obj/shims/crypto.js".to_string(), false));
+        let x = browser_field_resolve(Platform::Node, "node_modules/obj/main.js", "./lib/node");
+        assert_eq!(x, ("node_modules/obj/lib/node.js".to_string(), false));
+    }
+
+    #[test]
+    fn test_resolve_browser_field_false() {
+        let x = browser_field_resolve(Platform::Browser, "node_modules/obj/main.js", "fs");
+        assert!(x.1, "fs should be ignored");
+        let x = browser_field_resolve(Platform::Browser, "node_modules/obj/main.js", "./server");
+        assert!(x.1, "./server should be ignored");
+        let x = browser_field_resolve(Platform::Node, "node_modules/obj/main.js", "./server");
+        assert_eq!(x, ("node_modules/obj/server.js".to_string(), false));
+    }
+
+    fn browser_field_resolve(platform: Platform, path: &str, source: &str) -> (String, bool) {
+        platform_resolve(
+            "test/resolve/browser_fields",
+            path,
+            platform,
+            ResolverType::Cjs,
+            source,
+        )
+        .unwrap()
+    }
+
     #[test]
     fn test_resolve_alias() {
         let alias = vec![("bar".to_string(), "foo".to_string())];
@@ -480,6 +697,117 @@ mod tests {
         assert_eq!(x, "node_modules/foo/foo.js".to_string());
     }
 
+    #[test]
+    fn test_resolve_alias_exact() {
+        let alias = vec![("bar$".to_string(), "foo/foo".to_string())];
+        let x = resolve(
+            "test/resolve/normal",
+            Some(alias.clone()),
+            None,
+            "index.ts",
+            "bar",
+        );
+        assert_eq!(x, "node_modules/foo/foo.js".to_string());
+        // `bar$` should not match `bar/foo`, which is resolved from node_modules/bar
+        let x = resolve(
+            "test/resolve/normal",
+            Some(alias),
+            None,
+            "index.ts",
+            "bar/foo",
+        );
+        assert_eq!(x, "node_modules/bar/foo.js".to_string());
+    }
+
+    #[test]
+    fn test_resolve_alias_wildcard() {
+        let src = std::env::current_dir()
+            .unwrap()
+            .join("test/resolve/normal/src");
+        let alias = vec![
+            ("@/*".to_string(), format!("{}/*", src.to_string_lossy())),
+            (
+                "@/components".to_string(),
+                src.join("components/v2").to_string_lossy().to_string(),
+            ),
+        ];
+        let x = resolve(
+            "test/resolve/normal",
+            Some(alias.clone()),
+            None,
+            "index.ts",
+            "@/utils",
+        );
+        assert_eq!(x, "src/utils.ts".to_string());
+        // more specific alias wins
+        let x = resolve(
+            "test/resolve/normal",
+            Some(alias),
+            None,
+            "index.ts",
+            "@/components/button",
+        );
+        assert_eq!(x, "src/components/v2/button.ts".to_string());
+    }
+
+    #[test]
+    fn test_resolve_tsconfig_paths() {
+        let x = tsconfig_resolve("normal", None, "@app/button");
+        assert_eq!(x, "src/app/button.ts");
+        let x = tsconfig_resolve("normal", None, "config");
+        assert_eq!(x, "src/config.ts");
+    }
+
+    #[test]
+    fn test_resolve_tsconfig_paths_fallback() {
+        let x = tsconfig_resolve("normal", None, "@shared/a");
+        assert_eq!(x, "src/shared/a.ts");
+        let x = tsconfig_resolve("normal", None, "@shared/b");
+        assert_eq!(x, "src/fallback/b.ts");
+    }
+
+    #[test]
+    fn test_resolve_tsconfig_paths_extends() {
+        let x = tsconfig_resolve("extends", None, "@app/button");
+        assert_eq!(x, "src/app/button.ts");
+    }
+
+    #[test]
+    fn test_resolve_tsconfig_paths_with_alias() {
+        let fixture = std::env::current_dir()
+            .unwrap()
+            .join("test/resolve/tsconfig/normal");
+        let alias = vec![(
+            "@app".to_string(),
+            fixture.join("src/other").to_string_lossy().to_string(),
+        )];
+        let x = tsconfig_resolve("normal", Some(alias), "@app/button");
+        assert_eq!(x, "src/other/button.ts");
+    }
+
+    fn tsconfig_resolve(base: &str, alias: Option<Vec<(String, String)>>, source: &str) -> String {
+        let fixture = std::env::current_dir()
+            .unwrap()
+            .join("test/resolve/tsconfig")
+            .join(base);
+        let mut config: Config = Default::default();
+        if let Some(alias) = alias {
+            config.resolve.alias = alias;
+        }
+        config.resolve.tsconfig_paths = super::parse_tsconfig_paths(&fixture).unwrap();
+        let resolver = super::get_resolver(&config, ResolverType::Esm);
+        let resource = super::do_resolve(
+            &fixture.join("index.ts").to_string_lossy(),
+            source,
+            &resolver,
+            None,
+        )
+        .unwrap();
+        resource
+            .get_resolved_path()
+            .replace(&format!("{}/", fixture.to_string_lossy()), "")
+    }
+
     #[test]
     fn test_resolve_externals() {
         let externals = HashMap::from([
@@ -581,6 +909,60 @@ mod tests {
         assert_eq!(x, ("empty".to_string(), Some("''".to_string()), None));
     }
 
+    #[test]
+    fn test_resolve_regex_externals() {
+        let externals = HashMap::from([
+            (
+                r"/^@scope\/(.*)$/".to_string(),
+                ExternalConfig::Basic("Scope.$1".to_string()),
+            ),
+            (
+                "/^node:/".to_string(),
+                ExternalConfig::Basic("commonjs".to_string()),
+            ),
+            (
+                "/^lodash\\/(.*)$/".to_string(),
+                ExternalConfig::Basic("commonjs lodash-es/$1".to_string()),
+            ),
+        ]);
+        let x = external_resolve(
+            "test/resolve/normal",
+            None,
+            Some(&externals),
+            "index.ts",
+            "@scope/foo",
+        );
+        assert_eq!(
+            x.1.unwrap(),
+            "(typeof globalThis !== 'undefined' ? globalThis : self).Scope.foo"
+        );
+        let x = external_resolve(
+            "test/resolve/normal",
+            None,
+            Some(&externals),
+            "index.ts",
+            "node:fs",
+        );
+        assert_eq!(x.1.unwrap(), "require(\"node:fs\")");
+        let x = external_resolve(
+            "test/resolve/normal",
+            None,
+            Some(&externals),
+            "index.ts",
+            "lodash/get",
+        );
+        assert_eq!(x.1.unwrap(), "require(\"lodash-es/get\")");
+        // not matched
+        let x = external_resolve(
+            "test/resolve/normal",
+            None,
+            Some(&externals),
+            "index.ts",
+            "foo",
+        );
+        assert_eq!(x.1, None);
+    }
+
     #[test]
     fn test_resolve_advanced_externals() {
         let externals = HashMap::from([
@@ -738,6 +1120,218 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_get_global_externals() {
+        let externals = HashMap::from([
+            (
+                "react".to_string(),
+                ExternalConfig::Basic("React".to_string()),
+            ),
+            ("empty".to_string(), ExternalConfig::Basic("".to_string())),
+            (
+                "fs".to_string(),
+                ExternalConfig::Basic("commonjs fs".to_string()),
+            ),
+            (
+                "/^@scope\\//".to_string(),
+                ExternalConfig::Basic("Scope".to_string()),
+            ),
+            (
+                "antd".to_string(),
+                ExternalConfig::Advanced(ExternalAdvanced {
+                    root: "antd".to_string(),
+                    script: None,
+                    module_type: None,
+                    subpath: None,
+                }),
+            ),
+            (
+                "script".to_string(),
+                ExternalConfig::Advanced(ExternalAdvanced {
+                    root: "ScriptType".to_string(),
+                    script: Some("https://example.com/lib/script.js".to_string()),
+                    subpath: None,
+                    module_type: None,
+                }),
+            ),
+        ]);
+        assert_eq!(
+            super::get_global_externals(&externals),
+            vec![
+                ("antd".to_string(), "antd".to_string()),
+                ("react".to_string(), "React".to_string()),
+            ]
+        );
+        assert!(super::is_global_external(&externals, "react"));
+        assert!(!super::is_global_external(&externals, "fs"));
+        assert!(!super::is_global_external(&externals, "script"));
+        assert_eq!(
+            super::get_umd_external_root("window.React"),
+            "root.window.React"
+        );
+    }
+
+    #[test]
+    fn test_resolve_exports_conditions() {
+        let x = exports_resolve(Platform::Browser, ResolverType::Esm, "pkg");
+        assert_eq!(x.unwrap(), "node_modules/pkg/browser.mjs");
+        let x = exports_resolve(Platform::Browser, ResolverType::Cjs, "pkg");
+        assert_eq!(x.unwrap(), "node_modules/pkg/browser.js");
+        let x = exports_resolve(Platform::Node, ResolverType::Esm, "pkg");
+        assert_eq!(x.unwrap(), "node_modules/pkg/node.mjs");
+        let x = exports_resolve(Platform::Node, ResolverType::Cjs, "pkg");
+        assert_eq!(x.unwrap(), "node_modules/pkg/node.cjs");
+    }
+
+    #[test]
+    fn test_resolve_exports_subpath_pattern() {
+        let x = exports_resolve(Platform::Browser, ResolverType::Esm, "pkg/utils/add");
+        assert_eq!(x.unwrap(), "node_modules/pkg/dist/utils/add.js");
+        let x = exports_resolve(Platform::Browser, ResolverType::Esm, "pkg/package.json");
+        assert_eq!(x.unwrap(), "node_modules/pkg/package.json");
+    }
+
+    #[test]
+    fn test_resolve_exports_not_exported() {
+        // main.js exists but is not exported
+        let x = exports_resolve(Platform::Browser, ResolverType::Esm, "pkg/main.js");
+        assert!(x.is_err());
+    }
+
+    #[test]
+    fn test_resolve_without_exports() {
+        let x = exports_resolve(Platform::Browser, ResolverType::Esm, "legacy");
+        assert_eq!(x.unwrap(), "node_modules/legacy/module.js");
+        let x = exports_resolve(Platform::Node, ResolverType::Cjs, "legacy");
+        assert_eq!(x.unwrap(), "node_modules/legacy/module.js");
+    }
+
+    #[test]
+    fn test_resolve_extensions_order() {
+        let source = "./src/foo";
+        let x = config_resolve("test/resolve/extensions", Default::default(), source);
+        assert_eq!(x.unwrap(), "src/foo.js");
+        let mut config: Config = Default::default();
+        config.resolve.extensions = vec![".ts".to_string(), "js".to_string()];
+        let x = config_resolve("test/resolve/extensions", config, source);
+        assert_eq!(x.unwrap(), "src/foo.ts");
+    }
+
+    #[test]
+    fn test_resolve_extensions_keep_defaults() {
+        let mut config: Config = Default::default();
+        config.resolve.extensions = ["js", "jsx", "ts", "tsx"]
+            .iter()
+            .map(|ext| ext.to_string())
+            .collect();
+        assert_eq!(
+            super::get_module_extensions(&config),
+            vec![".js", ".jsx", ".ts", ".tsx", ".mjs", ".cjs", ".json"]
+        );
+        let x = config_resolve("test/resolve/extensions", config, "./src/data");
+        assert_eq!(x.unwrap(), "src/data.json");
+    }
+
+    #[test]
+    fn test_resolve_main_fields() {
+        let mut config: Config = Default::default();
+        config.resolve.main_fields = Some(vec!["main".to_string()]);
+        let x = config_resolve("test/resolve/exports", config, "legacy");
+        assert_eq!(x.unwrap(), "node_modules/legacy/main.js");
+        let mut config: Config = Default::default();
+        config.resolve.main_fields = Some(vec!["module".to_string(), "main".to_string()]);
+        let x = config_resolve("test/resolve/exports", config, "legacy");
+        assert_eq!(x.unwrap(), "node_modules/legacy/module.js");
+    }
+
+    #[test]
+    fn test_resolve_node_builtins() {
+        let fixture = std::env::current_dir()
+            .unwrap()
+            .join("test/resolve/node-builtins");
+        let shims =
+            r#"{"resolve":{"nodeBuiltins":{"buffer":"buffer-shim","node:path":"path-shim"}}}"#;
+        let config = Config::new(&fixture, None, Some(shims)).unwrap();
+        let x = config_resolve("test/resolve/node-builtins", config, "buffer");
+        assert_eq!(x.unwrap(), "node_modules/buffer-shim/index.js");
+        let config = Config::new(&fixture, None, Some(shims)).unwrap();
+        let x = config_resolve("test/resolve/node-builtins", config, "node:path");
+        assert_eq!(x.unwrap(), "node_modules/path-shim/index.js");
+    }
+
+    #[test]
+    fn test_resolve_unshimmed_node_builtins() {
+        let fixture = std::env::current_dir()
+            .unwrap()
+            .join("test/resolve/node-builtins");
+        let config = Config::new(&fixture, None, None).unwrap();
+        assert!(config_resolve("test/resolve/node-builtins", config, "fs").is_err());
+        let config = Config::new(
+            &fixture,
+            None,
+            Some(r#"{"resolve":{"nodeBuiltins":{"fs":""}}}"#),
+        )
+        .unwrap();
+        assert!(config.externals.contains_key("fs"));
+        assert!(config.externals.contains_key("node:fs"));
+    }
+
+    fn config_resolve(base: &str, config: Config, source: &str) -> anyhow::Result<String> {
+        let fixture = std::env::current_dir().unwrap().join(base);
+        let resolver = super::get_resolver(&config, ResolverType::Esm);
+        let resource = super::do_resolve(
+            &fixture.join("index.ts").to_string_lossy(),
+            source,
+            &resolver,
+            None,
+        )?;
+        Ok(resource
+            .get_resolved_path()
+            .replace(&format!("{}/", fixture.to_string_lossy()), ""))
+    }
+
+    fn exports_resolve(
+        platform: Platform,
+        resolve_type: ResolverType,
+        source: &str,
+    ) -> anyhow::Result<String> {
+        platform_resolve(
+            "test/resolve/exports",
+            "index.ts",
+            platform,
+            resolve_type,
+            source,
+        )
+        .map(|(path, _)| path)
+    }
+
+    // returns the resolved path and whether it is ignored
+    fn platform_resolve(
+        base: &str,
+        path: &str,
+        platform: Platform,
+        resolve_type: ResolverType,
+        source: &str,
+    ) -> anyhow::Result<(String, bool)> {
+        let fixture = std::env::current_dir().unwrap().join(base);
+        let config = Config {
+            platform,
+            ..Default::default()
+        };
+        let resolver = super::get_resolver(&config, resolve_type);
+        let resource = super::do_resolve(
+            &fixture.join(path).to_string_lossy(),
+            source,
+            &resolver,
+            None,
+        )?;
+        let is_ignored = matches!(resource, super::ResolverResource::Ignored(_));
+        let path = resource
+            .get_resolved_path()
+            .replace(&format!("{}/", fixture.to_string_lossy()), "");
+        Ok((path, is_ignored))
+    }
+
     fn resolve(
         base: &str,
         alias: Option<Vec<(String, String)>>,
@@ -0,0 +1,224 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, Result};
+use path_clean::PathClean;
+use serde::Deserialize;
+use tracing::debug;
+
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+struct TsConfigJson {
+    extends: Option<Extends>,
+    #[serde(default)]
+    compiler_options: CompilerOptions,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Extends {
+    Single(String),
+    Multiple(Vec<String>),
+}
+
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+struct CompilerOptions {
+    base_url: Option<String>,
+    paths: Option<BTreeMap<String, Vec<String>>>,
+}
+
+#[derive(Default)]
+struct TsConfig {
+    base_url: Option<PathBuf>,
+    // paths with the dir they are relative to
+    paths: Option<(PathBuf, BTreeMap<String, Vec<String>>)>,
+}
+
+// read compilerOptions.paths of root/tsconfig.json (with extends) as aliases
+// e.g.
+// { "baseUrl": ".", "paths": { "@app/*": ["./src/app/*"], "config": ["./src/config.ts"] } }
+// => [("@app", ["/root/src/app"]), ("config$", ["/root/src/config.ts"])]
+pub fn parse_tsconfig_paths(root: &Path) -> Result<Vec<(String, Vec<String>)>> {
+    let tsconfig_path = root.join("tsconfig.json");
+    if !tsconfig_path.exists() {
+        return Ok(vec![]);
+    }
+    let tsconfig = load_tsconfig(&tsconfig_path, &mut vec![])?;
+    let Some((paths_base, paths)) = tsconfig.paths else {
+        return Ok(vec![]);
+    };
+    // paths are relative to baseUrl, or the tsconfig which defines them
+    let base = tsconfig.base_url.unwrap_or(paths_base);
+    let alias = paths
+        .into_iter()
+        .filter_map(|(from, to)| {
+            let (from, to) = if let Some(prefix) = from.strip_suffix("/*") {
+                let to = to
+                    .iter()
+                    .map(|to| to.strip_suffix("/*").map(|to| to.to_string()))
+                    .collect::<Option<Vec<_>>>();
+                (prefix.to_string(), to)
+            } else if !from.contains('*') && to.iter().all(|to| !to.contains('*')) {
+                (format!("{}$", from), Some(to))
+            } else {
+                (from, None)
+            };
+            let Some(to) = to else {
+                debug!("unsupported tsconfig paths pattern: {}", from);
+                return None;
+            };
+            let to = to
+                .into_iter()
+                .map(|to| base.join(to).clean().to_string_lossy().to_string())
+                .collect();
+            Some((from, to))
+        })
+        .collect();
+    Ok(alias)
+}
+
+fn load_tsconfig(path: &Path, visited: &mut Vec<PathBuf>) -> Result<TsConfig> {
+    if visited.contains(&path.to_path_buf()) {
+        return Err(anyhow!("circular extends in {}", path.display()));
+    }
+    visited.push(path.to_path_buf());
+    let content =
+        fs::read_to_string(path).map_err(|e| anyhow!("read {} failed: {}", path.display(), e))?;
+    let json: TsConfigJson =
+        json5::from_str(&content).map_err(|e| anyhow!("parse {} failed: {}", path.display(), e))?;
+    let dir = path.parent().unwrap();
+
+    // fields of the extended configs are overridden by the current one
+    let mut tsconfig = TsConfig::default();
+    let extends = match json.extends {
+        Some(Extends::Single(extends)) => vec![extends],
+        Some(Extends::Multiple(extends)) => extends,
+        None => vec![],
+    };
+    for extends in extends {
+        let extends_path = resolve_extends(dir, &extends)?;
+        let parent = load_tsconfig(&extends_path, visited)?;
+        // only the ancestors are tracked, a config can be extended by several ones
+        visited.pop();
+        if parent.base_url.is_some() {
+            tsconfig.base_url = parent.base_url;
+        }
+        if parent.paths.is_some() {
+            tsconfig.paths = parent.paths;
+        }
+    }
+    let base_url = json
+        .compiler_options
+        .base_url
+        .map(|base_url| dir.join(base_url));
+    if let Some(paths) = json.compiler_options.paths {
+        tsconfig.paths = Some((base_url.clone().unwrap_or(dir.to_path_buf()), paths));
+    }
+    if base_url.is_some() {
+        tsconfig.base_url = base_url;
+    }
+    Ok(tsconfig)
+}
+
+// e.g.
+// ./tsconfig.base => ./tsconfig.base.json
+// @tsconfig/node16/tsconfig.json => node_modules/@tsconfig/node16/tsconfig.json
+// some-config => node_modules/some-config/tsconfig.json
+fn resolve_extends(dir: &Path, extends: &str) -> Result<PathBuf> {
+    let candidates = if extends.starts_with('.') || Path::new(extends).is_absolute() {
+        let path = dir.join(extends);
+        vec![path.clone(), with_json_extension(&path)]
+    } else {
+        dir.ancestors()
+            .flat_map(|dir| {
+                let path = dir.join("node_modules").join(extends);
+                [
+                    path.clone(),
+                    with_json_extension(&path),
+                    path.join("tsconfig.json"),
+                ]
+            })
+            .collect()
+    };
+    candidates
+        .into_iter()
+        .find(|path| path.is_file())
+        .ok_or_else(|| {
+            anyhow!(
+                "tsconfig extends {} not found in {}",
+                extends,
+                dir.display()
+            )
+        })
+}
+
+// e.g. tsconfig.app => tsconfig.app.json, the extension is appended instead of replaced
+fn with_json_extension(path: &Path) -> PathBuf {
+    let mut path = path.as_os_str().to_os_string();
+    path.push(".json");
+    PathBuf::from(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse_tsconfig_paths;
+
+    #[test]
+    fn test_paths() {
+        let (root, alias) = parse("normal");
+        assert_eq!(
+            alias,
+            vec![
+                ("@app".to_string(), vec![format!("{}/src/app", root)]),
+                (
+                    "@shared".to_string(),
+                    vec![
+                        format!("{}/src/shared", root),
+                        format!("{}/src/fallback", root)
+                    ]
+                ),
+                (
+                    "config$".to_string(),
+                    vec![format!("{}/src/config.ts", root)]
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_extends() {
+        let (root, alias) = parse("extends");
+        // paths are relative to the baseUrl of the tsconfig which defines them
+        assert_eq!(
+            alias,
+            vec![("@app".to_string(), vec![format!("{}/src/app", root)])]
+        );
+    }
+
+    #[test]
+    fn test_extends_diamond() {
+        // tsconfig.app and configs/lib both extend configs/shared
+        let (root, alias) = parse("diamond");
+        assert_eq!(
+            alias,
+            vec![("@shared".to_string(), vec![format!("{}/src/shared", root)])]
+        );
+    }
+
+    #[test]
+    fn test_no_tsconfig() {
+        let root = std::env::current_dir().unwrap().join("test/resolve/normal");
+        assert!(parse_tsconfig_paths(&root).unwrap().is_empty());
+    }
+
+    fn parse(base: &str) -> (String, Vec<(String, Vec<String>)>) {
+        let root = std::env::current_dir()
+            .unwrap()
+            .join("test/resolve/tsconfig")
+            .join(base);
+        let alias = parse_tsconfig_paths(&root).unwrap();
+        (root.to_string_lossy().to_string(), alias)
+    }
+}
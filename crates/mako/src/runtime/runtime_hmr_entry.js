@@ -78,6 +78,14 @@ self.$RefreshSig$ = () => (type) => type;
 
   socket.addEventListener('message', (rawMessage) => {
     const msg = JSON.parse(rawMessage.data);
+    // only css is changed, js modules don't need to be re-executed,
+    // the skipped hash will be caught up by the next js update
+    if (msg.type === 'css-update' && !hadRuntimeError) {
+      module.hot.reloadStylesheets().catch((e) => {
+        console.error('[HMR] CSS update failed', e);
+      });
+      return;
+    }
     latestHash = msg.hash;
 
     if (!updating) {
@@ -1,19 +1,30 @@
 use std::cmp::Ordering;
 use std::collections::HashMap;
 use std::fs;
-use std::path::Path;
+use std::io::Write;
+use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex};
 
 use anyhow::Result;
 use colored::*;
+use flate2::write::GzEncoder;
+use flate2::Compression;
 use indexmap::IndexMap;
 use pathdiff::diff_paths;
+use rayon::prelude::*;
 use serde::Serialize;
 use swc_core::common::source_map::Pos;
+use swc_core::css::codegen::writer::basic::{BasicCssWriter, BasicCssWriterConfig};
+use swc_core::css::codegen::{CodeGenerator, CodegenConfig, Emit};
 
 use crate::compiler::{Compiler, Context};
+use crate::config::Mode;
 use crate::features::rsc::{RscClientInfo, RscCssModules};
-use crate::generate::chunk::ChunkType;
+use crate::generate::chunk::{Chunk, ChunkType};
+use crate::generate::chunk_pot::util::render_module_js;
+use crate::generate::generate_chunks::RUNTIME_CHUNK_ID;
+use crate::module::{Module, ModuleAst};
+use crate::module_graph::ModuleGraph;
 
 impl Compiler {
     pub fn create_stats_info(&self) -> StatsJsonMap {
@@ -183,11 +194,17 @@ impl Compiler {
             .iter()
             .filter_map(|chunk| match &chunk.chunk_type {
                 ChunkType::Entry(_, name, _) => {
-                    let mut chunks = chunk_graph
-                        .entry_dependencies_chunk(&chunk.id)
-                        .into_iter()
-                        .map(|id| id.id)
-                        .collect::<Vec<_>>();
+                    let mut chunks = vec![];
+                    // the runtime chunk should be loaded first
+                    if context.config.runtime_chunk().is_some() {
+                        chunks.push(RUNTIME_CHUNK_ID.to_string());
+                    }
+                    chunks.extend(
+                        chunk_graph
+                            .entry_dependencies_chunk(&chunk.id)
+                            .into_iter()
+                            .map(|id| id.id),
+                    );
 
                     chunks.push(chunk.id.id.clone());
 
@@ -208,6 +225,12 @@ impl Compiler {
         stats_map.rsc_client_components = stats_info.get_rsc_client_components();
         stats_map.rsc_css_modules = stats_info.get_rsc_css_modules();
 
+        if context.config.stats.is_some() {
+            stats_map.module_graph = Some(module_graph.to_json());
+            stats_map.chunk_graph = Some(chunk_graph.to_json());
+            stats_map.sizes = Some(Stats::new(&chunks, &module_graph, &context));
+        }
+
         stats_map
     }
 
@@ -515,6 +538,13 @@ pub struct StatsJsonMap {
     rsc_client_components: Vec<RscClientInfo>,
     #[serde(rename = "rscCSSModules")]
     rsc_css_modules: Vec<RscCssModules>,
+    // only generated when stats is enabled, see ModuleGraphJson and ChunkGraphJson
+    #[serde(skip_serializing_if = "Option::is_none")]
+    module_graph: Option<serde_json::Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    chunk_graph: Option<serde_json::Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    sizes: Option<Stats>,
     pub start_time: i64,
     pub end_time: i64,
 }
@@ -533,16 +563,107 @@ impl StatsJsonMap {
             entrypoints: HashMap::new(),
             rsc_client_components: vec![],
             rsc_css_modules: vec![],
+            module_graph: None,
+            chunk_graph: None,
+            sizes: None,
             start_time: 0,
             end_time: 0,
         }
     }
 }
 
-pub fn write_stats(path: &Path, stats: &StatsJsonMap) {
+// sizes of the generated code of modules and chunks, the chunk size is the sum of its
+// modules, the runtime and module wrappers are not included
+#[derive(Serialize, Debug, Clone)]
+pub struct Stats {
+    pub modules: Vec<StatsModuleSize>,
+    pub chunks: Vec<StatsChunkSize>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct StatsModuleSize {
+    pub id: String,
+    pub chunk: String,
+    // minified when minify is enabled in production mode
+    pub size: u64,
+}
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct StatsChunkSize {
+    pub id: String,
+    pub size: u64,
+    pub gzip_size: u64,
+}
+
+impl Stats {
+    fn new(chunks: &[&Chunk], module_graph: &ModuleGraph, context: &Arc<Context>) -> Self {
+        let mut modules = vec![];
+        let mut chunk_sizes = vec![];
+        for chunk in chunks {
+            let codes = chunk
+                .get_modules()
+                .iter()
+                .collect::<Vec<_>>()
+                .par_iter()
+                .filter_map(|id| {
+                    let module = module_graph.get_module(id)?;
+                    let code = module_code(module, context)?;
+                    Some((id.id.clone(), code))
+                })
+                .collect::<Vec<_>>();
+            let mut encoder = GzEncoder::new(vec![], Compression::default());
+            let mut size = 0;
+            for (id, code) in codes {
+                encoder.write_all(&code).unwrap();
+                size += code.len() as u64;
+                modules.push(StatsModuleSize {
+                    id,
+                    chunk: chunk.id.id.clone(),
+                    size: code.len() as u64,
+                });
+            }
+            chunk_sizes.push(StatsChunkSize {
+                id: chunk.id.id.clone(),
+                size,
+                gzip_size: encoder.finish().unwrap().len() as u64,
+            });
+        }
+        Self {
+            modules,
+            chunks: chunk_sizes,
+        }
+    }
+}
+
+fn module_code(module: &Module, context: &Arc<Context>) -> Option<Vec<u8>> {
+    match &module.info.as_ref()?.ast {
+        ModuleAst::Script(ast) => render_module_js(&ast.ast, context)
+            .ok()
+            .map(|(code, _)| code),
+        ModuleAst::Css(ast) => {
+            let mut code = String::new();
+            let writer = BasicCssWriter::new(&mut code, None, BasicCssWriterConfig::default());
+            let mut gen = CodeGenerator::new(
+                writer,
+                CodegenConfig {
+                    minify: context.config.minify.is_some()
+                        && matches!(context.config.mode, Mode::Production),
+                },
+            );
+            gen.emit(&ast.ast).ok()?;
+            Some(code.into_bytes())
+        }
+        ModuleAst::Json(value) => serde_json::to_vec(value).ok(),
+        ModuleAst::None => None,
+    }
+}
+
+pub fn write_stats(path: &Path, stats: &StatsJsonMap) -> PathBuf {
     let path = path.join("stats.json");
     let stats_json = serde_json::to_string_pretty(stats).unwrap();
-    fs::write(path, stats_json).unwrap();
+    fs::write(&path, stats_json).unwrap();
+    path
 }
 
 // 文件大小转换
@@ -577,3 +698,54 @@ fn file_size(path: &str) -> Result<u64> {
     let metadata = fs::metadata(path)?;
     Ok(metadata.len())
 }
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use crate::utils::test_helper::setup_compiler;
+
+    #[test]
+    fn test_sizes() {
+        let compiler = setup_compiler("test/build/stats-sizes", false);
+        compiler.compile().unwrap();
+        let content =
+            fs::read_to_string(compiler.context.config.output.path.join("stats.json")).unwrap();
+        let stats: serde_json::Value = serde_json::from_str(&content).unwrap();
+        let sizes = &stats["sizes"];
+        assert_eq!(keys(sizes), vec!["chunks", "modules"]);
+
+        let modules = sizes["modules"].as_array().unwrap();
+        for module in modules {
+            assert_eq!(keys(module), vec!["chunk", "id", "size"]);
+            assert!(module["id"].is_string() && module["chunk"].is_string());
+            assert!(module["size"].as_u64().unwrap() > 0);
+        }
+
+        let chunks = sizes["chunks"].as_array().unwrap();
+        // the entry chunk and the async chunk
+        assert_eq!(chunks.len(), 2);
+        for chunk in chunks {
+            assert_eq!(keys(chunk), vec!["gzipSize", "id", "size"]);
+            let id = chunk["id"].as_str().unwrap();
+            let modules_size: u64 = modules
+                .iter()
+                .filter(|module| module["chunk"] == id)
+                .map(|module| module["size"].as_u64().unwrap())
+                .sum();
+            assert_eq!(chunk["size"].as_u64().unwrap(), modules_size, "{}", id);
+            assert!(chunk["gzipSize"].as_u64().unwrap() > 0, "{}", id);
+        }
+    }
+
+    fn keys(value: &serde_json::Value) -> Vec<&str> {
+        let mut keys = value
+            .as_object()
+            .unwrap()
+            .keys()
+            .map(|key| key.as_str())
+            .collect::<Vec<_>>();
+        keys.sort();
+        keys
+    }
+}
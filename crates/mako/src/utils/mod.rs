@@ -1,6 +1,7 @@
 pub mod logger;
 #[cfg(feature = "profile")]
 pub mod profile_gui;
+pub mod profiler;
 #[cfg(test)]
 pub(crate) mod test_helper;
 pub(crate) mod thread_pool;
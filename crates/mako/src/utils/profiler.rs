@@ -0,0 +1,237 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use anyhow::Result;
+use colored::Colorize;
+use serde::Serialize;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Phase {
+    Resolve,
+    Parse,
+    Transform,
+    TreeShake,
+    Chunk,
+    Generate,
+    Write,
+}
+
+impl Phase {
+    pub const ALL: [Phase; 7] = [
+        Phase::Resolve,
+        Phase::Parse,
+        Phase::Transform,
+        Phase::TreeShake,
+        Phase::Chunk,
+        Phase::Generate,
+        Phase::Write,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Phase::Resolve => "resolve",
+            Phase::Parse => "parse",
+            Phase::Transform => "transform",
+            Phase::TreeShake => "tree-shake",
+            Phase::Chunk => "chunk",
+            Phase::Generate => "generate",
+            Phase::Write => "write",
+        }
+    }
+}
+
+// Chrome trace event, see https://docs.google.com/document/d/1CvAClvFfyA5R-PhYUmn5OOQtYMH4h6I0nSsKchNAySU
+#[derive(Serialize, Debug, Clone)]
+struct TraceEvent {
+    name: &'static str,
+    ph: &'static str,
+    // in microseconds
+    ts: u64,
+    dur: u64,
+    pid: u32,
+    tid: usize,
+}
+
+// phase timers of a build, nothing is recorded unless enabled
+// build phases run on the thread pool, so their durations are the sum of all tasks
+pub struct Profiler {
+    enabled: bool,
+    start: Instant,
+    durations: [AtomicU64; Phase::ALL.len()],
+    events: Mutex<Vec<TraceEvent>>,
+    // tid => thread name
+    threads: Mutex<BTreeMap<usize, String>>,
+}
+
+impl Profiler {
+    pub fn new(enabled: bool) -> Self {
+        Self {
+            enabled,
+            start: Instant::now(),
+            durations: Default::default(),
+            events: Mutex::new(vec![]),
+            threads: Mutex::new(BTreeMap::new()),
+        }
+    }
+
+    pub fn enabled(&self) -> bool {
+        self.enabled
+    }
+
+    pub fn record<T, F>(&self, phase: Phase, func: F) -> T
+    where
+        F: FnOnce() -> T,
+    {
+        if !self.enabled {
+            return func();
+        }
+        let start = Instant::now();
+        let result = func();
+        let dur = start.elapsed();
+        self.durations[phase as usize].fetch_add(dur.as_nanos() as u64, Ordering::Relaxed);
+
+        // tasks of the thread pool are put in the track of their thread,
+        // and the track 0 is for the other threads, e.g. main
+        let tid = rayon::current_thread_index().map_or(0, |i| i + 1);
+        self.threads
+            .lock()
+            .unwrap()
+            .entry(tid)
+            .or_insert_with(|| std::thread::current().name().unwrap_or("main").to_string());
+        self.events.lock().unwrap().push(TraceEvent {
+            name: phase.name(),
+            ph: "X",
+            ts: start.duration_since(self.start).as_micros() as u64,
+            dur: dur.as_micros() as u64,
+            pid: 1,
+            tid,
+        });
+        result
+    }
+
+    pub fn summary(&self) -> Vec<(Phase, Duration)> {
+        Phase::ALL
+            .iter()
+            .map(|phase| {
+                let nanos = self.durations[*phase as usize].load(Ordering::Relaxed);
+                (*phase, Duration::from_nanos(nanos))
+            })
+            .collect()
+    }
+
+    pub fn print_summary(&self) {
+        println!("{}", "Build phases:".bold());
+        for (phase, dur) in self.summary() {
+            println!("  {:<12}{}ms", phase.name(), dur.as_millis());
+        }
+    }
+
+    pub fn write_trace(&self, path: &Path) -> Result<()> {
+        let mut events = self
+            .threads
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(tid, name)| {
+                serde_json::json!({
+                    "name": "thread_name",
+                    "ph": "M",
+                    "pid": 1,
+                    "tid": tid,
+                    "args": { "name": name },
+                })
+            })
+            .collect::<Vec<_>>();
+        events.extend(
+            self.events
+                .lock()
+                .unwrap()
+                .iter()
+                .map(|event| serde_json::to_value(event).unwrap()),
+        );
+        let trace = serde_json::json!({ "traceEvents": events });
+        fs::write(path, serde_json::to_string(&trace)?)?;
+        Ok(())
+    }
+}
+
+impl Default for Profiler {
+    fn default() -> Self {
+        Self::new(false)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::{Phase, Profiler};
+    use crate::utils::test_helper::setup_compiler;
+
+    #[test]
+    fn test_profile() {
+        let compiler = setup_compiler("test/build/profile", false);
+        let profiler = &compiler.context.profiler;
+        assert!(profiler.enabled());
+        compiler.compile().unwrap();
+
+        let summary = profiler.summary();
+        let phases = summary
+            .iter()
+            .map(|(phase, _)| phase.name())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            phases,
+            vec![
+                "resolve",
+                "parse",
+                "transform",
+                "tree-shake",
+                "chunk",
+                "generate",
+                "write"
+            ]
+        );
+        // every phase runs in the build
+        assert!(
+            summary.iter().all(|(_, dur)| !dur.is_zero()),
+            "{:?}",
+            summary
+        );
+
+        let path = compiler.context.config.output.path.join("trace.json");
+        profiler.write_trace(&path).unwrap();
+        let trace: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(path).unwrap()).unwrap();
+        let events = trace["traceEvents"].as_array().unwrap();
+        let tracks = events
+            .iter()
+            .filter(|event| event["ph"] == "M")
+            .map(|event| event["tid"].clone())
+            .collect::<Vec<_>>();
+        let tasks = events
+            .iter()
+            .filter(|event| event["ph"] == "X")
+            .collect::<Vec<_>>();
+        assert!(tasks.iter().all(|event| tracks.contains(&event["tid"])
+            && event["ts"].is_u64()
+            && event["dur"].is_u64()));
+        // modules are built in the thread pool
+        assert!(tasks
+            .iter()
+            .filter(|event| event["name"] == "parse")
+            .all(|event| event["tid"].as_u64().unwrap() > 0));
+    }
+
+    #[test]
+    fn test_disabled() {
+        let profiler = Profiler::new(false);
+        assert_eq!(profiler.record(Phase::Parse, || 1), 1);
+        assert!(profiler.summary().iter().all(|(_, dur)| dur.is_zero()));
+        assert!(profiler.events.lock().unwrap().is_empty());
+    }
+}
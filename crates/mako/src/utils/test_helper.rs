@@ -3,7 +3,7 @@ use std::fs;
 use tracing_subscriber::{fmt, EnvFilter};
 
 use crate::compiler::{self, Compiler};
-use crate::config::{Config, Mode};
+use crate::config::Config;
 use crate::module::{Module, ModuleId};
 
 #[macro_export]
@@ -60,10 +60,10 @@ pub fn setup_compiler(base: &str, cleanup: bool) -> Compiler {
         }
         fs::create_dir_all(&root).unwrap();
     }
-    let mut config = Config::new(&root, None, None).unwrap();
+    // the defaults of production mode, e.g. devtool, are used
+    let mut config = Config::new(&root, None, Some(r#"{"mode":"production"}"#)).unwrap();
     config.hmr = None;
-    config.minify = false;
-    config.mode = Mode::Production;
+    config.minify = None;
     config.optimization = None;
 
     compiler::Compiler::new(config, root, Default::default(), None).unwrap()
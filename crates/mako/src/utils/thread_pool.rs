@@ -1,29 +1,87 @@
+use std::any::Any;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::sync::mpsc::Sender;
 use std::sync::OnceLock;
 
+use anyhow::{anyhow, Result};
 use rayon::{Scope, ThreadPool, ThreadPoolBuilder};
 
 static THREAD_POOL: OnceLock<ThreadPool> = OnceLock::new();
 
-fn build_rayon_thread_pool() -> ThreadPool {
+fn build_rayon_thread_pool(threads: Option<usize>) -> ThreadPool {
+    // threads from config > MAKO_THREADS > rayon's default (number of cpus)
+    let threads = threads
+        .or_else(|| {
+            std::env::var("MAKO_THREADS")
+                .ok()
+                .and_then(|threads| threads.parse().ok())
+        })
+        .unwrap_or(0);
     ThreadPoolBuilder::new()
+        .num_threads(threads)
         .thread_name(|i| format!("rayon thread {}", i))
         .build()
         .expect("failed to create rayon thread pool.")
 }
 
+fn get_thread_pool() -> &'static ThreadPool {
+    THREAD_POOL.get_or_init(|| build_rayon_thread_pool(None))
+}
+
+// should be called before any spawn/scope/join, otherwise it takes no effect
+// returns false if the thread pool is already initialized
+pub fn init_thread_pool(threads: Option<usize>) -> bool {
+    init(&THREAD_POOL, threads)
+}
+
+fn init(pool: &OnceLock<ThreadPool>, threads: Option<usize>) -> bool {
+    let mut initialized = false;
+    pool.get_or_init(|| {
+        initialized = true;
+        build_rayon_thread_pool(threads)
+    });
+    initialized
+}
+
 pub fn spawn<F>(func: F)
 where
     F: FnOnce() + Send + 'static,
 {
-    THREAD_POOL.get_or_init(build_rayon_thread_pool).spawn(func)
+    get_thread_pool().spawn(func)
 }
 
+// like spawn, but a panic in func is caught and sent to rs as an error,
+// so the receiver side fails fast instead of waiting forever
+// (rayon aborts the process when a spawned task panics)
+pub fn spawn_catch<F, T>(rs: Sender<Result<T>>, func: F)
+where
+    F: FnOnce(&Sender<Result<T>>) + Send + 'static,
+    T: Send + 'static,
+{
+    spawn(move || {
+        if let Err(e) = catch_unwind(AssertUnwindSafe(|| func(&rs))) {
+            let _ = rs.send(Err(anyhow!("task panicked: {}", panic_message(&*e))));
+        }
+    })
+}
+
+fn panic_message(e: &(dyn Any + Send)) -> String {
+    if let Some(s) = e.downcast_ref::<&str>() {
+        s.to_string()
+    } else if let Some(s) = e.downcast_ref::<String>() {
+        s.clone()
+    } else {
+        "unknown panic".to_string()
+    }
+}
+
+// panics in the scope are re-raised after all tasks are joined
 pub fn scope<'scope, OP, R>(op: OP) -> R
 where
     OP: FnOnce(&Scope<'scope>) -> R + Send,
     R: Send,
 {
-    THREAD_POOL.get_or_init(build_rayon_thread_pool).scope(op)
+    get_thread_pool().scope(op)
 }
 
 pub fn join<A, B, RA, RB>(op_a: A, op_b: B) -> (RA, RB)
@@ -33,7 +91,69 @@ where
     RA: Send,
     RB: Send,
 {
-    THREAD_POOL
-        .get_or_init(build_rayon_thread_pool)
-        .join(op_a, op_b)
+    get_thread_pool().join(op_a, op_b)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::mpsc::channel;
+
+    use super::*;
+
+    #[test]
+    fn test_build_with_threads() {
+        assert_eq!(build_rayon_thread_pool(Some(3)).current_num_threads(), 3);
+    }
+
+    #[test]
+    fn test_init_thread_pool() {
+        // the global pool is initialized before MAKO_THREADS is set, so other tests are not affected
+        let threads = get_thread_pool().current_num_threads();
+        assert!(!init_thread_pool(Some(threads + 1)));
+        assert_eq!(get_thread_pool().current_num_threads(), threads);
+
+        let pool = OnceLock::new();
+        assert!(init(&pool, Some(3)));
+        assert!(!init(&pool, Some(5)));
+        assert_eq!(pool.get().unwrap().current_num_threads(), 3);
+
+        std::env::set_var("MAKO_THREADS", "2");
+        let from_env = OnceLock::new();
+        init(&from_env, None);
+        let from_config = OnceLock::new();
+        init(&from_config, Some(3));
+        std::env::remove_var("MAKO_THREADS");
+        assert_eq!(from_env.get().unwrap().current_num_threads(), 2);
+        // threads from config take precedence over MAKO_THREADS
+        assert_eq!(from_config.get().unwrap().current_num_threads(), 3);
+    }
+
+    #[test]
+    fn test_spawn_catch() {
+        let (rs, rr) = channel::<Result<usize>>();
+        spawn_catch(rs.clone(), |rs| {
+            rs.send(Ok(1)).unwrap();
+        });
+        spawn_catch(rs, |_| {
+            panic!("oops");
+        });
+        let mut results = rr.iter().collect::<Vec<_>>();
+        results.sort_by_key(|r| r.is_err());
+        assert_eq!(results.len(), 2);
+        assert_eq!(*results[0].as_ref().unwrap(), 1);
+        assert_eq!(
+            results[1].as_ref().unwrap_err().to_string(),
+            "task panicked: oops"
+        );
+    }
+
+    #[test]
+    fn test_scope_panic() {
+        let result = catch_unwind(|| {
+            scope(|s| {
+                s.spawn(|_| panic!("oops"));
+            })
+        });
+        assert!(result.is_err());
+    }
 }
@@ -8,6 +8,7 @@ static TOKIO_RUNTIME: OnceLock<tokio::runtime::Runtime> = OnceLock::new();
 fn build_tokio_runtime() -> tokio::runtime::Runtime {
     tokio::runtime::Builder::new_multi_thread()
         .enable_io()
+        .enable_time()
         .worker_threads(2)
         .thread_name("tokio-worker")
         .build()
@@ -2,6 +2,7 @@ use std::sync::Arc;
 
 use swc_core::css::ast::{Url, UrlValue};
 use swc_core::css::visit::VisitMut;
+use tracing::warn;
 
 use crate::ast::file::File;
 use crate::ast::utils::{is_remote_or_data_or_hash, remove_first_tilde};
@@ -19,14 +20,23 @@ impl VisitMut for CSSAssets {
     // e.g.
     // .foo { background: url(foo.png) }
     fn visit_mut_url(&mut self, n: &mut Url) {
-        if n.value.is_none() {
+        let url = match &n.value {
+            Some(box UrlValue::Str(s)) => s.value.to_string(),
+            Some(box UrlValue::Raw(s)) => s.value.to_string(),
+            None => "".to_string(),
+        };
+        // e.g.
+        // .foo { background: url() }
+        if url.is_empty() {
+            let loc = self.context.meta.css.cm.lookup_char_pos(n.span.lo);
+            warn!(
+                "Empty url() found in {}:{}:{}, skipped",
+                self.path,
+                loc.line,
+                loc.col_display + 1
+            );
             return;
         }
-        let value = n.value.as_ref().unwrap();
-        let url = match value {
-            box UrlValue::Str(s) => s.value.to_string(),
-            box UrlValue::Raw(s) => s.value.to_string(),
-        };
 
         if is_remote_or_data_or_hash(&url) {
             return;
@@ -38,6 +48,9 @@ impl VisitMut for CSSAssets {
             resolve_type: ResolveType::Css,
             order: 0,
             span: None,
+            chunk_hint: None,
+            attributes: None,
+            import_conditions: None,
         };
         let resolved = resolve::resolve(&self.path, &dep, &self.context.resolvers, &self.context);
         if let Ok(resource) = resolved {
@@ -48,7 +61,18 @@ impl VisitMut for CSSAssets {
                 true,
                 self.context.clone(),
             );
-            let asset_content = asset_content.unwrap_or(resolved_path);
+            let asset_content = asset_content.map_or(resolved_path, |asset_content| {
+                // urls relative to the css file work for "/", "runtime" and "auto",
+                // others like cdn are prefixed since css may not be served with assets
+                let public_path = &self.context.config.public_path;
+                if asset_content.starts_with("data:")
+                    || ["/", "runtime", "auto"].contains(&public_path.as_str())
+                {
+                    asset_content
+                } else {
+                    format!("{}{}", public_path, asset_content)
+                }
+            });
             match n.value {
                 Some(box UrlValue::Str(ref mut s)) => {
                     s.value = asset_content.into();
@@ -66,9 +90,13 @@ impl VisitMut for CSSAssets {
 
 #[cfg(test)]
 mod tests {
+    use std::sync::Arc;
+
     use swc_core::css::visit::VisitMutWith;
 
-    use crate::ast::tests::TestUtils;
+    use crate::ast::tests::{TestUtils, TestUtilsOpts};
+    use crate::compiler::Context;
+    use crate::config::Mode;
 
     #[test]
     fn test_base64() {
@@ -100,6 +128,10 @@ mod tests {
             run(r#".foo { background: url(data://a.png) }"#),
             ".foo{background:url(data://a.png)}"
         );
+        assert_eq!(
+            run(r#".foo { background: url("data:image/png;base64,iVBORw0KGgo=") }"#),
+            r#".foo{background:url("data:image/png;base64,iVBORw0KGgo=")}"#
+        );
     }
 
     #[test]
@@ -110,13 +142,57 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_empty() {
+        assert_eq!(
+            run(r#".foo { background: url() }"#),
+            ".foo{background:url()}"
+        );
+        assert_eq!(
+            run(r#".foo { background: url("") }"#),
+            r#".foo{background:url("")}"#
+        );
+    }
+
     #[test]
     fn test_big_image() {
         assert!(run(r#".foo { background: url(big.jpg) }"#).contains(".foo{background:url(big."));
     }
 
+    #[test]
+    fn test_public_path() {
+        assert!(run_with_public_path(
+            r#".foo { background: url(big.jpg) }"#,
+            "https://cdn.example.com/"
+        )
+        .contains(".foo{background:url(https://cdn.example.com/big."));
+        assert!(
+            run_with_public_path(r#".foo { background: url(big.jpg) }"#, "auto")
+                .contains(".foo{background:url(big.")
+        );
+        assert!(run_with_public_path(
+            r#".foo { background: url(umi.png) }"#,
+            "https://cdn.example.com/"
+        )
+        .contains(".foo{background:url(data:image/png;base64,"));
+    }
+
     fn run(css_code: &str) -> String {
-        let mut test_utils = TestUtils::gen_css_ast(css_code.to_string(), true);
+        run_with_public_path(css_code, "/")
+    }
+
+    fn run_with_public_path(css_code: &str, public_path: &str) -> String {
+        let mut context = Context::default();
+        context.config.devtool = None;
+        context.config.mode = Mode::Production;
+        context.config.public_path = public_path.to_string();
+        let mut test_utils = TestUtils::with_context(
+            TestUtilsOpts {
+                file: Some("test.css".to_string()),
+                content: Some(css_code.to_string()),
+            },
+            Arc::new(context),
+        );
         let ast = test_utils.ast.css_mut();
         let current_dir = std::env::current_dir().unwrap();
         let css_path = current_dir.join("src/visitors/fixtures/css_assets/test.css");
@@ -1,41 +1,59 @@
-use swc_core::css::ast::{ImportHref, UrlValue};
-use swc_core::css::visit::Visit;
+use swc_core::common::{Span, Spanned};
+use swc_core::css::ast::{ImportConditions, ImportHref, ImportPrelude, UrlValue};
+use swc_core::css::codegen::writer::basic::{BasicCssWriter, BasicCssWriterConfig};
+use swc_core::css::codegen::{CodeGenerator, CodegenConfig, Emit};
+use swc_core::css::visit::{Visit, VisitWith};
 
 use crate::ast::utils;
-use crate::module::{Dependency, ResolveType};
+use crate::module::{CssImportConditions, Dependency, ResolveType};
 
 pub struct CSSDepAnalyzer {
     pub dependencies: Vec<Dependency>,
-    order: usize,
 }
 
 impl CSSDepAnalyzer {
     pub fn new() -> Self {
         Self {
             dependencies: vec![],
-            // start with 1
-            // 0 for swc helpers
-            order: 1,
         }
     }
 
-    fn add_dependency(&mut self, url: String) {
-        if utils::is_remote_or_data_or_hash(&url) {
+    fn add_dependency(&mut self, url: String, span: Span) {
+        if url.is_empty() || utils::is_remote_or_data_or_hash(&url) {
             return;
         }
         let url = utils::remove_first_tilde(url);
         self.dependencies.push(Dependency {
             source: url,
             resolve_as: None,
-            order: self.order,
+            // the source position, renumbered after the analysis
+            order: span.lo.0 as usize,
             resolve_type: ResolveType::Css,
-            span: None,
+            span: Some(span),
+            chunk_hint: None,
+            attributes: None,
+            import_conditions: None,
         });
-        self.order += 1;
     }
 }
 
 impl Visit for CSSDepAnalyzer {
+    // e.g.
+    // @import "a.css" print;
+    // @import url(a.css) supports(display: grid) screen;
+    fn visit_import_prelude(&mut self, n: &ImportPrelude) {
+        let len = self.dependencies.len();
+        n.href.visit_with(self);
+        if self.dependencies.len() > len
+            && let Some(conditions) = &n.import_conditions
+        {
+            let import_conditions = get_import_conditions(conditions);
+            if import_conditions != CssImportConditions::default() {
+                self.dependencies.last_mut().unwrap().import_conditions = Some(import_conditions);
+            }
+        }
+    }
+
     fn visit_import_href(&mut self, n: &ImportHref) {
         match n {
             // e.g.
@@ -47,24 +65,55 @@ impl Visit for CSSDepAnalyzer {
                     UrlValue::Raw(raw) => raw.value.to_string(),
                 });
                 if let Some(src) = src {
-                    self.add_dependency(src);
+                    self.add_dependency(src, url.span);
                 }
             }
             // e.g.
             // @import "a.css"
-            ImportHref::Str(src) => {
-                let src = src.value.to_string();
-                self.add_dependency(src);
+            ImportHref::Str(str) => {
+                let src = str.value.to_string();
+                self.add_dependency(src, str.span);
             }
         }
     }
 }
 
+// e.g. supports(display: grid) print => { supports: "display:grid", media: "print" }
+fn get_import_conditions(conditions: &ImportConditions) -> CssImportConditions {
+    let supports = conditions.supports.as_ref().map(|supports| {
+        supports
+            .value
+            .iter()
+            .filter_map(emit_to_string)
+            .collect::<Vec<_>>()
+            .join(" ")
+    });
+    let media = conditions
+        .media
+        .as_ref()
+        .and_then(|media| emit_to_string(media.as_ref()));
+    CssImportConditions { supports, media }
+}
+
+fn emit_to_string<T: Spanned>(node: &T) -> Option<String>
+where
+    for<'a> CodeGenerator<BasicCssWriter<'a, &'a mut String>>: Emit<T>,
+{
+    let mut code = String::new();
+    {
+        let writer = BasicCssWriter::new(&mut code, None, BasicCssWriterConfig::default());
+        let mut gen = CodeGenerator::new(writer, CodegenConfig { minify: true });
+        gen.emit(node).ok()?;
+    }
+    Some(code)
+}
+
 #[cfg(test)]
 mod tests {
     use swc_core::css::visit::VisitWith;
 
     use crate::ast::tests::TestUtils;
+    use crate::module::CssImportConditions;
 
     #[test]
     fn test_normal() {
@@ -93,6 +142,46 @@ mod tests {
         assert!(run(r#"@import url(data://a.com/a.css);"#).is_empty());
         assert!(run(r#"@import url(//a.com/a.css);"#).is_empty());
         assert!(run(r#"@import url(#a);"#).is_empty());
+        assert!(run(r#"@import "https://fonts.googleapis.com/css?family=Roboto";"#).is_empty());
+        assert!(run(r#"@import url(HTTPS://a.com/a.css);"#).is_empty());
+        assert!(run(r#"@import "//a.com/a.css";"#).is_empty());
+        assert!(run(r#".foo { background: url(data:image/png;base64,iVBORw0KGgo=); }"#).is_empty());
+    }
+
+    #[test]
+    fn test_empty() {
+        assert!(run(r#"@import url();"#).is_empty());
+        assert!(run(r#".foo { background: url(); }"#).is_empty());
+    }
+
+    #[test]
+    fn test_conditions() {
+        let conditions = |supports: Option<&str>, media: Option<&str>| {
+            Some(CssImportConditions {
+                supports: supports.map(|s| s.to_string()),
+                media: media.map(|s| s.to_string()),
+            })
+        };
+        assert_eq!(
+            run_conditions(r#"@import "print.css" print;"#),
+            vec![conditions(None, Some("print"))]
+        );
+        assert_eq!(
+            run_conditions(r#"@import url("x.css") supports(display: grid);"#),
+            vec![conditions(Some("display:grid"), None)]
+        );
+        assert_eq!(
+            run_conditions(
+                r#"@import url(x.css) supports(display: flex) screen and (min-width: 100px);"#
+            ),
+            vec![conditions(
+                Some("display:flex"),
+                Some("screen and (min-width:100px)")
+            )]
+        );
+        assert_eq!(run_conditions(r#"@import "a.css";"#), vec![None]);
+        // remote imports are still skipped
+        assert!(run_conditions(r#"@import "https://a.com/a.css" print;"#).is_empty());
     }
 
     #[test]
@@ -106,6 +195,18 @@ mod tests {
         );
     }
 
+    fn run_conditions(css_code: &str) -> Vec<Option<CssImportConditions>> {
+        let mut test_utils = TestUtils::gen_css_ast(css_code.to_string(), false);
+        let ast = test_utils.ast.css_mut();
+        let mut analyzer = super::CSSDepAnalyzer::new();
+        ast.ast.visit_with(&mut analyzer);
+        analyzer
+            .dependencies
+            .into_iter()
+            .map(|dep| dep.import_conditions)
+            .collect()
+    }
+
     fn run(css_code: &str) -> Vec<String> {
         let mut test_utils = TestUtils::gen_css_ast(css_code.to_string(), false);
         let ast = test_utils.ast.css_mut();
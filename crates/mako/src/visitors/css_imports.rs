@@ -1,8 +1,19 @@
+use std::borrow::Cow;
+use std::sync::Arc;
+
+use anyhow::Result;
 use swc_core::common::util::take::Take;
-use swc_core::css::ast::{AtRule, AtRulePrelude, ImportHref, Rule, Stylesheet, UrlValue};
+use swc_core::css::ast::{
+    AtRule, AtRulePrelude, ComponentValue, ImportHref, Rule, Stylesheet, UrlValue,
+};
 use swc_core::css::visit::{VisitMut, VisitMutWith};
+use tracing::debug;
 
+use crate::ast::css_ast::CssAst;
 use crate::ast::utils::is_remote_or_data_or_hash;
+use crate::compiler::Context;
+use crate::module::{CssImportConditions, ModuleId};
+use crate::module_graph::ModuleGraph;
 
 pub struct CSSImports;
 
@@ -65,11 +76,94 @@ impl VisitMut for CSSImports {
     }
 }
 
+// the css imported with conditions, e.g. @import 'a.css' print, is wrapped in
+// @media print { ... } when the chunks are generated, since it's emitted once in the chunk,
+// it's only wrapped when all the importers import it with the same conditions
+pub fn with_import_conditions<'a>(
+    module_id: &ModuleId,
+    stylesheet: &'a Stylesheet,
+    module_graph: &ModuleGraph,
+    context: &Arc<Context>,
+) -> Cow<'a, Stylesheet> {
+    let mut conditions: Option<&CssImportConditions> = None;
+    for (_, dep) in module_graph.get_dependents(module_id) {
+        match (&dep.import_conditions, conditions) {
+            (None, _) => return Cow::Borrowed(stylesheet),
+            (Some(current), Some(conditions)) if current != conditions => {
+                return Cow::Borrowed(stylesheet)
+            }
+            (Some(current), _) => conditions = Some(current),
+        }
+    }
+    let Some(conditions) = conditions else {
+        return Cow::Borrowed(stylesheet);
+    };
+    match wrap_with_import_conditions(stylesheet, conditions, context) {
+        Ok(wrapped) => Cow::Owned(wrapped),
+        Err(e) => {
+            debug!("wrap css with import conditions failed: {}", e);
+            Cow::Borrowed(stylesheet)
+        }
+    }
+}
+
+// e.g. { supports: "display:grid", media: "print" }
+// => @supports (display:grid) { @media print { ...rules } }
+fn wrap_with_import_conditions(
+    stylesheet: &Stylesheet,
+    conditions: &CssImportConditions,
+    context: &Arc<Context>,
+) -> Result<Stylesheet> {
+    let mut code = String::new();
+    let mut depth = 0;
+    if let Some(supports) = &conditions.supports {
+        // a declaration is wrapped in parens to be a condition, e.g. (display:grid)
+        code.push_str(&format!("@supports ({}) {{", supports));
+        depth += 1;
+    }
+    if let Some(media) = &conditions.media {
+        code.push_str(&format!("@media {} {{", media));
+        depth += 1;
+    }
+    code.push_str(&"}".repeat(depth));
+    let mut wrapper = CssAst::build(
+        "virtual:css-import-conditions",
+        &code,
+        context.clone(),
+        false,
+    )?
+    .ast;
+    if let Some(Rule::AtRule(at_rule)) = wrapper.rules.first_mut() {
+        fill_innermost_block(at_rule, stylesheet.rules.clone());
+    }
+    wrapper.span = stylesheet.span;
+    Ok(wrapper)
+}
+
+fn fill_innermost_block(at_rule: &mut AtRule, rules: Vec<Rule>) {
+    let Some(block) = at_rule.block.as_mut() else {
+        return;
+    };
+    if let Some(ComponentValue::AtRule(inner)) = block.value.first_mut() {
+        fill_innermost_block(inner, rules);
+        return;
+    }
+    block.value = rules
+        .into_iter()
+        .map(|rule| match rule {
+            Rule::QualifiedRule(rule) => ComponentValue::QualifiedRule(rule),
+            Rule::AtRule(rule) => ComponentValue::AtRule(rule),
+            Rule::ListOfComponentValues(list) => ComponentValue::ListOfComponentValues(list),
+        })
+        .collect();
+}
+
 #[cfg(test)]
 mod tests {
     use swc_core::css::visit::VisitMutWith;
 
     use crate::ast::tests::TestUtils;
+    use crate::module::CssImportConditions;
 
     #[test]
     fn test_keep_none_relative() {
@@ -113,6 +207,29 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_wrap_with_import_conditions() {
+        let wrap = |supports: Option<&str>, media: Option<&str>| {
+            let mut test_utils = TestUtils::gen_css_ast(".a { color: red; }".to_string(), false);
+            let conditions = CssImportConditions {
+                supports: supports.map(|s| s.to_string()),
+                media: media.map(|s| s.to_string()),
+            };
+            let context = test_utils.context.clone();
+            let ast = test_utils.ast.css_mut();
+            ast.ast = super::wrap_with_import_conditions(&ast.ast, &conditions, &context).unwrap();
+            test_utils.css_ast_to_code()
+        };
+        assert_eq!(
+            wrap(None, Some("print")),
+            "@media print {\n  .a {\n    color: red;\n  }\n}"
+        );
+        assert_eq!(
+            wrap(Some("display:grid"), Some("screen and (min-width:100px)")),
+            "@supports (display: grid) {\n  @media screen and (min-width: 100px) {\n    .a {\n      color: red;\n    }\n  }\n}"
+        );
+    }
+
     fn run(css_code: &str) -> String {
         let mut test_utils = TestUtils::gen_css_ast(css_code.to_string(), false);
         let ast = test_utils.ast.css_mut();
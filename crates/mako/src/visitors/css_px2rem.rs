@@ -51,8 +51,8 @@ impl Px2Rem {
         }
         let is_prop_valid = if let Some(decl) = &self.current_decl {
             let is_whitelist_empty = self.config.prop_whitelist.is_empty();
-            let is_in_whitelist = self.config.prop_whitelist.contains(decl);
-            let is_in_blacklist = self.config.prop_blacklist.contains(decl);
+            let is_in_whitelist = is_prop_matched(&self.config.prop_whitelist, decl);
+            let is_in_blacklist = is_prop_matched(&self.config.prop_blacklist, decl);
             (is_whitelist_empty || is_in_whitelist) && !is_in_blacklist
         } else {
             true
@@ -141,6 +141,29 @@ impl VisitMut for Px2Rem {
     }
 }
 
+// same as postcss-pxtorem, `*` matches any chars
+// e.g. border* matches border and border-top, *position* matches background-position-x
+fn is_prop_matched(props: &[String], prop: &str) -> bool {
+    props.iter().any(|pattern| {
+        if !pattern.contains('*') {
+            return pattern == prop;
+        }
+        let parts = pattern.split('*').collect::<Vec<_>>();
+        let (first, last) = (parts[0], parts[parts.len() - 1]);
+        if !prop.starts_with(first) || prop.len() < first.len() + last.len() {
+            return false;
+        }
+        let mut rest = &prop[first.len()..];
+        for part in &parts[1..parts.len() - 1] {
+            match rest.find(part) {
+                Some(idx) => rest = &rest[idx + part.len()..],
+                None => return false,
+            }
+        }
+        rest.ends_with(last)
+    })
+}
+
 #[cached(key = "String", convert = r#"{ patterns.join(",") }"#)]
 fn parse_patterns(patterns: &[String]) -> Vec<Regex> {
     patterns
@@ -345,6 +368,39 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_prop_blacklist_wildcard() {
+        let config = Px2RemConfig {
+            prop_blacklist: vec!["border*".to_string(), "*position*".to_string()],
+            ..Default::default()
+        };
+        assert_eq!(
+            run(
+                r#".a{border:100px solid;border-top-width:100px;background-position-x:100px;width:100px}"#,
+                config
+            ),
+            r#".a{border:100px solid;border-top-width:100px;background-position-x:100px;width:1rem}"#
+        );
+    }
+
+    #[test]
+    fn test_keep_border_1px() {
+        assert_eq!(
+            run_with_min_pixel_value(r#".a{border:1px solid red;padding:200px}"#, 2.0),
+            r#".a{border:1px solid red;padding:2rem}"#
+        );
+    }
+
+    #[test]
+    fn test_url_and_string() {
+        assert_eq!(
+            run_with_default(
+                r#".a{background:url(a-100px.png);width:100px}.a:after{content:"100px"}"#
+            ),
+            r#".a{background:url(a-100px.png);width:1rem}.a:after{content:"100px"}"#
+        );
+    }
+
     #[test]
     fn test_prop_whitelist() {
         assert_eq!(
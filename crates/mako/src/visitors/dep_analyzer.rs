@@ -1,33 +1,90 @@
-use swc_core::common::{Mark, Span};
-use swc_core::ecma::ast::{CallExpr, Expr, Lit, ModuleDecl, NewExpr, Str};
+use std::collections::HashMap;
+
+use swc_core::common::comments::{CommentKind, Comments};
+use swc_core::common::{Mark, Span, Spanned};
+use swc_core::ecma::ast::{
+    CallExpr, Expr, KeyValueProp, Lit, ModuleDecl, NewExpr, ObjectLit, Prop, PropName,
+    PropOrSpread, Str,
+};
 use swc_core::ecma::visit::{Visit, VisitWith};
 
+use crate::ast::comments::MakoComments;
 use crate::ast::utils;
-use crate::module::{Dependency, ResolveType};
+use crate::module::{ChunkHint, Dependency, ResolveType};
+use crate::plugins::context_module::parse_async_context_request;
+use crate::plugins::import_meta_glob::parse_glob_request;
+use crate::visitors::worker_module::is_worker_constructor;
 
 pub struct DepAnalyzer {
     pub dependencies: Vec<Dependency>,
-    order: usize,
+    // indexes of dependencies by (source, resolve_type)
+    indexes: HashMap<(String, ResolveType), usize>,
+    // dynamic imports without any static part, e.g. import(lang),
+    // which can't be bundled and are left to the runtime
+    pub fully_dynamic_imports: Vec<Span>,
     unresolved_mark: Mark,
+    comments: Option<MakoComments>,
 }
 
 impl DepAnalyzer {
     pub fn new(unresolved_mark: Mark) -> Self {
         Self {
             dependencies: vec![],
-            order: 1,
+            indexes: HashMap::new(),
+            fully_dynamic_imports: vec![],
             unresolved_mark,
+            comments: None,
         }
     }
-    fn add_dependency(&mut self, source: String, resolve_type: ResolveType, span: Option<Span>) {
+
+    // comments are used to read magic comments of dynamic imports
+    pub fn with_comments(mut self, comments: MakoComments) -> Self {
+        self.comments = Some(comments);
+        self
+    }
+
+    // dependencies are deduplicated by (source, resolve_type)
+    // so that e.g. `import './a'` and `require('./a')` are both kept,
+    // returns the first one for duplicates, which the fields of the duplicates are merged into
+    fn add_dependency(
+        &mut self,
+        source: String,
+        resolve_type: ResolveType,
+        span: Option<Span>,
+    ) -> &mut Dependency {
+        let key = (source.clone(), resolve_type.clone());
+        if let Some(&index) = self.indexes.get(&key) {
+            return &mut self.dependencies[index];
+        }
+        self.indexes.insert(key, self.dependencies.len());
         self.dependencies.push(Dependency {
             source,
             resolve_as: None,
-            order: self.order,
+            // the source position, renumbered after the analysis
+            order: span.map_or(0, |span| span.lo.0 as usize),
             resolve_type,
             span,
+            chunk_hint: None,
+            attributes: None,
+            import_conditions: None,
         });
-        self.order += 1;
+        self.dependencies.last_mut().unwrap()
+    }
+
+    fn get_chunk_hint(&self, expr: &CallExpr) -> Option<ChunkHint> {
+        let comments = self.comments.as_ref()?;
+        let arg = expr.args.first()?;
+        let leading = comments.get_leading(arg.span_lo())?;
+        leading
+            .iter()
+            .filter(|c| c.kind == CommentKind::Block)
+            .filter_map(|c| parse_chunk_hint(&c.text))
+            .reduce(|mut hint, other| {
+                hint.name = other.name.or(hint.name);
+                hint.prefetch |= other.prefetch;
+                hint.preload |= other.preload;
+                hint
+            })
     }
 }
 
@@ -41,28 +98,31 @@ impl Visit for DepAnalyzer {
                     return;
                 }
                 let src = import.src.value.to_string();
-                self.add_dependency(
+                let dep = self.add_dependency(
                     src,
                     ResolveType::Import(import.into()),
                     Some(import.src.span),
                 );
+                set_attributes(dep, import.with.as_deref());
             }
             // e.g.
             // export { a, b, c } from './module';
             ModuleDecl::ExportNamed(export) => {
                 if let Some(src) = &export.src {
-                    self.add_dependency(
+                    let dep = self.add_dependency(
                         src.value.to_string(),
                         ResolveType::ExportNamed(export.into()),
                         Some(src.span),
                     );
+                    set_attributes(dep, export.with.as_deref());
                 }
             }
             // e.g.
             // export * from './module';
             ModuleDecl::ExportAll(export) => {
                 let src = export.src.value.to_string();
-                self.add_dependency(src, ResolveType::ExportAll, Some(export.src.span));
+                let dep = self.add_dependency(src, ResolveType::ExportAll, Some(export.src.span));
+                set_attributes(dep, export.with.as_deref());
             }
             _ => {}
         }
@@ -75,19 +135,44 @@ impl Visit for DepAnalyzer {
     fn visit_call_expr(&mut self, expr: &CallExpr) {
         // e.g.
         // require('a')
+        // require('./dir/*.js?import-meta-glob'), which is transformed from import.meta.glob
         if utils::is_commonjs_require(expr, &self.unresolved_mark) {
             if let Some(src) = utils::get_first_str_arg(expr) {
-                self.add_dependency(src, ResolveType::Require, Some(expr.span));
+                let resolve_type = if parse_glob_request(&src).is_some() {
+                    ResolveType::Glob
+                } else {
+                    ResolveType::Require
+                };
+                self.add_dependency(src, resolve_type, Some(expr.span));
+                return;
+            }
+        }
+        // e.g.
+        // require.resolve('a')
+        else if utils::is_commonjs_require_resolve(expr, &self.unresolved_mark) {
+            if let Some(src) = utils::get_first_str_arg(expr) {
+                self.add_dependency(src, ResolveType::RequireResolve, Some(expr.span));
                 return;
             }
         }
         // e.g.
         // import('a')
+        // import(`./locales/${lang}.js`), which is replaced with the context module
+        // of the matching files by ContextModuleVisitor
         else if utils::is_dynamic_import(expr) {
             if let Some(src) = utils::get_first_str_arg(expr) {
-                self.add_dependency(src, ResolveType::DynamicImport, Some(expr.span));
+                let chunk_hint = self.get_chunk_hint(expr);
+                let resolve_type = match parse_async_context_request(&src) {
+                    Some((prefix, suffix)) => ResolveType::DynamicImportGlob { prefix, suffix },
+                    None => ResolveType::DynamicImport,
+                };
+                let dep = self.add_dependency(src, resolve_type, Some(expr.span));
+                merge_chunk_hint(dep, chunk_hint);
                 return;
             }
+            if expr.args.first().is_some_and(|arg| arg.spread.is_none()) {
+                self.fully_dynamic_imports.push(expr.span);
+            }
         }
         expr.visit_children_with(self);
     }
@@ -98,11 +183,142 @@ impl Visit for DepAnalyzer {
         // new Worker(new URL('a', import.meta.url));
         if let Some(str) = resolve_web_worker(expr, self.unresolved_mark) {
             self.add_dependency(str.value.to_string(), ResolveType::Worker, Some(str.span));
+            // skip the inner `new URL()`, it's the worker entry rather than an asset
+            if let Some(args) = &expr.args {
+                args.iter().skip(1).for_each(|arg| arg.visit_with(self));
+            }
+            return;
+        }
+        // Assets
+        // e.g.
+        // new URL('./a.png', import.meta.url);
+        if let Some(str) = resolve_url_asset(expr, self.unresolved_mark) {
+            self.add_dependency(str.value.to_string(), ResolveType::UrlAsset, Some(str.span));
         }
         expr.visit_children_with(self);
     }
 }
 
+// collect import attributes and route `type: 'json'` to the json loader
+// e.g.
+// import a from './a.txt' with { type: 'json' }
+// notice: the legacy `assert { type: 'json' }` is parsed into `with` too
+fn set_attributes(dep: &mut Dependency, with: Option<&ObjectLit>) {
+    let Some(with) = with else {
+        return;
+    };
+    let attributes = with
+        .props
+        .iter()
+        .filter_map(|prop| match prop {
+            PropOrSpread::Prop(box Prop::KeyValue(KeyValueProp {
+                key,
+                value: box Expr::Lit(Lit::Str(value)),
+            })) => {
+                let key = match key {
+                    PropName::Ident(ident) => ident.sym.to_string(),
+                    PropName::Str(str) => str.value.to_string(),
+                    _ => return None,
+                };
+                Some((key, value.value.to_string()))
+            }
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    // files are loaded as the type regardless of the extension
+    // e.g. import a from './a.txt' with { type: 'json' } => ./a.txt?json
+    let loaded_as = attributes
+        .iter()
+        .find_map(|(k, v)| match (k.as_str(), v.as_str()) {
+            ("type", "json") => Some(("json", ".json")),
+            ("type", "webassembly") => Some(("webassembly", ".wasm")),
+            _ => None,
+        });
+    if let Some((param, ext)) = loaded_as
+        && dep.resolve_as.is_none()
+        && !dep.source.split('?').next().unwrap().ends_with(ext)
+    {
+        let separator = if dep.source.contains('?') { '&' } else { '?' };
+        dep.resolve_as = Some(format!("{}{}{}", dep.source, separator, param));
+    }
+    // attributes of the duplicates are merged, the first one wins for the same key
+    match &mut dep.attributes {
+        Some(current) => {
+            for (key, value) in attributes {
+                if !current.iter().any(|(k, _)| *k == key) {
+                    current.push((key, value));
+                }
+            }
+        }
+        None => dep.attributes = Some(attributes),
+    }
+}
+
+// e.g. import(/* webpackChunkName: "a" */ './a') and import(/* webpackPrefetch: true */ './a')
+// are merged into one hint, the first chunk name wins
+fn merge_chunk_hint(dep: &mut Dependency, hint: Option<ChunkHint>) {
+    let Some(hint) = hint else {
+        return;
+    };
+    match &mut dep.chunk_hint {
+        Some(current) => {
+            current.name = current.name.take().or(hint.name);
+            current.prefetch |= hint.prefetch;
+            current.preload |= hint.preload;
+        }
+        None => dep.chunk_hint = Some(hint),
+    }
+}
+
+// parse webpack magic comments, returns None if no known directive is found
+// e.g.
+// webpackChunkName: "foo", webpackPrefetch: true
+fn parse_chunk_hint(comment: &str) -> Option<ChunkHint> {
+    let mut hint = ChunkHint::default();
+    let mut matched = false;
+    for directive in comment.split(',') {
+        let Some((key, value)) = directive.split_once(':') else {
+            continue;
+        };
+        let value = value
+            .trim()
+            .trim_matches(|c| c == '"' || c == '\'' || c == '`');
+        match key.trim() {
+            "webpackChunkName" if !value.is_empty() => {
+                hint.name = Some(value.to_string());
+            }
+            "webpackPrefetch" => {
+                hint.prefetch = value == "true";
+            }
+            "webpackPreload" => {
+                hint.preload = value == "true";
+            }
+            _ => continue,
+        }
+        matched = true;
+    }
+    matched.then_some(hint)
+}
+
+// get the value of url when the following conditions are met
+// notice: only relative url is collected, bare specifiers and remote urls are skipped
+// e.g.
+// new URL('./a.png', import.meta.url);
+fn resolve_url_asset(expr: &NewExpr, unresolved_mark: Mark) -> Option<&Str> {
+    if let box Expr::Ident(ident) = &expr.callee
+        && utils::is_ident_undefined(ident, "URL", &unresolved_mark)
+        && let Some(args) = &expr.args
+        && args
+            .get(1)
+            .is_some_and(|arg| utils::is_import_meta_url(&arg.expr))
+        && let box Expr::Lit(Lit::Str(ref str)) = &args[0].expr
+        && utils::is_relative_url(&str.value)
+    {
+        return Some(str);
+    }
+    None
+}
+
 // get the value of url when the following conditions are met
 // notice: only add dependency when the second argument is import.meta.url
 // e.g.
@@ -113,8 +329,7 @@ fn resolve_web_worker(expr: &NewExpr, unresolved_mark: Mark) -> Option<&Str> {
     }
 
     if let box Expr::Ident(ident) = &expr.callee {
-        #[allow(clippy::needless_borrow)]
-        if utils::is_ident_undefined(&ident, "Worker", &unresolved_mark) {
+        if is_worker_constructor(ident, &unresolved_mark) {
             let args = expr.args.as_ref().unwrap();
             if let Expr::New(expr) = &*args[0].expr {
                 // TODO: refactor
@@ -151,9 +366,11 @@ fn resolve_web_worker(expr: &NewExpr, unresolved_mark: Mark) -> Option<&Str> {
 #[cfg(test)]
 mod tests {
     use swc_core::common::GLOBALS;
-    use swc_core::ecma::visit::VisitWith;
+    use swc_core::ecma::visit::{VisitMutWith, VisitWith};
 
     use crate::ast::tests::TestUtils;
+    use crate::module::{ChunkHint, Dependency, ImportType, NamedExportType, ResolveType};
+    use crate::plugins::context_module::ContextModuleVisitor;
 
     #[test]
     fn test_normal() {
@@ -172,11 +389,190 @@ mod tests {
         assert_eq!(run(r#"export * from "a";"#), vec!["a"]);
     }
 
+    #[test]
+    fn test_import_attributes() {
+        let deps = run_deps(r#"import a from './a.json' with { type: 'json' };"#);
+        assert_eq!(
+            deps[0].attributes,
+            Some(vec![("type".to_string(), "json".to_string())])
+        );
+        assert_eq!(deps[0].resolve_as, None);
+        // legacy assert syntax
+        let deps = run_deps(r#"import a from './a.json' assert { type: 'json' };"#);
+        assert_eq!(
+            deps[0].attributes,
+            Some(vec![("type".to_string(), "json".to_string())])
+        );
+        let deps = run_deps(r#"export * from './a.css' with { type: "css", "foo": 'bar' };"#);
+        assert_eq!(
+            deps[0].attributes,
+            Some(vec![
+                ("type".to_string(), "css".to_string()),
+                ("foo".to_string(), "bar".to_string())
+            ])
+        );
+        // non-json file is loaded as json
+        let deps = run_deps(r#"import a from './a.txt' with { type: 'json' };"#);
+        assert_eq!(deps[0].resolve_as, Some("./a.txt?json".to_string()));
+        let deps = run_deps(r#"export { a } from './a?b' assert { type: 'json' };"#);
+        assert_eq!(deps[0].resolve_as, Some("./a?b&json".to_string()));
+        // non-wasm file is loaded as wasm
+        let deps = run_deps(r#"import a from './a.bin' with { type: 'webassembly' };"#);
+        assert_eq!(deps[0].resolve_as, Some("./a.bin?webassembly".to_string()));
+        let deps = run_deps(r#"import a from './a.wasm' with { type: 'webassembly' };"#);
+        assert_eq!(deps[0].resolve_as, None);
+        // no attributes
+        let deps = run_deps(r#"import a from './a.json';"#);
+        assert_eq!(deps[0].attributes, None);
+    }
+
+    #[test]
+    fn test_export_namespace() {
+        let deps = run_deps(r#"export * as utils from './utils';"#);
+        assert_eq!(deps.len(), 1);
+        assert_eq!(deps[0].source, "./utils");
+        assert_eq!(
+            deps[0].resolve_type,
+            ResolveType::ExportNamed(NamedExportType::Namespace)
+        );
+        // namespace re-export and export all are different dependencies
+        let deps = run_deps(r#"export * as utils from './utils'; export * from './utils';"#);
+        assert_eq!(
+            deps.into_iter()
+                .map(|dep| dep.resolve_type)
+                .collect::<Vec<_>>(),
+            vec![
+                ResolveType::ExportNamed(NamedExportType::Namespace),
+                ResolveType::ExportAll
+            ]
+        );
+    }
+
+    #[test]
+    fn test_export_default_from() {
+        let resolve_types = |code| {
+            run_deps(code)
+                .into_iter()
+                .map(|dep| (dep.source, dep.resolve_type))
+                .collect::<Vec<_>>()
+        };
+        let default = ResolveType::ExportNamed(NamedExportType::Default);
+        assert_eq!(
+            resolve_types(r#"export { default } from './x';"#),
+            vec![("./x".to_string(), default.clone())]
+        );
+        assert_eq!(
+            resolve_types(r#"export { default as Foo } from './x';"#),
+            vec![("./x".to_string(), default)]
+        );
+        assert_eq!(
+            resolve_types(r#"export { default as Foo, bar } from './x';"#),
+            vec![(
+                "./x".to_string(),
+                ResolveType::ExportNamed(NamedExportType::Default | NamedExportType::Named)
+            )]
+        );
+    }
+
     #[test]
     fn test_dynamic_import() {
         assert_eq!(run(r#"import('a');"#), vec!["a"]);
     }
 
+    #[test]
+    fn test_dynamic_import_glob() {
+        let deps = run_deps_with_context_module(r#"import(`./locales/${lang}.js`);"#);
+        assert_eq!(deps.len(), 1);
+        assert_eq!(deps[0].source, "./locales/?context&glob=**/*.js&async");
+        assert_eq!(
+            deps[0].resolve_type,
+            ResolveType::DynamicImportGlob {
+                prefix: "./locales/".to_string(),
+                suffix: ".js".to_string(),
+            }
+        );
+        // no suffix
+        let deps = run_deps_with_context_module(r#"import('./locales/' + lang);"#);
+        assert_eq!(
+            deps[0].resolve_type,
+            ResolveType::DynamicImportGlob {
+                prefix: "./locales/".to_string(),
+                suffix: "".to_string(),
+            }
+        );
+        // require is not async
+        let deps = run_deps_with_context_module(r#"require(`./locales/${lang}.js`);"#);
+        assert_eq!(deps[0].resolve_type, ResolveType::Require);
+    }
+
+    #[test]
+    fn test_import_meta_glob() {
+        // import.meta.glob('./dir/*.js') is transformed to it by ImportMetaGlob
+        let deps = run_deps(r#"require('./dir/*.js?import-meta-glob&eager');"#);
+        assert_eq!(deps[0].resolve_type, ResolveType::Glob);
+    }
+
+    #[test]
+    fn test_fully_dynamic_import() {
+        let fully_dynamic_imports = |code| {
+            let mut test_utils = TestUtils::gen_js_ast(code);
+            let ast = test_utils.ast.js_mut();
+            let mut analyzer = super::DepAnalyzer::new(ast.unresolved_mark);
+            GLOBALS.set(&test_utils.context.meta.script.globals, || {
+                ast.ast.visit_mut_with(&mut ContextModuleVisitor {
+                    unresolved_mark: ast.unresolved_mark,
+                });
+                ast.ast.visit_with(&mut analyzer);
+            });
+            assert!(analyzer.dependencies.is_empty());
+            analyzer.fully_dynamic_imports.len()
+        };
+        assert_eq!(fully_dynamic_imports(r#"import(lang);"#), 1);
+        assert_eq!(fully_dynamic_imports(r#"import(`${lang}`);"#), 1);
+        assert_eq!(fully_dynamic_imports(r#"import('' + lang);"#), 1);
+        assert_eq!(fully_dynamic_imports(r#"import(...args);"#), 0);
+        assert_eq!(fully_dynamic_imports(r#"require(lang);"#), 0);
+    }
+
+    #[test]
+    fn test_dynamic_import_chunk_hint() {
+        assert_eq!(
+            run_chunk_hints(r#"import(/* webpackChunkName: "foo" */ 'a');"#),
+            vec![Some(ChunkHint {
+                name: Some("foo".to_string()),
+                ..Default::default()
+            })]
+        );
+        assert_eq!(
+            run_chunk_hints(
+                r#"import(/*webpackChunkName:'foo' ,  webpackPrefetch: true,webpackPreload:true*/ 'a');"#
+            ),
+            vec![Some(ChunkHint {
+                name: Some("foo".to_string()),
+                prefetch: true,
+                preload: true,
+            })]
+        );
+        // multiple comments
+        assert_eq!(
+            run_chunk_hints(
+                r#"import(/* webpackChunkName: "foo" */ /* webpackPrefetch: true */ 'a');"#
+            ),
+            vec![Some(ChunkHint {
+                name: Some("foo".to_string()),
+                prefetch: true,
+                preload: false,
+            })]
+        );
+        // no hint
+        assert_eq!(run_chunk_hints(r#"import('a');"#), vec![None]);
+        assert_eq!(run_chunk_hints(r#"import(/* foo */ 'a');"#), vec![None]);
+        assert_eq!(
+            run_chunk_hints("import(// webpackChunkName: \"foo\"\n'a');"),
+            vec![None]
+        );
+    }
+
     #[test]
     fn test_require() {
         assert_eq!(run(r#"require('a');"#), vec!["a"]);
@@ -184,12 +580,26 @@ mod tests {
         assert!(run(r#"require(a);"#).is_empty());
     }
 
+    #[test]
+    fn test_require_resolve() {
+        assert_eq!(run(r#"require.resolve('a');"#), vec!["a"]);
+        assert_eq!(run(r#"const p = require.resolve('./a');"#), vec!["./a"]);
+        // require is defined
+        assert!(run(r#"const require = {}; require.resolve('a');"#).is_empty());
+        assert!(run(r#"require.resolve(a);"#).is_empty());
+        assert!(run(r#"require.foo('a');"#).is_empty());
+    }
+
     #[test]
     fn test_worker() {
         assert_eq!(
             run(r#"new Worker(new URL('a', import.meta.url));"#),
             vec!["a"]
         );
+        assert_eq!(
+            run(r#"new SharedWorker(new URL('a', import.meta.url), { type: 'module' });"#),
+            vec!["a"]
+        );
         // Worker is defined
         assert!(run(r#"const Worker = 1;new Worker(new URL('a', import.meta.url));"#).is_empty());
         // URL is defined
@@ -202,6 +612,54 @@ mod tests {
         assert!(run(r#"new Worker(new URL('https://a', import.meta.url));"#).is_empty());
     }
 
+    #[test]
+    fn test_url_asset() {
+        assert_eq!(
+            run(r#"new URL('./a.png', import.meta.url);"#),
+            vec!["./a.png"]
+        );
+        assert_eq!(
+            run(r#"new URL('../a.png', import.meta.url);"#),
+            vec!["../a.png"]
+        );
+        // URL is defined
+        assert!(run(r#"const URL = 1;new URL('./a.png', import.meta.url);"#).is_empty());
+        // no import.meta.url
+        assert!(run(r#"new URL('./a.png');"#).is_empty());
+        // ignore bare specifiers
+        assert!(run(r#"new URL('a.png', import.meta.url);"#).is_empty());
+        // ignore remote
+        assert!(run(r#"new URL('https://a.com/a.png', import.meta.url);"#).is_empty());
+        // worker entry is not an asset
+        assert_eq!(
+            run(r#"new Worker(new URL('./a', import.meta.url));"#),
+            vec!["./a"]
+        );
+    }
+
+    #[test]
+    fn test_dedup() {
+        // same resolve type
+        assert_eq!(run(r#"import a from 'a'; import b from 'a';"#), vec!["a"]);
+        assert_eq!(run(r#"require('a'); require('a');"#), vec!["a"]);
+        assert_eq!(run(r#"import('a'); import('a');"#), vec!["a"]);
+        // different resolve types
+        assert_eq!(
+            run_deps(r#"import 'a'; require('a');"#)
+                .into_iter()
+                .map(|dep| (dep.source, dep.resolve_type))
+                .collect::<Vec<_>>(),
+            vec![
+                ("a".to_string(), ResolveType::Import(ImportType::SideEffect)),
+                ("a".to_string(), ResolveType::Require)
+            ]
+        );
+        assert_eq!(
+            run(r#"import a from 'a'; import { b } from 'a';"#),
+            vec!["a", "a"]
+        );
+    }
+
     #[test]
     fn test_embedded() {
         assert_eq!(run(r#"export function a() { require('b') }"#), vec!["b"]);
@@ -209,18 +667,115 @@ mod tests {
         assert_eq!(run(r#"require(require("b"))"#), vec!["b"]);
     }
 
+    #[test]
+    fn test_dedup_merges_fields() {
+        let deps = run_deps(
+            r#"import a from './a.json'; import { b } from './a.json' with { type: 'json' };"#,
+        );
+        assert_eq!(deps.len(), 2);
+        let deps = run_deps(
+            r#"import './a.json' with { type: 'json' }; import './a.json' with { foo: 'bar' };"#,
+        );
+        assert_eq!(deps.len(), 1);
+        assert_eq!(
+            deps[0].attributes,
+            Some(vec![
+                ("type".to_string(), "json".to_string()),
+                ("foo".to_string(), "bar".to_string())
+            ])
+        );
+        assert_eq!(
+            run_chunk_hints(
+                r#"import(/* webpackChunkName: "a" */ 'a'); import(/* webpackPrefetch: true */ 'a');"#
+            ),
+            vec![Some(ChunkHint {
+                name: Some("a".to_string()),
+                prefetch: true,
+                preload: false,
+            })]
+        );
+        // the hint of a later duplicate is kept
+        assert_eq!(
+            run_chunk_hints(r#"import('a'); import(/* webpackChunkName: "a" */ 'a');"#),
+            vec![Some(ChunkHint {
+                name: Some("a".to_string()),
+                prefetch: false,
+                preload: false,
+            })]
+        );
+    }
+
+    #[test]
+    fn test_order_by_source_position() {
+        let code = r#"
+const b = require('b');
+import a from 'a';
+export function f() { return import('d'); }
+import c from 'c';
+        "#;
+        let deps = run_deps(code);
+        let sources = deps
+            .iter()
+            .map(|dep| dep.source.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(sources, vec!["b", "a", "d", "c"]);
+        // the order is the start of the span
+        assert!(deps
+            .iter()
+            .all(|dep| dep.order == dep.span.unwrap().lo.0 as usize));
+        assert!(deps.windows(2).all(|w| w[0].order < w[1].order));
+    }
+
+    fn run_chunk_hints(js_code: &str) -> Vec<Option<ChunkHint>> {
+        let mut test_utils = TestUtils::gen_js_ast(js_code);
+        let ast = test_utils.ast.js_mut();
+        let comments = test_utils
+            .context
+            .meta
+            .script
+            .origin_comments
+            .read()
+            .unwrap()
+            .get_swc_comments()
+            .clone();
+        let mut analyzer = super::DepAnalyzer::new(ast.unresolved_mark).with_comments(comments);
+        GLOBALS.set(&test_utils.context.meta.script.globals, || {
+            ast.ast.visit_with(&mut analyzer);
+        });
+        analyzer
+            .dependencies
+            .into_iter()
+            .map(|dep| dep.chunk_hint)
+            .collect()
+    }
+
     fn run(js_code: &str) -> Vec<String> {
+        run_deps(js_code)
+            .into_iter()
+            .map(|dep| dep.source)
+            .collect()
+    }
+
+    fn run_deps_with_context_module(js_code: &str) -> Vec<Dependency> {
         let mut test_utils = TestUtils::gen_js_ast(js_code);
         let ast = test_utils.ast.js_mut();
         let mut analyzer = super::DepAnalyzer::new(ast.unresolved_mark);
         GLOBALS.set(&test_utils.context.meta.script.globals, || {
+            ast.ast.visit_mut_with(&mut ContextModuleVisitor {
+                unresolved_mark: ast.unresolved_mark,
+            });
             ast.ast.visit_with(&mut analyzer);
         });
-        let sources = analyzer
-            .dependencies
-            .iter()
-            .map(|dep| dep.source.clone())
-            .collect();
-        sources
+        analyzer.dependencies
+    }
+
+    fn run_deps(js_code: &str) -> Vec<Dependency> {
+        let mut test_utils = TestUtils::gen_js_ast(js_code);
+        let ast = test_utils.ast.js_mut();
+        let mut analyzer = super::DepAnalyzer::new(ast.unresolved_mark);
+        GLOBALS.set(&test_utils.context.meta.script.globals, || {
+            ast.ast.visit_with(&mut analyzer);
+        });
+        analyzer.dependencies
     }
 }
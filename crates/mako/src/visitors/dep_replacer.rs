@@ -3,17 +3,22 @@ use std::sync::Arc;
 
 use swc_core::common::{Mark, DUMMY_SP};
 use swc_core::ecma::ast::{
-    AssignOp, BlockStmt, Expr, ExprOrSpread, FnExpr, Function, Ident, ImportDecl, Lit, NamedExport,
-    NewExpr, Stmt, Str, ThrowStmt, VarDeclKind,
+    AssignOp, BinExpr, BinaryOp, BlockStmt, ExportAll, Expr, ExprOrSpread, FnExpr, Function, Ident,
+    ImportDecl, Lit, NamedExport, NewExpr, Stmt, Str, ThrowStmt, VarDeclKind,
 };
 use swc_core::ecma::utils::{member_expr, quote_ident, quote_str, ExprFactory};
 use swc_core::ecma::visit::{VisitMut, VisitMutWith};
 
 use crate::ast::file::parse_path;
-use crate::ast::utils::{is_commonjs_require, is_dynamic_import, is_remote_or_data};
+use crate::ast::utils::{
+    is_commonjs_require, is_commonjs_require_resolve, is_dynamic_import, is_import_meta_url,
+    is_remote_or_data,
+};
 use crate::compiler::Context;
-use crate::module::{Dependency, ModuleId};
+use crate::config::Platform;
+use crate::module::{ChunkHint, Dependency, ModuleId};
 use crate::visitors::virtual_css_modules::is_css_path;
+use crate::visitors::worker_module::is_worker_constructor;
 
 pub struct DepReplacer<'a> {
     pub module_id: &'a ModuleId,
@@ -32,6 +37,12 @@ pub struct DependenciesToReplace {
     // e.g. "react" => ("hashed_id", "/abs/to/react/index.js")
     pub resolved: HashMap<String, (ResolvedModuleId, ResolvedModulePath)>,
     pub missing: HashMap<String, Dependency>,
+    // url_assets stores the "source" maps to the emitted asset file name
+    // e.g. "./a.png" => "a.8e6c05c3.png"
+    pub url_assets: HashMap<String, String>,
+    // chunk_hints stores the generated id of dynamic imports maps to the magic comment hints
+    // e.g. "hashed_id" => ChunkHint { prefetch: true, .. }
+    pub chunk_hints: HashMap<ResolvedModuleId, ChunkHint>,
 }
 
 pub fn miss_throw_stmt<T: AsRef<str>>(source: T) -> Expr {
@@ -86,6 +97,24 @@ pub fn miss_throw_stmt<T: AsRef<str>>(source: T) -> Expr {
 
 impl VisitMut for DepReplacer<'_> {
     fn visit_mut_expr(&mut self, expr: &mut Expr) {
+        // `require.resolve('./a')` => `"module_id"`
+        if let Expr::Call(call_expr) = expr
+            && is_commonjs_require_resolve(call_expr, &self.unresolved_mark)
+            && let Some(ExprOrSpread {
+                expr: box Expr::Lit(Lit::Str(source)),
+                ..
+            }) = call_expr.args.first()
+        {
+            let source_string = source.value.to_string();
+            if self.to_replace.missing.contains_key(&source_string) {
+                *expr = miss_throw_stmt(&source_string);
+                return;
+            }
+            if let Some((module_id, _)) = self.to_replace.resolved.get(&source_string) {
+                *expr = Expr::Lit(quote_str!(source.span, module_id.clone()).into());
+                return;
+            }
+        }
         if let Expr::Call(call_expr) = expr {
             let is_commonjs_require_flag = is_commonjs_require(call_expr, &self.unresolved_mark);
             if is_commonjs_require_flag || is_dynamic_import(call_expr) {
@@ -166,6 +195,8 @@ impl VisitMut for DepReplacer<'_> {
     fn visit_mut_new_expr(&mut self, new_expr: &mut NewExpr) {
         if let Some(str) = resolve_web_worker_mut(new_expr, self.unresolved_mark) {
             self.replace_source(str);
+        } else {
+            self.replace_url_asset(new_expr);
         }
         new_expr.visit_mut_children_with(self);
     }
@@ -179,9 +210,62 @@ impl VisitMut for DepReplacer<'_> {
             self.replace_source(src.as_mut());
         }
     }
+
+    fn visit_mut_export_all(&mut self, n: &mut ExportAll) {
+        self.replace_source(&mut n.src);
+    }
 }
 
 impl DepReplacer<'_> {
+    // new URL('./a.png', import.meta.url)
+    // => new URL(__mako_require__.publicPath + "a.8e6c05c3.png", document.baseURI || self.location.href)
+    fn replace_url_asset(&mut self, new_expr: &mut NewExpr) {
+        let is_url = new_expr.callee.as_ident().is_some_and(|ident| {
+            ident.sym == *"URL" && ident.span.ctxt.outer() == self.unresolved_mark
+        });
+        if !is_url {
+            return;
+        }
+        let Some(args) = new_expr.args.as_mut() else {
+            return;
+        };
+        if !args.get(1).is_some_and(|arg| is_import_meta_url(&arg.expr)) {
+            return;
+        }
+        let Some(file_name) = args[0]
+            .expr
+            .as_lit()
+            .and_then(|lit| match lit {
+                Lit::Str(str) => self.to_replace.url_assets.get(&str.value.to_string()),
+                _ => None,
+            })
+            .cloned()
+        else {
+            return;
+        };
+        let is_browser = matches!(self.context.config.platform, Platform::Browser);
+        if is_browser {
+            args[0].expr = Expr::Bin(BinExpr {
+                span: DUMMY_SP,
+                op: BinaryOp::Add,
+                left: member_expr!(DUMMY_SP, __mako_require__.publicPath),
+                right: Lit::Str(file_name.into()).into(),
+            })
+            .into();
+            args[1].expr = Expr::Bin(BinExpr {
+                span: DUMMY_SP,
+                op: BinaryOp::LogicalOr,
+                left: member_expr!(DUMMY_SP, document.baseURI),
+                right: member_expr!(DUMMY_SP, self.location.href),
+            })
+            .into();
+        } else {
+            args[0].expr = Lit::Str(file_name.into()).into();
+            args[1].expr =
+                Expr::Lit(quote_str!(format!("file://{}", self.module_id.id)).into()).into();
+        }
+    }
+
     fn replace_source(&mut self, source: &mut Str) {
         if let Some(replacement) = self.to_replace.resolved.get(&source.value.to_string()) {
             let module_id = replacement.0.clone();
@@ -198,9 +282,9 @@ pub fn resolve_web_worker_mut(new_expr: &mut NewExpr, unresolved_mark: Mark) ->
         return None;
     }
 
-    if let box Expr::Ident(Ident { span, sym, .. }) = &mut new_expr.callee {
-        // `Worker` must be unresolved
-        if sym == "Worker" && (span.ctxt.outer() == unresolved_mark) {
+    if let box Expr::Ident(ident) = &mut new_expr.callee {
+        // `Worker` or `SharedWorker` must be unresolved
+        if is_worker_constructor(ident, &unresolved_mark) {
             let args = new_expr.args.as_mut().unwrap();
 
             // new Worker(new URL(''), base);
@@ -253,6 +337,34 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_require_resolve() {
+        assert_eq!(
+            run(
+                r#"const p = require.resolve("./a");"#,
+                build_resolved("./a", "/a.js"),
+                Default::default()
+            ),
+            r#"const p = "/a.js";"#,
+        );
+        // require.resolve is not executed
+        assert_eq!(
+            run(
+                r#"require.resolve("./a");"#,
+                Default::default(),
+                build_missing("./a"),
+            ),
+            r#"
+Object(function makoMissingModule() {
+    var e = new Error("Cannot find module './a'");
+    e.code = "MODULE_NOT_FOUND";
+    throw e;
+}());
+            "#
+            .trim(),
+        );
+    }
+
     #[test]
     fn test_dynamic_import() {
         assert_eq!(
@@ -287,6 +399,22 @@ mod tests {
             ),
             r#"export { x } from "/x/index.js";"#,
         );
+        assert_eq!(
+            run(
+                r#"export * as x from "x";"#,
+                build_resolved("x", "/x/index.js"),
+                Default::default()
+            ),
+            r#"export * as x from "/x/index.js";"#,
+        );
+        assert_eq!(
+            run(
+                r#"export * from "x";"#,
+                build_resolved("x", "/x/index.js"),
+                Default::default()
+            ),
+            r#"export * from "/x/index.js";"#,
+        );
     }
 
     #[test]
@@ -301,6 +429,24 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_url_asset() {
+        assert_eq!(
+            run_with_url_assets(
+                r#"new URL('./big.jpg', import.meta.url)"#,
+                hashmap! {
+                    "./big.jpg".to_string() => "big.8e6c05c3.jpg".to_string()
+                },
+            ),
+            r#"new URL(__mako_require__.publicPath + "big.8e6c05c3.jpg", document.baseURI || self.location.href);"#,
+        );
+        // not collected
+        assert_eq!(
+            run_with_url_assets(r#"new URL('./big.jpg')"#, Default::default()),
+            r#"new URL('./big.jpg');"#,
+        );
+    }
+
     #[test]
     fn test_missing_dep() {
         assert_eq!(
@@ -362,6 +508,9 @@ try {
                 source: key.to_string(),
                 resolve_as: None,
                 span: None,
+                chunk_hint: None,
+                attributes: None,
+                import_conditions: None,
                 order: 0,
             }
         }
@@ -372,12 +521,36 @@ try {
         resolved: HashMap<String, (ResolvedModuleId, ResolvedModulePath)>,
         missing: HashMap<String, Dependency>,
     ) -> String {
+        run_with_replace(
+            js_code,
+            DependenciesToReplace {
+                resolved,
+                missing,
+                url_assets: Default::default(),
+                chunk_hints: Default::default(),
+            },
+        )
+    }
+
+    fn run_with_url_assets(js_code: &str, url_assets: HashMap<String, String>) -> String {
+        run_with_replace(
+            js_code,
+            DependenciesToReplace {
+                resolved: Default::default(),
+                missing: Default::default(),
+                url_assets,
+                chunk_hints: Default::default(),
+            },
+        )
+    }
+
+    fn run_with_replace(js_code: &str, to_replace: DependenciesToReplace) -> String {
         let mut test_utils = TestUtils::gen_js_ast(js_code);
         let ast = test_utils.ast.js_mut();
         GLOBALS.set(&test_utils.context.meta.script.globals, || {
             let mut visitor = DepReplacer {
                 module_id: &ModuleId::new("index.jsx".into()),
-                to_replace: &DependenciesToReplace { resolved, missing },
+                to_replace: &to_replace,
                 context: &test_utils.context,
                 unresolved_mark: ast.unresolved_mark,
                 top_level_mark: ast.top_level_mark,
@@ -0,0 +1,148 @@
+use swc_core::common::{Mark, DUMMY_SP};
+use swc_core::ecma::ast::{
+    Callee, ComputedPropName, EmptyStmt, Expr, ExprStmt, Lit, MemberExpr, MemberProp, Stmt,
+};
+use swc_core::ecma::utils::undefined;
+use swc_core::ecma::visit::{VisitMut, VisitMutWith};
+
+use crate::ast::utils::is_ident_undefined;
+use crate::config::{DropConsoleConfig, MinifyConfig};
+
+// e.g. with minify.dropConsole and minify.dropDebugger
+// console.log(a); debugger; => ;;
+// a && console.log(a) => a && void 0
+// the arguments are dropped too, so console.log(sideEffect()) is removed entirely
+pub struct DropConsole<'a> {
+    pub config: &'a MinifyConfig,
+    pub unresolved_mark: Mark,
+}
+
+impl DropConsole<'_> {
+    fn is_dropped_console(&self, expr: &Expr) -> bool {
+        let Expr::Call(call_expr) = expr else {
+            return false;
+        };
+        let Callee::Expr(box Expr::Member(MemberExpr {
+            obj: box Expr::Ident(obj),
+            prop,
+            ..
+        })) = &call_expr.callee
+        else {
+            return false;
+        };
+        if !is_ident_undefined(obj, "console", &self.unresolved_mark) {
+            return false;
+        }
+        match &self.config.drop_console {
+            DropConsoleConfig::Bool(drop) => *drop,
+            DropConsoleConfig::Methods(methods) => {
+                let method = match prop {
+                    MemberProp::Ident(ident) => ident.sym.to_string(),
+                    MemberProp::Computed(ComputedPropName {
+                        expr: box Expr::Lit(Lit::Str(str)),
+                        ..
+                    }) => str.value.to_string(),
+                    _ => return false,
+                };
+                methods.contains(&method)
+            }
+        }
+    }
+}
+
+impl VisitMut for DropConsole<'_> {
+    fn visit_mut_stmt(&mut self, stmt: &mut Stmt) {
+        let dropped = match stmt {
+            Stmt::Debugger(_) => self.config.drop_debugger,
+            Stmt::Expr(ExprStmt { expr, .. }) => self.is_dropped_console(expr),
+            _ => false,
+        };
+        if dropped {
+            *stmt = Stmt::Empty(EmptyStmt { span: DUMMY_SP });
+            return;
+        }
+        stmt.visit_mut_children_with(self);
+    }
+
+    fn visit_mut_expr(&mut self, expr: &mut Expr) {
+        if self.is_dropped_console(expr) {
+            *expr = *undefined(DUMMY_SP);
+            return;
+        }
+        expr.visit_mut_children_with(self);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use swc_core::common::GLOBALS;
+    use swc_core::ecma::visit::VisitMutWith;
+
+    use crate::ast::tests::TestUtils;
+    use crate::config::{DropConsoleConfig, MinifyConfig};
+
+    #[test]
+    fn test_drop_console() {
+        let config = MinifyConfig {
+            drop_console: DropConsoleConfig::Bool(true),
+            ..Default::default()
+        };
+        assert_eq!(run("console.log(x);", &config), ";");
+        // the side effects in arguments are dropped too
+        assert_eq!(run("console.log(sideEffect());", &config), ";");
+        assert_eq!(run("a && console.warn(a);", &config), "a && void 0;");
+        assert_eq!(run("debugger;", &config), "debugger;");
+    }
+
+    #[test]
+    fn test_drop_console_methods() {
+        let config = MinifyConfig {
+            drop_console: DropConsoleConfig::Methods(vec!["log".to_string()]),
+            ..Default::default()
+        };
+        assert_eq!(
+            run(
+                "console.log(x); console['log'](y); console.error(z);",
+                &config
+            ),
+            ";\n;\nconsole.error(z);"
+        );
+    }
+
+    #[test]
+    fn test_drop_debugger() {
+        let config = MinifyConfig {
+            drop_debugger: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            run("function f() { debugger; console.log(x); }", &config),
+            "function f() {\n    ;\n    console.log(x);\n}"
+        );
+    }
+
+    #[test]
+    fn test_local_console() {
+        let config = MinifyConfig {
+            drop_console: DropConsoleConfig::Bool(true),
+            ..Default::default()
+        };
+        assert_eq!(
+            run("const console = logger; console.log(x);", &config),
+            "const console = logger;\nconsole.log(x);"
+        );
+    }
+
+    fn run(js_code: &str, config: &MinifyConfig) -> String {
+        let mut test_utils = TestUtils::gen_js_ast(js_code);
+        let ast = test_utils.ast.js_mut();
+        GLOBALS.set(&test_utils.context.meta.script.globals, || {
+            let mut visitor = super::DropConsole {
+                config,
+                unresolved_mark: ast.unresolved_mark,
+            };
+            ast.ast.visit_mut_with(&mut visitor);
+        });
+        test_utils.js_ast_to_code()
+    }
+}
@@ -11,6 +11,7 @@ use swc_core::ecma::visit::{VisitMut, VisitMutWith};
 
 use crate::ast::utils::{is_dynamic_import, promise_all, require_ensure};
 use crate::compiler::Context;
+use crate::config::Platform;
 use crate::generate::chunk::ChunkId;
 use crate::visitors::dep_replacer::DependenciesToReplace;
 
@@ -19,6 +20,8 @@ pub struct DynamicImport<'a> {
     interop: Ident,
     changed: bool,
     dep_to_replace: &'a DependenciesToReplace,
+    // prefetch or preload calls of the hinted chunks, run when the module is executed
+    hint_stmts: Vec<Stmt>,
 }
 
 impl<'a> DynamicImport<'a> {
@@ -30,6 +33,7 @@ impl<'a> DynamicImport<'a> {
             interop,
             changed: false,
             dep_to_replace: dep_map,
+            hint_stmts: vec![],
         }
     }
 }
@@ -67,6 +71,12 @@ impl<'a> VisitMut for DynamicImport<'a> {
             .into();
 
             n.body.insert(insert_at, stmt.into());
+
+            let hint_stmts = std::mem::take(&mut self.hint_stmts);
+            n.body.splice(
+                insert_at + 1..insert_at + 1,
+                hint_stmts.into_iter().map(|stmt| stmt.into()),
+            );
         }
     }
 
@@ -114,6 +124,26 @@ impl<'a> VisitMut for DynamicImport<'a> {
                         chunk_ids
                     };
 
+                    if matches!(self.context.config.platform, Platform::Browser)
+                        && let Some(hint) = self.dep_to_replace.chunk_hints.get(&resolved_source)
+                    {
+                        // e.g. __mako_require__.prefetch("id")
+                        let hints = [(hint.preload, "preload"), (hint.prefetch, "prefetch")];
+                        for (_, method) in hints.iter().filter(|(enabled, _)| *enabled) {
+                            for chunk_id in &chunk_ids {
+                                self.hint_stmts.push(
+                                    quote_ident!("__mako_require__")
+                                        .make_member(quote_ident!(*method))
+                                        .as_call(
+                                            DUMMY_SP,
+                                            vec![quote_str!(chunk_id.clone()).as_arg()],
+                                        )
+                                        .into_stmt(),
+                                );
+                            }
+                        }
+                    }
+
                     self.changed = true;
                     // build new expr
                     // e.g.
@@ -171,15 +201,38 @@ mod tests {
     use super::DynamicImport;
     use crate::ast::tests::TestUtils;
     use crate::generate::chunk::{Chunk, ChunkType};
+    use crate::module::ChunkHint;
     use crate::visitors::dep_replacer::DependenciesToReplace;
 
     // TODO: add nested chunk test
     #[test]
     fn test_dynamic_import() {
         assert_eq!(
-            run(r#"import("foo");"#),
+            run(r#"import("foo");"#, HashMap::new()),
+            r#"
+var interop = __mako_require__("hashed_helper")._;
+Promise.all([
+    __mako_require__.ensure("foo")
+]).then(__mako_require__.dr(interop, __mako_require__.bind(__mako_require__, "foo")));
+            "#
+            .trim()
+        );
+    }
+
+    #[test]
+    fn test_dynamic_import_with_prefetch_hint() {
+        let hint = ChunkHint {
+            prefetch: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            run(
+                r#"import("foo");"#,
+                maplit::hashmap! { "foo".to_string() => hint }
+            ),
             r#"
 var interop = __mako_require__("hashed_helper")._;
+__mako_require__.prefetch("foo");
 Promise.all([
     __mako_require__.ensure("foo")
 ]).then(__mako_require__.dr(interop, __mako_require__.bind(__mako_require__, "foo")));
@@ -188,7 +241,7 @@ Promise.all([
         );
     }
 
-    fn run(js_code: &str) -> String {
+    fn run(js_code: &str, chunk_hints: HashMap<String, ChunkHint>) -> String {
         let mut test_utils = TestUtils::gen_js_ast(js_code);
         {
             let mut foo = Chunk::new("foo".to_string().into(), ChunkType::Async);
@@ -204,6 +257,8 @@ Promise.all([
                 ("hashed_helper".to_string(), "dummy".into())
             },
             missing: HashMap::new(),
+            url_assets: HashMap::new(),
+            chunk_hints,
         };
 
         GLOBALS.set(&test_utils.context.meta.script.globals, || {
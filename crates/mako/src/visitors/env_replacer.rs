@@ -30,6 +30,8 @@ pub struct EnvReplacer {
     unresolved_mark: Mark,
     envs: Lrc<AHashMap<JsWord, Expr>>,
     meta_envs: Lrc<AHashMap<String, Expr>>,
+    // whether there are keys like `process.env.NODE_ENV`
+    has_member_keys: bool,
 }
 
 impl EnvReplacer {
@@ -41,20 +43,53 @@ impl EnvReplacer {
             // convert NODE_ENV to MODE
             let key: String = if k.eq(&js_word!("NODE_ENV")) {
                 "MODE".into()
+            } else if k.contains('.') {
+                // other member keys like `process.env.API` are not part of import.meta.env
+                continue;
             } else {
                 k.to_string()
             };
 
-            meta_env_map.insert(key, v.clone());
+            meta_env_map.entry(key).or_insert_with(|| v.clone());
+        }
+        // e.g. `import.meta.env.VITE_API` is defined as `VITE_API` of import.meta.env
+        for (k, v) in envs.iter() {
+            if let Some(key) = k.strip_prefix("import.meta.env.") {
+                meta_env_map.insert(key.to_string(), v.clone());
+            }
         }
 
+        let has_member_keys = envs.keys().any(|k| k.contains('.'));
         Self {
             unresolved_mark,
+            has_member_keys,
             envs,
             meta_envs: Lrc::new(meta_env_map),
         }
     }
 
+    // e.g. `process.env.NODE_ENV` or `__APP__.version`, the root must be a global
+    fn get_member_path(&self, expr: &Expr) -> Option<String> {
+        match expr {
+            Expr::Ident(Ident { sym, span, .. }) if span.ctxt.outer() == self.unresolved_mark => {
+                Some(sym.to_string())
+            }
+            Expr::Member(MemberExpr { obj, prop, .. }) => {
+                let prop = match prop {
+                    MemberProp::Ident(Ident { sym, .. }) => sym,
+                    MemberProp::Computed(ComputedPropName {
+                        expr: box Expr::Lit(Lit::Str(Str { value, .. })),
+                        ..
+                    }) => value,
+                    _ => return None,
+                };
+                self.get_member_path(obj)
+                    .map(|obj| format!("{}.{}", obj, prop))
+            }
+            _ => None,
+        }
+    }
+
     fn get_env(envs: &EnvsType, sym: &JsWord) -> Option<Expr> {
         match envs {
             EnvsType::Node(envs) => envs.get(sym).cloned(),
@@ -80,6 +115,15 @@ impl VisitMut for EnvReplacer {
             }
         }
 
+        if let Expr::Member(_) = expr
+            && self.has_member_keys
+            && let Some(path) = self.get_member_path(expr)
+            && let Some(env) = self.envs.get(&JsWord::from(path))
+        {
+            *expr = env.clone();
+            return;
+        }
+
         if let Expr::Member(MemberExpr { obj, prop, .. }) = expr {
             if let Expr::Member(MemberExpr {
                 obj: first_obj,
@@ -95,10 +139,12 @@ impl VisitMut for EnvReplacer {
                 let mut envs = EnvsType::Node(self.envs.clone());
 
                 if match &**first_obj {
+                    // a local `process` is not replaced
                     Expr::Ident(Ident {
                         sym: js_word!("process"),
+                        span,
                         ..
-                    }) => true,
+                    }) => span.ctxt.outer() == self.unresolved_mark,
                     Expr::MetaProp(MetaPropExpr {
                         kind: MetaPropKind::ImportMeta,
                         ..
@@ -157,11 +203,18 @@ impl VisitMut for EnvReplacer {
                 // replace independent `import.meta.env` to json object
                 let mut props = Vec::new();
 
-                // convert envs to object properties
-                for (k, v) in self.meta_envs.iter() {
+                // convert envs to object properties, sorted to keep the output stable
+                let mut keys = self.meta_envs.keys().collect::<Vec<_>>();
+                keys.sort();
+                for k in keys {
+                    let key = if Ident::verify_symbol(k).is_ok() {
+                        PropName::Ident(Ident::new(k.clone().into(), DUMMY_SP))
+                    } else {
+                        PropName::Str(k.clone().into())
+                    };
                     props.push(PropOrSpread::Prop(Box::new(Prop::KeyValue(KeyValueProp {
-                        key: PropName::Ident(Ident::new(k.clone().into(), DUMMY_SP)),
-                        value: Box::new(v.clone()),
+                        key,
+                        value: Box::new(self.meta_envs[k].clone()),
                     }))));
                 }
 
@@ -266,7 +319,8 @@ mod tests {
     use serde_json::{json, Value};
     use swc_core::common::sync::Lrc;
     use swc_core::common::GLOBALS;
-    use swc_core::ecma::visit::VisitMutWith;
+    use swc_core::ecma::transforms::optimization::simplifier;
+    use swc_core::ecma::visit::{FoldWith, VisitMutWith};
 
     use super::{build_env_map, EnvReplacer};
     use crate::ast::tests::TestUtils;
@@ -384,6 +438,132 @@ log([
         );
     }
 
+    #[test]
+    fn test_node_env() {
+        assert_eq!(
+            run(
+                r#"log(process.env.NODE_ENV)"#,
+                hashmap! {
+                    "NODE_ENV".to_string() => json!("\"production\"")
+                }
+            ),
+            r#"log("production");"#
+        );
+    }
+
+    #[test]
+    fn test_member_key() {
+        assert_eq!(
+            run(
+                r#"log(process.env.API, __APP__.version, __APP__["version"], __APP__.name)"#,
+                hashmap! {
+                    "process.env.API".to_string() => json!("\"/api\""),
+                    "__APP__.version".to_string() => json!("\"1.0.0\"")
+                }
+            ),
+            r#"log("/api", "1.0.0", "1.0.0", __APP__.name);"#
+        );
+    }
+
+    #[test]
+    fn test_boolean_flag_branch() {
+        assert_eq!(
+            run_with_simplifier(
+                r#"if (FEATURE) { log("on") } else { log("off") }"#,
+                hashmap! {
+                    "FEATURE".to_string() => json!(false)
+                }
+            ),
+            r#"log("off");"#
+        );
+    }
+
+    #[test]
+    fn test_shadowed() {
+        assert_eq!(
+            run(
+                r#"const process = { env: {} }; const A = 2; log(process.env.NODE_ENV, A);"#,
+                hashmap! {
+                    "NODE_ENV".to_string() => json!("\"production\""),
+                    "A".to_string() => json!(1)
+                }
+            ),
+            r#"const process = {
+    env: {}
+};
+const A = 2;
+log(process.env.NODE_ENV, A);"#
+        );
+        assert_eq!(
+            run(
+                r#"function f(__APP__) { return __APP__.version; }"#,
+                hashmap! {
+                    "__APP__.version".to_string() => json!("\"1.0.0\"")
+                }
+            ),
+            r#"function f(__APP__) {
+    return __APP__.version;
+}"#
+        );
+    }
+
+    #[test]
+    fn test_import_meta_env() {
+        assert_eq!(
+            run(
+                r#"log(import.meta.env.MODE, import.meta.env.VITE_API, import.meta.env["VITE_API"])"#,
+                hashmap! {
+                    "NODE_ENV".to_string() => json!("\"production\""),
+                    "import.meta.env.VITE_API".to_string() => json!("\"/api\"")
+                }
+            ),
+            r#"log("production", "/api", "/api");"#
+        );
+    }
+
+    #[test]
+    fn test_import_meta_env_object() {
+        assert_eq!(
+            run(
+                r#"log(import.meta.env)"#,
+                hashmap! {
+                    "NODE_ENV".to_string() => json!("\"production\""),
+                    "import.meta.env.VITE_API".to_string() => json!("\"/api\""),
+                    "import.meta.env.VITE-FLAG".to_string() => json!(true),
+                    "process.env.API".to_string() => json!("\"/api\"")
+                }
+            ),
+            r#"log({
+    MODE: "production",
+    "VITE-FLAG": true,
+    VITE_API: "/api"
+});"#
+        );
+    }
+
+    #[test]
+    fn test_undefined_import_meta_env() {
+        assert_eq!(
+            run(r#"log(import.meta.env.VITE_UNDEFINED)"#, Default::default()),
+            r#"log(undefined);"#
+        );
+    }
+
+    fn run_with_simplifier(js_code: &str, envs: HashMap<String, Value>) -> String {
+        let mut test_utils = TestUtils::gen_js_ast(js_code);
+        let envs = build_env_map(envs, &test_utils.context).unwrap();
+        let ast = test_utils.ast.js_mut();
+        GLOBALS.set(&test_utils.context.meta.script.globals, || {
+            let mut visitor = EnvReplacer::new(Lrc::new(envs), ast.unresolved_mark);
+            ast.ast.visit_mut_with(&mut visitor);
+            ast.ast = ast
+                .ast
+                .clone()
+                .fold_with(&mut simplifier(ast.unresolved_mark, Default::default()));
+        });
+        test_utils.js_ast_to_code()
+    }
+
     fn run(js_code: &str, envs: HashMap<String, Value>) -> String {
         let mut test_utils = TestUtils::gen_js_ast(js_code);
         let envs = build_env_map(envs, &test_utils.context).unwrap();
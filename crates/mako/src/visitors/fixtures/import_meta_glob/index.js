@@ -0,0 +1 @@
+import.meta.glob('./*.js');
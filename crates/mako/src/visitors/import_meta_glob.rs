@@ -0,0 +1,133 @@
+use swc_core::common::{Mark, DUMMY_SP};
+use swc_core::ecma::ast::{
+    CallExpr, Callee, Expr, ExprOrSpread, KeyValueProp, Lit, MemberExpr, MemberProp, MetaPropExpr,
+    MetaPropKind, Prop, PropName, PropOrSpread,
+};
+use swc_core::ecma::utils::{quote_ident, quote_str, ExprFactory};
+use swc_core::ecma::visit::{VisitMut, VisitMutWith};
+
+use crate::ast::utils::is_relative_url;
+use crate::plugins::import_meta_glob::create_glob_request;
+
+// e.g.
+// import.meta.glob('./dir/*.js')
+// => require("./dir/*.js?import-meta-glob")
+// import.meta.glob('./dir/*.js', { eager: true }) or import.meta.globEager('./dir/*.js')
+// => require("./dir/*.js?import-meta-glob&eager")
+// the pattern is expanded when it's resolved, see ImportMetaGlobPlugin
+pub struct ImportMetaGlob {
+    pub unresolved_mark: Mark,
+}
+
+impl VisitMut for ImportMetaGlob {
+    fn visit_mut_expr(&mut self, expr: &mut Expr) {
+        if let Expr::Call(call_expr) = expr
+            && let Some(is_eager_fn) = get_glob_fn(call_expr)
+            && let Some(ExprOrSpread {
+                expr: box Expr::Lit(Lit::Str(pattern)),
+                spread: None,
+            }) = call_expr.args.first()
+            && is_relative_url(&pattern.value)
+        {
+            let eager = is_eager_fn || call_expr.args.get(1).is_some_and(is_eager_option);
+            let request = create_glob_request(&pattern.value, eager);
+            *expr = quote_ident!(DUMMY_SP.apply_mark(self.unresolved_mark), "require")
+                .as_call(DUMMY_SP, vec![quote_str!(request).as_arg()]);
+            return;
+        }
+        expr.visit_mut_children_with(self);
+    }
+}
+
+// returns Some(true) for import.meta.globEager, Some(false) for import.meta.glob
+fn get_glob_fn(call_expr: &CallExpr) -> Option<bool> {
+    if let Callee::Expr(box Expr::Member(MemberExpr {
+        obj:
+            box Expr::MetaProp(MetaPropExpr {
+                kind: MetaPropKind::ImportMeta,
+                ..
+            }),
+        prop: MemberProp::Ident(prop),
+        ..
+    })) = &call_expr.callee
+    {
+        match prop.sym.as_ref() {
+            "glob" => Some(false),
+            "globEager" => Some(true),
+            _ => None,
+        }
+    } else {
+        None
+    }
+}
+
+// e.g. { eager: true }
+fn is_eager_option(arg: &ExprOrSpread) -> bool {
+    let Expr::Object(obj) = &*arg.expr else {
+        return false;
+    };
+    obj.props.iter().any(|prop| {
+        if let PropOrSpread::Prop(box Prop::KeyValue(KeyValueProp { key, value })) = prop {
+            let is_eager_key = match key {
+                PropName::Ident(ident) => ident.sym == *"eager",
+                PropName::Str(str) => str.value == *"eager",
+                _ => false,
+            };
+            is_eager_key && matches!(&**value, Expr::Lit(Lit::Bool(b)) if b.value)
+        } else {
+            false
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use swc_core::common::GLOBALS;
+    use swc_core::ecma::visit::VisitMutWith;
+
+    use crate::ast::tests::TestUtils;
+
+    #[test]
+    fn test_lazy() {
+        assert_eq!(
+            run(r#"const modules = import.meta.glob('./*.js');"#),
+            r#"const modules = require("./*.js?import-meta-glob");"#
+        );
+    }
+
+    #[test]
+    fn test_eager() {
+        let expected = r#"const modules = require("./*.js?import-meta-glob&eager");"#;
+        assert_eq!(
+            run(r#"const modules = import.meta.glob('./*.js', { eager: true });"#),
+            expected
+        );
+        assert_eq!(
+            run(r#"const modules = import.meta.globEager('./*.js');"#),
+            expected
+        );
+    }
+
+    #[test]
+    fn test_ignore() {
+        // not a string literal
+        assert_eq!(run(r#"import.meta.glob(a);"#), "import.meta.glob(a);");
+        // not relative
+        assert_eq!(
+            run(r#"import.meta.glob('*.js');"#),
+            r#"import.meta.glob('*.js');"#
+        );
+    }
+
+    fn run(js_code: &str) -> String {
+        let mut test_utils = TestUtils::gen_js_ast(js_code);
+        let ast = test_utils.ast.js_mut();
+        GLOBALS.set(&test_utils.context.meta.script.globals, || {
+            let mut visitor = super::ImportMetaGlob {
+                unresolved_mark: ast.unresolved_mark,
+            };
+            ast.ast.visit_mut_with(&mut visitor);
+        });
+        test_utils.js_ast_to_code()
+    }
+}
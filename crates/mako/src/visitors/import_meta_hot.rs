@@ -0,0 +1,85 @@
+use swc_core::common::{Mark, DUMMY_SP};
+use swc_core::ecma::ast::{Expr, Ident, MemberExpr, MemberProp, MetaPropExpr, MetaPropKind};
+use swc_core::ecma::utils::{quote_ident, ExprFactory};
+use swc_core::ecma::visit::{VisitMut, VisitMutWith};
+
+// e.g.
+// import.meta.hot.accept()
+// => module.meta.hot.accept() when hmr is enabled
+// => undefined.accept() otherwise, which should be guarded by `if (import.meta.hot)`
+pub struct ImportMetaHot {
+    pub hmr: bool,
+    pub unresolved_mark: Mark,
+}
+
+impl VisitMut for ImportMetaHot {
+    fn visit_mut_expr(&mut self, expr: &mut Expr) {
+        if let Expr::Member(MemberExpr {
+            obj:
+                box Expr::MetaProp(MetaPropExpr {
+                    kind: MetaPropKind::ImportMeta,
+                    ..
+                }),
+            prop: MemberProp::Ident(Ident { sym, .. }),
+            ..
+        }) = expr
+            && sym == "hot"
+        {
+            *expr = if self.hmr {
+                quote_ident!(DUMMY_SP.apply_mark(self.unresolved_mark), "module")
+                    .make_member(quote_ident!("meta"))
+                    .make_member(quote_ident!("hot"))
+            } else {
+                quote_ident!(DUMMY_SP.apply_mark(self.unresolved_mark), "undefined").into()
+            };
+            return;
+        }
+
+        expr.visit_mut_children_with(self);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use swc_core::common::GLOBALS;
+    use swc_core::ecma::visit::VisitMutWith;
+
+    use crate::ast::tests::TestUtils;
+
+    #[test]
+    fn test_hmr() {
+        assert_eq!(
+            run(r#"if (import.meta.hot) import.meta.hot.accept();"#, true),
+            "if (module.meta.hot) module.meta.hot.accept();"
+        );
+    }
+
+    #[test]
+    fn test_no_hmr() {
+        assert_eq!(
+            run(r#"if (import.meta.hot) import.meta.hot.accept();"#, false),
+            "if (undefined) undefined.accept();"
+        );
+    }
+
+    #[test]
+    fn test_ignore() {
+        assert_eq!(
+            run(r#"console.log(import.meta.url, import.meta.hotter);"#, true),
+            "console.log(import.meta.url, import.meta.hotter);"
+        );
+    }
+
+    fn run(js_code: &str, hmr: bool) -> String {
+        let mut test_utils = TestUtils::gen_js_ast(js_code);
+        let ast = test_utils.ast.js_mut();
+        GLOBALS.set(&test_utils.context.meta.script.globals, || {
+            let mut visitor = super::ImportMetaHot {
+                hmr,
+                unresolved_mark: ast.unresolved_mark,
+            };
+            ast.ast.visit_mut_with(&mut visitor);
+        });
+        test_utils.js_ast_to_code()
+    }
+}
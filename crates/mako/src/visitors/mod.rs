@@ -8,15 +8,17 @@ pub(crate) mod css_px2rem;
 pub(crate) mod default_export_namer;
 pub(crate) mod dep_analyzer;
 pub(crate) mod dep_replacer;
+pub(crate) mod drop_console;
 pub(crate) mod dynamic_import;
 pub(crate) mod dynamic_import_to_require;
 pub(crate) mod env_replacer;
 pub(crate) mod fix_helper_inject_position;
 pub(crate) mod fix_symbol_conflict;
+pub(crate) mod import_meta_glob;
+pub(crate) mod import_meta_hot;
 pub(crate) mod import_template_to_string_literal;
 pub(crate) mod mako_require;
 pub(crate) mod meta_url_replacer;
-pub(crate) mod new_url_assets;
 pub(crate) mod optimize_define_utils;
 pub(crate) mod provide;
 pub(crate) mod public_path_assignment;
@@ -89,6 +89,9 @@ impl Fold for OptimizePackageImports {
                     resolve_type: ResolveType::Import(import_decl.into()),
                     order: 0,
                     span: None,
+                    chunk_hint: None,
+                    attributes: None,
+                    import_conditions: None,
                 };
 
                 match resolve(&self.path, &dep, &self.context.resolvers, &self.context) {
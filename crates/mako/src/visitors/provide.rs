@@ -5,6 +5,7 @@ use swc_core::common::{Mark, SyntaxContext, DUMMY_SP};
 use swc_core::ecma::ast::{Expr, Ident, MemberExpr, Module, ModuleItem, VarDeclKind};
 use swc_core::ecma::utils::{quote_ident, quote_str, ExprFactory};
 use swc_core::ecma::visit::{VisitMut, VisitMutWith};
+use swc_core::quote;
 
 use crate::config::Providers;
 pub struct Provide {
@@ -51,6 +52,16 @@ impl VisitMut for Provide {
                             .as_call(DUMMY_SP, vec![quote_str!(from.as_str()).as_arg()])
                             .into_var_decl(VarDeclKind::Const, n.clone().into())
                             .into()
+                    } else if key == "default" {
+                        // both esm and cjs modules are supported
+                        // eg: const $ = ((m) => m && m.__esModule ? m.default : m)(require("jquery"));
+                        quote!(
+                            "const $name = ((m) => m && m.__esModule ? m.default : m)($require);"
+                                as ModuleItem,
+                            name = n.clone(),
+                            require: Expr = quote_ident!("__mako_require__")
+                                .as_call(DUMMY_SP, vec![quote_str!(from.as_str()).as_arg()])
+                        )
                     } else {
                         // require("buffer")
                         let require_expr = quote_ident!("__mako_require__")
@@ -165,6 +176,39 @@ console.log({
         );
     }
 
+    #[test]
+    fn test_provide_default_export() {
+        assert_eq!(
+            run(r#"
+$('#app').hide();
+            "#),
+            r#"
+const $ = ((m)=>m && m.__esModule ? m.default : m)(__mako_require__("jquery"));
+$('#app').hide();
+            "#
+            .trim()
+        );
+    }
+
+    #[test]
+    fn test_provide_skip_shadowed() {
+        assert_eq!(
+            run(r#"
+function foo($, Buffer) {
+    $('#app').hide();
+    Buffer.from('foo');
+}
+            "#),
+            r#"
+function foo($, Buffer) {
+    $('#app').hide();
+    Buffer.from('foo');
+}
+            "#
+            .trim()
+        );
+    }
+
     fn run(js_code: &str) -> String {
         let mut test_utils = TestUtils::gen_js_ast(js_code);
         let ast = test_utils.ast.js_mut();
@@ -172,6 +216,7 @@ console.log({
             let mut providers = HashMap::new();
             providers.insert("process".into(), ("process".into(), "".into()));
             providers.insert("Buffer".into(), ("buffer".into(), "Buffer".into()));
+            providers.insert("$".into(), ("jquery".into(), "default".into()));
             let mut visitor = Provide::new(providers, ast.unresolved_mark, ast.top_level_mark);
             ast.ast.visit_mut_with(&mut visitor);
         });
@@ -186,12 +186,15 @@ if ($RefreshIsReactComponentLike$(module.exports)) {
 
 #[cfg(test)]
 mod tests {
+    use std::sync::Arc;
 
     use swc_core::common::{Mark, GLOBALS};
     use swc_core::ecma::visit::VisitMutWith;
 
     use super::react;
-    use crate::ast::tests::TestUtils;
+    use crate::ast::tests::{TestUtils, TestUtilsOpts};
+    use crate::compiler::Context;
+    use crate::config::{Mode, ReactConfig};
 
     #[test]
     fn test_use_refresh() {
@@ -200,6 +203,23 @@ mod tests {
         assert!(code.contains("if (prevRefreshReg) self.$RefreshReg$ = prevRefreshReg;"));
     }
 
+    #[test]
+    fn test_refresh_register() {
+        let code = run(
+            r#"
+function Foo() { const [foo] = useState("foo"); return <div>{foo}</div> }
+const Bar = () => <div>bar</div>;
+function helper() { return 1 }
+            "#,
+            true,
+        );
+        // hooks signature
+        assert!(code.contains("var _s = $RefreshSig$();"));
+        assert!(code.contains(r#"$RefreshReg$(_c, "Foo");"#));
+        assert!(code.contains(r#"$RefreshReg$(_c1, "Bar");"#));
+        assert!(!code.contains(r#""helper""#));
+    }
+
     #[test]
     fn test_jsx() {
         let code = run("function Foo() { return <>foo</> }", false);
@@ -217,6 +237,53 @@ function Foo() {
         );
     }
 
+    #[test]
+    fn test_automatic_runtime() {
+        let code = run_with_react(
+            "function App() { return <div>foo</div> }",
+            react_config("automatic", "react"),
+        );
+        assert_eq!(
+            code,
+            r#"
+import { jsx as _jsx } from "react/jsx-runtime";
+function App() {
+    return /*#__PURE__*/ _jsx("div", {
+        children: "foo"
+    });
+}
+        "#
+            .trim()
+        );
+    }
+
+    #[test]
+    fn test_automatic_runtime_import_source() {
+        let code = run_with_react("<div>foo</div>", react_config("automatic", "preact"));
+        assert!(code.contains(r#"import { jsx as _jsx } from "preact/jsx-runtime";"#));
+    }
+
+    #[test]
+    fn test_classic_runtime() {
+        let code = run_with_react("<>foo</>", react_config("classic", "react"));
+        assert_eq!(
+            code,
+            r#"/*#__PURE__*/ React.createElement(React.Fragment, null, "foo");"#
+        );
+    }
+
+    #[test]
+    fn test_classic_runtime_pragma() {
+        let mut config = react_config("classic", "react");
+        config.pragma = "h".to_string();
+        config.pragma_frag = "Fragment".to_string();
+        let code = run_with_react("<><div>foo</div></>", config);
+        assert_eq!(
+            code,
+            r#"/*#__PURE__*/ h(Fragment, null, /*#__PURE__*/ h("div", null, "foo"));"#
+        );
+    }
+
     #[test]
     fn test_svgr() {
         // ref: jsoneditor/dist/img/jsoneditor-icons.svg
@@ -263,4 +330,40 @@ const Foo = () => (
         });
         test_utils.js_ast_to_code()
     }
+
+    fn react_config(runtime: &str, import_source: &str) -> ReactConfig {
+        ReactConfig {
+            pragma: "React.createElement".to_string(),
+            import_source: import_source.to_string(),
+            runtime: serde_json::from_value(serde_json::json!(runtime)).unwrap(),
+            pragma_frag: "React.Fragment".to_string(),
+        }
+    }
+
+    fn run_with_react(js_code: &str, react_config: ReactConfig) -> String {
+        let mut context = Context::default();
+        context.config.devtool = None;
+        context.config.mode = Mode::Production;
+        context.config.minify = None;
+        context.config.react = react_config;
+        let mut test_utils = TestUtils::with_context(
+            TestUtilsOpts {
+                file: Some("test.jsx".to_string()),
+                content: Some(js_code.to_string()),
+            },
+            Arc::new(context),
+        );
+        let ast = test_utils.ast.js_mut();
+        GLOBALS.set(&test_utils.context.meta.script.globals, || {
+            let mut visitor = react(
+                Default::default(),
+                test_utils.context.clone(),
+                false,
+                &ast.top_level_mark,
+                &ast.unresolved_mark,
+            );
+            ast.ast.visit_mut_with(&mut visitor);
+        });
+        test_utils.js_ast_to_code()
+    }
 }
@@ -1,9 +1,7 @@
 use std::sync::Arc;
 
 use swc_core::common::Mark;
-use swc_core::ecma::ast::{
-    AssignExpr, BlockStmt, CallExpr, Decl, Expr, ExprOrSpread, ExprStmt, ReturnStmt, Stmt, TryStmt,
-};
+use swc_core::ecma::ast::{ArrowExpr, CallExpr, ExprOrSpread, Function, TryStmt};
 use swc_core::ecma::visit::{VisitMut, VisitMutWith};
 
 use crate::ast::utils::{get_first_str_arg, is_commonjs_require};
@@ -29,7 +27,10 @@ impl TryResolve {
                     resolve_as: None,
                     resolve_type: ResolveType::Require,
                     order: 0,
-                    span: None,
+                    span: Some(call_expr.span),
+                    chunk_hint: None,
+                    attributes: None,
+                    import_conditions: None,
                 }];
                 self.context
                     .plugin_driver
@@ -45,7 +46,10 @@ impl TryResolve {
                         resolve_as: None,
                         resolve_type: ResolveType::Require,
                         order: 0,
-                        span: None,
+                        span: Some(call_expr.span),
+                        chunk_hint: None,
+                        attributes: None,
+                        import_conditions: None,
                     },
                     &self.context.resolvers,
                     &self.context,
@@ -62,49 +66,32 @@ impl TryResolve {
 }
 
 impl VisitMut for TryResolve {
-    fn visit_mut_stmt(&mut self, stmt: &mut Stmt) {
-        if let Stmt::Try(box TryStmt {
-            block: BlockStmt { stmts, .. },
-            ..
-        }) = stmt
-        {
-            for stmt in stmts {
-                match stmt {
-                    // e.g. return require('x');
-                    Stmt::Return(ReturnStmt {
-                        arg: Some(box Expr::Call(call_expr)),
-                        ..
-                    }) => {
-                        self.handle_call_expr(call_expr);
-                    }
-                    Stmt::Expr(ExprStmt {
-                        expr: box Expr::Call(call_expr),
-                        ..
-                    })
-                    | Stmt::Expr(ExprStmt {
-                        expr:
-                            box Expr::Assign(AssignExpr {
-                                right: box Expr::Call(call_expr),
-                                ..
-                            }),
-                        ..
-                    }) => self.handle_call_expr(call_expr),
-                    // e.g. var x = require('x');
-                    Stmt::Decl(Decl::Var(var_decl)) => {
-                        for decl in &mut var_decl.decls {
-                            if let Some(Expr::Call(call_expr)) = decl.init.as_deref_mut() {
-                                self.handle_call_expr(call_expr);
-                            }
-                            // support `const a = 1 && require('not-found-module');`
-                            // when decl.init is BinaryExpression, handle left and right recursively
-                            // ref: https://github.com/umijs/mako/issues/1007
-                        }
-                    }
-                    _ => {}
-                }
-            }
-        }
-        stmt.visit_mut_children_with(self);
+    fn visit_mut_try_stmt(&mut self, n: &mut TryStmt) {
+        n.block.visit_mut_with(&mut TryBlock { try_resolve: self });
+        n.handler.visit_mut_with(self);
+        n.finalizer.visit_mut_with(self);
+    }
+}
+
+// requires anywhere in the try block, e.g.
+// try { const a = 1 && require('x'); } catch (e) {}
+// but not the ones in functions, since they are not called in the try block
+struct TryBlock<'a> {
+    try_resolve: &'a mut TryResolve,
+}
+
+impl<'a> VisitMut for TryBlock<'a> {
+    fn visit_mut_call_expr(&mut self, n: &mut CallExpr) {
+        self.try_resolve.handle_call_expr(n);
+        n.visit_mut_children_with(self);
+    }
+
+    fn visit_mut_function(&mut self, n: &mut Function) {
+        n.visit_mut_children_with(self.try_resolve);
+    }
+
+    fn visit_mut_arrow_expr(&mut self, n: &mut ArrowExpr) {
+        n.visit_mut_children_with(self.try_resolve);
     }
 }
 
@@ -186,6 +173,41 @@ try {
         );
     }
 
+    #[test]
+    fn test_try_require_nested_expr() {
+        assert_eq!(
+            run(r#"try{if(a){const x = 1 && require('foo')}}catch(e){}"#),
+            r#"
+try {
+    if (a) {
+        const x = 1 && require(Object(function makoMissingModule() {
+            var e = new Error("Cannot find module 'foo'");
+            e.code = "MODULE_NOT_FOUND";
+            throw e;
+        }()));
+    }
+} catch (e) {}
+        "#
+            .trim()
+        );
+    }
+
+    #[test]
+    fn test_try_require_in_function() {
+        // the function is not called in the try block
+        assert_eq!(
+            run(r#"try{fn = () => require('foo')}catch(e){require('bar')}"#),
+            r#"
+try {
+    fn = ()=>require('foo');
+} catch (e) {
+    require('bar');
+}
+        "#
+            .trim()
+        );
+    }
+
     #[test]
     fn test_try_require_dont_support_import() {
         assert_eq!(
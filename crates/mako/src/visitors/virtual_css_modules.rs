@@ -14,7 +14,10 @@ fn is_css_modules_path(path: &str) -> bool {
 }
 
 pub fn is_css_path(path: &str) -> bool {
-    path.ends_with(".css") || path.ends_with(".less") || path.ends_with(".scss")
+    path.ends_with(".css")
+        || path.ends_with(".less")
+        || path.ends_with(".scss")
+        || path.ends_with(".sass")
 }
 
 impl VisitMut for VirtualCSSModules {
@@ -105,6 +108,10 @@ mod tests {
             run(r#"import x from "./foo.css";"#, true),
             r#"import x from "./foo.css?asmodule";"#
         );
+        assert_eq!(
+            run(r#"import x from "./foo.sass";"#, true),
+            r#"import x from "./foo.sass?asmodule";"#
+        );
         assert_eq!(
             run(r#"import "./foo.css";"#, true),
             r#"import "./foo.css";"#
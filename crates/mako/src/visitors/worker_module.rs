@@ -1,9 +1,15 @@
 use swc_core::common::Mark;
-use swc_core::ecma::ast::{Expr, Lit, NewExpr, Str};
+use swc_core::ecma::ast::{Expr, Ident, Lit, NewExpr, Str};
 use swc_core::ecma::visit::{VisitMut, VisitMutWith};
 
 use crate::ast::utils::{is_ident_undefined, is_remote_or_data};
 
+// new Worker() or new SharedWorker()
+pub fn is_worker_constructor(ident: &Ident, unresolved_mark: &Mark) -> bool {
+    is_ident_undefined(ident, "Worker", unresolved_mark)
+        || is_ident_undefined(ident, "SharedWorker", unresolved_mark)
+}
+
 pub struct WorkerModule {
     unresolved_mark: Mark,
 }
@@ -23,8 +29,7 @@ impl VisitMut for WorkerModule {
         }
 
         if let box Expr::Ident(ident) = &mut new_expr.callee {
-            #[allow(clippy::needless_borrow)]
-            if is_ident_undefined(&ident, "Worker", &self.unresolved_mark) {
+            if is_worker_constructor(ident, &self.unresolved_mark) {
                 let args = new_expr.args.as_mut().unwrap();
 
                 // new Worker(new URL(''), base);
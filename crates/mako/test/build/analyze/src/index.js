@@ -0,0 +1,4 @@
+import { foo } from "./foo";
+
+console.log(foo);
+import("./lazy").then(console.log);
@@ -0,0 +1,4 @@
+import logo from './logo.png';
+import './index.css';
+
+console.log(logo);
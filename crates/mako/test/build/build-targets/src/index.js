@@ -0,0 +1,4 @@
+import env from 'env-pkg';
+import shared from 'shared-pkg';
+
+export default `${env} ${shared}`;
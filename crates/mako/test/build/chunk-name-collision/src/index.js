@@ -0,0 +1,2 @@
+import(/* webpackChunkName: "pages/a" */ './a');
+import(/* webpackChunkName: "pages_a" */ './b');
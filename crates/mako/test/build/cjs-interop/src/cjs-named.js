@@ -0,0 +1 @@
+exports.named = "named";
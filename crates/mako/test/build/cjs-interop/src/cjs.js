@@ -0,0 +1 @@
+exports.default = "cjs";
@@ -0,0 +1,6 @@
+import cjs from "./cjs";
+import { named } from "./cjs-named";
+import esModule from "./es-module";
+import esm from "./esm";
+
+console.log(cjs, named, esModule, esm);
@@ -0,0 +1,3 @@
+import { shared } from './shared';
+
+export default 'a' + shared;
@@ -0,0 +1,3 @@
+import('./a').then((m) => console.log(m));
+import('./b').then((m) => console.log(m));
+import('./c').then((m) => console.log(m));
@@ -0,0 +1 @@
+import('./small').then(({ small }) => console.log(small));
@@ -0,0 +1,4 @@
+import React from 'react';
+import { shared } from './shared';
+
+console.log('b', React, shared);
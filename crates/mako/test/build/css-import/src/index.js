@@ -0,0 +1,5 @@
+import './global.css';
+import styles from './style.module.css';
+import * as plain from './plain.css';
+
+console.log(styles.foo, plain);
@@ -0,0 +1,3 @@
+import './false.css';
+
+console.log('devtool: false');
@@ -0,0 +1,3 @@
+import './inline-source-map.css';
+
+console.log('devtool: inline-source-map');
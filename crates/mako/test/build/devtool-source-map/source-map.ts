@@ -0,0 +1,3 @@
+import './source-map.css';
+
+console.log('devtool: source-map');
@@ -0,0 +1 @@
+import("./lazy").then((m) => console.log(m.lazy));
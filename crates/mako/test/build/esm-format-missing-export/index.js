@@ -0,0 +1,3 @@
+import { b } from './a';
+
+console.log(b);
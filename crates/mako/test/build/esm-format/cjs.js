@@ -0,0 +1,4 @@
+const { PI } = require('./math');
+
+exports.name = 'cjs';
+exports.circle = (r) => PI * r * r;
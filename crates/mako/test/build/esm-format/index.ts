@@ -0,0 +1,15 @@
+import { add, PI } from './math';
+import * as utils from './utils';
+import config from './config.json';
+import cjs, { circle } from './cjs';
+import React from 'react';
+import { debounce } from 'lodash';
+
+export { add };
+export const area = (r: number) => PI * r * r;
+
+export default function main() {
+  console.log(utils.name, utils.PI, config.name);
+  console.log(cjs.name, circle(1), React.version, debounce);
+  return import('./lazy').then(({ lazy }) => lazy(add(1, 2)));
+}
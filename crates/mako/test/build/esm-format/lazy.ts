@@ -0,0 +1,5 @@
+import { add } from './math';
+
+export function lazy(n: number) {
+  return add(n, 1);
+}
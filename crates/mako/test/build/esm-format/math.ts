@@ -0,0 +1,5 @@
+export const PI = 3.14;
+
+export function add(a: number, b: number) {
+  return a + b;
+}
@@ -0,0 +1,2 @@
+export const name = 'utils';
+export * from './math';
@@ -0,0 +1,16 @@
+import './m0';
+import './m1';
+import './m2';
+import './m3';
+import './m4';
+import './m5';
+import './m6';
+import './m7';
+import './m8';
+import './m9';
+import './m10';
+import './m11';
+import './m12';
+import './m13';
+import './m14';
+import './m15';
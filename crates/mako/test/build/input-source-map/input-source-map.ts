@@ -0,0 +1,3 @@
+import { add } from './lib/compiled';
+
+console.log(add(1, 2));
@@ -0,0 +1,3 @@
+import './a.css';
+
+import('./c').then((m) => console.log(m));
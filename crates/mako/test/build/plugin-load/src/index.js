@@ -0,0 +1,2 @@
+import routes from 'virtual:routes';
+console.log(routes);
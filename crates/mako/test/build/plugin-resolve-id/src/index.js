@@ -0,0 +1,3 @@
+import config from 'virtual:config';
+import mode from 'env:mode';
+console.log(config.name, mode);
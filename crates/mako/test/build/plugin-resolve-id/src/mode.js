@@ -0,0 +1 @@
+export default 'mode from env';
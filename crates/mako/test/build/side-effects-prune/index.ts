@@ -0,0 +1,4 @@
+import { used } from 'pure';
+import { kept } from 'globs';
+
+console.log(used, kept);
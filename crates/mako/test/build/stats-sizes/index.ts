@@ -0,0 +1,6 @@
+import './index.css';
+import { add } from './add';
+import data from './data.json';
+
+console.log(add(1, 2), data.name);
+import('./lazy').then(({ lazy }) => lazy());
@@ -0,0 +1,4 @@
+import * as math from './add.wasm';
+import * as math2 from './add.bin' with { type: 'webassembly' };
+
+console.log(math.add(1, 2), math2.add(1, 2));
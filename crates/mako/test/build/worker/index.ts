@@ -0,0 +1,7 @@
+const worker = new Worker(new URL('./worker.ts', import.meta.url), {
+  type: 'module',
+});
+worker.postMessage('ping');
+
+const shared = new SharedWorker(new URL('./shared.ts', import.meta.url));
+shared.port.postMessage('ping');
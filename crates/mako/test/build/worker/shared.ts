@@ -0,0 +1,4 @@
+self.onconnect = (e) => {
+  const port = e.ports[0];
+  port.onmessage = (e) => port.postMessage(`shared: ${e.data}`);
+};
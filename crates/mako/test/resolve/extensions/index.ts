@@ -0,0 +1 @@
+import './src/foo';
@@ -0,0 +1,29 @@
+const assert = require("assert");
+const { parseBuildResult } = require("../../../scripts/test-utils");
+const { files } = parseBuildResult(__dirname);
+
+// default only
+const defaultOnly = require("./dist/default.js");
+assert.strictEqual(defaultOnly.default(), "hello", "default export should work");
+assert.deepStrictEqual(Object.keys(defaultOnly), ["default"]);
+
+// named only
+const namedOnly = require("./dist/named.js");
+assert.strictEqual(namedOnly.foo, "foo", "named const export should work");
+assert.strictEqual(namedOnly.bar(), "bar", "named function export should work");
+assert.strictEqual(namedOnly.qux, "baz", "renamed export should work");
+assert.strictEqual(namedOnly.baz, undefined, "local name should not be exported");
+assert.strictEqual(namedOnly.default, undefined, "should not have default export");
+
+// mixed
+const mixed = require("./dist/mixed.js");
+assert.strictEqual(mixed.default, "mixed", "default export should work");
+assert.strictEqual(mixed.b, "a", "renamed export should work");
+assert.strictEqual(mixed.dep, "ext-dep", "external should be required");
+
+// externals are required instead of read from global
+assert(
+  files["mixed.js"].includes('module.exports = require("ext-dep");'),
+  "external should be required",
+);
+assert(!files["mixed.js"].includes("ExtDep"), "external should not be read from global");
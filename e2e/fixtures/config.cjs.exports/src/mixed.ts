@@ -0,0 +1,6 @@
+import { name } from 'ext-dep';
+
+const a = 'a';
+export { a as b };
+export const dep = name;
+export default 'mixed';
@@ -0,0 +1,6 @@
+export const foo = 'foo';
+export function bar() {
+  return 'bar';
+}
+const baz = 'baz';
+export { baz as qux };
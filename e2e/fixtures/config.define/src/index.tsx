@@ -39,3 +39,11 @@ it("defined stringified object value should be right", () => {
   expect(III).toEqual({ v: 1 })
 });
 
+it("defined member expression value should be right", () => {
+  expect(process.env.API_URL).toEqual("/api")
+  expect(__APP__.version).toEqual("1.0.0")
+});
+it("shadowed variables should not be replaced", () => {
+  const AAA = "local";
+  expect(AAA).toEqual("local")
+});
@@ -75,6 +75,20 @@ assert.match(
   "should external 2-level subpath with PascalCase"
 );
 
+assert.match(
+  content,
+  moduleReg(
+    "@scope/foo",
+    "module.exports = \\(typeof globalThis !== 'undefined' \\? globalThis : self\\).Scope.foo;"
+  ),
+  "should external with regex and captured groups"
+);
+
+assert(
+  content.includes(`module.exports = require("node:fs");`),
+  `should external with regex to commonjs`,
+);
+
 assert.match(
   content,
   moduleReg(
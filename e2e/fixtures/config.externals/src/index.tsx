@@ -8,6 +8,9 @@ import zh_CN from "antd/es/locale/zh_CN";
 import DatePicker from "antd/es/date-picker";
 import InputGroup from "antd/es/input/Group";
 import ScriptType from 'script';
+import scopeFoo from "@scope/foo";
+import fs from "node:fs";
 
 console.log(hoo, hoo_require, foo_require, empty, version, zh_CN, InputGroup, DatePicker);
 console.log(ScriptType);
+console.log(scopeFoo, fs);
@@ -28,3 +28,15 @@ assert(
   trim(files["index.css"]).includes(`.e{`),
   "const e = require('./e.css') should not be css modules"
 );
+
+assert.match(
+  trim(files["index.css"]),
+  /.shared-.{8}{color:red;}/,
+  "css composed from other file should be included as css modules"
+);
+
+assert(
+  files["index.js"].includes(`"composed": \`composed-`) &&
+    files["index.js"].includes(`["shared"]}\``),
+  "composes from other file should reference its exported class name"
+);
@@ -0,0 +1,32 @@
+const assert = require("assert");
+const { parseBuildResult } = require("../../../scripts/test-utils");
+const { files } = parseBuildResult(__dirname);
+
+const content = files["index.css"];
+
+// shared.css is imported by both a.ts and b.ts, but only emitted once
+assert.strictEqual(
+  content.match(/\.shared \{/g).length,
+  1,
+  "shared.css should be emitted once",
+);
+
+// css is emitted in the order it is first imported
+assert.strictEqual(
+  content.replace(/\/\*# sourceMappingURL.*\*\//, "").trim(),
+  `
+.index {
+  color: red;
+}
+.shared {
+  color: gray;
+}
+.a {
+  color: red;
+}
+.b {
+  color: red;
+}
+  `.trim(),
+  "css should be emitted in first-import order",
+);
@@ -0,0 +1,4 @@
+import './shared.css';
+import './a.css';
+
+export const A = 'a';
@@ -0,0 +1,4 @@
+import './b.css';
+import './shared.css';
+
+export const B = 'b';
@@ -0,0 +1,5 @@
+import './index.css';
+import { A } from './a';
+import { B } from './b';
+
+console.log(A, B);
@@ -0,0 +1,41 @@
+const assert = require("assert");
+const { parseBuildResult } = require("../../../scripts/test-utils");
+const { files } = parseBuildResult(__dirname);
+
+const strip = (content) => content.replace(/\/\*# sourceMappingURL.*\*\//, "").trim();
+
+// each entry has its own css in import order, shared css is emitted to both entries
+assert.strictEqual(
+  strip(files["a.css"]),
+  `
+.base {
+  color: gray;
+}
+.a {
+  color: red;
+}
+.adep {
+  color: orange;
+}
+.a2 {
+  color: pink;
+}
+  `.trim(),
+  "a.css should only contain styles of entry a",
+);
+assert.strictEqual(
+  strip(files["b.css"]),
+  `
+.b {
+  color: blue;
+}
+.base {
+  color: gray;
+}
+  `.trim(),
+  "b.css should only contain styles of entry b",
+);
+
+// styles are not injected by js
+assert(!files["a.js"].includes("color"), "a.js should not contain styles");
+assert(!files["b.js"].includes("color"), "b.js should not contain styles");
@@ -0,0 +1,4 @@
+import './base.css';
+import './a.css';
+import './a2.css';
+console.log('a');
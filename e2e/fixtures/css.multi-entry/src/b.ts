@@ -0,0 +1,3 @@
+import './b.css';
+import './base.css';
+console.log('b');
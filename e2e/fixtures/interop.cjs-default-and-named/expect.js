@@ -0,0 +1,16 @@
+const assert = require("assert");
+
+const { defaultOnly, named, mixed } = require("./dist/index.js");
+
+// the default import of a cjs module without __esModule is the whole module.exports
+assert.strictEqual(defaultOnly.foo(), "foo", "default import should be module.exports");
+assert.strictEqual(defaultOnly.foo.bar, "bar", "default import should keep the properties");
+assert.strictEqual(defaultOnly.esModule, "default", "default import of __esModule should be exports.default");
+
+// named imports are the properties of module.exports
+assert.strictEqual(named.bar, "bar", "named import should be the property of module.exports");
+
+assert.strictEqual(mixed.foo(), "foo", "mixed default import should be module.exports");
+assert.strictEqual(mixed.bar, "bar", "mixed named import should be the property of module.exports");
+assert.strictEqual(mixed.esModule, "default", "mixed default import of __esModule should be exports.default");
+assert.strictEqual(mixed.esModuleBar, "bar", "mixed named import of __esModule should be the property");
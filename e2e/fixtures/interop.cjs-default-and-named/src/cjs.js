@@ -0,0 +1,4 @@
+module.exports = function foo() {
+  return "foo";
+};
+module.exports.bar = "bar";
@@ -0,0 +1,4 @@
+import foo from "./cjs";
+import esModule from "./es-module";
+
+export { foo, esModule };
@@ -0,0 +1,3 @@
+export * as defaultOnly from "./default";
+export * as named from "./named";
+export * as mixed from "./mixed";
@@ -0,0 +1,4 @@
+import foo, { bar } from "./cjs";
+import esModule, { bar as esModuleBar } from "./es-module";
+
+export { foo, bar, esModule, esModuleBar };
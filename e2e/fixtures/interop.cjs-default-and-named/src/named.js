@@ -0,0 +1,3 @@
+import { bar } from "./cjs";
+
+export { bar };
@@ -0,0 +1,8 @@
+const assert = require("assert");
+const { parseBuildResult } = require("../../../scripts/test-utils");
+parseBuildResult(__dirname);
+
+const output = require("./dist/index.js");
+assert.strictEqual(output.default, "x", "`export { default } from` should re-export the default");
+assert.strictEqual(output.Foo(), "y", "`export { default as Foo } from` should re-export the default as Foo");
+assert.deepStrictEqual(output.values, ["x", "y"], "default re-exports should be imported by other modules");
@@ -0,0 +1,6 @@
+import x, { Foo as foo } from './reexport';
+
+export { default } from './x';
+export { default as Foo } from './y';
+
+export const values = [x, foo()];
@@ -0,0 +1,2 @@
+export { default } from './x';
+export { default as Foo } from './y';
@@ -0,0 +1,5 @@
+const { injectSimpleJest } = require("../../../scripts/test-utils");
+
+injectSimpleJest();
+
+require("./dist/index");
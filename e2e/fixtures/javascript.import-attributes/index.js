@@ -0,0 +1,10 @@
+import foo from './foo.json' with { type: 'json' };
+import bar from './bar.txt' assert { type: 'json' };
+
+it('should load json with import attributes', () => {
+  expect(foo.name).toEqual('foo');
+});
+
+it('should load non-json file as json with type json', () => {
+  expect(bar.name).toEqual('bar');
+});
@@ -0,0 +1,13 @@
+const {
+  injectSimpleJest,
+  parseBuildResult,
+} = require("../../../scripts/test-utils");
+const { files } = parseBuildResult(__dirname);
+
+injectSimpleJest();
+
+require("./dist/index");
+
+expect(
+  Object.keys(files).filter((f) => f.endsWith("-async.js")).length,
+).toEqual(2);
@@ -0,0 +1,16 @@
+const lazy = import.meta.glob('./modules/*.js');
+const eager = import.meta.glob('./modules/**/*.js', { eager: true });
+
+it('should expand to eager modules', () => {
+  expect(Object.keys(eager)).toEqual([
+    './modules/a.js',
+    './modules/b.js',
+    './modules/dir/c.js',
+  ]);
+  expect(eager['./modules/dir/c.js'].default).toEqual('c');
+});
+
+it('should expand to lazy modules', () => {
+  expect(Object.keys(lazy)).toEqual(['./modules/a.js', './modules/b.js']);
+  expect(lazy['./modules/a.js']()).resolves.toMatchObject({ default: 'a' });
+});
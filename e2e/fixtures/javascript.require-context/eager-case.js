@@ -0,0 +1,23 @@
+let context = require.context("./context", false, /^\.\/(A|B)\.js$/i, "eager");
+
+it("eager: no sub directorie", () => {
+  it("  should get list of context files", () => {
+    expect(context.keys().sort()).toStrictEqual(["./a.js", "./b.js"]);
+  });
+
+  it("  should resolve with the module", () => {
+    expect(context("./a.js")).resolves.toStrictEqual({
+      default: "a.js",
+    });
+  });
+
+  it("  follow webpack id convention", () => {
+    expect(context.id).toBe("./context/ eager nonrecursive ^./(A|B).js$/");
+  });
+});
+
+it("eager: rejects when require unknow request", () => {
+  expect(() => {
+    return context("./not_exists.js");
+  }).rejects.toThrow("Cannot find module './not_exists.js'");
+});
@@ -1,2 +1,3 @@
 require("./sync-case");
 require("./lazy-case");
+require("./eager-case");
@@ -0,0 +1,30 @@
+const assert = require("assert");
+
+const { parseBuildResult, trim } = require("../../../scripts/test-utils");
+const { files } = parseBuildResult(__dirname);
+
+const content = trim(files["index.css"]);
+
+assert.match(
+  content,
+  /\.title{color:red;border-radius:4px;}/,
+  "should support variables and mixins"
+);
+
+assert.match(
+  content,
+  /\.link{color:green;}/,
+  "should support variables from imported less files"
+);
+
+assert.match(
+  content,
+  /\.button{color:#1DA57A;border-radius:2px;}/i,
+  "should support modifyVars and mixins from imported less files"
+);
+
+assert.doesNotMatch(
+  content,
+  /\.rounded/,
+  "should not output parametric mixins"
+);
@@ -0,0 +1,5 @@
+import { b } from "./b";
+import { common } from "./common";
+
+export const a = "a" + b;
+export const aCommon = "a" + common;
@@ -0,0 +1,17 @@
+const {
+	injectSimpleJest,
+	parseBuildResult,
+	moduleDefinitionOf,
+} = require("../../../scripts/test-utils");
+const { files } = parseBuildResult(__dirname);
+injectSimpleJest();
+
+// a.js and b.js are only imported by the root, they share the scope of index.js
+expect(files["index.js"]).not.toContain(moduleDefinitionOf("a.js"));
+expect(files["index.js"]).not.toContain(moduleDefinitionOf("b.js"));
+// shared.js is also required by cjs.js, so it stays wrapped
+expect(files["index.js"]).toContain(moduleDefinitionOf("shared.js"));
+// common.js is also imported by lazy.js of the async chunk, so it stays wrapped
+expect(files["index.js"]).toContain(moduleDefinitionOf("common.js"));
+
+require("./dist/index.js");
@@ -0,0 +1,13 @@
+import { a, aCommon } from "./a";
+import { shared } from "./shared";
+import { loadLazy } from "./loader";
+const fromCjs = require("./cjs");
+
+it("should hoist the modules imported by a single parent", async function () {
+  expect(a).toBe("ab");
+  expect(aCommon).toBe("acommon");
+  expect(shared).toBe("shared");
+  expect(fromCjs).toBe("shared");
+  const { lazy } = await loadLazy();
+  expect(lazy).toBe("lazycommon");
+});
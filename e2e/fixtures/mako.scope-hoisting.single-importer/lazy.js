@@ -0,0 +1,3 @@
+import { common } from "./common";
+
+export const lazy = "lazy" + common;
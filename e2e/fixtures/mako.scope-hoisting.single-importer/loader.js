@@ -0,0 +1 @@
+export const loadLazy = () => import("./lazy");
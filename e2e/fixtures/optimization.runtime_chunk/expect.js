@@ -0,0 +1,16 @@
+const assert = require("assert");
+const path = require("path");
+const { parseBuildResult } = require("../../../scripts/test-utils");
+const { files, distDir } = parseBuildResult(__dirname);
+
+assert(files["runtime.js"].includes("__mako_require_module__"), "runtime chunk should contain the runtime");
+assert(!files["a.js"].includes("__mako_require_module__"), "entry a should not contain the runtime");
+assert(!files["b.js"].includes("__mako_require_module__"), "entry b should not contain the runtime");
+
+// entries loaded before the runtime are executed once the runtime is ready
+require(path.join(distDir, "b.js"));
+assert.equal(globalThis.b, undefined);
+require(path.join(distDir, "runtime.js"));
+assert.equal(globalThis.b, 1);
+require(path.join(distDir, "a.js"));
+assert.equal(globalThis.a, 2, "the shared module should be executed once");
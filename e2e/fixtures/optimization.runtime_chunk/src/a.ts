@@ -0,0 +1,2 @@
+import { count } from "./shared";
+globalThis.a = count();
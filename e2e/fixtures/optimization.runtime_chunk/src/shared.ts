@@ -0,0 +1,5 @@
+let n = 0;
+// modules are shared by entries with the single runtime
+export function count() {
+  return ++n;
+}
@@ -0,0 +1,15 @@
+const assert = require("assert");
+const { parseBuildResult } = require("../../../scripts/test-utils");
+parseBuildResult(__dirname);
+
+const output = require("./dist/index.js");
+assert.strictEqual(
+  output.watcher,
+  "MODULE_NOT_FOUND",
+  "try-wrapped require of missing module should throw when executed",
+);
+assert.throws(
+  () => output.loadNative(),
+  (e) => e.code === "MODULE_NOT_FOUND" && e.message.includes("optional-native-dep"),
+  "optional dep should throw only when it's required",
+);
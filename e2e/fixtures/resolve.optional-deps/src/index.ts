@@ -0,0 +1,12 @@
+let watcher = null;
+try {
+  watcher = require('not-installed-watcher');
+} catch (e) {
+  watcher = e.code;
+}
+
+export { watcher };
+
+export function loadNative() {
+  return require('optional-native-dep');
+}
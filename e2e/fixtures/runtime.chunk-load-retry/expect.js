@@ -0,0 +1,34 @@
+const assert = require("assert");
+const vm = require("vm");
+const { parseBuildResult } = require("../../../scripts/test-utils");
+const { files } = parseBuildResult(__dirname);
+
+// a document whose first load of a chunk fails, and the retry succeeds
+let attempts = 0;
+const context = vm.createContext({
+  setTimeout,
+  clearTimeout,
+  console,
+  document: {
+    querySelector: () => null,
+    createElement: () => ({}),
+    head: {
+      appendChild(script) {
+        attempts += 1;
+        if (attempts === 1) {
+          script.onerror({ type: "error", target: script });
+        } else {
+          vm.runInContext(files[script.src.replace(/^\//, "")], context);
+          script.onload({ type: "load", target: script });
+        }
+      },
+    },
+  },
+});
+context.self = context;
+vm.runInContext(files["index.js"], context);
+
+module.exports = async () => {
+  assert.strictEqual(await context.result, "lazy");
+  assert.strictEqual(attempts, 2, "the chunk should be loaded again after the failure");
+};
@@ -0,0 +1 @@
+globalThis.result = import('./lazy').then((m) => m.value);
@@ -0,0 +1,31 @@
+const assert = require("assert");
+const vm = require("vm");
+const { parseBuildResult } = require("../../../scripts/test-utils");
+const { files } = parseBuildResult(__dirname);
+
+// a document which records the appended link tags
+const links = [];
+const context = vm.createContext({
+  setTimeout,
+  clearTimeout,
+  console,
+  document: {
+    querySelector: () => null,
+    createElement: () => ({}),
+    head: {
+      appendChild(el) {
+        links.push(el);
+      },
+    },
+  },
+});
+context.self = context;
+vm.runInContext(files["index.js"], context);
+
+module.exports = async () => {
+  assert.deepStrictEqual(
+    links.map((link) => [link.rel, link.href]),
+    [["prefetch", "/static/lazy-async.js"]],
+    "the hinted chunk should be prefetched once with the public path",
+  );
+};
@@ -0,0 +1,7 @@
+export function load() {
+  return import(/* webpackChunkName: "lazy", webpackPrefetch: true */ './lazy');
+}
+
+export function loadAgain() {
+  return import(/* webpackPrefetch: true */ './lazy');
+}
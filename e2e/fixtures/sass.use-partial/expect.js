@@ -0,0 +1,33 @@
+const assert = require("assert");
+const { parseBuildResult, trim } = require("../../../scripts/test-utils");
+const { files } = parseBuildResult(__dirname);
+
+const content = trim(files["index.css"]);
+
+assert.match(
+  content,
+  /\.card{color:#333;/,
+  "should support @use of partials from includePaths"
+);
+
+assert(
+  content.includes("border:1pxsolidpurple;"),
+  "should support @use of relative partials and additionalData"
+);
+
+assert(
+  content.includes(".card.title{font-weight:bold;}"),
+  "should support nesting"
+);
+
+assert.match(
+  content,
+  /background:url\("?data:image\/png;base64,/,
+  "should analyze deps of the compiled css"
+);
+
+assert(
+  content.includes(".indented{color:red;}") &&
+    content.includes(".indented.nested{color:blue;}"),
+  "should support indented syntax without the scss additionalData"
+);
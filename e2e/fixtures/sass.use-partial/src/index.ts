@@ -0,0 +1,2 @@
+import './index.scss';
+import './indented.sass';
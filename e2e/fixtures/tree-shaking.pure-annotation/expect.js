@@ -0,0 +1,11 @@
+const assert = require("assert");
+const { parseBuildResult } = require("../../../scripts/test-utils");
+const { files } = parseBuildResult(__dirname);
+
+const content = files["index.js"];
+
+assert(!content.includes("pure unused call"), "should remove unused pure call");
+assert(!content.includes("unused new"), "should remove unused pure new expression");
+assert(!content.includes("pure unused result"), "should remove pure call assigned to unused variable");
+assert(content.includes("pure assigned"), "should keep pure call whose result is used");
+assert(content.includes("side effect"), "should keep call without pure annotation");
@@ -0,0 +1,2 @@
+import { kept } from './lib';
+console.log(kept);
@@ -0,0 +1,10 @@
+function create(name: string) {
+  console.log('created ' + name);
+  return { name };
+}
+
+/*#__PURE__*/ create('pure unused call');
+/*#__PURE__*/ new Map([['pure', 'unused new']]);
+const unusedResult = /*#__PURE__*/ create('pure unused result');
+export const kept = /*#__PURE__*/ create('pure assigned');
+create('side effect');
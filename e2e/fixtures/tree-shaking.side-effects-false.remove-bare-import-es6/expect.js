@@ -4,5 +4,4 @@ const { files } = parseBuildResult(__dirname);
 
 const content = files["index.js"];
 
-// TODO: esm 的也没删？
-// assert(!content.includes("REMOVE"), `should not have REMOVE`);
+assert(!content.includes("console.log('hello')"), `should remove bare import of side effects free module`);
@@ -0,0 +1,11 @@
+const assert = require("assert");
+const { parseBuildResult } = require("../../../scripts/test-utils");
+const { files } = parseBuildResult(__dirname);
+
+const content = files["index.js"];
+
+assert(content.includes("a used"), "should keep used export");
+assert(!content.includes("a unused"), "should remove unused exports");
+assert(!content.includes("a helper"), "should remove declarations only used by unused exports");
+assert(content.includes("b chained"), "should keep export used through re-export chain");
+assert(!content.includes("b not chained"), "should remove unused export behind re-export chain");
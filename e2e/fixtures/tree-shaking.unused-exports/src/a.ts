@@ -0,0 +1,6 @@
+const helper = () => 'a helper';
+export const used = 'a used';
+export const unused = 'a unused ' + helper();
+export function unusedFn() { return 'a unused fn'; }
+export class UnusedClass { x = 'a unused class'; }
+export default 'a unused default';
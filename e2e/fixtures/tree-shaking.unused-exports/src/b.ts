@@ -0,0 +1,2 @@
+export const chained = 'b chained';
+export const notChained = 'b not chained';
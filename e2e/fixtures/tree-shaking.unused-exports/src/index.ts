@@ -0,0 +1,2 @@
+import { used, chained } from './reexport';
+console.log(used, chained);
@@ -0,0 +1,2 @@
+export { used, unused } from './a';
+export { chained } from './chain';
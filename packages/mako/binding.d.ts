@@ -60,18 +60,33 @@ export interface BuildParams {
   root: string;
   config: {
     entry?: Record<string, string>;
+    env?: {
+      development?: Record<string, any>;
+      production?: Record<string, any>;
+    };
     output?: {
       path: string;
       mode: 'bundle' | 'bundless';
       esVersion?: string;
       meta?: boolean;
+      chunkLoadTimeout?: number;
+      chunkLoadRetries?: number;
       preserveModules?: boolean;
       preserveModulesRoot?: string;
       skipWrite?: boolean;
+      filename?: string;
+      assetFileName?: string;
+      format?: "esm";
+      clean?: boolean | { keep?: string[] };
+      banner?: string;
+      footer?: string;
     };
     resolve?: {
       alias?: Array<[string, string]>;
       extensions?: string[];
+      mainFields?: string[];
+      optionalDeps?: string[];
+      nodeBuiltins?: Record<string, string>;
     };
     manifest?:
       | false
@@ -103,6 +118,12 @@ export interface BuildParams {
       | false
       | {
           strategy: 'auto';
+          options?: {
+            vendor?: boolean;
+            allowChunks?: 'all' | 'entry' | 'async';
+            minSize?: number;
+            minChunks?: number;
+          };
         }
       | {
           strategy: 'granular';
@@ -115,6 +136,7 @@ export interface BuildParams {
           strategy: 'advanced';
           options: {
             minSize?: number;
+            maxSize?: number;
             groups: {
               name: string;
               allowChunks?: 'all' | 'entry' | 'async';
@@ -129,10 +151,29 @@ export interface BuildParams {
     providers?: Record<string, string[]>;
     publicPath?: string;
     inlineLimit?: number;
-    targets?: Record<string, number>;
+    targets?: Record<string, number> | string | string[];
+    polyfill?: "usage" | "entry" | false;
     platform?: 'node' | 'browser';
     hmr?: false | {};
-    devServer?: false | { host?: string; port?: number };
+    devServer?:
+      | false
+      | {
+          host?: string;
+          port?: number;
+          proxy?: Record<
+            string,
+            {
+              target: string;
+              changeOrigin?: boolean;
+              pathRewrite?: Record<string, string>;
+            }
+          >;
+          historyApiFallback?:
+            | boolean
+            | {
+                rewrites?: { from: string; to: string }[];
+              };
+        };
     px2rem?:
       | false
       | {
@@ -144,13 +185,27 @@ export interface BuildParams {
           selectorDoubleList?: string[];
           mediaQuery?: boolean;
         };
+    autoprefixer?:
+      | false
+      | {
+          targets?: Record<string, number>;
+        };
     stats?: boolean;
+    analyze?: boolean | {};
     hash?: boolean;
     autoCSSModules?: boolean;
     ignoreCSSParserErrors?: boolean;
     dynamicImportToRequire?: boolean;
     umd?: false | string;
+    threads?: number;
+    profile?: boolean;
     cjs?: boolean;
+    buildTargets?: {
+      platform: 'browser' | 'node';
+      format?: 'esm' | 'cjs';
+      path: string;
+      externals?: Record<string, string>;
+    }[];
     writeToDisk?: boolean;
     transformImport?: {
       libraryName: string;
@@ -158,10 +213,26 @@ export interface BuildParams {
       style?: boolean | string;
     }[];
     clean?: boolean;
+    cache?:
+      | boolean
+      | {
+          dir?: string;
+        };
     nodePolyfill?: boolean;
     ignores?: string[];
-    moduleIdStrategy?: 'hashed' | 'named';
-    minify?: boolean;
+    moduleIdStrategy?: 'hashed' | 'named' | 'deterministic' | 'numeric';
+    minify?:
+      | boolean
+      | {
+          dropConsole?: boolean | string[];
+          dropDebugger?: boolean;
+        };
+    mangle?:
+      | false
+      | {
+          reserved?: string[];
+          toplevel?: boolean;
+        };
     _minifish?:
       | false
       | {
@@ -195,6 +266,8 @@ export interface BuildParams {
       | false
       | {
           skipModules?: boolean;
+          runtimeChunk?: 'single';
+          moduleIds?: 'hashed' | 'named' | 'deterministic' | 'numeric';
         };
     react?: {
       runtime?: 'automatic' | 'classic';
@@ -203,7 +276,14 @@ export interface BuildParams {
       pragmaFrag?: string;
     };
     emitAssets?: boolean;
+    svgr?: boolean;
+    cssInJs?:
+      | false
+      | {
+          labelFormat?: string;
+        };
     cssModulesExportOnlyLocales?: boolean;
+    decorators?: 'legacy' | '2022-03';
     inlineCSS?: false | {};
     rscServer?:
       | false
@@ -219,9 +299,15 @@ export interface BuildParams {
     experimental?: {
       webpackSyntaxValidate?: string[];
     };
+    warnings?: {
+      cjsInterop?: boolean;
+      circularDependencies?: boolean;
+    };
     watch?: {
       ignoredPaths?: string[];
+      ignored?: string[];
       _nodeModulesRegexes?: string[];
+      debounce?: number;
     };
   };
   plugins: Array<JsHooks>;
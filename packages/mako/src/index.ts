@@ -2,16 +2,15 @@ import fs from 'fs';
 import path from 'path';
 import { omit } from 'lodash';
 import resolve from 'resolve';
-import { type Options } from 'sass';
 import * as binding from '../binding';
 import { ForkTSChecker as ForkTSChecker } from './forkTSChecker';
 import { LessLoaderOpts, lessLoader } from './lessLoader';
-import { sassLoader } from './sassLoader';
+import { SassLoaderOpts, sassLoader } from './sassLoader';
 
 type Config = binding.BuildParams['config'] & {
   plugins?: binding.BuildParams['plugins'];
   less?: LessLoaderOpts;
-  sass?: Options<'async'> & { resources: string[] };
+  sass?: SassLoaderOpts;
   forkTSChecker?: boolean;
 };
 
@@ -78,7 +77,7 @@ export async function build(params: BuildParams) {
     ],
   ];
 
-  const lessPluginAlias =
+  const resolveAlias =
     params.config.resolve?.alias?.reduce(
       (accumulator: Record<string, string>, currentValue) => {
         accumulator[currentValue[0]] = currentValue[1];
@@ -94,7 +93,7 @@ export async function build(params: BuildParams) {
     math: params.config.less?.math,
     sourceMap: params.config.less?.sourceMap || false,
     plugins: [
-      ['less-plugin-resolve', { aliases: lessPluginAlias }],
+      ['less-plugin-resolve', { aliases: resolveAlias }],
       ...(params.config.less?.plugins || []),
     ],
   });
@@ -117,6 +116,8 @@ export async function build(params: BuildParams) {
     const sassOpts = {
       ...(makoConfig?.sass || {}),
       ...(params.config?.sass || {}),
+      root: params.root,
+      alias: resolveAlias,
     };
     let sass = sassLoader(null, sassOpts);
     params.config.plugins.push({
@@ -1,7 +1,24 @@
 import url from 'url';
 import { type Options } from 'sass';
 
-function sassLoader(fn: Function | null, opts: Options<'async'>) {
+export type SassLoaderOpts = Options<'async'> & {
+  resources?: string[];
+  /**
+   * Extra directories to look up `@use` and `@import` from, same as `loadPaths`.
+   */
+  includePaths?: string[];
+  /**
+   * Prepended to every sass file before compiling, e.g. `@use "vars" as *;`.
+   */
+  additionalData?: string;
+};
+
+export type SassRenderOpts = SassLoaderOpts & {
+  root: string;
+  alias: Record<string, string>;
+};
+
+function sassLoader(fn: Function | null, opts: SassRenderOpts) {
   return {
     render: async (filePath: string) => {
       let filename = '';
@@ -10,7 +27,7 @@ function sassLoader(fn: Function | null, opts: Options<'async'>) {
       } catch (e) {
         return;
       }
-      if (filename?.endsWith('.scss')) {
+      if (filename?.endsWith('.scss') || filename?.endsWith('.sass')) {
         const { render } = require('./render');
         return render({ filename, opts });
       } else {
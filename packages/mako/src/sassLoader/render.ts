@@ -1,8 +1,35 @@
-import { type Options } from 'sass';
+import fs from 'fs';
+import path from 'path';
+import url from 'url';
+import { type FileImporter } from 'sass';
+import { SassRenderOpts } from '.';
+
+// resolve `@use` and `@import` of aliased paths the same way as mako's resolver does,
+// the partial and extension lookups are done by sass with the returned file url
+function aliasImporter(
+  root: string,
+  alias: Record<string, string>,
+): FileImporter<'async'> {
+  return {
+    findFileUrl(request: string) {
+      const source = request.startsWith('~') ? request.slice(1) : request;
+      for (const [key, value] of Object.entries(alias)) {
+        if (source === key || source.startsWith(`${key}/`)) {
+          const target = path.resolve(root, value + source.slice(key.length));
+          return url.pathToFileURL(target);
+        }
+      }
+      if (request.startsWith('~')) {
+        return url.pathToFileURL(path.join(root, 'node_modules', source));
+      }
+      return null;
+    },
+  };
+}
 
 async function render(param: {
   filename: string;
-  opts: Options<'async'> & { resources: string[] };
+  opts: SassRenderOpts;
 }): Promise<{ content: string; type: 'css' }> {
   let sass;
   try {
@@ -12,8 +39,32 @@ async function render(param: {
       'The "sass" package is not installed. Please run "npm install sass" to install it.',
     );
   }
+  const {
+    root,
+    alias,
+    includePaths = [],
+    additionalData,
+    ...opts
+  } = param.opts;
+  const input = fs.readFileSync(param.filename, 'utf-8');
+  const indented = param.filename.endsWith('.sass');
+  // additionalData is written in the scss syntax, so it's not prepended to indented files
   const result = await sass
-    .compileAsync(param.filename, { style: 'compressed', ...param.opts })
+    .compileStringAsync(
+      additionalData && !indented ? `${additionalData}\n${input}` : input,
+      {
+        style: 'compressed',
+        ...opts,
+        syntax: indented ? 'indented' : 'scss',
+        url: url.pathToFileURL(param.filename),
+        loadPaths: [
+          path.dirname(param.filename),
+          ...includePaths.map((p) => path.resolve(root, p)),
+          ...(opts.loadPaths || []),
+        ],
+        importers: [aliasImporter(root, alias), ...(opts.importers || [])],
+      },
+    )
     .catch((err: any) => {
       throw new Error(err.toString());
     });
@@ -303,6 +303,52 @@ ReactDOM.createRoot(document.getElementById("root")!).render(<App />);
   await cleanup({ process, browser });
 });
 
+runTest('css: entry > css, css-update without re-executing js', async () => {
+  write(
+    normalizeFiles({
+      '/src/index.css': `.foo {color:red;}`,
+      '/src/index.ts': `
+import "./index.css";
+const w = window as any;
+w.executedCount = (w.executedCount || 0) + 1;
+document.getElementById('root')!.innerHTML = \`<div class="foo">App</div><section>\${Math.random()}</section>\`;
+    `,
+    }),
+  );
+  await startMakoDevServer();
+  await delay(DELAY_TIME);
+  const browser = await chromium.launch();
+  const context = await browser.newContext(devices['iPhone 11']);
+  const page = await context.newPage();
+  const updateTypes = [];
+  page.on('websocket', (ws) => {
+    ws.on('framereceived', ({ payload }) => {
+      updateTypes.push(JSON.parse(payload).type);
+    });
+  });
+  await page.goto(`http://localhost:${MAKO_DEV_PORT}`);
+  let lastResult;
+  let thisResult;
+  lastResult = normalizeHtml(await getRootHtml(page));
+  const lastColor = await getElementColor(page, '.foo');
+  assert.equal(lastColor, 'rgb(255, 0, 0)', 'Initial render');
+  write({
+    '/src/index.css': `.foo {color:blue;}`,
+  });
+  await delay(DELAY_TIME);
+  thisResult = normalizeHtml(await getRootHtml(page));
+  const thisColor = await getElementColor(page, '.foo');
+  assert.equal(thisColor, 'rgb(0, 0, 255)', 'Second render');
+  assert.deepEqual(updateTypes, ['css-update'], 'should send css-update');
+  assert.equal(
+    await page.evaluate(() => window.executedCount),
+    1,
+    'should not re-execute js modules',
+  );
+  assert.equal(lastResult.random, thisResult.random, 'should not reload');
+  await cleanup({ process, browser });
+});
+
 runTest('css: entry > css modules', async () => {
   write(
     normalizeFiles({
@@ -690,6 +736,56 @@ ReactDOM.createRoot(document.getElementById("root")!).render(<><App /><section>{
   );
 });
 
+runTest('js: entry > import.meta.hot.accept boundary > js', async () => {
+  await commonTest(
+    {
+      '/src/util.ts': `
+export const text = 'foo';
+`,
+      '/src/boundary.ts': `
+import { text } from './util';
+const w = window as any;
+export function render() {
+  const count = w.acceptCount || 0;
+  w.random ||= Math.random();
+  document.getElementById('root')!.innerHTML = \`<div>\${text} \${count}</div><section>\${w.random}</section>\`;
+}
+render();
+if (import.meta.hot) {
+  import.meta.hot.accept((mod) => {
+    w.acceptCount = (w.acceptCount || 0) + 1;
+    mod.render();
+  });
+}
+`,
+      '/src/index.ts': `
+import './boundary';
+if (import.meta.hot) {
+  import.meta.hot.dispose(() => {
+    throw new Error('entry should not be disposed');
+  });
+}
+`,
+    },
+    (lastResult) => {
+      assert.equal(lastResult.html, '<div>foo 0</div>', 'Initial render');
+    },
+    {
+      '/src/util.ts': `
+export const text = 'bar';
+`,
+    },
+    (thisResult) => {
+      assert.equal(
+        thisResult.html,
+        '<div>bar 1</div>',
+        'accept callback of the nearest boundary should be invoked',
+      );
+    },
+    false,
+  );
+});
+
 runTest('js: entry > react component', async () => {
   await commonTest(
     {